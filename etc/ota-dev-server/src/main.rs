@@ -1,6 +1,13 @@
-use axum::Router;
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Router,
+};
 use local_ip_address::local_ip;
 use std::net::SocketAddr;
+use tower::ServiceExt;
 use tower_http::{services::ServeDir, trace::TraceLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
@@ -22,7 +29,44 @@ async fn main() {
 }
 
 fn using_serve_dir() -> Router {
-    Router::new().nest_service("/", ServeDir::new(TARGET_DIR))
+    Router::new().fallback(serve_file)
+}
+
+/// `ServeDir` answers `Range` requests but ignores `If-Range`, so a range of an image rebuilt
+/// since the download started is only sent if its `Last-Modified` date still matches
+async fn serve_file(request: Request) -> Response {
+    let (parts, _) = request.into_parts();
+    let build_request = |with_range: bool| {
+        let mut builder = Request::builder()
+            .method(parts.method.clone())
+            .uri(parts.uri.clone())
+            .version(parts.version);
+        for (name, value) in parts.headers.iter() {
+            if with_range || name != header::RANGE {
+                builder = builder.header(name, value);
+            }
+        }
+        builder.body(Body::empty()).unwrap()
+    };
+
+    let serve_dir = ServeDir::new(TARGET_DIR);
+    let response = serve_dir
+        .clone()
+        .oneshot(build_request(true))
+        .await
+        .into_response();
+    let if_range = parts.headers.get(header::IF_RANGE);
+    if if_range.is_some()
+        && response.status() == StatusCode::PARTIAL_CONTENT
+        && response.headers().get(header::LAST_MODIFIED) != if_range
+    {
+        tracing::info!("image changed since the download started, sending all of it");
+        return serve_dir
+            .oneshot(build_request(false))
+            .await
+            .into_response();
+    }
+    response
 }
 
 async fn serve(app: Router, port: u16) {
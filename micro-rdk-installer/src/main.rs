@@ -106,6 +106,10 @@ struct WriteCredentialsArgs {
     /// prompted for it
     #[arg(long = "wifi-password")]
     wifi_password: Option<SecretString>,
+    /// Hex encoded Ed25519 public key trusted to sign OTA images. Once written, the
+    /// device refuses OTA images without a valid `signature`
+    #[arg(long = "ota-public-key", value_parser = parse_ota_public_key)]
+    ota_public_key: Option<Vec<u8>>,
 }
 
 /// Flash a pre-compiled binary with the micro-RDK, the robot config, and wifi info
@@ -147,6 +151,10 @@ struct WriteFlashArgs {
     /// prompted for it
     #[arg(long = "wifi-password")]
     wifi_password: Option<SecretString>,
+    /// Hex encoded Ed25519 public key trusted to sign OTA images. Once written, the
+    /// device refuses OTA images without a valid `signature`
+    #[arg(long = "ota-public-key", value_parser = parse_ota_public_key)]
+    ota_public_key: Option<Vec<u8>>,
 }
 
 /// Generate a binary of a complete NVS data partition that conatins Wi-Fi and security
@@ -170,6 +178,10 @@ struct CreateNVSPartitionArgs {
     /// prompted for it
     #[arg(long = "wifi-password")]
     wifi_password: Option<SecretString>,
+    /// Hex encoded Ed25519 public key trusted to sign OTA images. Once written, the
+    /// device refuses OTA images without a valid `signature`
+    #[arg(long = "ota-public-key", value_parser = parse_ota_public_key)]
+    ota_public_key: Option<Vec<u8>>,
 }
 
 #[derive(Parser)]
@@ -200,6 +212,23 @@ fn validate_version(version: &str) -> Result<String, String> {
     Ok(version.to_owned())
 }
 
+fn parse_ota_public_key(key: &str) -> Result<Vec<u8>, String> {
+    let key = key.trim();
+    if key.len() != 64 {
+        return Err(format!(
+            "expected 64 hex characters (32 bytes), found {}",
+            key.len()
+        ));
+    }
+    if !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("{} is not a valid hex string", key));
+    }
+    Ok((0..key.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&key[i..i + 2], 16).unwrap())
+        .collect())
+}
+
 fn request_wifi(
     wifi_ssid: Option<String>,
     wifi_password: Option<SecretString>,
@@ -237,6 +266,7 @@ fn create_nvs_partition_binary(
     size: usize,
     wifi_ssid: Option<String>,
     wifi_password: Option<SecretString>,
+    ota_public_key: Option<Vec<u8>>,
 ) -> Result<Vec<u8>, Error> {
     let mut storage_data = ViamFlashStorageData::default();
     let config_str = fs::read_to_string(config_path.clone())
@@ -247,6 +277,7 @@ fn create_nvs_partition_binary(
     storage_data.robot_credentials.robot_secret = Some(app_config.cloud.secret);
     let wifi_cred = request_wifi(wifi_ssid, wifi_password)?;
     storage_data.wifi = Some(wifi_cred);
+    storage_data.ota_public_key = ota_public_key;
     log::info!(
         "Creating NVS partition with robot id: {:?}, wifi ssid: {:?}.",
         storage_data
@@ -362,6 +393,7 @@ fn main() -> Result<(), Error> {
                 nvs_metadata.size as usize,
                 args.wifi_ssid.clone(),
                 args.wifi_password.clone(),
+                args.ota_public_key.clone(),
             )?;
             write_credentials_to_app_binary(
                 app_path,
@@ -406,6 +438,7 @@ fn main() -> Result<(), Error> {
                     nvs_metadata.size as usize,
                     args.wifi_ssid.clone(),
                     args.wifi_password.clone(),
+                    args.ota_public_key.clone(),
                 )?;
                 write_credentials_to_app_binary(
                     app_path.clone(),
@@ -428,6 +461,7 @@ fn main() -> Result<(), Error> {
                 args.size,
                 args.wifi_ssid.clone(),
                 args.wifi_password.clone(),
+                args.ota_public_key.clone(),
             )?)
             .map_err(Error::FileError)?;
         }
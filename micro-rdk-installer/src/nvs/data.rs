@@ -30,15 +30,17 @@ pub struct RobotCredentials {
 pub struct ViamFlashStorageData {
     pub wifi: Option<WifiCredentials>,
     pub robot_credentials: RobotCredentials,
+    /// Ed25519 key OTA images must be signed with, unsigned images are refused once it is set
+    pub ota_public_key: Option<Vec<u8>>,
 }
 
 impl ViamFlashStorageData {
-    fn to_nvs_key_value_pairs(&self, namespace_idx: u8) -> Result<[NVSKeyValuePair; 5], Error> {
+    fn to_nvs_key_value_pairs(&self, namespace_idx: u8) -> Result<Vec<NVSKeyValuePair>, Error> {
         let wifi_cred = self
             .wifi
             .clone()
             .ok_or(Error::NVSDataProcessingError("no wifi".to_string()))?;
-        Ok([
+        let mut pairs = vec![
             NVSKeyValuePair {
                 key: "WIFI_SSID".to_string(),
                 value: NVSValue::String(wifi_cred.ssid),
@@ -77,7 +79,15 @@ impl ViamFlashStorageData {
                 )?),
                 namespace_idx,
             },
-        ])
+        ];
+        if let Some(public_key) = self.ota_public_key.as_ref() {
+            pairs.push(NVSKeyValuePair {
+                key: "OTA_PUB_KEY".to_string(),
+                value: NVSValue::Bytes(public_key.clone()),
+                namespace_idx,
+            });
+        }
+        Ok(pairs)
     }
 
     pub fn to_entries(&self, namespace_idx: u8) -> Result<Vec<NVSEntry>, Error> {
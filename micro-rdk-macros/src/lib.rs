@@ -53,6 +53,8 @@
 //!             angular_velocity_supported: false,
 //!             linear_acceleration_supported: false,
 //!             compass_heading_supported: false,
+//!             orientation_supported: false,
+//!             accuracy_supported: false,
 //!         }
 //!     }
 //! }
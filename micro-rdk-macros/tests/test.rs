@@ -37,6 +37,8 @@ impl MovementSensor for TestMovementSensor {
             linear_velocity_supported: false,
             angular_velocity_supported: false,
             compass_heading_supported: true,
+            orientation_supported: false,
+            accuracy_supported: false,
         }
     }
 
@@ -66,6 +66,8 @@ fn get_bits(field_ty: &Type) -> Result<usize, TokenStream> {
         Type::Path(type_path) if type_path.path.is_ident("f32") => 32,
         Type::Path(type_path) if type_path.path.is_ident("u64") => 64,
         Type::Path(type_path) if type_path.path.is_ident("i64") => 64,
+        // strings without a size have a variable length
+        Type::Path(type_path) if type_path.path.is_ident("String") => 0,
         Type::Array(type_array) => {
             if let Expr::Lit(len) = &type_array.len {
                 if let Lit::Int(len) = &len.lit {
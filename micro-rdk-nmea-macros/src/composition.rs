@@ -23,6 +23,7 @@ pub(crate) enum CodeGenPurpose {
 pub(crate) struct PgnComposition {
     pub(crate) attribute_getters: Vec<TokenStream2>,
     pub(crate) parsing_logic: Vec<TokenStream2>,
+    pub(crate) serialization_logic: Vec<TokenStream2>,
     pub(crate) struct_initialization: Vec<TokenStream2>,
     pub(crate) proto_conversion_logic: Vec<TokenStream2>,
     pub(crate) pgn_declaration: Option<TokenStream2>,
@@ -33,6 +34,7 @@ impl PgnComposition {
         Self {
             attribute_getters: vec![],
             parsing_logic: vec![],
+            serialization_logic: vec![],
             struct_initialization: vec![],
             proto_conversion_logic: vec![],
             pgn_declaration: None,
@@ -46,6 +48,8 @@ impl PgnComposition {
     pub(crate) fn merge(&mut self, mut other: Self) {
         self.attribute_getters.append(&mut other.attribute_getters);
         self.parsing_logic.append(&mut other.parsing_logic);
+        self.serialization_logic
+            .append(&mut other.serialization_logic);
         self.struct_initialization
             .append(&mut other.struct_initialization);
         self.proto_conversion_logic
@@ -83,6 +87,9 @@ impl PgnComposition {
                 statements
                     .parsing_logic
                     .push(quote! { let _ = cursor.read(#offset)?; });
+                statements
+                    .serialization_logic
+                    .push(quote! { cursor.write_reserved(#offset)?; });
             }
 
             let new_statements = if is_supported_numeric_type(&field.ty) {
@@ -161,6 +168,7 @@ impl PgnComposition {
     ) -> Result<TokenStream2, TokenStream> {
         let name = &input.ident;
         let parsing_logic = self.parsing_logic;
+        let serialization_logic = self.serialization_logic;
         let attribute_getters = self.attribute_getters;
         let struct_initialization = self.struct_initialization;
         let proto_conversion_logic = self.proto_conversion_logic;
@@ -188,6 +196,11 @@ impl PgnComposition {
                     })
                 }
 
+                fn write_to_cursor(&self, cursor: &mut #crate_ident::parse_helpers::parsers::DataCursor) -> Result<(), #error_ident> {
+                    use #crate_ident::parse_helpers::parsers::FieldWriter;
+                    #(#serialization_logic)*
+                    Ok(())
+                }
 
                 fn to_readings(self) -> Result<#mrdk_crate::common::sensor::GenericReadingsResult, #error_ident> {
                     let mut readings = std::collections::HashMap::new();
@@ -201,6 +214,7 @@ impl PgnComposition {
     pub(crate) fn into_fieldset_token_stream(self, input: &DeriveInput) -> TokenStream2 {
         let name = &input.ident;
         let parsing_logic = self.parsing_logic;
+        let serialization_logic = self.serialization_logic;
         let attribute_getters = self.attribute_getters;
         let struct_initialization = self.struct_initialization;
         let proto_conversion_logic = self.proto_conversion_logic;
@@ -224,6 +238,12 @@ impl PgnComposition {
                     })
                 }
 
+                fn write_to_cursor(&self, cursor: &mut #crate_ident::parse_helpers::parsers::DataCursor) -> Result<(), #error_ident> {
+                    use #crate_ident::parse_helpers::parsers::FieldWriter;
+                    #(#serialization_logic)*
+                    Ok(())
+                }
+
                 fn to_readings(&self) -> Result<#mrdk_crate::common::sensor::GenericReadingsResult, #error_ident> {
                     let mut readings = std::collections::HashMap::new();
                     #(#proto_conversion_logic)*
@@ -241,6 +261,10 @@ fn get_read_statement(name: &Ident, purpose: CodeGenPurpose) -> TokenStream2 {
     }
 }
 
+fn get_write_statement(name: &Ident) -> TokenStream2 {
+    quote! {writer.write_to_cursor(&self.#name, cursor)?;}
+}
+
 fn handle_number_field(
     name: &Ident,
     field: &Field,
@@ -361,27 +385,27 @@ fn handle_number_field(
 
     let nmea_crate = get_micro_nmea_crate_ident();
     let read_statement = get_read_statement(name, purpose);
+    let write_statement = get_write_statement(name);
     let is_decimal = if let Type::Path(type_path) = &field.ty {
         type_path.path.is_ident("u128")
     } else {
         false
     };
-    if is_decimal {
-        new_statements.parsing_logic.push(quote! {
-            let reader = #nmea_crate::parse_helpers::parsers::BinaryCodedDecimalField::new(#bits_size)?;
-            #read_statement
-        });
+    let field_reader = if is_decimal {
+        quote! { #nmea_crate::parse_helpers::parsers::BinaryCodedDecimalField::new(#bits_size) }
     } else if macro_attrs.value_offset.is_some() {
-        new_statements.parsing_logic.push(quote! {
-            let reader = #nmea_crate::parse_helpers::parsers::NumberField::<u32>::new(#bits_size)?;
-            #read_statement
-        });
+        quote! { #nmea_crate::parse_helpers::parsers::NumberField::<u32>::new(#bits_size)? }
     } else {
-        new_statements.parsing_logic.push(quote! {
-            let reader = #nmea_crate::parse_helpers::parsers::NumberField::<#num_ty>::new(#bits_size)?;
-            #read_statement
-        });
-    }
+        quote! { #nmea_crate::parse_helpers::parsers::NumberField::<#num_ty>::new(#bits_size)? }
+    };
+    new_statements.parsing_logic.push(quote! {
+        let reader = #field_reader;
+        #read_statement
+    });
+    new_statements.serialization_logic.push(quote! {
+        let writer = #field_reader;
+        #write_statement
+    });
 
     new_statements.struct_initialization.push(quote! {#name,});
     Ok(new_statements)
@@ -402,6 +426,12 @@ fn handle_array_field(name: &Ident, field: &Field, purpose: CodeGenPurpose) -> P
         #read_statement
     });
 
+    let write_statement = get_write_statement(name);
+    new_statements.serialization_logic.push(quote! {
+        let writer = #nmea_crate::parse_helpers::parsers::ArrayField::<u8, #len>::new();
+        #write_statement
+    });
+
     new_statements.struct_initialization.push(quote! {#name,});
 
     new_statements
@@ -415,17 +445,33 @@ fn handle_string_field(
     let mut new_statements = PgnComposition::new();
     let nmea_crate = get_micro_nmea_crate_ident();
     let read_statement = get_read_statement(name, purpose);
-    let reader = if let Some(length_field) = macro_attrs.length_field.as_ref() {
-        quote! { #nmea_crate::parse_helpers::parsers::FixedSizeStringField::new(#length_field as usize); }
+    let write_statement = get_write_statement(name);
+    let bits_size = macro_attrs.bits;
+    let (reader, writer) = if let Some(length_field) = macro_attrs.length_field.as_ref() {
+        (
+            quote! { #nmea_crate::parse_helpers::parsers::FixedSizeStringField::new(#length_field as usize) },
+            quote! { #nmea_crate::parse_helpers::parsers::FixedSizeStringField::new(self.#length_field as usize) },
+        )
+    } else if bits_size != 0 {
+        let reader =
+            quote! { #nmea_crate::parse_helpers::parsers::FixedSizeStringField::new(#bits_size) };
+        (reader.clone(), reader)
     } else if macro_attrs.encoding_is_variable {
-        quote! { #nmea_crate::parse_helpers::parsers::VariableLengthAndEncodingStringField; }
+        let reader =
+            quote! { #nmea_crate::parse_helpers::parsers::VariableLengthAndEncodingStringField };
+        (reader.clone(), reader)
     } else {
-        quote! { #nmea_crate::parse_helpers::parsers::VariableLengthStringField; }
+        let reader = quote! { #nmea_crate::parse_helpers::parsers::VariableLengthStringField };
+        (reader.clone(), reader)
     };
     new_statements.parsing_logic.push(quote! {
-        let reader = #reader
+        let reader = #reader;
         #read_statement
     });
+    new_statements.serialization_logic.push(quote! {
+        let writer = #writer;
+        #write_statement
+    });
     new_statements.struct_initialization.push(quote! {#name,});
 
     new_statements.attribute_getters.push(quote! {
@@ -468,6 +514,12 @@ fn handle_lookup_field(
 
         new_statements.parsing_logic.push(setters);
 
+        let write_statement = get_write_statement(name);
+        new_statements.serialization_logic.push(quote! {
+            let writer = #nmea_crate::parse_helpers::parsers::LookupField::<#enum_type>::new(#bits_size)?;
+            #write_statement
+        });
+
         new_statements.struct_initialization.push(quote! {#name,});
         let proto_import_prefix = crate::utils::get_proto_import_prefix();
         let prop_name = name.to_string();
@@ -526,6 +578,12 @@ fn handle_fieldset(
             #read_statement
         });
 
+        let write_statement = get_write_statement(name);
+        new_statements.serialization_logic.push(quote! {
+            let writer = #nmea_crate::parse_helpers::parsers::FieldSetList::<#f_type>::new(self.#length_field_token as usize);
+            #write_statement
+        });
+
         new_statements.attribute_getters.push(quote! {
             pub fn #name(&self) -> Vec<#f_type> { self.#name.clone() }
         });
@@ -584,6 +642,12 @@ fn handle_polymorphic_field(
             #read_statement
         });
 
+        let write_statement = get_write_statement(name);
+        new_statements.serialization_logic.push(quote! {
+            let writer = #nmea_crate::parse_helpers::parsers::PolymorphicDataTypeReader::<#f_type>::new(self.#lookup_field_token);
+            #write_statement
+        });
+
         new_statements.attribute_getters.push(quote! {
             pub fn #name(&self) -> #f_type { self.#name.clone() }
         });
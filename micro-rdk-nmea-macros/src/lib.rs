@@ -6,7 +6,8 @@ use crate::composition::{CodeGenPurpose, PgnComposition};
 use proc_macro::TokenStream;
 
 /// PgnMessageDerive is a macro that implements parsing logic for a struct in the form of a method
-/// `from_bytes(data: Vec<u8>, source_id: u8) -> Result<Self, NmeaParseError>`, attribute accessors,
+/// `from_bytes(data: Vec<u8>, source_id: u8) -> Result<Self, NmeaParseError>`, the inverse
+/// `write_to_cursor` writing the struct back to NMEA message data, attribute accessors,
 /// and a function `to_readings` for serializing to an instance of `GenericReadingsResult` as defined in
 /// micro-RDK. Refer to the comment for `attributes::MacroAttributes` to understand the attributes
 /// annotating the struct fields to customize the parsing/deserializing logic
@@ -41,8 +42,8 @@ pub fn pgn_message_derive(item: TokenStream) -> TokenStream {
     }
 }
 
-/// FieldsetDerive is a macro that defines a struct implementing parsing logic for
-/// data found in a repeated field in a NMEA message via the `FieldSet` trait
+/// FieldsetDerive is a macro that defines a struct implementing parsing and serialization logic
+/// for data found in a repeated field in a NMEA message via the `FieldSet` trait
 #[proc_macro_derive(
     FieldsetDerive,
     attributes(
@@ -2,7 +2,7 @@ use check_keyword::CheckKeyword;
 use num2words::Num2Words;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Number, Value};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{BufReader, Write};
 use std::path::Path;
@@ -463,6 +463,20 @@ struct MessageJson {
 }
 
 impl MessageJson {
+    // messages with repeating or variable length fields, or longer than the 8 bytes of
+    // a CAN frame, are sent with the fast-packet protocol
+    fn fits_single_frame(&self) -> bool {
+        self.repeating_start_1 == 0
+            && self.repeating_start_2 == 0
+            && self.field_list.iter().all(|field| field.size != 0)
+            && self
+                .field_list
+                .iter()
+                .map(|field| field.size)
+                .sum::<usize>()
+                <= 64
+    }
+
     fn into_bytes(self) -> Result<Option<(String, Vec<u8>)>, String> {
         let standard_pgn_range_1 = 61440..65279;
         let standard_pgn_range_2 = 126976..130815;
@@ -1789,8 +1803,14 @@ fn main() {
         .expect("JSON missing messages key");
     let message_formats: Vec<MessageJson> =
         serde_json::from_value(messages_value).expect("could not parse messages");
+    let mut single_frame_pgns: BTreeMap<u32, bool> = BTreeMap::new();
     for msg_json in message_formats {
         let pgn = msg_json.pgn;
+        if (0x1F000..=0x1FEFF).contains(&pgn) {
+            // a PGN with several definitions is only sent in a single frame if all of them fit
+            let fits = msg_json.fits_single_frame();
+            *single_frame_pgns.entry(pgn).or_insert(fits) &= fits;
+        }
         match msg_json.into_bytes() {
             Ok(Some((struct_name, struct_bytes))) => {
                 pgn_struct_names.push(struct_name);
@@ -1829,5 +1849,19 @@ fn main() {
         }
     }
 
-    messages_file.write_all("\n);".as_bytes()).unwrap()
+    messages_file.write_all("\n);".as_bytes()).unwrap();
+
+    let single_frame_pgns: Vec<String> = single_frame_pgns
+        .into_iter()
+        .filter_map(|(pgn, fits)| fits.then(|| pgn.to_string()))
+        .collect();
+    messages_file
+        .write_all(
+            format!(
+                "\n\n/// PGNs of the fast-packet range whose messages fit in a single frame\npub const SINGLE_FRAME_PGNS: &[u32] = &[{}];\n",
+                single_frame_pgns.join(", ")
+            )
+            .as_bytes(),
+        )
+        .expect("failed to write single frame PGNs");
 }
@@ -0,0 +1,274 @@
+//! Access to a CAN bus. The [CanBus] trait is implemented by the TWAI controller of the ESP32
+//! (with the `esp32` feature), by SocketCAN interfaces on Linux (with the `native` feature) and
+//! by an in-process [LoopbackCanNetwork] meant for tests and simulations.
+
+use std::{
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError, Sender},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use super::frame::{CanFrame, CanFrameError};
+
+#[derive(Debug, Error)]
+pub enum CanBusError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("CAN driver error: {0}")]
+    Driver(String),
+    #[error("CAN bus is disconnected")]
+    Disconnected,
+    #[error(transparent)]
+    InvalidFrame(#[from] CanFrameError),
+}
+
+pub trait CanBus {
+    /// Waits up to `timeout` for a frame, returns `None` if none was received
+    fn read_frame(&mut self, timeout: Duration) -> Result<Option<CanFrame>, CanBusError>;
+    fn write_frame(&mut self, frame: &CanFrame) -> Result<(), CanBusError>;
+}
+
+impl<B> CanBus for Box<B>
+where
+    B: CanBus + ?Sized,
+{
+    fn read_frame(&mut self, timeout: Duration) -> Result<Option<CanFrame>, CanBusError> {
+        (**self).read_frame(timeout)
+    }
+    fn write_frame(&mut self, frame: &CanFrame) -> Result<(), CanBusError> {
+        (**self).write_frame(frame)
+    }
+}
+
+/// A bus living in the process: the frames written by one of its endpoints are read by
+/// all the others
+#[derive(Clone, Default)]
+pub struct LoopbackCanNetwork {
+    endpoints: Arc<Mutex<Vec<Sender<CanFrame>>>>,
+}
+
+impl LoopbackCanNetwork {
+    pub fn connect(&self) -> LoopbackCanBus {
+        let (sender, receiver) = channel();
+        let mut endpoints = self.endpoints.lock().unwrap();
+        endpoints.push(sender);
+        LoopbackCanBus {
+            index: endpoints.len() - 1,
+            receiver,
+            network: self.clone(),
+        }
+    }
+}
+
+pub struct LoopbackCanBus {
+    index: usize,
+    receiver: Receiver<CanFrame>,
+    network: LoopbackCanNetwork,
+}
+
+impl CanBus for LoopbackCanBus {
+    fn read_frame(&mut self, timeout: Duration) -> Result<Option<CanFrame>, CanBusError> {
+        match self.receiver.recv_timeout(timeout) {
+            Ok(frame) => Ok(Some(frame)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => Err(CanBusError::Disconnected),
+        }
+    }
+
+    fn write_frame(&mut self, frame: &CanFrame) -> Result<(), CanBusError> {
+        let endpoints = self.network.endpoints.lock().unwrap();
+        endpoints
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != self.index)
+            // endpoints that were dropped simply stop receiving frames
+            .for_each(|(_, endpoint)| {
+                let _ = endpoint.send(frame.clone());
+            });
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "native", target_os = "linux"))]
+pub use socketcan_bus::SocketCanBus;
+
+#[cfg(all(feature = "native", target_os = "linux"))]
+mod socketcan_bus {
+    use std::{io::ErrorKind, time::Duration};
+
+    use socketcan::{
+        CanFrame as SocketCanFrame, CanSocket, EmbeddedFrame, ExtendedId, Frame, Socket,
+    };
+
+    use super::{CanBus, CanBusError};
+    use crate::can::frame::CanFrame;
+
+    /// A SocketCAN interface such as `can0`, or a virtual `vcan0` interface for testing
+    pub struct SocketCanBus {
+        socket: CanSocket,
+    }
+
+    impl SocketCanBus {
+        pub fn open(interface: &str) -> Result<Self, CanBusError> {
+            Ok(Self {
+                socket: CanSocket::open(interface)?,
+            })
+        }
+    }
+
+    impl CanBus for SocketCanBus {
+        fn read_frame(&mut self, timeout: Duration) -> Result<Option<CanFrame>, CanBusError> {
+            self.socket.set_read_timeout(timeout)?;
+            match self.socket.read_frame() {
+                // NMEA 2000 only uses extended frames, error and remote frames are ignored
+                Ok(SocketCanFrame::Data(frame)) if frame.is_extended() => {
+                    Ok(Some(CanFrame::new(frame.raw_id(), frame.data().to_vec())?))
+                }
+                Ok(_) => Ok(None),
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    Ok(None)
+                }
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        fn write_frame(&mut self, frame: &CanFrame) -> Result<(), CanBusError> {
+            let id = ExtendedId::new(frame.id()).ok_or(CanBusError::Driver(format!(
+                "{:#x} is not an extended id",
+                frame.id()
+            )))?;
+            let frame = SocketCanFrame::new(id, frame.data())
+                .ok_or(CanBusError::Driver("invalid frame".to_string()))?;
+            Ok(self.socket.write_frame(&frame)?)
+        }
+    }
+}
+
+#[cfg(feature = "esp32")]
+pub use twai_bus::TwaiCanBus;
+
+#[cfg(feature = "esp32")]
+mod twai_bus {
+    use std::{
+        sync::atomic::{AtomicBool, Ordering},
+        time::Duration,
+    };
+
+    use micro_rdk::esp32::esp_idf_svc::{
+        hal::{
+            can::{
+                config::{Config, Timing},
+                CanDriver, Flags, Frame, CAN,
+            },
+            delay::TickType,
+            gpio::AnyIOPin,
+        },
+        sys::{EspError, ESP_ERR_TIMEOUT},
+    };
+
+    use super::{CanBus, CanBusError};
+    use crate::can::frame::CanFrame;
+
+    impl From<EspError> for CanBusError {
+        fn from(value: EspError) -> Self {
+            CanBusError::Driver(value.to_string())
+        }
+    }
+
+    // the ESP32 has a single TWAI controller, only one bus can own it at a time
+    static TWAI_TAKEN: AtomicBool = AtomicBool::new(false);
+
+    struct TwaiClaim;
+
+    impl TwaiClaim {
+        fn take() -> Result<Self, CanBusError> {
+            if TWAI_TAKEN.swap(true, Ordering::SeqCst) {
+                return Err(CanBusError::Driver(
+                    "the TWAI controller is already in use".to_string(),
+                ));
+            }
+            Ok(Self)
+        }
+    }
+
+    impl Drop for TwaiClaim {
+        fn drop(&mut self) {
+            TWAI_TAKEN.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// The TWAI controller of the ESP32, connected to the bus through a transceiver
+    /// wired to `tx_pin` and `rx_pin`. NMEA 2000 runs at 250 kbit/s.
+    pub struct TwaiCanBus<'a> {
+        driver: CanDriver<'a>,
+        // declared after the driver so the controller is released once it is uninstalled
+        _claim: TwaiClaim,
+    }
+
+    impl TwaiCanBus<'_> {
+        /// Fails if another [TwaiCanBus] still owns the controller
+        pub fn new(tx_pin: i32, rx_pin: i32) -> Result<Self, CanBusError> {
+            let claim = TwaiClaim::take()?;
+            let tx = unsafe { AnyIOPin::new(tx_pin) };
+            let rx = unsafe { AnyIOPin::new(rx_pin) };
+            let config = Config::new().timing(Timing::B250K);
+            // SAFETY: the claim guarantees no other driver owns the peripheral
+            let mut driver = CanDriver::new(unsafe { CAN::new() }, tx, rx, &config)?;
+            driver.start()?;
+            Ok(Self {
+                driver,
+                _claim: claim,
+            })
+        }
+    }
+
+    impl CanBus for TwaiCanBus<'_> {
+        fn read_frame(&mut self, timeout: Duration) -> Result<Option<CanFrame>, CanBusError> {
+            match self.driver.receive(TickType::from(timeout).ticks()) {
+                Ok(frame) if frame.is_extended() => Ok(Some(CanFrame::new(
+                    frame.identifier(),
+                    frame.data().to_vec(),
+                )?)),
+                Ok(_) => Ok(None),
+                Err(err) if err.code() == ESP_ERR_TIMEOUT as i32 => Ok(None),
+                Err(err) => Err(err.into()),
+            }
+        }
+
+        fn write_frame(&mut self, frame: &CanFrame) -> Result<(), CanBusError> {
+            let frame = Frame::new(frame.id(), Flags::Extended.into(), frame.data())
+                .ok_or(CanBusError::Driver("invalid frame".to_string()))?;
+            Ok(self
+                .driver
+                .transmit(&frame, TickType::from(Duration::from_millis(100)).ticks())?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{CanBus, LoopbackCanNetwork};
+    use crate::can::frame::CanFrame;
+
+    #[test]
+    fn loopback_test() {
+        let network = LoopbackCanNetwork::default();
+        let mut first = network.connect();
+        let mut second = network.connect();
+        let mut third = network.connect();
+
+        let frame = CanFrame::new(0x0DF50B02, vec![0, 0x86, 1, 0, 0, 0xBC, 2, 0xFF]).unwrap();
+        assert!(first.write_frame(&frame).is_ok());
+        let timeout = Duration::from_millis(10);
+        assert_eq!(second.read_frame(timeout).unwrap(), Some(frame.clone()));
+        assert_eq!(third.read_frame(timeout).unwrap(), Some(frame));
+        assert_eq!(first.read_frame(timeout).unwrap(), None);
+        assert_eq!(second.read_frame(timeout).unwrap(), None);
+    }
+}
@@ -0,0 +1,133 @@
+use thiserror::Error;
+
+/// Largest identifier of a CAN 2.0B (extended) frame
+pub const MAX_EXTENDED_ID: u32 = 0x1FFF_FFFF;
+/// Maximum amount of data carried by a CAN frame
+pub const MAX_FRAME_DATA_SIZE: usize = 8;
+/// Destination address of messages sent to every device on the bus
+pub const GLOBAL_ADDRESS: u8 = 255;
+
+#[derive(Debug, Error)]
+pub enum CanFrameError {
+    #[error("{0:#x} is not a valid 29-bit identifier")]
+    InvalidId(u32),
+    #[error("a CAN frame holds at most 8 bytes of data, got {0}")]
+    DataTooLong(usize),
+    #[error("PGN {0} is out of range")]
+    InvalidPgn(u32),
+}
+
+/// A CAN frame with a 29-bit identifier, as carried on an NMEA 2000 bus. The identifier
+/// encodes the priority, PGN, source and (for PDU1 PGNs) destination of the message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanFrame {
+    id: u32,
+    data: Vec<u8>,
+}
+
+impl CanFrame {
+    pub fn new(id: u32, data: Vec<u8>) -> Result<Self, CanFrameError> {
+        if id > MAX_EXTENDED_ID {
+            return Err(CanFrameError::InvalidId(id));
+        }
+        if data.len() > MAX_FRAME_DATA_SIZE {
+            return Err(CanFrameError::DataTooLong(data.len()));
+        }
+        Ok(Self { id, data })
+    }
+
+    /// Builds the frame of a message, the destination is ignored for PDU2 PGNs
+    pub fn from_parts(
+        priority: u8,
+        pgn: u32,
+        source: u8,
+        destination: u8,
+        data: Vec<u8>,
+    ) -> Result<Self, CanFrameError> {
+        if pgn > 0x3FFFF {
+            return Err(CanFrameError::InvalidPgn(pgn));
+        }
+        let pgn = if is_pdu1(pgn) {
+            (pgn & 0x3FF00) | destination as u32
+        } else {
+            pgn
+        };
+        let id = ((priority as u32 & 0x7) << 26) | (pgn << 8) | source as u32;
+        Self::new(id, data)
+    }
+
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn priority(&self) -> u8 {
+        ((self.id >> 26) & 0x7) as u8
+    }
+
+    /// The PGN of the message, the PDU specific byte holding the destination address
+    /// of PDU1 PGNs is cleared
+    pub fn pgn(&self) -> u32 {
+        let pgn = (self.id >> 8) & 0x3FFFF;
+        if is_pdu1(pgn) {
+            pgn & 0x3FF00
+        } else {
+            pgn
+        }
+    }
+
+    pub fn source(&self) -> u8 {
+        (self.id & 0xFF) as u8
+    }
+
+    /// The destination address for PDU1 PGNs, the global address otherwise
+    pub fn destination(&self) -> u8 {
+        let pgn = (self.id >> 8) & 0x3FFFF;
+        if is_pdu1(pgn) {
+            (pgn & 0xFF) as u8
+        } else {
+            GLOBAL_ADDRESS
+        }
+    }
+}
+
+// PGNs whose PDU format is below 240 are addressed to a single destination
+fn is_pdu1(pgn: u32) -> bool {
+    ((pgn >> 8) & 0xFF) < 240
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CanFrame, GLOBAL_ADDRESS};
+
+    #[test]
+    fn frame_id_test() {
+        // water depth (128267) with priority 3 from source 2
+        let frame = CanFrame::new(0x0DF50B02, vec![0; 8]).unwrap();
+        assert_eq!(frame.priority(), 3);
+        assert_eq!(frame.pgn(), 128267);
+        assert_eq!(frame.source(), 2);
+        assert_eq!(frame.destination(), GLOBAL_ADDRESS);
+        assert_eq!(
+            CanFrame::from_parts(3, 128267, 2, 0x20, vec![0; 8]).unwrap(),
+            frame
+        );
+
+        // ISO request (59904) from source 0x05 to 0x20
+        let frame = CanFrame::new(0x18EA2005, vec![0; 3]).unwrap();
+        assert_eq!(frame.priority(), 6);
+        assert_eq!(frame.pgn(), 59904);
+        assert_eq!(frame.source(), 0x05);
+        assert_eq!(frame.destination(), 0x20);
+        assert_eq!(
+            CanFrame::from_parts(6, 59904, 0x05, 0x20, vec![0; 3]).unwrap(),
+            frame
+        );
+
+        assert!(CanFrame::new(0x2000_0000, vec![]).is_err());
+        assert!(CanFrame::new(0x0DF50B02, vec![0; 9]).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+pub mod bus;
+pub mod frame;
+pub mod reassembly;
+pub mod source;
@@ -0,0 +1,636 @@
+//! Reassembly of NMEA 2000 messages from the CAN frames read on the bus.
+//!
+//! Messages of up to 8 bytes are sent in a single frame. Longer messages are either sent with
+//! the NMEA 2000 fast-packet protocol (up to 223 bytes in at most 32 frames) or with the ISO 11783
+//! transport protocol (up to 1785 bytes), which announces the message with a connection management
+//! frame (TP.CM) and sends it in data transfer frames (TP.DT). The transport protocol is either
+//! broadcast (BAM) or addressed to a single device that controls the flow with clear to send
+//! frames (RTS/CTS).
+//!
+//! The reassembler only listens to the bus: it never answers a request to send, it follows the
+//! transfers between the devices exchanging messages.
+
+use std::{collections::HashMap, time::Duration};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use thiserror::Error;
+
+use super::frame::{CanFrame, GLOBAL_ADDRESS};
+use crate::parse_helpers::parsers::NmeaMessageMetadata;
+
+/// Time after which a sequence whose next frame hasn't been received is discarded
+pub const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_millis(750);
+
+const TP_CONNECTION_MANAGEMENT_PGN: u32 = 60416;
+const TP_DATA_TRANSFER_PGN: u32 = 60160;
+
+const TP_REQUEST_TO_SEND: u8 = 16;
+const TP_CLEAR_TO_SEND: u8 = 17;
+const TP_END_OF_MESSAGE_ACK: u8 = 19;
+const TP_BROADCAST_ANNOUNCE: u8 = 32;
+const TP_ABORT: u8 = 255;
+
+const FAST_PACKET_FIRST_FRAME_DATA_SIZE: usize = 6;
+const FAST_PACKET_FRAME_DATA_SIZE: usize = 7;
+const FAST_PACKET_MAX_FRAMES: usize = 32;
+const TP_PACKET_DATA_SIZE: usize = 7;
+
+// generated from the message definitions
+#[cfg(generate_nmea_definitions)]
+use crate::gen::messages::SINGLE_FRAME_PGNS;
+
+// without the message definitions, the single frame PGNs most commonly found on a bus
+#[cfg(not(generate_nmea_definitions))]
+const SINGLE_FRAME_PGNS: &[u32] = &[
+    126992, 126993, 127245, 127250, 127251, 127257, 127258, 127488, 127493, 127501, 127502, 127505,
+    127508, 127509, 127751, 128000, 128259, 128267, 129025, 129026, 129283, 129291, 129539, 130306,
+    130310, 130311, 130312, 130313, 130314, 130315, 130316, 130576,
+];
+
+/// Returns whether messages of `pgn` are sent with the fast-packet protocol, which is the case
+/// for the proprietary fast-packet PGNs and for the standard PGNs from 126976, except for those
+/// whose definition fits in a single frame
+pub fn is_fast_packet_pgn(pgn: u32) -> bool {
+    match pgn {
+        0x1EF00..=0x1EFFF | 0x1FF00..=0x1FFFF => true,
+        0x1F000..=0x1FEFF => !SINGLE_FRAME_PGNS.contains(&pgn),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReassemblyError {
+    #[error("frame of PGN {0} has too little data")]
+    FrameTooShort(u32),
+    #[error("fast-packet message of PGN {0} announces an invalid size of {1} bytes")]
+    InvalidFastPacketSize(u32, usize),
+    #[error("transport of PGN {0} announces {1} bytes in {2} packets")]
+    InvalidTransportSize(u32, usize, usize),
+    #[error("unexpected frame {1} in the sequence of PGN {0}")]
+    UnexpectedFrame(u32, usize),
+}
+
+/// A complete message and the metadata found in the frames that carried it
+#[derive(Clone, Debug)]
+pub struct ReassembledMessage {
+    metadata: NmeaMessageMetadata,
+    data: Vec<u8>,
+}
+
+impl ReassembledMessage {
+    pub fn metadata(&self) -> &NmeaMessageMetadata {
+        &self.metadata
+    }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_parts(self) -> (NmeaMessageMetadata, Vec<u8>) {
+        (self.metadata, self.data)
+    }
+
+    /// The message as expected by the parsers of this library, the data prefixed by
+    /// a header holding the metadata
+    pub fn to_raw_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.metadata.to_header_bytes(self.data.len());
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+#[cfg(generate_nmea_definitions)]
+impl TryFrom<ReassembledMessage> for crate::gen::messages::NmeaMessage {
+    type Error = crate::parse_helpers::errors::NmeaParseError;
+    fn try_from(value: ReassembledMessage) -> Result<Self, Self::Error> {
+        let data =
+            crate::gen::messages::NmeaMessageBody::from_bytes(value.metadata.pgn(), value.data)?;
+        Ok(Self {
+            metadata: value.metadata,
+            data,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SequenceKey {
+    FastPacket { pgn: u32, source: u8, sequence: u8 },
+    Transport { source: u8, destination: u8 },
+}
+
+struct PartialMessage {
+    priority: u8,
+    pgn: u32,
+    destination: u8,
+    data: Vec<u8>,
+    received: Vec<bool>,
+    last_frame: DateTime<Utc>,
+}
+
+impl PartialMessage {
+    fn new(
+        frame: &CanFrame,
+        pgn: u32,
+        destination: u8,
+        size: usize,
+        frames: usize,
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            priority: frame.priority(),
+            pgn,
+            destination,
+            data: vec![0; size],
+            received: vec![false; frames],
+            last_frame: timestamp,
+        }
+    }
+
+    // Frames may be received more than once or out of order, the data is copied to the
+    // position given by the index of the frame and bytes past the announced size are dropped
+    fn insert(&mut self, index: usize, offset: usize, bytes: &[u8], timestamp: DateTime<Utc>) {
+        let len = bytes.len().min(self.data.len().saturating_sub(offset));
+        self.data[offset..(offset + len)].copy_from_slice(&bytes[..len]);
+        self.received[index] = true;
+        self.last_frame = timestamp;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.received.iter().all(|received| *received)
+    }
+
+    fn into_message(self, source: u8, timestamp: DateTime<Utc>) -> ReassembledMessage {
+        ReassembledMessage {
+            metadata: NmeaMessageMetadata::new(
+                self.pgn,
+                Some(timestamp),
+                self.destination as u16,
+                source as u16,
+                self.priority as u16,
+            ),
+            data: self.data,
+        }
+    }
+}
+
+/// Turns the frames read on the bus into complete messages. Incomplete sequences are
+/// discarded when their next frame isn't received within the timeout.
+pub struct FrameReassembler {
+    sequences: HashMap<SequenceKey, PartialMessage>,
+    timeout: TimeDelta,
+    is_fast_packet: fn(u32) -> bool,
+}
+
+impl Default for FrameReassembler {
+    fn default() -> Self {
+        Self::new(DEFAULT_SEQUENCE_TIMEOUT, is_fast_packet_pgn)
+    }
+}
+
+impl FrameReassembler {
+    /// `is_fast_packet` tells which PGNs are sent with the fast-packet protocol
+    pub fn new(timeout: Duration, is_fast_packet: fn(u32) -> bool) -> Self {
+        Self {
+            sequences: HashMap::new(),
+            timeout: TimeDelta::from_std(timeout).unwrap_or(TimeDelta::MAX),
+            is_fast_packet,
+        }
+    }
+
+    /// Processes a frame received at `timestamp`, returns the message it completes if any
+    pub fn push_frame(
+        &mut self,
+        frame: &CanFrame,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<ReassembledMessage>, ReassemblyError> {
+        self.expire(timestamp);
+        match frame.pgn() {
+            TP_CONNECTION_MANAGEMENT_PGN => self.push_connection_management(frame, timestamp),
+            TP_DATA_TRANSFER_PGN => self.push_data_transfer(frame, timestamp),
+            pgn if (self.is_fast_packet)(pgn) => self.push_fast_packet(frame, timestamp),
+            pgn => Ok(Some(ReassembledMessage {
+                metadata: NmeaMessageMetadata::new(
+                    pgn,
+                    Some(timestamp),
+                    frame.destination() as u16,
+                    frame.source() as u16,
+                    frame.priority() as u16,
+                ),
+                data: frame.data().to_vec(),
+            })),
+        }
+    }
+
+    /// Discards the sequences that didn't receive a frame within the timeout before `now`
+    pub fn expire(&mut self, now: DateTime<Utc>) {
+        let timeout = self.timeout;
+        self.sequences.retain(|key, partial| {
+            let alive = now - partial.last_frame <= timeout;
+            if !alive {
+                log::debug!(
+                    "discarding incomplete sequence {:?} of PGN {}",
+                    key,
+                    partial.pgn
+                );
+            }
+            alive
+        });
+    }
+
+    fn push_fast_packet(
+        &mut self,
+        frame: &CanFrame,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<ReassembledMessage>, ReassemblyError> {
+        let pgn = frame.pgn();
+        let data = frame.data();
+        if data.len() < 2 {
+            return Err(ReassemblyError::FrameTooShort(pgn));
+        }
+        let key = SequenceKey::FastPacket {
+            pgn,
+            source: frame.source(),
+            sequence: data[0] >> 5,
+        };
+        let index = (data[0] & 0x1F) as usize;
+
+        if index == 0 {
+            let size = data[1] as usize;
+            let frames = 1 + size
+                .saturating_sub(FAST_PACKET_FIRST_FRAME_DATA_SIZE)
+                .div_ceil(FAST_PACKET_FRAME_DATA_SIZE);
+            if frames > FAST_PACKET_MAX_FRAMES {
+                return Err(ReassemblyError::InvalidFastPacketSize(pgn, size));
+            }
+            // a first frame restarts the sequence
+            let mut partial =
+                PartialMessage::new(frame, pgn, frame.destination(), size, frames, timestamp);
+            partial.insert(0, 0, &data[2..], timestamp);
+            let _ = self.sequences.insert(key, partial);
+        } else {
+            let Some(partial) = self.sequences.get_mut(&key) else {
+                // the first frame was missed or the sequence timed out
+                return Ok(None);
+            };
+            if index >= partial.received.len() {
+                let _ = self.sequences.remove(&key);
+                return Err(ReassemblyError::UnexpectedFrame(pgn, index));
+            }
+            let offset =
+                FAST_PACKET_FIRST_FRAME_DATA_SIZE + (index - 1) * FAST_PACKET_FRAME_DATA_SIZE;
+            partial.insert(index, offset, &data[1..], timestamp);
+        }
+        Ok(self.take_if_complete(key, frame.source(), timestamp))
+    }
+
+    fn push_connection_management(
+        &mut self,
+        frame: &CanFrame,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<ReassembledMessage>, ReassemblyError> {
+        let data = frame.data();
+        if data.len() < 8 {
+            return Err(ReassemblyError::FrameTooShort(frame.pgn()));
+        }
+        match data[0] {
+            control @ (TP_REQUEST_TO_SEND | TP_BROADCAST_ANNOUNCE) => {
+                let size = u16::from_le_bytes([data[1], data[2]]) as usize;
+                let packets = data[3] as usize;
+                let pgn = u32::from_le_bytes([data[5], data[6], data[7], 0]);
+                if packets == 0 || packets * TP_PACKET_DATA_SIZE < size {
+                    return Err(ReassemblyError::InvalidTransportSize(pgn, size, packets));
+                }
+                let destination = if control == TP_BROADCAST_ANNOUNCE {
+                    GLOBAL_ADDRESS
+                } else {
+                    frame.destination()
+                };
+                let key = SequenceKey::Transport {
+                    source: frame.source(),
+                    destination,
+                };
+                let partial =
+                    PartialMessage::new(frame, pgn, destination, size, packets, timestamp);
+                let _ = self.sequences.insert(key, partial);
+            }
+            TP_CLEAR_TO_SEND => {
+                // sent by the receiver of the message, the sender may be held for a while
+                let key = SequenceKey::Transport {
+                    source: frame.destination(),
+                    destination: frame.source(),
+                };
+                if let Some(partial) = self.sequences.get_mut(&key) {
+                    partial.last_frame = timestamp;
+                }
+            }
+            TP_ABORT => {
+                // either side of the connection may abort it
+                let _ = self.sequences.remove(&SequenceKey::Transport {
+                    source: frame.source(),
+                    destination: frame.destination(),
+                });
+                let _ = self.sequences.remove(&SequenceKey::Transport {
+                    source: frame.destination(),
+                    destination: frame.source(),
+                });
+            }
+            TP_END_OF_MESSAGE_ACK => {}
+            control => log::debug!("ignoring transport control byte {}", control),
+        }
+        Ok(None)
+    }
+
+    fn push_data_transfer(
+        &mut self,
+        frame: &CanFrame,
+        timestamp: DateTime<Utc>,
+    ) -> Result<Option<ReassembledMessage>, ReassemblyError> {
+        let data = frame.data();
+        if data.len() < 2 {
+            return Err(ReassemblyError::FrameTooShort(frame.pgn()));
+        }
+        let key = SequenceKey::Transport {
+            source: frame.source(),
+            destination: frame.destination(),
+        };
+        let Some(partial) = self.sequences.get_mut(&key) else {
+            return Ok(None);
+        };
+        let sequence_number = data[0] as usize;
+        if sequence_number == 0 || sequence_number > partial.received.len() {
+            let pgn = partial.pgn;
+            let _ = self.sequences.remove(&key);
+            return Err(ReassemblyError::UnexpectedFrame(pgn, sequence_number));
+        }
+        let index = sequence_number - 1;
+        partial.insert(index, index * TP_PACKET_DATA_SIZE, &data[1..], timestamp);
+        Ok(self.take_if_complete(key, frame.source(), timestamp))
+    }
+
+    fn take_if_complete(
+        &mut self,
+        key: SequenceKey,
+        source: u8,
+        timestamp: DateTime<Utc>,
+    ) -> Option<ReassembledMessage> {
+        if self.sequences.get(&key)?.is_complete() {
+            self.sequences
+                .remove(&key)
+                .map(|partial| partial.into_message(source, timestamp))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{DateTime, Utc};
+
+    use super::{is_fast_packet_pgn, FrameReassembler, ReassembledMessage, ReassemblyError};
+    use crate::can::frame::CanFrame;
+
+    // Parses a log recorded with `candump -l`, each line being of the form
+    // `(<seconds>.<microseconds>) <interface> <id in hex>#<data in hex>`
+    fn parse_candump_log(log: &str) -> Vec<(DateTime<Utc>, CanFrame)> {
+        log.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let mut parts = line.split_whitespace();
+                let time = parts.next().unwrap().trim_matches(['(', ')']);
+                let (seconds, micros) = time.split_once('.').unwrap();
+                let timestamp = DateTime::from_timestamp(
+                    seconds.parse().unwrap(),
+                    micros.parse::<u32>().unwrap() * 1000,
+                )
+                .unwrap();
+                let (id, data) = parts.nth(1).unwrap().split_once('#').unwrap();
+                let data = (0..data.len())
+                    .step_by(2)
+                    .map(|i| u8::from_str_radix(&data[i..(i + 2)], 16).unwrap())
+                    .collect();
+                let frame = CanFrame::new(u32::from_str_radix(id, 16).unwrap(), data).unwrap();
+                (timestamp, frame)
+            })
+            .collect()
+    }
+
+    fn reassemble(
+        reassembler: &mut FrameReassembler,
+        log: &str,
+    ) -> Vec<Result<Option<ReassembledMessage>, ReassemblyError>> {
+        parse_candump_log(log)
+            .iter()
+            .map(|(timestamp, frame)| reassembler.push_frame(frame, *timestamp))
+            .collect()
+    }
+
+    fn completed(
+        results: Vec<Result<Option<ReassembledMessage>, ReassemblyError>>,
+    ) -> Vec<ReassembledMessage> {
+        results
+            .into_iter()
+            .filter_map(|res| res.ok().flatten())
+            .collect()
+    }
+
+    #[test]
+    fn single_frame_and_fast_packet_test() {
+        // a 14 bytes direction data (130577) message from source 0x23 interleaved with
+        // a water depth (128267) message and with the first frame of another sequence
+        let log = "
+            (1700000000.000000) can0 09FE1123#400E010203040506
+            (1700000000.001000) can0 09FE1123#41070809A0B0C0D0
+            (1700000000.002000) can0 0DF50B02#0086010000BC02FF
+            (1700000000.003000) can0 09FE1124#600EFFFFFFFFFFFF
+            (1700000000.004000) can0 09FE1123#42E0FFFFFFFFFFFF
+        ";
+        let mut reassembler = FrameReassembler::default();
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        let messages = completed(results);
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].metadata().pgn(), 128267);
+        assert_eq!(messages[0].metadata().src(), 2);
+        assert_eq!(messages[0].metadata().priority(), 3);
+        assert_eq!(messages[0].data(), [0, 0x86, 1, 0, 0, 0xBC, 2, 0xFF]);
+
+        assert_eq!(messages[1].metadata().pgn(), 130577);
+        assert_eq!(messages[1].metadata().src(), 0x23);
+        assert_eq!(messages[1].metadata().dst(), 255);
+        assert_eq!(messages[1].metadata().priority(), 2);
+        assert_eq!(
+            messages[1].metadata().timestamp(),
+            DateTime::from_timestamp(1700000000, 4_000_000)
+        );
+        assert_eq!(
+            messages[1].data(),
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 0xA0, 0xB0, 0xC0, 0xD0, 0xE0]
+        );
+    }
+
+    #[test]
+    fn fast_packet_timeout_test() {
+        // the last frame of the first sequence comes too late, the sequence is then sent again
+        let log = "
+            (1700000000.000000) can0 09FE1123#400E010203040506
+            (1700000000.100000) can0 09FE1123#41070809A0B0C0D0
+            (1700000001.000000) can0 09FE1123#42E0FFFFFFFFFFFF
+            (1700000001.100000) can0 09FE1123#600E010203040506
+            (1700000001.200000) can0 09FE1123#61070809A0B0C0D0
+            (1700000001.300000) can0 09FE1123#62E0FFFFFFFFFFFF
+        ";
+        let mut reassembler = FrameReassembler::default();
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        assert!(results[2].as_ref().unwrap().is_none());
+        let messages = completed(results);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(
+            messages[0].metadata().timestamp(),
+            DateTime::from_timestamp(1700000001, 300_000_000)
+        );
+        assert_eq!(messages[0].data().len(), 14);
+
+        // a frame past the announced size
+        let log = "
+            (1700000000.000000) can0 09FE1123#400E010203040506
+            (1700000000.001000) can0 09FE1123#43070809A0B0C0D0
+        ";
+        let results = reassemble(&mut reassembler, log);
+        assert!(matches!(
+            results[1],
+            Err(ReassemblyError::UnexpectedFrame(130577, 3))
+        ));
+    }
+
+    #[test]
+    fn transport_broadcast_test() {
+        // a 19 bytes PGN list (126464) broadcast by source 0x05
+        let log = "
+            (1700000000.000000) can0 1CECFF05#20130003FF00EE01
+            (1700000000.050000) can0 1CEBFF05#0110111213141516
+            (1700000000.100000) can0 1CEBFF05#021718191A1B1C1D
+            (1700000000.150000) can0 1CEBFF05#031E1F202122FFFF
+        ";
+        let mut reassembler = FrameReassembler::default();
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        let messages = completed(results);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].metadata().pgn(), 126464);
+        assert_eq!(messages[0].metadata().src(), 0x05);
+        assert_eq!(messages[0].metadata().dst(), 255);
+        assert_eq!(messages[0].metadata().priority(), 7);
+        assert_eq!(messages[0].data(), (0x10..=0x22).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn transport_connection_test() {
+        // 16 bytes sent by 0x05 to 0x20 two packets at a time, the second packet is
+        // sent twice and the receiver acknowledges the message once complete
+        let log = "
+            (1700000000.000000) can0 1CEC2005#101000030200EE01
+            (1700000000.010000) can0 1CEC0520#110201FFFF00EE01
+            (1700000000.020000) can0 1CEB2005#01A0A1A2A3A4A5A6
+            (1700000000.030000) can0 1CEB2005#02A7A8A9AAABACAD
+            (1700000000.040000) can0 1CEB2005#02A7A8A9AAABACAD
+            (1700000000.050000) can0 1CEC0520#110103FFFF00EE01
+            (1700000000.060000) can0 1CEB2005#03AEAFFFFFFFFFFF
+            (1700000000.070000) can0 1CEC0520#13100003FF00EE01
+        ";
+        let mut reassembler = FrameReassembler::default();
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        assert!(results[6].as_ref().unwrap().is_some());
+        let messages = completed(results);
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].metadata().pgn(), 126464);
+        assert_eq!(messages[0].metadata().src(), 0x05);
+        assert_eq!(messages[0].metadata().dst(), 0x20);
+        assert_eq!(messages[0].data(), (0xA0..=0xAF).collect::<Vec<u8>>());
+
+        // the receiver aborts the connection
+        let log = "
+            (1700000001.000000) can0 1CEC2005#101000030200EE01
+            (1700000001.010000) can0 1CEC0520#FF01FFFFFF00EE01
+            (1700000001.020000) can0 1CEB2005#01A0A1A2A3A4A5A6
+            (1700000001.030000) can0 1CEB2005#02A7A8A9AAABACAD
+            (1700000001.040000) can0 1CEB2005#03AEAFFFFFFFFFFF
+        ";
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        assert!(completed(results).is_empty());
+    }
+
+    #[test]
+    fn recorded_single_frames_test() {
+        // environmental messages recorded from the bus of a boat by the raw-nmea-can sensor,
+        // sent by a weather station (0x16) and a temperature sensor (0x23)
+        let log = "
+            (1738645551.216666) can0 09FD0616#5BFFFFFFFFF603FF
+            (1738645551.217223) can0 09FD0A16#5B0000E07A0F00FF
+            (1738645551.222351) can0 09FD0723#FFC0D474FF7FFFFF
+            (1738645551.233407) can0 09FD0C23#FF0000509004FFFF
+            (1738645551.279997) can0 09FD0623#FFD474FFFFFFFFFF
+            (1738645551.282323) can0 09FD0716#5BFFFFFFFF7FF603
+        ";
+        let frames = parse_candump_log(log);
+        let mut reassembler = FrameReassembler::default();
+        let results = reassemble(&mut reassembler, log);
+        assert!(results.iter().all(|res| res.is_ok()));
+        let messages = completed(results);
+        assert_eq!(messages.len(), frames.len());
+        for (message, (timestamp, frame)) in messages.iter().zip(frames.iter()) {
+            assert_eq!(message.metadata().pgn(), frame.pgn());
+            assert_eq!(message.metadata().src(), frame.source() as u16);
+            assert_eq!(message.metadata().priority(), 2);
+            assert_eq!(message.metadata().timestamp(), Some(*timestamp));
+            assert_eq!(message.data(), frame.data());
+        }
+        assert_eq!(
+            messages
+                .iter()
+                .map(|msg| msg.metadata().pgn())
+                .collect::<Vec<_>>(),
+            [130310, 130314, 130311, 130316, 130310, 130311]
+        );
+    }
+
+    #[test]
+    fn fast_packet_pgn_test() {
+        // single frame messages of the fast-packet range: rudder, engine parameters rapid
+        // update, inverter status, DC voltage and current, leeway angle, set and drift
+        for pgn in [127245, 127488, 127509, 127751, 128000, 129291] {
+            assert!(!is_fast_packet_pgn(pgn), "{}", pgn);
+        }
+        // product information, engine parameters dynamic, AIS class A position report
+        for pgn in [126996, 127489, 129038] {
+            assert!(is_fast_packet_pgn(pgn), "{}", pgn);
+        }
+        // ISO address claim and a proprietary fast-packet PGN
+        assert!(!is_fast_packet_pgn(60928));
+        assert!(is_fast_packet_pgn(130824));
+    }
+
+    #[cfg(generate_nmea_definitions)]
+    #[test]
+    fn reassembled_message_parse() {
+        use crate::gen::messages::{NmeaMessage, NmeaMessageBody};
+
+        let log = "(1700000000.000000) can0 0DF50B02#0086010000BC02FF";
+        let mut reassembler = FrameReassembler::default();
+        let mut messages = completed(reassemble(&mut reassembler, log));
+        assert_eq!(messages.len(), 1);
+        let message = NmeaMessage::try_from(messages.pop().unwrap());
+        assert!(message.is_ok());
+        let message = message.unwrap();
+        assert_eq!(message.metadata.src(), 2);
+        let NmeaMessageBody::Pgn128267Message(depth) = message.data else {
+            panic!("expected a water depth message");
+        };
+        assert_eq!(depth.depth().unwrap(), 3.9);
+    }
+}
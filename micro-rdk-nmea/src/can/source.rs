@@ -0,0 +1,264 @@
+//! A sensor reading NMEA 2000 messages directly from a CAN bus, which serves the latest
+//! message received for each PGN and source in the format expected by the viamboat sensors,
+//! making a separate gateway unnecessary. Messages are keyed by "<PGN in hex>-<source>" and
+//! their value is the raw message (header and data) encoded in base64.
+//!
+//! The bus is configured with the `interface` attribute on Linux (defaults to `can0`) and with
+//! the `tx_pin` and `rx_pin` attributes of the transceiver on ESP32. Messages can be restricted
+//! to a list of PGNs with the `pgns` attribute.
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
+use micro_rdk::{
+    common::{
+        config::ConfigType,
+        registry::Dependency,
+        sensor::{GenericReadingsResult, Readings, Sensor, SensorError, SensorType},
+    },
+    google::protobuf::{value::Kind, Value},
+    DoCommand,
+};
+
+use super::{
+    bus::{CanBus, CanBusError},
+    reassembly::{FrameReassembler, ReassembledMessage},
+};
+
+// how long the reader waits for a frame before checking whether it should stop
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+// the reader only reassembles frames, messages are stored on the heap
+const READER_STACK_SIZE: usize = 8192;
+
+type MessageBuffer = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+#[derive(DoCommand)]
+pub struct RawNmeaSource {
+    messages: MessageBuffer,
+    running: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl RawNmeaSource {
+    /// Starts reading messages from `bus` in a background thread, only keeping the messages
+    /// of `pgns` if provided
+    pub fn new<B>(bus: B, pgns: Option<Vec<u32>>) -> Result<Self, SensorError>
+    where
+        B: CanBus + Send + 'static,
+    {
+        let messages: MessageBuffer = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+        let reader = std::thread::Builder::new()
+            .name("nmea-can-reader".to_string())
+            .stack_size(READER_STACK_SIZE)
+            .spawn({
+                let messages = messages.clone();
+                let running = running.clone();
+                move || read_bus(bus, pgns, messages, running)
+            })
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+        Ok(Self {
+            messages,
+            running,
+            reader: Some(reader),
+        })
+    }
+
+    pub fn from_config(cfg: ConfigType, _: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let pgns = cfg.get_attribute::<Vec<u32>>("pgns").ok();
+        let bus = open_bus(&cfg)?;
+        Ok(Arc::new(Mutex::new(Self::new(bus, pgns)?)))
+    }
+}
+
+#[cfg(all(feature = "native", target_os = "linux"))]
+fn open_bus(cfg: &ConfigType) -> Result<Box<dyn CanBus + Send>, SensorError> {
+    let interface = cfg
+        .get_attribute::<String>("interface")
+        .unwrap_or("can0".to_string());
+    let bus = super::bus::SocketCanBus::open(&interface)
+        .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+    Ok(Box::new(bus))
+}
+
+#[cfg(feature = "esp32")]
+fn open_bus(cfg: &ConfigType) -> Result<Box<dyn CanBus + Send>, SensorError> {
+    let tx_pin = cfg.get_attribute::<i32>("tx_pin")?;
+    let rx_pin = cfg.get_attribute::<i32>("rx_pin")?;
+    let bus = super::bus::TwaiCanBus::new(tx_pin, rx_pin)
+        .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+    Ok(Box::new(bus))
+}
+
+#[cfg(not(any(all(feature = "native", target_os = "linux"), feature = "esp32")))]
+fn open_bus(_: &ConfigType) -> Result<Box<dyn CanBus + Send>, SensorError> {
+    Err(SensorError::ConfigError(
+        "no CAN bus available on this platform",
+    ))
+}
+
+fn message_key(message: &ReassembledMessage) -> String {
+    format!(
+        "{:X}-{}",
+        message.metadata().pgn(),
+        message.metadata().src()
+    )
+}
+
+fn read_bus<B: CanBus>(
+    mut bus: B,
+    pgns: Option<Vec<u32>>,
+    messages: MessageBuffer,
+    running: Arc<AtomicBool>,
+) {
+    let mut reassembler = FrameReassembler::default();
+    while running.load(Ordering::Relaxed) {
+        let frame = match bus.read_frame(READ_TIMEOUT) {
+            Ok(Some(frame)) => frame,
+            Ok(None) => continue,
+            Err(CanBusError::Disconnected) => {
+                log::error!("CAN bus disconnected, stopping reading NMEA messages");
+                break;
+            }
+            Err(err) => {
+                log::error!("failed to read from the CAN bus: {}", err);
+                std::thread::sleep(READ_TIMEOUT);
+                continue;
+            }
+        };
+        match reassembler.push_frame(&frame, Utc::now()) {
+            Ok(Some(message)) => {
+                if pgns
+                    .as_ref()
+                    .is_some_and(|pgns| !pgns.contains(&message.metadata().pgn()))
+                {
+                    continue;
+                }
+                let _ = messages
+                    .lock()
+                    .unwrap()
+                    .insert(message_key(&message), message.to_raw_bytes());
+            }
+            Ok(None) => {}
+            Err(err) => log::debug!("dropping frame {:#x}: {}", frame.id(), err),
+        }
+    }
+}
+
+impl Drop for RawNmeaSource {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+impl Sensor for RawNmeaSource {}
+
+impl Readings for RawNmeaSource {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        Ok(self
+            .messages
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(key, bytes)| {
+                (
+                    key.clone(),
+                    Value {
+                        kind: Some(Kind::StringValue(general_purpose::STANDARD.encode(bytes))),
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use base64::{engine::general_purpose, Engine};
+    use micro_rdk::{
+        common::sensor::{GenericReadingsResult, Readings},
+        google::protobuf::value::Kind,
+    };
+
+    use super::RawNmeaSource;
+    use crate::{
+        can::{
+            bus::{CanBus, LoopbackCanNetwork},
+            frame::CanFrame,
+        },
+        parse_helpers::parsers::NmeaMessageMetadata,
+    };
+
+    fn wait_for_readings(source: &mut RawNmeaSource, count: usize) -> GenericReadingsResult {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        loop {
+            let readings = source.get_generic_readings().unwrap();
+            if readings.len() >= count || Instant::now() > deadline {
+                return readings;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn raw_source_test() {
+        let network = LoopbackCanNetwork::default();
+        let mut source = RawNmeaSource::new(network.connect(), Some(vec![128267, 130577])).unwrap();
+        let mut device = network.connect();
+
+        let frames = [
+            // system time (126992) is filtered out
+            (0x0DF01002, vec![0, 0xF0, 0x4D, 0x4D, 0, 0, 0, 0]),
+            // fast-packet direction data (130577) from source 35
+            (0x09FE1123, vec![0x40, 0x0E, 1, 2, 3, 4, 5, 6]),
+            (0x09FE1123, vec![0x41, 7, 8, 9, 0xA0, 0xB0, 0xC0, 0xD0]),
+            // water depth (128267) from source 2
+            (0x0DF50B02, vec![0, 0x86, 1, 0, 0, 0xBC, 2, 0xFF]),
+            (
+                0x09FE1123,
+                vec![0x42, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF],
+            ),
+        ];
+        for (id, data) in frames {
+            assert!(device
+                .write_frame(&CanFrame::new(id, data).unwrap())
+                .is_ok());
+        }
+
+        let readings = wait_for_readings(&mut source, 2);
+        assert_eq!(readings.len(), 2);
+        let Some(Kind::StringValue(depth)) = &readings["1F50B-2"].kind else {
+            panic!("expected a base64 string");
+        };
+        let bytes = general_purpose::STANDARD.decode(depth).unwrap();
+        assert_eq!(bytes.len(), 40);
+        assert_eq!(bytes[32..], [0, 0x86, 1, 0, 0, 0xBC, 2, 0xFF]);
+        let metadata = NmeaMessageMetadata::try_from(bytes).unwrap();
+        assert_eq!(metadata.pgn(), 128267);
+        assert_eq!(metadata.src(), 2);
+        assert_eq!(metadata.priority(), 3);
+
+        let Some(Kind::StringValue(direction)) = &readings["1FE11-35"].kind else {
+            panic!("expected a base64 string");
+        };
+        let bytes = general_purpose::STANDARD.decode(direction).unwrap();
+        assert_eq!(
+            bytes[32..],
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 0xA0, 0xB0, 0xC0, 0xD0, 0xE0]
+        );
+    }
+}
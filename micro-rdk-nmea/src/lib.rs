@@ -1,13 +1,16 @@
 use micro_rdk::common::registry::{ComponentRegistry, RegistryError};
 
+pub mod can;
 pub mod gen;
 pub mod messages;
+pub mod nmea0183;
 pub mod parse_helpers;
 #[cfg(generate_nmea_definitions)]
 pub mod viamboat;
 
-#[allow(unused_variables)]
 pub fn register_models(registry: &mut ComponentRegistry) -> Result<(), RegistryError> {
+    registry.register_sensor("raw-nmea-can", &can::source::RawNmeaSource::from_config)?;
+    registry.register_movement_sensor("nmea-0183-gps", &nmea0183::gps::Nmea0183Gps::from_config)?;
     #[cfg(generate_nmea_definitions)]
     let res = viamboat::register_models(registry);
     #[cfg(not(generate_nmea_definitions))]
@@ -21,7 +24,7 @@ mod tests {
     use base64::{engine::general_purpose, Engine};
 
     use crate::{
-        gen::messages::{Pgn128267Message, MESSAGE_DATA_OFFSET},
+        gen::messages::{NmeaMessageBody, Pgn128267Message, MESSAGE_DATA_OFFSET},
         messages::message::Message,
         parse_helpers::{errors::NumberFieldError, parsers::DataCursor},
     };
@@ -77,4 +80,36 @@ mod tests {
             matches!(err, NumberFieldError::FieldNotPresent(x) if x.as_str() == "range")
         }));
     }
+
+    #[test]
+    fn message_round_trip() {
+        // water depth (128267) messages, then an actual pressure (130314) message whose
+        // trailing reserved byte is restored by padding to the size of a CAN frame, followed
+        // by temperature (130316) and environmental parameters (130310, 130311) messages
+        // with unavailable fields and lookup values
+        let message_strs = [
+            "C/UBAHg+gD/TL/RmAAAAAFZODAAAAAAACAD/ABMAAwD/1AAAAAAA/w==",
+            "C/UBAHg+gD8l2A2A/////40fszsAAAAACAD/AAIAAwAAhgEAALwC/w==",
+            "Cv0BAHg+gD8voKFnAAAAAIdQAwAAAAAACAD/ABYAAgBbAADgeg8A/w==",
+            "DP0BAHg+gD8voKFnAAAAAL+PAwAAAAAACAD/ACMAAgD/AABQkAT//w==",
+            "Bv0BAHg+gD8voKFnAAAAAFpOAwAAAAAACAD/ABYAAgBb//////YD/w==",
+            "Bv0BAHg+gD8voKFnAAAAAL1FBAAAAAAACAD/ACMAAgD/1HT//////w==",
+            "B/0BAHg+gD8voKFnAAAAANNOBAAAAAAACAD/ABYAAgBb/////3/2Aw==",
+            "B/0BAHg+gD8voKFnAAAAAI9kAwAAAAAACAD/ACMAAgD/wNR0/3///w==",
+        ];
+        for message_str in message_strs {
+            let mut data = Vec::<u8>::new();
+            let res = general_purpose::STANDARD.decode_vec(message_str, &mut data);
+            assert!(res.is_ok());
+            let pgn = u32::from_le_bytes(data[0..4].try_into().unwrap());
+            let body = data.split_off(MESSAGE_DATA_OFFSET);
+            let message = NmeaMessageBody::from_bytes(pgn, body.clone());
+            assert!(message.is_ok());
+            let message = message.unwrap();
+            assert!(!matches!(message, NmeaMessageBody::Unsupported(_)));
+            let bytes = message.to_bytes();
+            assert!(bytes.is_ok());
+            assert_eq!(bytes.unwrap(), body);
+        }
+    }
 }
@@ -11,13 +11,29 @@ use crate::parse_helpers::{
     parsers::{DataCursor, FieldSet},
 };
 
+/// Size of the data of a CAN frame
+pub const SINGLE_FRAME_DATA_SIZE: usize = 8;
+
 pub trait Message: Sized + Clone {
     const PGN: u32;
     fn from_cursor(cursor: DataCursor) -> Result<Self, NmeaParseError>;
+    fn write_to_cursor(&self, cursor: &mut DataCursor) -> Result<(), NmeaParseError>;
     fn to_readings(self) -> Result<GenericReadingsResult, NmeaParseError>;
     fn pgn_id(&self) -> u32 {
         Self::PGN
     }
+
+    /// Serializes the message into the data of an NMEA 2000 message. Messages fitting in
+    /// a single CAN frame are padded with 0xFF up to the 8 bytes of the frame.
+    fn to_bytes(&self) -> Result<Vec<u8>, NmeaParseError> {
+        let mut cursor = DataCursor::new(Vec::new());
+        self.write_to_cursor(&mut cursor)?;
+        let mut data = cursor.into_data();
+        if data.len() < SINGLE_FRAME_DATA_SIZE {
+            data.resize(SINGLE_FRAME_DATA_SIZE, 0xFF);
+        }
+        Ok(data)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -52,6 +68,10 @@ impl UnparsedNmeaMessageBody {
     pub fn pgn_id(&self) -> u32 {
         self.pgn
     }
+
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
 }
 
 pub trait PolymorphicPgnParent<T> {
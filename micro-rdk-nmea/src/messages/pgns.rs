@@ -36,6 +36,16 @@ macro_rules! impl_match {
                 }?;
                 Ok(Self { base, variant })
             }
+            fn write_to_cursor(&self, cursor: &mut $crate::parse_helpers::parsers::DataCursor) -> Result<(), $crate::parse_helpers::errors::NmeaParseError> {
+                self.base.write_to_cursor(cursor)?;
+                match &self.variant {
+                    $(
+                        $variantlabel::$varianttylabel(var) => {
+                            var.write_to_cursor(cursor)
+                        }
+                    ),*
+                }
+            }
             fn to_readings(self) -> Result<GenericReadingsResult, $crate::parse_helpers::errors::NmeaParseError> {
                 let mut base_readings = self.base.to_readings()?;
                 let variant_readings = match &self.variant {
@@ -81,6 +91,16 @@ macro_rules! define_proprietary_pgn {
                 }?;
                 Ok(Self { base, variant })
             }
+            fn write_to_cursor(&self, cursor: &mut $crate::parse_helpers::parsers::DataCursor) -> Result<(), $crate::parse_helpers::errors::NmeaParseError> {
+                self.base.write_to_cursor(cursor)?;
+                match &self.variant {
+                    $(
+                        $variantlabel::$varianttylabel(var) => {
+                            var.write_to_cursor(cursor)
+                        }
+                    ),*
+                }
+            }
             fn to_readings(self) -> Result<GenericReadingsResult, $crate::parse_helpers::errors::NmeaParseError> {
                 let mut base_readings = self.base.to_readings()?;
                 let variant_readings = match &self.variant {
@@ -130,6 +150,13 @@ macro_rules! define_pgns {
                     Self::Unsupported(msg) => msg.to_readings()
                 }
             }
+
+            pub fn to_bytes(&self) -> Result<Vec<u8>, $crate::parse_helpers::errors::NmeaParseError> {
+                match self {
+                    $(Self::$pgndef(msg) => msg.to_bytes()),*,
+                    Self::Unsupported(msg) => Ok(msg.data().to_vec())
+                }
+            }
         }
 
         pub const MESSAGE_DATA_OFFSET: usize = 32;
@@ -0,0 +1,444 @@
+//! A movement sensor for GPS receivers sending NMEA 0183 sentences over a serial link. The
+//! sentences are read in a background thread from any byte stream: the UART of the ESP32
+//! (`uart`, `tx_pin`, `rx_pin` and `baud_rate` attributes, the latter defaulting to 9600)
+//! or a serial device on native platforms (`path` attribute, the port is expected to be
+//! configured beforehand, e.g. with `stty`). Both are read with a timeout so the reader
+//! stops and releases the port when the sensor is dropped.
+//!
+//! The position and fix quality come from GGA sentences, falling back to RMC sentences, the
+//! speed and course over ground from RMC or VTG sentences and the dilution of precision from
+//! GSA sentences. The compass heading is the true heading (HDT) when the receiver provides
+//! one and the course over ground otherwise.
+
+use std::{
+    io::{ErrorKind, Read},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use micro_rdk::{
+    common::{
+        config::ConfigType,
+        math_utils::Vector3,
+        movement_sensor::{
+            GeoPosition, MovementSensor, MovementSensorAccuracy, MovementSensorSupportedMethods,
+            MovementSensorType,
+        },
+        registry::Dependency,
+        sensor::SensorError,
+    },
+    DoCommand, MovementSensorReadings,
+};
+
+use super::sentence::Sentence;
+
+const KNOTS_TO_METERS_PER_SECOND: f64 = 0.514444;
+// sentences are at most 82 characters long, longer lines are garbage
+const MAX_SENTENCE_LENGTH: usize = 128;
+// how long the reader waits when no data is available
+const READ_BACKOFF: Duration = Duration::from_millis(50);
+// the reader only splits lines, sentences are parsed into small structs
+const READER_STACK_SIZE: usize = 8192;
+
+#[derive(Clone, Debug, Default)]
+struct GpsState {
+    position: Option<GeoPosition>,
+    fix_quality: Option<u8>,
+    speed: Option<f64>,
+    course: Option<f64>,
+    heading: Option<f64>,
+    hdop: Option<f32>,
+    vdop: Option<f32>,
+}
+
+impl GpsState {
+    fn update(&mut self, sentence: Sentence) {
+        match sentence {
+            Sentence::Gga(gga) => {
+                self.fix_quality = Some(gga.fix_quality);
+                if gga.fix_quality == 0 {
+                    // the last position is stale once the fix is lost
+                    self.position = None;
+                    return;
+                }
+                if let (Some(lat), Some(lon)) = (gga.latitude, gga.longitude) {
+                    self.position = Some(GeoPosition {
+                        lat,
+                        lon,
+                        alt: gga.altitude.unwrap_or_default(),
+                    });
+                }
+                self.hdop = gga.hdop.or(self.hdop);
+            }
+            Sentence::Rmc(rmc) => {
+                if !rmc.valid {
+                    return;
+                }
+                // GGA sentences also carry the altitude, they are preferred when available
+                if let (false, Some(lat), Some(lon)) = (
+                    self.fix_quality.is_some_and(|quality| quality > 0),
+                    rmc.latitude,
+                    rmc.longitude,
+                ) {
+                    self.position = Some(GeoPosition { lat, lon, alt: 0.0 });
+                }
+                self.speed = rmc
+                    .speed_knots
+                    .map(|knots| knots * KNOTS_TO_METERS_PER_SECOND)
+                    .or(self.speed);
+                self.course = rmc.course.or(self.course);
+            }
+            Sentence::Vtg(vtg) => {
+                if let Some(speed) = vtg
+                    .speed_knots
+                    .map(|knots| knots * KNOTS_TO_METERS_PER_SECOND)
+                    .or(vtg.speed_kmh.map(|kmh| kmh / 3.6))
+                {
+                    self.speed = Some(speed);
+                }
+                self.course = vtg.course_true.or(self.course);
+            }
+            Sentence::Hdt(hdt) => self.heading = hdt.heading,
+            Sentence::Gsa(gsa) => {
+                self.hdop = gsa.hdop;
+                self.vdop = gsa.vdop;
+            }
+        }
+    }
+}
+
+/// Nmea0183Gps reports the data of a GPS receiver sending NMEA 0183 sentences
+#[derive(DoCommand, MovementSensorReadings)]
+pub struct Nmea0183Gps {
+    state: Arc<Mutex<GpsState>>,
+    running: Arc<AtomicBool>,
+    reader: Option<JoinHandle<()>>,
+}
+
+impl Nmea0183Gps {
+    /// Starts reading sentences from `reader` in a background thread, which stops at the end
+    /// of the stream. Reads should time out (or fail with `WouldBlock`) when no data is
+    /// available, otherwise dropping the sensor waits for the next byte.
+    pub fn new<R>(reader: R) -> Result<Self, SensorError>
+    where
+        R: Read + Send + 'static,
+    {
+        let state: Arc<Mutex<GpsState>> = Default::default();
+        let running = Arc::new(AtomicBool::new(true));
+        let reader = std::thread::Builder::new()
+            .name("nmea-0183-reader".to_string())
+            .stack_size(READER_STACK_SIZE)
+            .spawn({
+                let state = state.clone();
+                let running = running.clone();
+                move || read_sentences(reader, state, running)
+            })
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+        Ok(Self {
+            state,
+            running,
+            reader: Some(reader),
+        })
+    }
+
+    pub fn from_config(
+        cfg: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<MovementSensorType, SensorError> {
+        let reader = open_serial(&cfg)?;
+        Ok(Arc::new(Mutex::new(Self::new(reader)?)))
+    }
+
+    fn state(&self) -> GpsState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+#[cfg(feature = "esp32")]
+fn open_serial(cfg: &ConfigType) -> Result<Box<dyn Read + Send>, SensorError> {
+    let uart = cfg.get_attribute::<String>("uart")?;
+    let tx_pin = cfg.get_attribute::<i32>("tx_pin")?;
+    let rx_pin = cfg.get_attribute::<i32>("rx_pin")?;
+    let baud_rate = cfg.get_attribute::<u32>("baud_rate").unwrap_or(9600);
+    Ok(Box::new(uart::UartReader::new(
+        &uart, tx_pin, rx_pin, baud_rate,
+    )?))
+}
+
+#[cfg(not(feature = "esp32"))]
+fn open_serial(cfg: &ConfigType) -> Result<Box<dyn Read + Send>, SensorError> {
+    let path = cfg.get_attribute::<String>("path")?;
+    let mut options = std::fs::OpenOptions::new();
+    options.read(true);
+    // reads return `WouldBlock` instead of waiting for data
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::custom_flags(&mut options, libc::O_NONBLOCK);
+    let file = options
+        .open(&path)
+        .map_err(|err| SensorError::SensorDriverError(format!("{}: {}", path, err)))?;
+    Ok(Box::new(file))
+}
+
+#[cfg(feature = "esp32")]
+mod uart {
+    use std::io::{Error, ErrorKind, Read};
+
+    use micro_rdk::{
+        common::sensor::SensorError,
+        esp32::esp_idf_svc::hal::{
+            delay::TickType,
+            gpio::AnyIOPin,
+            uart::{config::Config, UartDriver, UART1, UART2},
+            units::Hertz,
+        },
+    };
+
+    use super::READ_BACKOFF;
+
+    pub(super) struct UartReader {
+        driver: UartDriver<'static>,
+    }
+
+    impl UartReader {
+        pub(super) fn new(
+            uart: &str,
+            tx_pin: i32,
+            rx_pin: i32,
+            baud_rate: u32,
+        ) -> Result<Self, SensorError> {
+            let tx = unsafe { AnyIOPin::new(tx_pin) };
+            let rx = unsafe { AnyIOPin::new(rx_pin) };
+            let config = Config::new().baudrate(Hertz(baud_rate));
+            let driver = match uart {
+                "uart1" => UartDriver::new(
+                    unsafe { UART1::new() },
+                    tx,
+                    rx,
+                    Option::<AnyIOPin>::None,
+                    Option::<AnyIOPin>::None,
+                    &config,
+                )?,
+                "uart2" => UartDriver::new(
+                    unsafe { UART2::new() },
+                    tx,
+                    rx,
+                    Option::<AnyIOPin>::None,
+                    Option::<AnyIOPin>::None,
+                    &config,
+                )?,
+                _ => return Err(SensorError::ConfigError("only uart1 or uart2 supported")),
+            };
+            Ok(Self { driver })
+        }
+    }
+
+    impl Read for UartReader {
+        // the driver returns no data on timeout, which must not be mistaken for the end of
+        // the stream
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self
+                .driver
+                .read(buf, TickType::from(READ_BACKOFF).ticks())
+                .map_err(Error::other)?
+            {
+                0 => Err(ErrorKind::TimedOut.into()),
+                read => Ok(read),
+            }
+        }
+    }
+}
+
+fn read_sentences<R: Read>(mut reader: R, state: Arc<Mutex<GpsState>>, running: Arc<AtomicBool>) {
+    let mut buffer = [0_u8; 64];
+    let mut line: Vec<u8> = Vec::with_capacity(MAX_SENTENCE_LENGTH);
+    while running.load(Ordering::Relaxed) {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                std::thread::sleep(READ_BACKOFF);
+                continue;
+            }
+            Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+            Err(err) => {
+                log::error!("failed to read NMEA 0183 sentences: {}", err);
+                break;
+            }
+        };
+        for byte in &buffer[..read] {
+            if *byte != b'\n' {
+                if line.len() < MAX_SENTENCE_LENGTH {
+                    line.push(*byte);
+                }
+                continue;
+            }
+            match std::str::from_utf8(&line).map(str::parse::<Sentence>) {
+                Ok(Ok(sentence)) => state.lock().unwrap().update(sentence),
+                Ok(Err(err)) => log::debug!("skipping NMEA 0183 sentence: {}", err),
+                Err(_) => log::debug!("skipping invalid NMEA 0183 sentence"),
+            }
+            line.clear();
+        }
+    }
+}
+
+impl Drop for Nmea0183Gps {
+    fn drop(&mut self) {
+        self.running.store(false, Ordering::Relaxed);
+        if let Some(reader) = self.reader.take() {
+            let _ = reader.join();
+        }
+    }
+}
+
+impl MovementSensor for Nmea0183Gps {
+    fn get_position(&mut self) -> Result<GeoPosition, SensorError> {
+        self.state()
+            .position
+            .ok_or(SensorError::SensorGenericError("no GPS fix available"))
+    }
+    // the velocity is expressed along the course over ground
+    fn get_linear_velocity(&mut self) -> Result<Vector3, SensorError> {
+        let speed = self
+            .state()
+            .speed
+            .ok_or(SensorError::SensorGenericError("no GPS speed available"))?;
+        Ok(Vector3 {
+            x: 0.0,
+            y: speed,
+            z: 0.0,
+        })
+    }
+    fn get_linear_acceleration(&mut self) -> Result<Vector3, SensorError> {
+        Err(SensorError::SensorMethodUnimplemented(
+            "linear acceleration not available",
+        ))
+    }
+    fn get_angular_velocity(&mut self) -> Result<Vector3, SensorError> {
+        Err(SensorError::SensorMethodUnimplemented(
+            "angular velocity not available",
+        ))
+    }
+    fn get_compass_heading(&mut self) -> Result<f64, SensorError> {
+        let state = self.state();
+        state
+            .heading
+            .or(state.course)
+            .ok_or(SensorError::SensorGenericError(
+                "no heading or course over ground available",
+            ))
+    }
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        let state = self.state();
+        Ok(MovementSensorAccuracy {
+            position_hdop: state.hdop,
+            position_vdop: state.vdop,
+            position_nmea_gga_fix: state.fix_quality.map(i32::from),
+            ..Default::default()
+        })
+    }
+    fn get_properties(&self) -> MovementSensorSupportedMethods {
+        MovementSensorSupportedMethods {
+            position_supported: true,
+            linear_velocity_supported: true,
+            angular_velocity_supported: false,
+            linear_acceleration_supported: false,
+            compass_heading_supported: true,
+            orientation_supported: false,
+            accuracy_supported: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Cursor, ErrorKind, Read},
+        time::{Duration, Instant},
+    };
+
+    use micro_rdk::common::movement_sensor::MovementSensor;
+
+    use super::Nmea0183Gps;
+
+    // the GGA, GSA, RMC and VTG examples found in NMEA 0183 references, preceded by sentences
+    // sent before a fix and a TXT sentence, which isn't supported
+    const RECORDED_LOG: &str = "$GPGGA,123520,,,,,0,00,,,M,,M,,*61\r
+$GNRMC,123520,V,,,,,,,230394,,,N*45\r
+$GPTXT,01,01,02,ANTSTATUS=OK*3B\r
+$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r
+$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39\r
+$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r
+$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48\r
+";
+
+    fn wait_for<F: Fn(&mut Nmea0183Gps) -> bool>(gps: &mut Nmea0183Gps, condition: F) {
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while !condition(gps) && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn gps_from_recorded_log_test() {
+        let mut gps = Nmea0183Gps::new(Cursor::new(RECORDED_LOG.as_bytes().to_vec())).unwrap();
+        // the speed of the RMC sentence is replaced by the one of the last VTG sentence
+        wait_for(&mut gps, |gps| {
+            gps.get_linear_velocity()
+                .is_ok_and(|velocity| velocity.y > 0.0 && velocity.y < 5.0)
+        });
+
+        let position = gps.get_position().unwrap();
+        assert!((position.lat - 48.1173).abs() < 1e-6);
+        assert!((position.lon - 11.516_666_666).abs() < 1e-6);
+        assert_eq!(position.alt, 545.4);
+
+        let velocity = gps.get_linear_velocity().unwrap();
+        assert!((velocity.y - 5.5 * 0.514444).abs() < 1e-9);
+        assert_eq!(gps.get_compass_heading().unwrap(), 54.7);
+
+        let accuracy = gps.get_accuracy().unwrap();
+        assert_eq!(accuracy.position_nmea_gga_fix, Some(1));
+        assert_eq!(accuracy.position_hdop, Some(1.3));
+        assert_eq!(accuracy.position_vdop, Some(2.1));
+
+        let mut gps = Nmea0183Gps::new(Cursor::new(b"$HEHDT,274.07,T*19\r\n".to_vec())).unwrap();
+        wait_for(&mut gps, |gps| gps.get_compass_heading().is_ok());
+        assert_eq!(gps.get_compass_heading().unwrap(), 274.07);
+    }
+
+    // a serial port without data
+    struct SilentPort;
+
+    impl Read for SilentPort {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(ErrorKind::WouldBlock.into())
+        }
+    }
+
+    #[test]
+    fn gps_without_fix_test() {
+        let mut gps = Nmea0183Gps::new(SilentPort).unwrap();
+        assert!(gps.get_position().is_err());
+        assert!(gps.get_linear_velocity().is_err());
+        assert!(gps.get_compass_heading().is_err());
+        // the reader waiting for data stops with the sensor
+        let start = Instant::now();
+        drop(gps);
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        let fix_lost = "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47\r
+$GPGGA,123520,,,,,0,00,,,M,,M,,*61\r
+";
+        let mut gps = Nmea0183Gps::new(Cursor::new(fix_lost.as_bytes().to_vec())).unwrap();
+        wait_for(&mut gps, |gps| {
+            gps.get_accuracy().unwrap().position_nmea_gga_fix == Some(0)
+        });
+        assert_eq!(gps.get_accuracy().unwrap().position_nmea_gga_fix, Some(0));
+        assert!(gps.get_position().is_err());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod gps;
+pub mod sentence;
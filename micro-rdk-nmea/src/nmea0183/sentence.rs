@@ -0,0 +1,356 @@
+//! Parsing of the NMEA 0183 sentences sent by GPS receivers and compasses. A sentence is a line
+//! of the form `$<talker><type>,<field>,...,<field>*<checksum>` where the checksum is the XOR
+//! of the characters between `$` and `*` written as two hexadecimal digits.
+//!
+//! Fields may be empty when the data isn't available (e.g. the position before a fix), which
+//! is represented by `None`.
+
+use std::str::FromStr;
+
+use chrono::{NaiveDate, NaiveTime};
+use thiserror::Error;
+
+#[derive(Debug, Error, PartialEq)]
+pub enum Nmea0183Error {
+    #[error("sentence is malformed")]
+    InvalidFormat,
+    #[error("sentence has no checksum")]
+    MissingChecksum,
+    #[error("checksum mismatch, expected {0:02X} got {1:02X}")]
+    ChecksumMismatch(u8, u8),
+    #[error("unsupported sentence type {0}")]
+    UnsupportedSentence(String),
+    #[error("invalid value for field {0}")]
+    InvalidField(&'static str),
+}
+
+/// Global positioning system fix data
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gga {
+    pub time: Option<NaiveTime>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    /// 0 when there is no fix, 1 for a GPS fix, 2 for a differential GPS fix, etc.
+    pub fix_quality: u8,
+    pub satellites: Option<u8>,
+    pub hdop: Option<f32>,
+    /// Altitude above the mean sea level in meters
+    pub altitude: Option<f32>,
+}
+
+/// Recommended minimum navigation information
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rmc {
+    pub time: Option<NaiveTime>,
+    /// false when the receiver warns that the data is not valid
+    pub valid: bool,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    pub speed_knots: Option<f64>,
+    /// Course over ground in degrees relative to the true north
+    pub course: Option<f64>,
+    pub date: Option<NaiveDate>,
+}
+
+/// Course and speed over ground
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vtg {
+    pub course_true: Option<f64>,
+    pub course_magnetic: Option<f64>,
+    pub speed_knots: Option<f64>,
+    pub speed_kmh: Option<f64>,
+}
+
+/// Heading relative to the true north
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hdt {
+    pub heading: Option<f64>,
+}
+
+/// Dilution of precision and active satellites
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gsa {
+    /// 1 when there is no fix, 2 for a 2D fix, 3 for a 3D fix
+    pub fix_type: u8,
+    pub satellites: Vec<u8>,
+    pub pdop: Option<f32>,
+    pub hdop: Option<f32>,
+    pub vdop: Option<f32>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Sentence {
+    Gga(Gga),
+    Rmc(Rmc),
+    Vtg(Vtg),
+    Hdt(Hdt),
+    Gsa(Gsa),
+}
+
+// Fields missing at the end of a sentence (sent by older versions of the standard) are
+// treated as empty fields
+fn field<'a>(fields: &[&'a str], index: usize) -> &'a str {
+    fields.get(index).copied().unwrap_or_default()
+}
+
+fn parse_optional<T: FromStr>(value: &str, name: &'static str) -> Result<Option<T>, Nmea0183Error> {
+    if value.is_empty() {
+        Ok(None)
+    } else {
+        value
+            .parse()
+            .map(Some)
+            .map_err(|_| Nmea0183Error::InvalidField(name))
+    }
+}
+
+// Coordinates are written as (d)ddmm.mmmm followed by the hemisphere
+fn parse_coordinate(
+    value: &str,
+    hemisphere: &str,
+    degree_digits: usize,
+    name: &'static str,
+) -> Result<Option<f64>, Nmea0183Error> {
+    if value.is_empty() || hemisphere.is_empty() {
+        return Ok(None);
+    }
+    if value.len() < degree_digits + 2 || !value.is_char_boundary(degree_digits) {
+        return Err(Nmea0183Error::InvalidField(name));
+    }
+    let degrees: f64 = value[..degree_digits]
+        .parse()
+        .map_err(|_| Nmea0183Error::InvalidField(name))?;
+    let minutes: f64 = value[degree_digits..]
+        .parse()
+        .map_err(|_| Nmea0183Error::InvalidField(name))?;
+    let coordinate = degrees + minutes / 60.0;
+    match hemisphere {
+        "N" | "E" => Ok(Some(coordinate)),
+        "S" | "W" => Ok(Some(-coordinate)),
+        _ => Err(Nmea0183Error::InvalidField(name)),
+    }
+}
+
+fn parse_time(value: &str) -> Result<Option<NaiveTime>, Nmea0183Error> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveTime::parse_from_str(value, "%H%M%S%.f")
+        .map(Some)
+        .map_err(|_| Nmea0183Error::InvalidField("time"))
+}
+
+fn parse_date(value: &str) -> Result<Option<NaiveDate>, Nmea0183Error> {
+    if value.is_empty() {
+        return Ok(None);
+    }
+    NaiveDate::parse_from_str(value, "%d%m%y")
+        .map(Some)
+        .map_err(|_| Nmea0183Error::InvalidField("date"))
+}
+
+impl Gga {
+    fn parse(fields: &[&str]) -> Result<Self, Nmea0183Error> {
+        Ok(Self {
+            time: parse_time(field(fields, 0))?,
+            latitude: parse_coordinate(field(fields, 1), field(fields, 2), 2, "latitude")?,
+            longitude: parse_coordinate(field(fields, 3), field(fields, 4), 3, "longitude")?,
+            fix_quality: parse_optional(field(fields, 5), "fix quality")?.unwrap_or_default(),
+            satellites: parse_optional(field(fields, 6), "satellites")?,
+            hdop: parse_optional(field(fields, 7), "hdop")?,
+            altitude: parse_optional(field(fields, 8), "altitude")?,
+        })
+    }
+}
+
+impl Rmc {
+    fn parse(fields: &[&str]) -> Result<Self, Nmea0183Error> {
+        Ok(Self {
+            time: parse_time(field(fields, 0))?,
+            valid: field(fields, 1) == "A",
+            latitude: parse_coordinate(field(fields, 2), field(fields, 3), 2, "latitude")?,
+            longitude: parse_coordinate(field(fields, 4), field(fields, 5), 3, "longitude")?,
+            speed_knots: parse_optional(field(fields, 6), "speed")?,
+            course: parse_optional(field(fields, 7), "course")?,
+            date: parse_date(field(fields, 8))?,
+        })
+    }
+}
+
+impl Vtg {
+    fn parse(fields: &[&str]) -> Result<Self, Nmea0183Error> {
+        Ok(Self {
+            course_true: parse_optional(field(fields, 0), "true course")?,
+            course_magnetic: parse_optional(field(fields, 2), "magnetic course")?,
+            speed_knots: parse_optional(field(fields, 4), "speed in knots")?,
+            speed_kmh: parse_optional(field(fields, 6), "speed in km/h")?,
+        })
+    }
+}
+
+impl Hdt {
+    fn parse(fields: &[&str]) -> Result<Self, Nmea0183Error> {
+        Ok(Self {
+            heading: parse_optional(field(fields, 0), "heading")?,
+        })
+    }
+}
+
+impl Gsa {
+    fn parse(fields: &[&str]) -> Result<Self, Nmea0183Error> {
+        let satellites = (2..14)
+            .map(|index| parse_optional(field(fields, index), "satellite"))
+            .collect::<Result<Vec<Option<u8>>, _>>()?
+            .into_iter()
+            .flatten()
+            .collect();
+        Ok(Self {
+            fix_type: parse_optional(field(fields, 1), "fix type")?.unwrap_or(1),
+            satellites,
+            pdop: parse_optional(field(fields, 14), "pdop")?,
+            hdop: parse_optional(field(fields, 15), "hdop")?,
+            vdop: parse_optional(field(fields, 16), "vdop")?,
+        })
+    }
+}
+
+impl FromStr for Sentence {
+    type Err = Nmea0183Error;
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let body = line
+            .trim()
+            .strip_prefix('$')
+            .ok_or(Nmea0183Error::InvalidFormat)?;
+        let (body, checksum) = body.split_once('*').ok_or(Nmea0183Error::MissingChecksum)?;
+        if checksum.len() != 2 {
+            return Err(Nmea0183Error::InvalidFormat);
+        }
+        let expected =
+            u8::from_str_radix(checksum, 16).map_err(|_| Nmea0183Error::InvalidFormat)?;
+        let computed = body.bytes().fold(0, |checksum, byte| checksum ^ byte);
+        if computed != expected {
+            return Err(Nmea0183Error::ChecksumMismatch(expected, computed));
+        }
+
+        let fields: Vec<&str> = body.split(',').collect();
+        // the address is made of the talker (GP for GPS, GN for multiple constellations,
+        // HE for a gyrocompass...) followed by the sentence type
+        let address = fields[0];
+        if address.len() != 5 || !address.is_ascii() {
+            return Err(Nmea0183Error::InvalidFormat);
+        }
+        let fields = &fields[1..];
+        match &address[2..] {
+            "GGA" => Gga::parse(fields).map(Sentence::Gga),
+            "RMC" => Rmc::parse(fields).map(Sentence::Rmc),
+            "VTG" => Vtg::parse(fields).map(Sentence::Vtg),
+            "HDT" => Hdt::parse(fields).map(Sentence::Hdt),
+            "GSA" => Gsa::parse(fields).map(Sentence::Gsa),
+            other => Err(Nmea0183Error::UnsupportedSentence(other.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{NaiveDate, NaiveTime};
+
+    use super::{Nmea0183Error, Sentence};
+
+    #[test]
+    fn parse_gga_test() {
+        let sentence =
+            "$GPGGA,123519,4807.038,N,01131.000,E,1,08,0.9,545.4,M,46.9,M,,*47".parse::<Sentence>();
+        assert!(sentence.is_ok());
+        let Sentence::Gga(gga) = sentence.unwrap() else {
+            panic!("expected a GGA sentence");
+        };
+        assert_eq!(gga.time, NaiveTime::from_hms_opt(12, 35, 19));
+        assert!((gga.latitude.unwrap() - 48.1173).abs() < 1e-6);
+        assert!((gga.longitude.unwrap() - 11.516_666_666).abs() < 1e-6);
+        assert_eq!(gga.fix_quality, 1);
+        assert_eq!(gga.satellites, Some(8));
+        assert_eq!(gga.hdop, Some(0.9));
+        assert_eq!(gga.altitude, Some(545.4));
+
+        // no fix yet
+        let sentence = "$GPGGA,123520,,,,,0,00,,,M,,M,,*61".parse::<Sentence>();
+        let Ok(Sentence::Gga(gga)) = sentence else {
+            panic!("expected a GGA sentence");
+        };
+        assert_eq!(gga.latitude, None);
+        assert_eq!(gga.fix_quality, 0);
+        assert_eq!(gga.hdop, None);
+    }
+
+    #[test]
+    fn parse_rmc_vtg_test() {
+        let sentence = "$GPRMC,123519,A,4807.038,N,01131.000,E,022.4,084.4,230394,003.1,W*6A\r\n"
+            .parse::<Sentence>();
+        let Ok(Sentence::Rmc(rmc)) = sentence else {
+            panic!("expected an RMC sentence");
+        };
+        assert!(rmc.valid);
+        assert_eq!(rmc.speed_knots, Some(22.4));
+        assert_eq!(rmc.course, Some(84.4));
+        assert_eq!(rmc.date, NaiveDate::from_ymd_opt(1994, 3, 23));
+
+        let sentence = "$GNRMC,123520,V,,,,,,,230394,,,N*45".parse::<Sentence>();
+        let Ok(Sentence::Rmc(rmc)) = sentence else {
+            panic!("expected an RMC sentence");
+        };
+        assert!(!rmc.valid);
+        assert_eq!(rmc.latitude, None);
+        assert_eq!(rmc.speed_knots, None);
+
+        let sentence = "$GPVTG,054.7,T,034.4,M,005.5,N,010.2,K*48".parse::<Sentence>();
+        let Ok(Sentence::Vtg(vtg)) = sentence else {
+            panic!("expected a VTG sentence");
+        };
+        assert_eq!(vtg.course_true, Some(54.7));
+        assert_eq!(vtg.course_magnetic, Some(34.4));
+        assert_eq!(vtg.speed_knots, Some(5.5));
+        assert_eq!(vtg.speed_kmh, Some(10.2));
+    }
+
+    #[test]
+    fn parse_hdt_gsa_test() {
+        let sentence = "$HEHDT,274.07,T*19".parse::<Sentence>();
+        assert_eq!(
+            sentence,
+            Ok(Sentence::Hdt(super::Hdt {
+                heading: Some(274.07)
+            }))
+        );
+
+        let sentence = "$GPGSA,A,3,04,05,,09,12,,,24,,,,,2.5,1.3,2.1*39".parse::<Sentence>();
+        let Ok(Sentence::Gsa(gsa)) = sentence else {
+            panic!("expected a GSA sentence");
+        };
+        assert_eq!(gsa.fix_type, 3);
+        assert_eq!(gsa.satellites, vec![4, 5, 9, 12, 24]);
+        assert_eq!(gsa.pdop, Some(2.5));
+        assert_eq!(gsa.hdop, Some(1.3));
+        assert_eq!(gsa.vdop, Some(2.1));
+    }
+
+    #[test]
+    fn invalid_sentence_test() {
+        assert_eq!(
+            "$HEHDT,274.07,T*18".parse::<Sentence>(),
+            Err(Nmea0183Error::ChecksumMismatch(0x18, 0x19))
+        );
+        assert_eq!(
+            "$HEHDT,274.07,T".parse::<Sentence>(),
+            Err(Nmea0183Error::MissingChecksum)
+        );
+        assert_eq!(
+            "HEHDT,274.07,T*19".parse::<Sentence>(),
+            Err(Nmea0183Error::InvalidFormat)
+        );
+        assert_eq!(
+            "$GPTXT,01,01,02,ANTSTATUS=OK*3B".parse::<Sentence>(),
+            Err(Nmea0183Error::UnsupportedSentence("TXT".to_string()))
+        );
+    }
+}
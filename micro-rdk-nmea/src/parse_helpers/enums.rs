@@ -1,25 +1,35 @@
-pub trait NmeaEnumeratedField: Sized + From<u32> + ToString {}
+pub trait NmeaEnumeratedField: Sized + From<u32> + Into<u32> + ToString {}
 
 /// For generating a lookup data type found in an NMEA message. The first argument is the name of the
 /// enum type that will be generated. Each successive argument is a tuple with
 /// (raw number value, name of enum instance, string representation)
 ///
 /// Note: we implement From<u32> rather than TryFrom<u32> because our equivalent library
-/// written in Go does not fail on unrecognized lookups.
+/// written in Go does not fail on unrecognized lookups. The default instance keeps the raw
+/// value it was parsed from, so that converting it back to u32 is lossless.
 #[macro_export]
 macro_rules! define_nmea_enum {
     ( $name:ident, $(($value:expr, $var:ident, $label:expr)),*, $default:ident) => {
         #[derive(Copy, Clone, Debug, PartialEq)]
         pub enum $name {
             $($var),*,
-            $default
+            $default(u32)
         }
 
         impl From<u32> for $name {
             fn from(value: u32) -> Self {
                 match value {
                     $($value => Self::$var),*,
-                    _ => Self::$default
+                    _ => Self::$default(value)
+                }
+            }
+        }
+
+        impl From<$name> for u32 {
+            fn from(value: $name) -> Self {
+                match value {
+                    $($name::$var => $value),*,
+                    $name::$default(raw) => raw
                 }
             }
         }
@@ -28,7 +38,7 @@ macro_rules! define_nmea_enum {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{}", match self {
                     $(Self::$var => $label),*,
-                    Self::$default => "could not parse"
+                    Self::$default(_) => "could not parse"
                 }.to_string())
             }
         }
@@ -34,4 +34,8 @@ pub enum NmeaParseError {
     FromUtf16Error(#[from] FromUtf16Error),
     #[error("unexpected encoding byte {0} encountered when parsing string")]
     UnexpectedEncoding(u8),
+    #[error("value does not fit in a {0} bit field")]
+    FieldTooLarge(usize),
+    #[error("expected {0} field sets, found {1}")]
+    UnexpectedFieldSetCount(usize, usize),
 }
@@ -8,12 +8,17 @@ use super::{
     errors::{NmeaParseError, NumberFieldError},
 };
 
-/// Cursor that consumes can consume bytes from a data vector by bit size.
+/// Cursor that consumes can consume bytes from a data vector by bit size. Bits are
+/// appended to the end of the data vector when writing, so a cursor created from an empty
+/// vector can be used to serialize a message.
 pub struct DataCursor {
     data: Vec<u8>,
     // the amount of bits by which the previously read field overflowed into
     // the first byte
     bit_offset: usize,
+    // the amount of bits of the last byte already used by the previously written
+    // field, 0 when that byte is full
+    write_offset: usize,
 }
 
 impl DataCursor {
@@ -21,33 +26,67 @@ impl DataCursor {
         DataCursor {
             data,
             bit_offset: 0,
+            write_offset: 0,
         }
     }
 
+    /// Reads `bit_size` bits into ceil(bit_size / 8) bytes, the first bit read
+    /// being the least significant bit of the first byte
     pub fn read(&mut self, bit_size: usize) -> Result<Vec<u8>, NmeaParseError> {
         let bits_to_read = bit_size + self.bit_offset;
         if bits_to_read > self.data.len() * 8 {
             return Err(NmeaParseError::NotEnoughData);
         }
-        let mut res = Vec::new();
-        res.extend(self.data.drain(..(bits_to_read / 8)));
+        let mut res = vec![0; bit_size.div_ceil(8)];
+        for bit in 0..bit_size {
+            let src_bit = bit + self.bit_offset;
+            if (self.data[src_bit / 8] >> (src_bit % 8)) & 1 == 1 {
+                res[bit / 8] |= 1 << (bit % 8);
+            }
+        }
 
-        let next_offset = bits_to_read % 8;
-        // if our bit_size overflows to the middle of a byte by x bits, we want to select the last
-        // x bits of the next byte without consuming it
-        if next_offset != 0 {
-            res.push(self.data[0] & (255 >> (8 - next_offset)));
+        // bytes are only consumed once all of their bits have been read
+        let _ = self.data.drain(..(bits_to_read / 8));
+        self.bit_offset = bits_to_read % 8;
+        if self.data.is_empty() {
+            self.write_offset = 0;
         }
+        Ok(res)
+    }
 
-        // if the previous field overflowed into the first byte by x bits, we want
-        // to shift that byte by x
-        if self.bit_offset != 0 {
-            if let Some(first_byte) = res.get_mut(0) {
-                *first_byte >>= self.bit_offset;
+    /// Appends the first `bit_size` bits of `data` (starting from the least significant bit
+    /// of the first byte), the inverse of `read`
+    pub fn write(&mut self, data: &[u8], bit_size: usize) -> Result<(), NmeaParseError> {
+        if bit_size > data.len() * 8 {
+            return Err(NmeaParseError::NotEnoughData);
+        }
+        for bit in 0..bit_size {
+            if self.write_offset == 0 {
+                self.data.push(0);
+            }
+            let value = (data[bit / 8] >> (bit % 8)) & 1;
+            if let Some(last_byte) = self.data.last_mut() {
+                *last_byte |= value << self.write_offset;
             }
+            self.write_offset = (self.write_offset + 1) % 8;
         }
-        self.bit_offset = next_offset;
-        Ok(res)
+        Ok(())
+    }
+
+    /// Appends `bit_size` reserved bits, which NMEA 2000 requires to be set to 1
+    pub fn write_reserved(&mut self, bit_size: usize) -> Result<(), NmeaParseError> {
+        self.write(&vec![u8::MAX; bit_size.div_ceil(8)], bit_size)
+    }
+
+    /// Returns the remaining data, the unused bits of a partially written last byte
+    /// are set to 1
+    pub fn into_data(mut self) -> Vec<u8> {
+        if self.write_offset != 0 {
+            if let Some(last_byte) = self.data.last_mut() {
+                *last_byte |= u8::MAX << self.write_offset;
+            }
+        }
+        self.data
     }
 }
 
@@ -57,6 +96,16 @@ pub trait FieldReader {
     fn read_from_cursor(&self, cursor: &mut DataCursor) -> Result<Self::FieldType, NmeaParseError>;
 }
 
+/// Trait for writing a data type (`FieldType`) to a DataCursor, such that reading it back
+/// with the `FieldReader` implementation yields the same value.
+pub trait FieldWriter: FieldReader {
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError>;
+}
+
 /// A field reader for parsing a basic number type. A reader with bit_size n will read its value
 /// as the first n bits of `size_of::<T>()` bytes with the remaining bits as zeroes (the resulting bytes
 /// will be parsed as Little-Endian). See invocation of the generate_integer_field_readers macro below
@@ -125,6 +174,12 @@ macro_rules! generate_integer_field_readers {
                 }
             }
 
+            impl FieldWriter for NumberField<$t> {
+                fn write_to_cursor(&self, value: &Self::FieldType, cursor: &mut DataCursor) -> Result<(), NmeaParseError> {
+                    cursor.write(&value.to_le_bytes(), self.bit_size)
+                }
+            }
+
             impl FieldReader for NumberFieldWithScale<$t> {
                 type FieldType = f64;
                 fn read_from_cursor(&self, cursor: &mut DataCursor) -> Result<Self::FieldType, NmeaParseError> {
@@ -138,6 +193,13 @@ macro_rules! generate_integer_field_readers {
                     })
                 }
             }
+
+            impl FieldWriter for NumberFieldWithScale<$t> {
+                fn write_to_cursor(&self, value: &Self::FieldType, cursor: &mut DataCursor) -> Result<(), NmeaParseError> {
+                    let raw = (value / self.scale).round() as $t;
+                    self.number_field.write_to_cursor(&raw, cursor)
+                }
+            }
         )*
     };
 }
@@ -156,6 +218,19 @@ impl FieldReader for NumberField<f32> {
     }
 }
 
+impl FieldWriter for NumberField<f32> {
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        if self.bit_size != 32 {
+            return Err(NumberFieldError::SizeNotAllowedforF32.into());
+        }
+        cursor.write(&value.to_le_bytes(), self.bit_size)
+    }
+}
+
 pub struct BinaryCodedDecimalField {
     bit_size: usize,
 }
@@ -170,7 +245,8 @@ impl FieldReader for BinaryCodedDecimalField {
     type FieldType = u128;
 
     fn read_from_cursor(&self, cursor: &mut DataCursor) -> Result<Self::FieldType, NmeaParseError> {
-        let data = cursor.read(self.bit_size)?;
+        let mut data = cursor.read(self.bit_size)?;
+        data.resize(8, 0);
         let mut value = u64::from_le_bytes(data[..].try_into()?) as u128;
         if value == 0 {
             return Ok(0);
@@ -190,6 +266,26 @@ impl FieldReader for BinaryCodedDecimalField {
     }
 }
 
+impl FieldWriter for BinaryCodedDecimalField {
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let mut digits = *value;
+        let mut raw: u64 = 0;
+        let mut mult: u64 = 1;
+
+        while digits > 0 {
+            raw += (digits & 0xF) as u64 * mult;
+            digits >>= 4;
+            mult *= 10;
+        }
+
+        cursor.write(&raw.to_le_bytes(), self.bit_size)
+    }
+}
+
 pub struct FixedSizeStringField {
     bit_size: usize,
 }
@@ -200,18 +296,20 @@ impl FixedSizeStringField {
     }
 }
 
+// Strings are terminated by the first 0x00 or 0xFF byte and may be padded with
+// trailing '@' or ' ' characters
 fn trim_string_bytes(string_data: &mut Vec<u8>) {
-    let last_index = string_data
+    let end = string_data
         .iter()
-        .position(|byte| {
-            if (*byte == 0xFF) || (*byte == 0) {
-                return true;
-            }
-            let char_at = *byte as char;
-            (char_at == '@') || (char_at == ' ')
-        })
+        .position(|byte| (*byte == 0xFF) || (*byte == 0))
         .unwrap_or(string_data.len());
-    let _ = string_data.split_off(last_index);
+    string_data.truncate(end);
+    while string_data
+        .last()
+        .is_some_and(|byte| (*byte == b'@') || (*byte == b' '))
+    {
+        let _ = string_data.pop();
+    }
 }
 
 impl FieldReader for FixedSizeStringField {
@@ -223,18 +321,47 @@ impl FieldReader for FixedSizeStringField {
     }
 }
 
+impl FieldWriter for FixedSizeStringField {
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let mut string_data = value.as_bytes().to_vec();
+        if string_data.len() * 8 > self.bit_size {
+            return Err(NmeaParseError::FieldTooLarge(self.bit_size));
+        }
+        string_data.resize(self.bit_size.div_ceil(8), 0xFF);
+        cursor.write(&string_data, self.bit_size)
+    }
+}
+
 pub struct VariableLengthStringField;
 
 impl FieldReader for VariableLengthStringField {
     type FieldType = String;
     fn read_from_cursor(&self, cursor: &mut DataCursor) -> Result<Self::FieldType, NmeaParseError> {
         let length = cursor.read(8)?[0];
-        let mut string_data = cursor.read((length * 8) as usize)?;
+        let mut string_data = cursor.read(length as usize * 8)?;
         trim_string_bytes(&mut string_data);
         Ok(String::from_utf8(string_data)?)
     }
 }
 
+impl FieldWriter for VariableLengthStringField {
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let string_data = value.as_bytes();
+        let length = u8::try_from(string_data.len())
+            .map_err(|_| NmeaParseError::FieldTooLarge(u8::MAX as usize * 8))?;
+        cursor.write(&[length], 8)?;
+        cursor.write(string_data, string_data.len() * 8)
+    }
+}
+
 pub struct VariableLengthAndEncodingStringField;
 
 impl FieldReader for VariableLengthAndEncodingStringField {
@@ -242,22 +369,43 @@ impl FieldReader for VariableLengthAndEncodingStringField {
     fn read_from_cursor(&self, cursor: &mut DataCursor) -> Result<Self::FieldType, NmeaParseError> {
         let length = cursor.read(8)?[0];
         let encoding = cursor.read(8)?[0];
-        let mut string_data = cursor.read((length * 8) as usize)?;
-        trim_string_bytes(&mut string_data);
+        let mut string_data = cursor.read(length as usize * 8)?;
         Ok(match encoding {
             0 => {
                 let utf16_vec: Vec<u16> = string_data
                     .chunks_exact(2)
-                    .map(|a| u16::from_ne_bytes([a[0], a[1]]))
+                    .map(|a| u16::from_le_bytes([a[0], a[1]]))
                     .collect();
+                // ASCII characters have a null upper byte in UTF-16, so the padding can
+                // only be trimmed once decoded
                 String::from_utf16(utf16_vec.as_slice())?
+                    .trim_end_matches(['\0', '\u{FFFF}', '@', ' '])
+                    .to_string()
+            }
+            1 => {
+                trim_string_bytes(&mut string_data);
+                String::from_utf8(string_data)?
             }
-            1 => String::from_utf8(string_data)?,
             x => return Err(NmeaParseError::UnexpectedEncoding(x)),
         })
     }
 }
 
+impl FieldWriter for VariableLengthAndEncodingStringField {
+    /// Strings are always written with the UTF-8 encoding
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let string_data = value.as_bytes();
+        let length = u8::try_from(string_data.len())
+            .map_err(|_| NmeaParseError::FieldTooLarge(u8::MAX as usize * 8))?;
+        cursor.write(&[length, 1], 16)?;
+        cursor.write(string_data, string_data.len() * 8)
+    }
+}
+
 /// A field reader for parsing data into a field type that implements the `Lookup` trait.
 /// The `bit_size` property is used to parse a raw number value first with a methodology similar to the one
 /// defined in the documentation for NumberField, after which the raw value is passed to `Lookup::from_value`
@@ -292,6 +440,20 @@ where
     }
 }
 
+impl<T> FieldWriter for LookupField<T>
+where
+    T: NmeaEnumeratedField + Copy,
+{
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let number_writer = NumberField::<u32>::new(self.bit_size)?;
+        number_writer.write_to_cursor(&(*value).into(), cursor)
+    }
+}
+
 /// A field reader for parsing an array [T; N] from a byte slice of data, where
 /// T is a number type supported by `NumberField`.
 pub struct ArrayField<T, const N: usize>(PhantomData<NumberField<T>>);
@@ -326,11 +488,30 @@ where
     }
 }
 
+impl<T, const N: usize> FieldWriter for ArrayField<T, N>
+where
+    NumberField<T>: FieldWriter,
+    <NumberField<T> as FieldReader>::FieldType: Default + Copy,
+{
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        let field_writer: NumberField<T> = Default::default();
+        for thing in value.iter() {
+            field_writer.write_to_cursor(thing, cursor)?;
+        }
+        Ok(())
+    }
+}
+
 /// Some NMEA 2000 messages have a set of fields that may be repeated a number of times (usually specified by the value
 /// of another field). This trait is for structs that implement this set of fields, most likely using the `FieldsetDerive` macro.
 pub trait FieldSet: Sized {
     fn to_readings(&self) -> Result<GenericReadingsResult, NmeaParseError>;
     fn from_data(cursor: &mut DataCursor) -> Result<Self, NmeaParseError>;
+    fn write_to_cursor(&self, cursor: &mut DataCursor) -> Result<(), NmeaParseError>;
 }
 
 /// A field reader that parses a vector of structs representing a field set (see `FieldSet`) from a byte slice.
@@ -365,12 +546,35 @@ where
     }
 }
 
+impl<T> FieldWriter for FieldSetList<T>
+where
+    T: FieldSet,
+{
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        if value.len() != self.length {
+            return Err(NmeaParseError::UnexpectedFieldSetCount(
+                self.length,
+                value.len(),
+            ));
+        }
+        for elem in value.iter() {
+            elem.write_to_cursor(cursor)?;
+        }
+        Ok(())
+    }
+}
+
 pub trait PolymorphicDataType: Sized {
     type EnumType: NmeaEnumeratedField + Copy;
     fn from_data(
         cursor: &mut DataCursor,
         enum_type: Self::EnumType,
     ) -> Result<Self, NmeaParseError>;
+    fn write_to_cursor(&self, cursor: &mut DataCursor) -> Result<(), NmeaParseError>;
     fn to_value(self) -> Value;
 }
 
@@ -401,6 +605,19 @@ where
     }
 }
 
+impl<T> FieldWriter for PolymorphicDataTypeReader<T>
+where
+    T: PolymorphicDataType,
+{
+    fn write_to_cursor(
+        &self,
+        value: &Self::FieldType,
+        cursor: &mut DataCursor,
+    ) -> Result<(), NmeaParseError> {
+        value.write_to_cursor(cursor)
+    }
+}
+
 #[macro_export]
 macro_rules! polymorphic_type {
     ($name:ident, $enumname:ident, $(($value:expr, $var:ident, $reader:expr, $field_type:ty)),*, $errorlabel:ident) => {
@@ -413,14 +630,23 @@ macro_rules! polymorphic_type {
         #[derive(Debug, Clone, Copy)]
         pub enum $enumname {
             $($var),*,
-            $errorlabel
+            $errorlabel(u32)
         }
 
         impl From<u32> for $enumname {
             fn from(value: u32) -> Self {
                 match value {
                     $($value => Self::$var),*,
-                    _ => Self::$errorlabel
+                    _ => Self::$errorlabel(value)
+                }
+            }
+        }
+
+        impl From<$enumname> for u32 {
+            fn from(value: $enumname) -> Self {
+                match value {
+                    $($enumname::$var => $value),*,
+                    $enumname::$errorlabel(raw) => raw
                 }
             }
         }
@@ -429,7 +655,7 @@ macro_rules! polymorphic_type {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 write!(f, "{}", match self {
                     $(Self::$var => stringify!($var)),*,
-                    Self::$errorlabel => "could not parse"
+                    Self::$errorlabel(_) => "could not parse"
                 }.to_string())
             }
         }
@@ -446,12 +672,22 @@ macro_rules! polymorphic_type {
                             Ok(Self::$var($reader.read_from_cursor(cursor)?))
                         }
                     ),*,
-                    $enumname::$errorlabel => {
+                    $enumname::$errorlabel(_) => {
                         Err($crate::parse_helpers::errors::NmeaParseError::UnknownPolymorphicLookupValue)
                     }
                 }
             }
 
+            fn write_to_cursor(&self, cursor: &mut $crate::parse_helpers::parsers::DataCursor) -> Result<(), $crate::parse_helpers::errors::NmeaParseError> {
+                match self {
+                    $(
+                        Self::$var(value) => {
+                            $crate::parse_helpers::parsers::FieldWriter::write_to_cursor(&$reader, value, cursor)
+                        }
+                    ),*
+                }
+            }
+
             fn to_value(self) -> micro_rdk::google::protobuf::Value {
                 micro_rdk::google::protobuf::Value { kind: None }
             }
@@ -472,6 +708,22 @@ pub struct NmeaMessageMetadata {
 }
 
 impl NmeaMessageMetadata {
+    pub fn new(
+        pgn: u32,
+        timestamp: Option<DateTime<Utc>>,
+        dst: u16,
+        src: u16,
+        priority: u16,
+    ) -> Self {
+        Self {
+            pgn,
+            timestamp,
+            dst,
+            src,
+            priority,
+        }
+    }
+
     pub fn src(&self) -> u16 {
         self.src
     }
@@ -491,6 +743,25 @@ impl NmeaMessageMetadata {
     pub fn pgn(&self) -> u32 {
         self.pgn
     }
+
+    /// Serializes the metadata into the 32 bytes header preceding the data of an unparsed
+    /// message carrying `data_len` bytes, as read by `TryFrom<Vec<u8>>`
+    pub fn to_header_bytes(&self, data_len: usize) -> Vec<u8> {
+        let (seconds, micros) = self
+            .timestamp
+            .map(|ts| (ts.timestamp() as u64, ts.timestamp_subsec_micros() as u64))
+            .unwrap_or_default();
+        let mut header = Vec::with_capacity(32);
+        header.extend_from_slice(&self.pgn.to_le_bytes());
+        header.extend_from_slice(&[0; 4]);
+        header.extend_from_slice(&seconds.to_le_bytes());
+        header.extend_from_slice(&micros.to_le_bytes());
+        header.extend_from_slice(&(data_len as u16).to_le_bytes());
+        header.extend_from_slice(&self.dst.to_le_bytes());
+        header.extend_from_slice(&self.src.to_le_bytes());
+        header.extend_from_slice(&self.priority.to_le_bytes());
+        header
+    }
 }
 
 impl TryFrom<Vec<u8>> for NmeaMessageMetadata {
@@ -520,19 +791,22 @@ impl TryFrom<Vec<u8>> for NmeaMessageMetadata {
 #[cfg(test)]
 mod tests {
     use base64::{engine::general_purpose, Engine};
+    use chrono::DateTime;
+    use micro_rdk_nmea_macros::FieldsetDerive;
 
     use crate::{
         define_nmea_enum,
         parse_helpers::{
             enums::NmeaEnumeratedField,
             errors::NmeaParseError,
-            parsers::{DataCursor, FieldReader, NmeaMessageMetadata},
+            parsers::{DataCursor, FieldReader, FieldWriter, NmeaMessageMetadata},
         },
     };
 
     use super::{
-        ArrayField, FieldSet, FieldSetList, LookupField, NumberField, NumberFieldWithScale,
-        PolymorphicDataTypeReader, VariableLengthStringField,
+        ArrayField, BinaryCodedDecimalField, FieldSet, FieldSetList, FixedSizeStringField,
+        LookupField, NumberField, NumberFieldWithScale, PolymorphicDataTypeReader,
+        VariableLengthAndEncodingStringField, VariableLengthStringField,
     };
 
     pub const MESSAGE_DATA_OFFSET: usize = 32;
@@ -552,6 +826,21 @@ mod tests {
         assert_eq!(metadata.priority, 3);
         assert_eq!(metadata.dst, 255);
         assert_eq!(metadata.src, 2);
+
+        let header = metadata.to_header_bytes(8);
+        assert_eq!(header.len(), MESSAGE_DATA_OFFSET);
+        assert_eq!(header[24..32], [8, 0, 255, 0, 2, 0, 3, 0]);
+        let written = NmeaMessageMetadata::try_from(header);
+        assert!(written.is_ok());
+        let written = written.unwrap();
+        assert_eq!(written.pgn, metadata.pgn);
+        assert_eq!(written.src, 2);
+
+        let timestamp = DateTime::from_timestamp(1700000000, 250_000);
+        let metadata = NmeaMessageMetadata::new(130577, timestamp, 255, 35, 2);
+        let written = NmeaMessageMetadata::try_from(metadata.to_header_bytes(14));
+        assert!(written.is_ok());
+        assert_eq!(written.unwrap().timestamp, timestamp);
     }
 
     #[test]
@@ -595,6 +884,60 @@ mod tests {
         assert_eq!(res.unwrap(), 2);
     }
 
+    #[test]
+    fn cursor_write_test() {
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(cursor.write(&[0b1101], 4).is_ok());
+        assert!(cursor.write(&0xABC_u16.to_le_bytes(), 12).is_ok());
+        assert!(cursor.write(&[0b010], 3).is_ok());
+        assert!(cursor.write_reserved(5).is_ok());
+        assert!(cursor.write(&[0], 9).is_err());
+        let data = cursor.into_data();
+        // 1101 in the lower bits of the first byte followed by the 12 bits of 0xABC,
+        // then 010 followed by reserved bits set to 1
+        assert_eq!(data, vec![0xCD, 0xAB, 0xFA]);
+
+        let mut cursor = DataCursor::new(data);
+        assert_eq!(cursor.read(4).unwrap(), vec![0b1101]);
+        assert_eq!(cursor.read(12).unwrap(), vec![0xBC, 0x0A]);
+        assert_eq!(cursor.read(3).unwrap(), vec![0b010]);
+        assert_eq!(cursor.read(5).unwrap(), vec![0b11111]);
+        assert!(cursor.read(1).is_err());
+
+        // unused bits of the last byte are set to 1
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(cursor.write(&[0b0101], 4).is_ok());
+        assert_eq!(cursor.into_data(), vec![0xF5]);
+    }
+
+    #[test]
+    fn number_field_write_test() {
+        let mut cursor = DataCursor::new(vec![]);
+        let u8_field = NumberField::<u8>::new(4).unwrap();
+        let u16_field = NumberField::<u16>::new(12).unwrap();
+        let i16_field = NumberField::<i16>::default();
+        let scaled_field = NumberFieldWithScale::<u32>::new(32, 0.01).unwrap();
+        let decimal_field = BinaryCodedDecimalField::new(16);
+        assert!(u8_field.write_to_cursor(&13, &mut cursor).is_ok());
+        assert!(u16_field.write_to_cursor(&2227, &mut cursor).is_ok());
+        assert!(i16_field.write_to_cursor(&-2, &mut cursor).is_ok());
+        assert!(scaled_field.write_to_cursor(&2.12, &mut cursor).is_ok());
+        assert!(decimal_field.write_to_cursor(&0x12345, &mut cursor).is_ok());
+        let data = cursor.into_data();
+        // 13 then 2227 (0x8B3) packed over two bytes, -2, 212 and 12345 (0x3039)
+        assert_eq!(data, vec![0x3D, 0x8B, 0xFE, 0xFF, 212, 0, 0, 0, 0x39, 0x30]);
+
+        let mut cursor = DataCursor::new(data);
+        assert_eq!(u8_field.read_from_cursor(&mut cursor).unwrap(), 13);
+        assert_eq!(u16_field.read_from_cursor(&mut cursor).unwrap(), 2227);
+        assert_eq!(i16_field.read_from_cursor(&mut cursor).unwrap(), -2);
+        assert_eq!(scaled_field.read_from_cursor(&mut cursor).unwrap(), 2.12);
+        assert_eq!(
+            decimal_field.read_from_cursor(&mut cursor).unwrap(),
+            0x12345
+        );
+    }
+
     define_nmea_enum!(TestLookup, (0, A, "A"), (8, B, "B"), UnknownLookupField);
 
     #[test]
@@ -612,6 +955,33 @@ mod tests {
         assert!(matches!(res.unwrap(), TestLookup::B));
     }
 
+    #[test]
+    fn lookup_field_write_test() {
+        let writer = LookupField::<TestLookup>::new(4).unwrap();
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(writer.write_to_cursor(&TestLookup::B, &mut cursor).is_ok());
+        assert!(writer
+            .write_to_cursor(&TestLookup::UnknownLookupField(5), &mut cursor)
+            .is_ok());
+        assert_eq!(cursor.into_data(), vec![0x58]);
+    }
+
+    #[test]
+    fn lookup_field_unknown_round_trip() {
+        let field = LookupField::<TestLookup>::new(4).unwrap();
+        let data = vec![0x3B];
+        let mut cursor = DataCursor::new(data.clone());
+        let low = field.read_from_cursor(&mut cursor).unwrap();
+        let high = field.read_from_cursor(&mut cursor).unwrap();
+        assert!(matches!(low, TestLookup::UnknownLookupField(11)));
+        assert!(matches!(high, TestLookup::UnknownLookupField(3)));
+
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(field.write_to_cursor(&low, &mut cursor).is_ok());
+        assert!(field.write_to_cursor(&high, &mut cursor).is_ok());
+        assert_eq!(cursor.into_data(), data);
+    }
+
     #[test]
     fn array_field_test() {
         let data_vec: Vec<u8> = vec![100, 6, 111, 77, 152, 113, 42, 42];
@@ -660,12 +1030,21 @@ mod tests {
 
             Ok(TestFieldSet { a, b, c })
         }
+
+        fn write_to_cursor(&self, cursor: &mut super::DataCursor) -> Result<(), NmeaParseError> {
+            let u16_writer = NumberField::<u16>::default();
+            let u8_writer = NumberField::<u8>::default();
+
+            u16_writer.write_to_cursor(&self.a, cursor)?;
+            u8_writer.write_to_cursor(&self.b, cursor)?;
+            u16_writer.write_to_cursor(&self.c, cursor)
+        }
     }
 
     #[test]
     fn fieldset_field_test() {
         let data_vec: Vec<u8> = vec![100, 6, 111, 77, 152, 113, 42, 42, 1, 2, 3];
-        let mut cursor = DataCursor::new(data_vec);
+        let mut cursor = DataCursor::new(data_vec.clone());
 
         let reader = FieldSetList::<TestFieldSet>::new(2);
         assert!(cursor.read(8).is_ok());
@@ -686,6 +1065,17 @@ mod tests {
 
         assert_eq!(res[0], expected_at_0);
         assert_eq!(res[1], expected_at_1);
+
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(reader.write_to_cursor(&res, &mut cursor).is_ok());
+        assert_eq!(cursor.into_data(), data_vec[1..]);
+
+        let mut cursor = DataCursor::new(vec![]);
+        let writer = FieldSetList::<TestFieldSet>::new(3);
+        assert!(matches!(
+            writer.write_to_cursor(&res, &mut cursor),
+            Err(NmeaParseError::UnexpectedFieldSetCount(3, 2))
+        ));
     }
 
     define_nmea_enum!(
@@ -753,6 +1143,26 @@ mod tests {
         ))
     }
 
+    #[test]
+    fn polymorphic_field_write_test() {
+        let mut cursor = DataCursor::new(vec![]);
+        for (key, value) in [
+            (TestKey::NumberTypeA, TestSeedPolymorphism::NumberTypeA(2.0)),
+            (
+                TestKey::LookupType,
+                TestSeedPolymorphism::LookupType(TestSeedLookup::B),
+            ),
+            (
+                TestKey::NumberTypeB,
+                TestSeedPolymorphism::NumberTypeB(180.0),
+            ),
+        ] {
+            let writer = PolymorphicDataTypeReader::<TestSeedPolymorphism>::new(key);
+            assert!(writer.write_to_cursor(&value, &mut cursor).is_ok());
+        }
+        assert_eq!(cursor.into_data(), vec![32, 78, 99, 3, 0]);
+    }
+
     #[test]
     fn string_field_test() {
         let test_str_bytes = b"ffreghorsgeuilf@ @  ".to_vec();
@@ -770,4 +1180,150 @@ mod tests {
         let res = res.unwrap();
         assert_eq!(res, result_str);
     }
+
+    #[test]
+    fn string_field_write_test() {
+        let mut cursor = DataCursor::new(vec![]);
+        let fixed = FixedSizeStringField::new(64);
+        assert!(VariableLengthStringField
+            .write_to_cursor(&"boat name".to_string(), &mut cursor)
+            .is_ok());
+        assert!(fixed
+            .write_to_cursor(&"abc".to_string(), &mut cursor)
+            .is_ok());
+        assert!(matches!(
+            fixed.write_to_cursor(&"too long string".to_string(), &mut cursor),
+            Err(NmeaParseError::FieldTooLarge(64))
+        ));
+        assert!(VariableLengthAndEncodingStringField
+            .write_to_cursor(&"hello".to_string(), &mut cursor)
+            .is_ok());
+        let data = cursor.into_data();
+        assert_eq!(data[..10], *b"\x09boat name");
+        assert_eq!(data[10..18], *b"abc\xFF\xFF\xFF\xFF\xFF");
+        assert_eq!(data[18..], *b"\x05\x01hello");
+
+        let mut cursor = DataCursor::new(data);
+        assert_eq!(
+            VariableLengthStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            "boat name"
+        );
+        assert_eq!(fixed.read_from_cursor(&mut cursor).unwrap(), "abc");
+        assert_eq!(
+            VariableLengthAndEncodingStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            "hello"
+        );
+
+        // UTF-16 strings
+        let mut cursor = DataCursor::new(b"\x0c\x00h\x00e\x00l\x00l\x00o\x00@\x00".to_vec());
+        assert_eq!(
+            VariableLengthAndEncodingStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn cursor_read_across_bytes_test() {
+        // a field starting in the middle of a byte takes its upper bits from the
+        // following bytes, every byte read has to be shifted by the offset
+        let mut cursor = DataCursor::new(vec![0x21, 0x43, 0x65]);
+        assert_eq!(cursor.read(4).unwrap(), vec![0x01]);
+        assert_eq!(cursor.read(16).unwrap(), vec![0x32, 0x54]);
+        assert_eq!(cursor.read(4).unwrap(), vec![0x06]);
+        assert!(cursor.read(1).is_err());
+
+        let mut cursor = DataCursor::new(vec![0x21, 0x43, 0x65]);
+        assert!(cursor.read(4).is_ok());
+        let reader = NumberField::<u16>::new(12).unwrap();
+        assert_eq!(reader.read_from_cursor(&mut cursor).unwrap(), 0x432);
+    }
+
+    #[test]
+    fn decimal_field_test() {
+        // fields smaller than 64 bits are padded before being decoded
+        let mut cursor = DataCursor::new(vec![0x39, 0x30, 0x40, 0xE2, 0x01, 0x00]);
+        let reader = BinaryCodedDecimalField::new(16);
+        assert_eq!(reader.read_from_cursor(&mut cursor).unwrap(), 0x12345);
+        let reader = BinaryCodedDecimalField::new(32);
+        assert_eq!(reader.read_from_cursor(&mut cursor).unwrap(), 0x123456);
+    }
+
+    #[test]
+    fn fixed_size_string_field_test() {
+        // spaces and '@' only pad the end of the string
+        let mut cursor = DataCursor::new(b"My Boat@@@  \xFF\xFF".to_vec());
+        let reader = FixedSizeStringField::new(112);
+        assert_eq!(reader.read_from_cursor(&mut cursor).unwrap(), "My Boat");
+
+        let mut cursor = DataCursor::new(b"a@b\x00cd".to_vec());
+        let reader = FixedSizeStringField::new(48);
+        assert_eq!(reader.read_from_cursor(&mut cursor).unwrap(), "a@b");
+    }
+
+    #[test]
+    fn long_string_field_test() {
+        // the length of the string in bits does not fit in a byte
+        let test_str = "a vessel name longer than thirty-one bytes";
+        let mut data = vec![test_str.len() as u8];
+        data.extend(test_str.as_bytes());
+        let mut cursor = DataCursor::new(data.clone());
+        assert_eq!(
+            VariableLengthStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            test_str
+        );
+
+        data.insert(1, 1);
+        let mut cursor = DataCursor::new(data);
+        assert_eq!(
+            VariableLengthAndEncodingStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            test_str
+        );
+    }
+
+    #[test]
+    fn utf16_string_field_test() {
+        // the upper byte of ASCII characters is null and must not terminate the string,
+        // code units are little-endian whatever the host
+        let mut cursor = DataCursor::new(b"\x08\x00h\x00\xE9\x00\x3B\x26@\x00".to_vec());
+        assert_eq!(
+            VariableLengthAndEncodingStringField
+                .read_from_cursor(&mut cursor)
+                .unwrap(),
+            "h\u{E9}\u{263B}"
+        );
+    }
+
+    #[derive(FieldsetDerive, Clone, Debug, PartialEq)]
+    struct TestStringFieldSet {
+        #[bits = 48]
+        callsign: String,
+        name: String,
+    }
+
+    #[test]
+    fn fieldset_fixed_size_string_test() {
+        // a string field with a size is a fixed size string rather than one prefixed
+        // by its length
+        let data = b"AB1234\x04boat".to_vec();
+        let mut cursor = DataCursor::new(data.clone());
+        let res = TestStringFieldSet::from_data(&mut cursor);
+        assert!(res.is_ok());
+        let res = res.unwrap();
+        assert_eq!(res.callsign, "AB1234");
+        assert_eq!(res.name, "boat");
+
+        let mut cursor = DataCursor::new(vec![]);
+        assert!(res.write_to_cursor(&mut cursor).is_ok());
+        assert_eq!(cursor.into_data(), data);
+    }
 }
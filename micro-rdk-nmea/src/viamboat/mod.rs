@@ -15,6 +15,30 @@ pub fn register_models(registry: &mut ComponentRegistry) -> Result<(), RegistryE
         "debug-pgn",
         &sensors::get_raw_sensor_key,
     )?;
+    registry.register_sensor("engine", &sensors::EngineSensor::from_config)?;
+    registry.register_dependency_getter(SensorCompName, "engine", &sensors::get_raw_sensor_key)?;
+    registry.register_sensor("fluid-level", &sensors::FluidLevelSensor::from_config)?;
+    registry.register_dependency_getter(
+        SensorCompName,
+        "fluid-level",
+        &sensors::get_raw_sensor_key,
+    )?;
+    registry.register_sensor("battery", &sensors::BatterySensor::from_config)?;
+    registry.register_dependency_getter(SensorCompName, "battery", &sensors::get_raw_sensor_key)?;
+    registry.register_sensor("environment", &sensors::EnvironmentSensor::from_config)?;
+    registry.register_dependency_getter(
+        SensorCompName,
+        "environment",
+        &sensors::get_raw_sensor_key,
+    )?;
+    registry.register_sensor("wind", &sensors::WindSensor::from_config)?;
+    registry.register_dependency_getter(SensorCompName, "wind", &sensors::get_raw_sensor_key)?;
+    registry.register_sensor("ais-targets", &sensors::AisSensor::from_config)?;
+    registry.register_dependency_getter(
+        SensorCompName,
+        "ais-targets",
+        &sensors::get_raw_sensor_key,
+    )?;
     registry.register_movement_sensor(
         "boat-movement-sensor",
         &sensors::ViamboatMovementSensor::from_config,
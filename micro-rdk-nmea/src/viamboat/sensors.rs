@@ -15,11 +15,14 @@
 //!
 //! The raw sensor is extracted by name provided in the config under the "raw_pgn_sensor" key. Some sensors
 //! also optionally take a list of PGNs and sources by which to filter the data from the raw sensor.
+//! The raw sensor can be a gateway or the `raw-nmea-can` sensor reading the CAN bus directly
+//! (see [crate::can::source]).
 
 use std::{
     collections::HashMap,
     f64::consts::PI,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use crate::{
@@ -30,12 +33,14 @@ use crate::{
     parse_helpers::errors::{NmeaParseError, NumberFieldError},
 };
 use base64::{engine::general_purpose, DecodeError, Engine};
+use chrono::{DateTime, Utc};
 use micro_rdk::{
     common::{
         config::ConfigType,
-        math_utils::Vector3,
+        math_utils::{Orientation, Vector3},
         movement_sensor::{
-            GeoPosition, MovementSensor, MovementSensorSupportedMethods, MovementSensorType,
+            GeoPosition, MovementSensor, MovementSensorAccuracy, MovementSensorSupportedMethods,
+            MovementSensorType,
         },
         registry::{Dependency, ResourceKey},
         robot::Resource,
@@ -225,6 +230,7 @@ const POSITION_PGN: u32 = 129025;
 const COG_SOG_PGN: u32 = 129026;
 const VESSEL_HEADING_PGN: u32 = 127250;
 const ATTITUDE_PGN: u32 = 127257;
+const GNSS_DOPS_PGN: u32 = 129539;
 
 /// ViamboatMovementSensor is a sensor that consolidates information about a boat
 /// from a curated selection of NMEA messages. If no sources are provided, valid messages
@@ -247,6 +253,8 @@ struct MovementSensorData {
     yaw: Option<f64>,
     pitch: Option<f64>,
     roll: Option<f64>,
+    hdop: Option<f64>,
+    vdop: Option<f64>,
 }
 
 impl ViamboatMovementSensor {
@@ -270,6 +278,7 @@ impl ViamboatMovementSensor {
                 COG_SOG_PGN,
                 VESSEL_HEADING_PGN,
                 ATTITUDE_PGN,
+                GNSS_DOPS_PGN,
             ]),
             self.sources.clone(),
         )?;
@@ -350,6 +359,24 @@ impl ViamboatMovementSensor {
                         }
                     };
                 }
+                NmeaMessageBody::Pgn129539Message(data) => {
+                    match data.hdop() {
+                        Ok(hdop) => {
+                            let _ = res.hdop.get_or_insert(hdop);
+                        }
+                        Err(err) => {
+                            log::error!("error acquiring hdop: {:?}", err);
+                        }
+                    };
+                    match data.vdop() {
+                        Ok(vdop) => {
+                            let _ = res.vdop.get_or_insert(vdop);
+                        }
+                        Err(err) => {
+                            log::error!("error acquiring vdop: {:?}", err);
+                        }
+                    };
+                }
                 _ => unreachable!(),
             };
         }
@@ -409,6 +436,40 @@ impl MovementSensor for ViamboatMovementSensor {
             },
         )
     }
+    // NMEA attitude angles are expressed with a z axis pointing down, yaw and pitch are negated to
+    // express the orientation with a z axis pointing up. The vessel heading stands in for a
+    // missing yaw.
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        let data = self
+            .get_data()
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+        let yaw = data.yaw.or(data.heading.map(f64::to_radians));
+        if data.roll.is_none() && data.pitch.is_none() && yaw.is_none() {
+            return Err(SensorError::SensorGenericError(
+                "attitude data not yet available",
+            ));
+        }
+        Ok(Orientation::from_euler_angles(
+            data.roll.unwrap_or_default(),
+            -data.pitch.unwrap_or_default(),
+            -yaw.unwrap_or_default(),
+        ))
+    }
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        let data = self
+            .get_data()
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+        if data.hdop.is_none() && data.vdop.is_none() {
+            return Err(SensorError::SensorGenericError(
+                "GNSS dilution of precision not yet available",
+            ));
+        }
+        Ok(MovementSensorAccuracy {
+            position_hdop: data.hdop.map(|hdop| hdop as f32),
+            position_vdop: data.vdop.map(|vdop| vdop as f32),
+            ..Default::default()
+        })
+    }
     // TODO: When RSDK-10106 is merged, change the implementation to call get_data and determine values
     // based on whether certain key/values are missing
     fn get_properties(&self) -> MovementSensorSupportedMethods {
@@ -418,6 +479,8 @@ impl MovementSensor for ViamboatMovementSensor {
             linear_acceleration_supported: false,
             angular_velocity_supported: false,
             compass_heading_supported: true,
+            orientation_supported: true,
+            accuracy_supported: true,
         }
     }
 }
@@ -546,3 +609,864 @@ impl Readings for DebugPgnSensor {
         ]))
     }
 }
+
+// Inserts the value of a numeric field unless a previous message already provided it, fields
+// not present in the message are skipped
+fn insert_number<T: Into<f64>>(
+    readings: &mut GenericReadingsResult,
+    key: &str,
+    value: Result<T, NumberFieldError>,
+) {
+    match value {
+        Ok(value) => {
+            let _ = readings.entry(key.to_string()).or_insert(Value {
+                kind: Some(Kind::NumberValue(value.into())),
+            });
+        }
+        Err(NumberFieldError::FieldNotPresent(_)) => {}
+        Err(err) => log::error!("error acquiring {}: {:?}", key, err),
+    }
+}
+
+fn insert_string(readings: &mut GenericReadingsResult, key: &str, value: String) {
+    let _ = readings.entry(key.to_string()).or_insert(Value {
+        kind: Some(Kind::StringValue(value)),
+    });
+}
+
+// Builds a reading key from the label of a lookup (e.g. "Sea Temperature" or "Inside"),
+// appending the kind of measurement when the label doesn't already name it
+fn source_key(source: &str, measurement: &str) -> String {
+    let key: String = source
+        .trim()
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect();
+    if key.ends_with(measurement) {
+        key
+    } else {
+        format!("{}_{}", key, measurement)
+    }
+}
+
+const ENGINE_RAPID_UPDATE_PGN: u32 = 127488;
+const ENGINE_DYNAMIC_PGN: u32 = 127489;
+
+/// EngineSensor reports the parameters of an engine, selected by the "instance" attribute
+/// (0, the default, for a single or port engine, 1 for a starboard engine). Temperatures are
+/// in degrees Celsius and pressures in bar.
+#[derive(DoCommand)]
+pub struct EngineSensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+    instance: u32,
+}
+
+impl EngineSensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        let instance = cfg.get_attribute::<u32>("instance").unwrap_or(0);
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+            instance,
+        })))
+    }
+}
+
+impl Sensor for EngineSensor {}
+
+impl Readings for EngineSensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = self
+            .gateway
+            .retrieve_messages(
+                Some(vec![ENGINE_RAPID_UPDATE_PGN, ENGINE_DYNAMIC_PGN]),
+                self.sources.clone(),
+            )
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+
+        let mut readings = HashMap::new();
+        for msg in messages {
+            match msg.data {
+                NmeaMessageBody::Pgn127488Message(data) => {
+                    if u32::from(data.instance()) != self.instance {
+                        continue;
+                    }
+                    insert_number(&mut readings, "rpm", data.speed());
+                    insert_number(&mut readings, "boost_pressure", data.boost_pressure());
+                    insert_number(&mut readings, "tilt_trim", data.tilt_trim());
+                }
+                NmeaMessageBody::Pgn127489Message(data) => {
+                    if u32::from(data.instance()) != self.instance {
+                        continue;
+                    }
+                    insert_number(&mut readings, "oil_pressure", data.oil_pressure());
+                    insert_number(&mut readings, "oil_temperature", data.oil_temperature());
+                    insert_number(&mut readings, "coolant_temperature", data.temperature());
+                    insert_number(
+                        &mut readings,
+                        "alternator_potential",
+                        data.alternator_potential(),
+                    );
+                    insert_number(&mut readings, "fuel_rate", data.fuel_rate());
+                    insert_number(
+                        &mut readings,
+                        "total_engine_hours",
+                        data.total_engine_hours(),
+                    );
+                    insert_number(&mut readings, "coolant_pressure", data.coolant_pressure());
+                    insert_number(&mut readings, "fuel_pressure", data.fuel_pressure());
+                    insert_number(&mut readings, "engine_load", data.engine_load());
+                    insert_number(&mut readings, "engine_torque", data.engine_torque());
+                }
+                _ => unreachable!(),
+            }
+        }
+        Ok(readings)
+    }
+}
+
+const FLUID_LEVEL_PGN: u32 = 127505;
+
+/// FluidLevelSensor reports the level (in percent) and capacity (in liters) of a tank,
+/// selected by the "instance" attribute (0 by default)
+#[derive(DoCommand)]
+pub struct FluidLevelSensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+    instance: u8,
+}
+
+impl FluidLevelSensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        let instance = cfg.get_attribute::<u8>("instance").unwrap_or(0);
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+            instance,
+        })))
+    }
+}
+
+impl Sensor for FluidLevelSensor {}
+
+impl Readings for FluidLevelSensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = self
+            .gateway
+            .retrieve_messages(Some(vec![FLUID_LEVEL_PGN]), self.sources.clone())
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+
+        let mut readings = HashMap::new();
+        for msg in messages {
+            let NmeaMessageBody::Pgn127505Message(data) = msg.data else {
+                unreachable!()
+            };
+            if data.instance().ok() != Some(self.instance) {
+                continue;
+            }
+            insert_string(&mut readings, "type", data.r#type().to_string());
+            insert_number(&mut readings, "level", data.level());
+            insert_number(&mut readings, "capacity", data.capacity());
+        }
+        Ok(readings)
+    }
+}
+
+const BATTERY_STATUS_PGN: u32 = 127508;
+const DC_DETAILED_STATUS_PGN: u32 = 127506;
+
+/// BatterySensor reports the status of a battery, selected by the "instance" attribute (0 by
+/// default). The state of charge and health are in percent and the time remaining in seconds.
+#[derive(DoCommand)]
+pub struct BatterySensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+    instance: u8,
+}
+
+impl BatterySensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        let instance = cfg.get_attribute::<u8>("instance").unwrap_or(0);
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+            instance,
+        })))
+    }
+}
+
+impl Sensor for BatterySensor {}
+
+impl Readings for BatterySensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = self
+            .gateway
+            .retrieve_messages(
+                Some(vec![BATTERY_STATUS_PGN, DC_DETAILED_STATUS_PGN]),
+                self.sources.clone(),
+            )
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+
+        let mut readings = HashMap::new();
+        for msg in messages {
+            match msg.data {
+                NmeaMessageBody::Pgn127508Message(data) => {
+                    if data.instance().ok() != Some(self.instance) {
+                        continue;
+                    }
+                    insert_number(&mut readings, "voltage", data.voltage());
+                    insert_number(&mut readings, "current", data.current());
+                    insert_number(&mut readings, "temperature", data.temperature());
+                }
+                NmeaMessageBody::Pgn127506Message(data) => {
+                    if data.instance().ok() != Some(self.instance) {
+                        continue;
+                    }
+                    insert_string(&mut readings, "dc_type", data.dc_type().to_string());
+                    insert_number(&mut readings, "state_of_charge", data.state_of_charge());
+                    insert_number(&mut readings, "state_of_health", data.state_of_health());
+                    insert_number(&mut readings, "time_remaining", data.time_remaining());
+                    insert_number(&mut readings, "ripple_voltage", data.ripple_voltage());
+                    insert_number(
+                        &mut readings,
+                        "remaining_capacity",
+                        data.remaining_capacity(),
+                    );
+                }
+                _ => unreachable!(),
+            }
+        }
+        Ok(readings)
+    }
+}
+
+const ENVIRONMENTAL_PARAMETERS_PGNS: [u32; 7] =
+    [130310, 130311, 130312, 130313, 130314, 130315, 130316];
+
+/// EnvironmentSensor reports the temperatures (in degrees Celsius), humidities (in percent)
+/// and pressures (in bar) measured on board, keyed by their source (e.g. "sea_temperature",
+/// "inside_humidity" or "atmospheric_pressure")
+#[derive(DoCommand)]
+pub struct EnvironmentSensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+}
+
+impl EnvironmentSensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+        })))
+    }
+}
+
+impl Sensor for EnvironmentSensor {}
+
+impl Readings for EnvironmentSensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = self
+            .gateway
+            .retrieve_messages(
+                Some(ENVIRONMENTAL_PARAMETERS_PGNS.to_vec()),
+                self.sources.clone(),
+            )
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+
+        let mut readings = HashMap::new();
+        for msg in messages {
+            match msg.data {
+                NmeaMessageBody::Pgn130310Message(data) => {
+                    insert_number(&mut readings, "sea_temperature", data.water_temperature());
+                    insert_number(
+                        &mut readings,
+                        "outside_temperature",
+                        data.outside_ambient_air_temperature(),
+                    );
+                    insert_number(
+                        &mut readings,
+                        "atmospheric_pressure",
+                        data.atmospheric_pressure(),
+                    );
+                }
+                NmeaMessageBody::Pgn130311Message(data) => {
+                    insert_number(
+                        &mut readings,
+                        &source_key(&data.temperature_source().to_string(), "temperature"),
+                        data.temperature(),
+                    );
+                    insert_number(
+                        &mut readings,
+                        &source_key(&data.humidity_source().to_string(), "humidity"),
+                        data.humidity(),
+                    );
+                    insert_number(
+                        &mut readings,
+                        "atmospheric_pressure",
+                        data.atmospheric_pressure(),
+                    );
+                }
+                NmeaMessageBody::Pgn130312Message(data) => insert_number(
+                    &mut readings,
+                    &source_key(&data.source().to_string(), "temperature"),
+                    data.actual_temperature(),
+                ),
+                NmeaMessageBody::Pgn130313Message(data) => insert_number(
+                    &mut readings,
+                    &source_key(&data.source().to_string(), "humidity"),
+                    data.actual_humidity(),
+                ),
+                NmeaMessageBody::Pgn130314Message(data) => insert_number(
+                    &mut readings,
+                    &source_key(&data.source().to_string(), "pressure"),
+                    data.pressure(),
+                ),
+                NmeaMessageBody::Pgn130315Message(data) => insert_number(
+                    &mut readings,
+                    &source_key(&format!("{} set", data.source()), "pressure"),
+                    data.pressure(),
+                ),
+                NmeaMessageBody::Pgn130316Message(data) => insert_number(
+                    &mut readings,
+                    &source_key(&data.source().to_string(), "temperature"),
+                    data.temperature(),
+                ),
+                _ => unreachable!(),
+            }
+        }
+        Ok(readings)
+    }
+}
+
+const WIND_DATA_PGN: u32 = 130306;
+
+/// WindSensor reports the wind speed (in knots), angle (in degrees) and reference (apparent
+/// or true, relative to the boat or to the north)
+#[derive(DoCommand)]
+pub struct WindSensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+}
+
+impl WindSensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+        })))
+    }
+}
+
+impl Sensor for WindSensor {}
+
+impl Readings for WindSensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = self
+            .gateway
+            .retrieve_messages(Some(vec![WIND_DATA_PGN]), self.sources.clone())
+            .map_err(|err| SensorError::SensorDriverError(err.to_string()))?;
+
+        let NmeaMessageBody::Pgn130306Message(data) = &messages[0].data else {
+            unreachable!()
+        };
+        let mut readings = HashMap::new();
+        insert_number(&mut readings, "speed", data.wind_speed());
+        insert_number(&mut readings, "angle", data.wind_angle());
+        insert_string(&mut readings, "reference", data.reference().to_string());
+        Ok(readings)
+    }
+}
+
+const AIS_CLASS_A_POSITION_PGN: u32 = 129038;
+const AIS_CLASS_B_POSITION_PGN: u32 = 129039;
+const DEFAULT_AIS_TARGET_TIMEOUT: Duration = Duration::from_secs(600);
+
+struct AisTarget {
+    readings: GenericReadingsResult,
+    // timestamp of the latest report of the target
+    last_seen: DateTime<Utc>,
+}
+
+/// AisSensor reports the list of vessels around the boat from the AIS position reports.
+/// The gateway only holds the latest report of each source, so the targets are accumulated
+/// by the sensor and forgotten when they haven't been reported for "target_timeout_secs"
+/// (10 minutes by default), according to the timestamps of the reports. Speeds are in knots
+/// and angles in degrees.
+#[derive(DoCommand)]
+pub struct AisSensor {
+    gateway: PgnGateway,
+    sources: Option<Vec<u8>>,
+    targets: HashMap<u32, AisTarget>,
+    target_timeout: Duration,
+}
+
+impl AisSensor {
+    pub fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<SensorType, SensorError> {
+        let sources = cfg.get_attribute::<Vec<u8>>("sources").ok();
+        let target_timeout = cfg
+            .get_attribute::<u64>("target_timeout_secs")
+            .map(Duration::from_secs)
+            .unwrap_or(DEFAULT_AIS_TARGET_TIMEOUT);
+        Ok(Arc::new(Mutex::new(Self {
+            gateway: PgnGateway {
+                sensor: get_raw_sensor_dependency(&cfg, deps)?,
+            },
+            sources,
+            targets: HashMap::new(),
+            target_timeout,
+        })))
+    }
+
+    fn update_target(
+        &mut self,
+        mmsi: Result<u32, NumberFieldError>,
+        reported_at: Option<DateTime<Utc>>,
+        readings: GenericReadingsResult,
+    ) {
+        match mmsi {
+            Ok(mmsi) => {
+                // the gateway keeps returning the latest report until a new one is received,
+                // so a report is only news if it is more recent than the one already known
+                let last_seen = reported_at.unwrap_or_else(Utc::now);
+                if self
+                    .targets
+                    .get(&mmsi)
+                    .is_some_and(|target| target.last_seen >= last_seen)
+                {
+                    return;
+                }
+                let _ = self.targets.insert(
+                    mmsi,
+                    AisTarget {
+                        readings,
+                        last_seen,
+                    },
+                );
+            }
+            Err(err) => log::error!("error acquiring AIS target MMSI: {:?}", err),
+        }
+    }
+}
+
+impl Sensor for AisSensor {}
+
+impl Readings for AisSensor {
+    fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+        let messages = match self.gateway.retrieve_messages(
+            Some(vec![AIS_CLASS_A_POSITION_PGN, AIS_CLASS_B_POSITION_PGN]),
+            self.sources.clone(),
+        ) {
+            Ok(messages) => messages,
+            Err(ViamboatSensorError::NoAvailableReadings) => vec![],
+            Err(err) => return Err(SensorError::SensorDriverError(err.to_string())),
+        };
+
+        for msg in messages {
+            let mut readings = HashMap::new();
+            let reported_at = msg.metadata.timestamp();
+            match msg.data {
+                NmeaMessageBody::Pgn129038Message(data) => {
+                    insert_string(&mut readings, "class", "A".to_string());
+                    insert_number(&mut readings, "latitude", data.latitude());
+                    insert_number(&mut readings, "longitude", data.longitude());
+                    insert_number(&mut readings, "sog", data.sog());
+                    insert_number(&mut readings, "cog", data.cog());
+                    insert_number(&mut readings, "heading", data.heading());
+                    insert_string(&mut readings, "nav_status", data.nav_status().to_string());
+                    self.update_target(data.user_id(), reported_at, readings);
+                }
+                NmeaMessageBody::Pgn129039Message(data) => {
+                    insert_string(&mut readings, "class", "B".to_string());
+                    insert_number(&mut readings, "latitude", data.latitude());
+                    insert_number(&mut readings, "longitude", data.longitude());
+                    insert_number(&mut readings, "sog", data.sog());
+                    insert_number(&mut readings, "cog", data.cog());
+                    insert_number(&mut readings, "heading", data.heading());
+                    self.update_target(data.user_id(), reported_at, readings);
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let now = Utc::now();
+        let target_timeout = self.target_timeout;
+        self.targets.retain(|_, target| {
+            !(now - target.last_seen)
+                .to_std()
+                .is_ok_and(|age| age > target_timeout)
+        });
+        let targets = self
+            .targets
+            .iter()
+            .map(|(mmsi, target)| {
+                let mut fields = target.readings.clone();
+                insert_string(&mut fields, "mmsi", mmsi.to_string());
+                Value {
+                    kind: Some(Kind::StructValue(Struct { fields })),
+                }
+            })
+            .collect();
+        Ok(HashMap::from([(
+            "targets".to_string(),
+            Value {
+                kind: Some(Kind::ListValue(ListValue { values: targets })),
+            },
+        )]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use base64::{engine::general_purpose, Engine};
+    use chrono::{DateTime, Utc};
+    use micro_rdk::{
+        common::sensor::{GenericReadingsResult, Readings, Sensor, SensorError, SensorType},
+        google::protobuf::{value::Kind, Value},
+        DoCommand,
+    };
+
+    use super::{
+        AisSensor, BatterySensor, EngineSensor, EnvironmentSensor, FluidLevelSensor, PgnGateway,
+        WindSensor,
+    };
+    use crate::parse_helpers::parsers::NmeaMessageMetadata;
+
+    // Serves raw messages as a gateway would, keyed by "<PGN in hex>-<source>"
+    #[derive(DoCommand)]
+    struct TestGateway {
+        readings: GenericReadingsResult,
+    }
+
+    impl Sensor for TestGateway {}
+
+    impl Readings for TestGateway {
+        fn get_generic_readings(&mut self) -> Result<GenericReadingsResult, SensorError> {
+            Ok(self.readings.clone())
+        }
+    }
+
+    fn gateway_reading(data: Vec<u8>) -> (String, Value) {
+        let metadata = NmeaMessageMetadata::try_from(data[..32].to_vec()).unwrap();
+        (
+            format!("{:X}-{}", metadata.pgn(), metadata.src()),
+            Value {
+                kind: Some(Kind::StringValue(general_purpose::STANDARD.encode(data))),
+            },
+        )
+    }
+
+    // A message recorded from a gateway, encoded in base64
+    fn recorded_message(message: &str) -> (String, Value) {
+        gateway_reading(general_purpose::STANDARD.decode(message).unwrap())
+    }
+
+    fn message(pgn: u32, src: u16, timestamp: DateTime<Utc>, data: &[u8]) -> (String, Value) {
+        let metadata = NmeaMessageMetadata::new(pgn, Some(timestamp), 255, src, 2);
+        let mut bytes = metadata.to_header_bytes(data.len());
+        bytes.extend_from_slice(data);
+        gateway_reading(bytes)
+    }
+
+    fn gateway(messages: Vec<(String, Value)>) -> Arc<Mutex<TestGateway>> {
+        Arc::new(Mutex::new(TestGateway {
+            readings: HashMap::from_iter(messages),
+        }))
+    }
+
+    fn pgn_gateway(gateway: &Arc<Mutex<TestGateway>>) -> PgnGateway {
+        let sensor: SensorType = gateway.clone();
+        PgnGateway { sensor }
+    }
+
+    fn number(readings: &GenericReadingsResult, key: &str) -> f64 {
+        match readings.get(key).and_then(|value| value.kind.as_ref()) {
+            Some(Kind::NumberValue(value)) => *value,
+            other => panic!("expected a number for {}, got {:?}", key, other),
+        }
+    }
+
+    fn string(readings: &GenericReadingsResult, key: &str) -> String {
+        match readings.get(key).and_then(|value| value.kind.as_ref()) {
+            Some(Kind::StringValue(value)) => value.clone(),
+            other => panic!("expected a string for {}, got {:?}", key, other),
+        }
+    }
+
+    fn assert_near(value: f64, expected: f64) {
+        assert!(
+            (value - expected).abs() < 1e-3,
+            "{} is not {}",
+            value,
+            expected
+        );
+    }
+
+    #[test]
+    fn engine_sensor_test() {
+        let now = Utc::now();
+        let gateway = gateway(vec![
+            // rapid update of engine 0: 2400 rpm, unavailable boost pressure, 10% trim
+            message(
+                127488,
+                0x10,
+                now,
+                &[0x00, 0x80, 0x25, 0xFF, 0xFF, 0x0A, 0xFF, 0xFF],
+            ),
+            // rapid update of engine 1, at 1000 rpm
+            message(
+                127488,
+                0x11,
+                now,
+                &[0x01, 0xA0, 0x0F, 0xFF, 0xFF, 0x00, 0xFF, 0xFF],
+            ),
+            // dynamic parameters of engine 0: oil pressure of 400000 Pa, coolant at 353.15 K,
+            // 14.2 V from the alternator and 1234 hours
+            message(
+                127489,
+                0x10,
+                now,
+                &[
+                    0x00, 0xA0, 0x0F, 0xFF, 0xFF, 0xF3, 0x89, 0x8C, 0x05, 0x7D, 0x00, 0x20, 0xC9,
+                    0x43, 0x00, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x00, 0x00, 0x32, 0x28,
+                ],
+            ),
+        ]);
+        let mut sensor = EngineSensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+            instance: 0,
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let readings = readings.unwrap();
+        assert_near(number(&readings, "rpm"), 2400.0);
+        assert_near(number(&readings, "tilt_trim"), 10.0);
+        assert!(!readings.contains_key("boost_pressure"));
+        assert_near(number(&readings, "oil_pressure"), 4.0);
+        assert!(!readings.contains_key("oil_temperature"));
+        assert_near(number(&readings, "coolant_temperature"), 80.0);
+        assert_near(number(&readings, "alternator_potential"), 14.2);
+
+        sensor.instance = 1;
+        let readings = sensor.get_generic_readings().unwrap();
+        assert_near(number(&readings, "rpm"), 1000.0);
+        assert!(!readings.contains_key("oil_pressure"));
+    }
+
+    #[test]
+    fn fluid_level_sensor_test() {
+        let now = Utc::now();
+        let gateway = gateway(vec![
+            // fuel tank 1 at 75% of 200 liters
+            message(
+                127505,
+                0x30,
+                now,
+                &[0x01, 0x3E, 0x49, 0xD0, 0x07, 0x00, 0x00, 0xFF],
+            ),
+            // water tank 0 at 50% of 100 liters
+            message(
+                127505,
+                0x31,
+                now,
+                &[0x10, 0xD4, 0x30, 0xE8, 0x03, 0x00, 0x00, 0xFF],
+            ),
+        ]);
+        let mut sensor = FluidLevelSensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+            instance: 1,
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let readings = readings.unwrap();
+        assert_eq!(string(&readings, "type"), "Fuel");
+        assert_near(number(&readings, "level"), 75.0);
+        assert_near(number(&readings, "capacity"), 200.0);
+
+        sensor.instance = 0;
+        let readings = sensor.get_generic_readings().unwrap();
+        assert_eq!(string(&readings, "type"), "Water");
+        assert_near(number(&readings, "level"), 50.0);
+        assert_near(number(&readings, "capacity"), 100.0);
+    }
+
+    #[test]
+    fn battery_sensor_test() {
+        let now = Utc::now();
+        let gateway = gateway(vec![
+            // battery 0 at 12.8 V, discharging 3.2 A at 298.15 K
+            message(
+                127508,
+                0x40,
+                now,
+                &[0x00, 0x00, 0x05, 0xE0, 0xFF, 0x77, 0x74, 0xFF],
+            ),
+            // battery 0 charged at 85% with a health of 97%
+            message(
+                127506,
+                0x40,
+                now,
+                &[
+                    0xFF, 0x00, 0x00, 0x55, 0x61, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+                ],
+            ),
+            // battery 1 at 25.6 V
+            message(
+                127508,
+                0x41,
+                now,
+                &[0x01, 0x00, 0x0A, 0x00, 0x00, 0xFF, 0xFF, 0xFF],
+            ),
+        ]);
+        let mut sensor = BatterySensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+            instance: 0,
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let readings = readings.unwrap();
+        assert_near(number(&readings, "voltage"), 12.8);
+        assert_near(number(&readings, "current"), -3.2);
+        assert_near(number(&readings, "temperature"), 25.0);
+        assert_eq!(string(&readings, "dc_type"), "Battery");
+        assert_near(number(&readings, "state_of_charge"), 85.0);
+        assert_near(number(&readings, "state_of_health"), 97.0);
+        assert!(!readings.contains_key("time_remaining"));
+
+        sensor.instance = 1;
+        let readings = sensor.get_generic_readings().unwrap();
+        assert_near(number(&readings, "voltage"), 25.6);
+        assert!(!readings.contains_key("state_of_charge"));
+    }
+
+    #[test]
+    fn environment_sensor_test() {
+        // actual pressure (130314) and temperature (130316) messages recorded from a gateway
+        let gateway = gateway(vec![
+            recorded_message("Cv0BAHg+gD8voKFnAAAAAIdQAwAAAAAACAD/ABYAAgBbAADgeg8A/w=="),
+            recorded_message("DP0BAHg+gD8voKFnAAAAAL+PAwAAAAAACAD/ACMAAgD/AABQkAT//w=="),
+        ]);
+        let mut sensor = EnvironmentSensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let readings = readings.unwrap();
+        assert_eq!(readings.len(), 2);
+        assert_near(number(&readings, "atmospheric_pressure"), 1.014496);
+        assert_near(number(&readings, "sea_temperature"), 25.938);
+
+        // environmental parameters (130310) without the water and air temperatures
+        gateway.lock().unwrap().readings = HashMap::from([recorded_message(
+            "Bv0BAHg+gD8voKFnAAAAAFpOAwAAAAAACAD/ABYAAgBb//////YD/w==",
+        )]);
+        let readings = sensor.get_generic_readings().unwrap();
+        assert_eq!(readings.len(), 1);
+        assert_near(number(&readings, "atmospheric_pressure"), 1.014);
+    }
+
+    #[test]
+    fn wind_sensor_test() {
+        // apparent wind of 5.14 m/s at 0.7854 rad
+        let gateway = gateway(vec![message(
+            130306,
+            0x50,
+            Utc::now(),
+            &[0x00, 0x02, 0x02, 0xAE, 0x1E, 0xFA, 0xFF, 0xFF],
+        )]);
+        let mut sensor = WindSensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let readings = readings.unwrap();
+        assert_near(number(&readings, "speed"), 9.991);
+        assert_near(number(&readings, "angle"), 45.0);
+        assert_eq!(string(&readings, "reference"), "Apparent");
+    }
+
+    // class A position report of MMSI 366123456 at 37.7749, -122.4194, heading east at 5 m/s
+    const AIS_CLASS_A_POSITION: [u8; 28] = [
+        0x01, 0xC0, 0x99, 0xD2, 0x15, 0x30, 0x48, 0x08, 0xB7, 0x08, 0xFE, 0x83, 0x16, 0x79, 0x5C,
+        0x3D, 0xF4, 0x01, 0x00, 0x00, 0x00, 0x5C, 0x3D, 0x00, 0x00, 0xC0, 0xFF, 0xFF,
+    ];
+
+    fn targets(readings: GenericReadingsResult) -> Vec<GenericReadingsResult> {
+        match readings.get("targets").and_then(|value| value.kind.clone()) {
+            Some(Kind::ListValue(list)) => list
+                .values
+                .into_iter()
+                .map(|value| match value.kind {
+                    Some(Kind::StructValue(target)) => target.fields,
+                    other => panic!("expected a target, got {:?}", other),
+                })
+                .collect(),
+            other => panic!("expected a list of targets, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn ais_sensor_test() {
+        let reported_at = Utc::now();
+        let gateway = gateway(vec![message(
+            129038,
+            0x60,
+            reported_at,
+            &AIS_CLASS_A_POSITION,
+        )]);
+        let mut sensor = AisSensor {
+            gateway: pgn_gateway(&gateway),
+            sources: None,
+            targets: HashMap::new(),
+            target_timeout: Duration::from_millis(200),
+        };
+        let readings = sensor.get_generic_readings();
+        assert!(readings.is_ok());
+        let targets_read = targets(readings.unwrap());
+        assert_eq!(targets_read.len(), 1);
+        let target = &targets_read[0];
+        assert_eq!(string(target, "mmsi"), "366123456");
+        assert_eq!(string(target, "class"), "A");
+        assert_near(number(target, "latitude"), 37.7749);
+        assert_near(number(target, "longitude"), -122.4194);
+        assert_near(number(target, "sog"), 9.7192);
+        assert_near(number(target, "cog"), 90.0);
+
+        // the gateway keeps returning the same report, which does not keep the target around
+        std::thread::sleep(Duration::from_millis(300));
+        assert!(targets(sensor.get_generic_readings().unwrap()).is_empty());
+        assert!(targets(sensor.get_generic_readings().unwrap()).is_empty());
+
+        // until the target is reported again
+        gateway.lock().unwrap().readings =
+            HashMap::from([message(129038, 0x60, Utc::now(), &AIS_CLASS_A_POSITION)]);
+        assert_eq!(targets(sensor.get_generic_readings().unwrap()).len(), 1);
+    }
+}
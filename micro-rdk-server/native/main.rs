@@ -4,17 +4,23 @@ mod native {
     const ROBOT_SECRET: Option<&str> = option_env!("MICRO_RDK_ROBOT_SECRET");
     const ROBOT_APP_ADDRESS: Option<&str> = option_env!("MICRO_RDK_ROBOT_APP_ADDRESS");
 
-    use std::rc::Rc;
+    // directory used to persist credentials and configuration across restarts, settable with
+    // `--storage-dir <path>` or this environment variable. Storage is in memory otherwise.
+    const STORAGE_DIR_ENV: &str = "MICRO_RDK_STORAGE_DIR";
+    const STORAGE_DIR_FLAG: &str = "--storage-dir";
+
+    use std::{fmt::Debug, rc::Rc};
 
     use micro_rdk::{
         common::{
             conn::{
                 network::{ExternallyManagedNetwork, Network},
                 server::WebRtcConfiguration,
-                viam::ViamServerBuilder,
+                viam::{ViamServerBuilder, ViamServerStorage},
             },
             credentials_storage::{RAMStorage, RobotConfigurationStorage, RobotCredentials},
             exec::Executor,
+            grpc::ServerError,
             log::initialize_logger,
             provisioning::server::ProvisioningInfo,
             registry::ComponentRegistry,
@@ -22,15 +28,46 @@ mod native {
         },
         native::{
             certificate::WebRtcCertificate, conn::mdns::NativeMdns, dtls::NativeDtls,
-            tcp::NativeH2Connector,
+            file_storage::FileStorage, tcp::NativeH2Connector,
         },
     };
 
+    fn storage_dir() -> Option<String> {
+        let mut args = std::env::args().skip(1);
+        while let Some(arg) = args.next() {
+            if arg == STORAGE_DIR_FLAG {
+                return args.next();
+            }
+            if let Some(dir) = arg
+                .strip_prefix(STORAGE_DIR_FLAG)
+                .and_then(|a| a.strip_prefix('='))
+            {
+                return Some(dir.to_owned());
+            }
+        }
+        std::env::var(STORAGE_DIR_ENV).ok()
+    }
+
     pub(crate) fn main_native() {
         initialize_logger::<env_logger::Logger>();
 
         log::info!("micro-rdk-server started (native)");
 
+        match storage_dir() {
+            Some(dir) => {
+                log::info!("persisting storage in {}", dir);
+                run_server(FileStorage::new(&dir).expect("failed to open storage directory"))
+            }
+            None => run_server(RAMStorage::new()),
+        }
+    }
+
+    fn run_server<Storage>(storage: Storage)
+    where
+        Storage: ViamServerStorage,
+        <Storage as RobotConfigurationStorage>::Error: Debug,
+        ServerError: From<<Storage as RobotConfigurationStorage>::Error>,
+    {
         let network = match local_ip_address::local_ip().expect("error parsing local IP") {
             std::net::IpAddr::V4(ip) => ExternallyManagedNetwork::new(ip),
             _ => panic!("oops expected ipv4"),
@@ -38,8 +75,6 @@ mod native {
 
         let registry = Box::<ComponentRegistry>::default();
 
-        let storage = RAMStorage::new();
-
         // At runtime, if the program does not detect credentials or configs in storage,
         // it will try to load statically compiled values.
 
@@ -110,6 +110,8 @@ impl MovementSensor for ADXL345 {
             angular_velocity_supported: false,
             linear_acceleration_supported: true,
             compass_heading_supported: false,
+            orientation_supported: false,
+            accuracy_supported: false,
         }
     }
 
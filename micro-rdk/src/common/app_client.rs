@@ -47,7 +47,10 @@ use super::{
 };
 
 #[cfg(feature = "data")]
-use crate::proto::app::data_sync::v1::DataCaptureUploadRequest;
+use crate::proto::app::data_sync::v1::{
+    streaming_data_capture_upload_request::UploadPacket, DataCaptureUploadMetadata,
+    DataCaptureUploadRequest, StreamingDataCaptureUploadRequest,
+};
 
 pub const VIAM_FOUNDING_YEAR: i32 = 2020;
 
@@ -418,6 +421,48 @@ impl AppClient {
         Ok(())
     }
 
+    /// Uploads binary data too large for a single `DataCaptureUpload` request. The metadata
+    /// packet is sent first, followed by the data split in chunks of at most `chunk_size` bytes.
+    /// Chunks are sliced from `data` and only encoded when the connection is ready to send them
+    #[cfg(feature = "data")]
+    pub async fn upload_data_streaming(
+        &self,
+        metadata: DataCaptureUploadMetadata,
+        data: Bytes,
+        chunk_size: usize,
+    ) -> Result<(), AppClientError> {
+        let metadata = encode_request(StreamingDataCaptureUploadRequest {
+            upload_packet: Some(UploadPacket::Metadata(metadata)),
+        })?;
+        let chunks = (0..data.len()).step_by(chunk_size).map(move |start| {
+            let chunk = data.slice(start..std::cmp::min(start + chunk_size, data.len()));
+            let packet = StreamingDataCaptureUploadRequest {
+                upload_packet: Some(UploadPacket::Data(chunk.to_vec())),
+            };
+            // encoding to a Vec can't fail, unlike encode_request
+            let packet = packet.encode_to_vec();
+            let mut frame = BytesMut::with_capacity(packet.len() + 5);
+            frame.put_u8(0);
+            frame.put_u32(packet.len() as u32);
+            frame.extend_from_slice(&packet);
+            Ok(Frame::data(frame.freeze()))
+        });
+        let body = futures_lite::stream::once(Ok(Frame::data(metadata)))
+            .chain(futures_lite::stream::iter(chunks));
+        let r = self
+            .grpc_client
+            .build_request(
+                "/viam.app.datasync.v1.DataSyncService/StreamingDataCaptureUpload",
+                Some(&self.jwt),
+                "",
+                BodyExt::boxed(StreamBody::new(body)),
+            )
+            .map_err(AppClientError::AppGrpcClientError)?;
+        self.grpc_client.send_request(r).await?;
+
+        Ok(())
+    }
+
     /// Obtains the Duration for which we should wait before next
     /// checking for a restart. If no Duration is returned, then the
     /// app has signaled that we should restart now.
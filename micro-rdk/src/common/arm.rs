@@ -0,0 +1,317 @@
+use super::{
+    actuator::{Actuator, ActuatorError},
+    config::AttributeError,
+    generic::DoCommand,
+    servo::ServoError,
+};
+use crate::proto::common::v1::Pose;
+
+#[cfg(feature = "builtin-components")]
+use super::{
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency},
+};
+
+use async_io::Timer;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "arm";
+
+// how often an arm is checked while waiting for it to stop moving
+static IS_MOVING_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Error)]
+pub enum ArmError {
+    #[error(transparent)]
+    ArmServoError(#[from] ServoError),
+    #[error(transparent)]
+    ArmActuatorError(#[from] ActuatorError),
+    #[error(transparent)]
+    ArmConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    ArmConfigError(&'static str),
+    #[error("expected {0} joint positions, got {1}")]
+    ArmJointCountMismatch(usize, usize),
+    #[error("invalid argument: {0}")]
+    ArmInvalidArgument(&'static str),
+    #[error("unimplemented: {0}")]
+    ArmMethodUnimplemented(&'static str),
+}
+
+pub trait Arm: Actuator + DoCommand {
+    /// Returns the position of each joint in degrees, ordered from the base to the end effector
+    fn get_joint_positions(&mut self) -> Result<Vec<f64>, ArmError>;
+
+    /// Returns an error if the arm cannot be moved to the given joint positions, without
+    /// moving it
+    fn check_joint_positions(&self, positions: &[f64]) -> Result<(), ArmError>;
+
+    /// Moves each joint to the given position in degrees, there must be exactly one
+    /// position per joint. No joint is moved if any of the positions is invalid.
+    /// An arm which cannot tell whether it is still moving returns the estimated time
+    /// for the joints to reach their positions
+    fn move_to_joint_positions(
+        &mut self,
+        positions: Vec<f64>,
+    ) -> Result<Option<Duration>, ArmError>;
+
+    /// Returns the pose of the end effector, this requires a kinematic model of the arm
+    fn get_end_position(&mut self) -> Result<Pose, ArmError> {
+        Err(ArmError::ArmMethodUnimplemented("get_end_position"))
+    }
+
+    /// Moves the end effector to the given pose, this requires a kinematic model of the arm
+    fn move_to_position(&mut self, _pose: Pose) -> Result<(), ArmError> {
+        Err(ArmError::ArmMethodUnimplemented("move_to_position"))
+    }
+}
+
+pub type ArmType = Arc<Mutex<dyn Arm>>;
+
+impl<L> Arm for Mutex<L>
+where
+    L: ?Sized + Arm,
+{
+    fn get_joint_positions(&mut self) -> Result<Vec<f64>, ArmError> {
+        self.get_mut().unwrap().get_joint_positions()
+    }
+    fn check_joint_positions(&self, positions: &[f64]) -> Result<(), ArmError> {
+        self.lock().unwrap().check_joint_positions(positions)
+    }
+    fn move_to_joint_positions(
+        &mut self,
+        positions: Vec<f64>,
+    ) -> Result<Option<Duration>, ArmError> {
+        self.get_mut().unwrap().move_to_joint_positions(positions)
+    }
+    fn get_end_position(&mut self) -> Result<Pose, ArmError> {
+        self.get_mut().unwrap().get_end_position()
+    }
+    fn move_to_position(&mut self, pose: Pose) -> Result<(), ArmError> {
+        self.get_mut().unwrap().move_to_position(pose)
+    }
+}
+
+impl<A> Arm for Arc<Mutex<A>>
+where
+    A: ?Sized + Arm,
+{
+    fn get_joint_positions(&mut self) -> Result<Vec<f64>, ArmError> {
+        self.lock().unwrap().get_joint_positions()
+    }
+    fn check_joint_positions(&self, positions: &[f64]) -> Result<(), ArmError> {
+        self.lock().unwrap().check_joint_positions(positions)
+    }
+    fn move_to_joint_positions(
+        &mut self,
+        positions: Vec<f64>,
+    ) -> Result<Option<Duration>, ArmError> {
+        self.lock().unwrap().move_to_joint_positions(positions)
+    }
+    fn get_end_position(&mut self) -> Result<Pose, ArmError> {
+        self.lock().unwrap().get_end_position()
+    }
+    fn move_to_position(&mut self, pose: Pose) -> Result<(), ArmError> {
+        self.lock().unwrap().move_to_position(pose)
+    }
+}
+
+/// Waits for an arm to reach the joint positions it was last moved to, `travel_time` is
+/// the estimate returned by `Arm::move_to_joint_positions` if any
+pub async fn wait_for_joint_positions(
+    arm: &ArmType,
+    travel_time: Option<Duration>,
+) -> Result<(), ArmError> {
+    match travel_time {
+        Some(travel_time) => {
+            let _ = Timer::after(travel_time).await;
+        }
+        None => {
+            while arm.lock().unwrap().is_moving()? {
+                let _ = Timer::after(IS_MOVING_POLL_INTERVAL).await;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Moves the arm through each set of joint positions in order, waiting for the arm to
+/// reach a set before moving it to the next one. The arm isn't moved at all if any of
+/// the sets is invalid
+pub async fn move_through_joint_positions(
+    arm: ArmType,
+    positions: Vec<Vec<f64>>,
+) -> Result<(), ArmError> {
+    {
+        let arm = arm.lock().unwrap();
+        for p in positions.iter() {
+            arm.check_joint_positions(p)?;
+        }
+    }
+    for p in positions {
+        let travel_time = arm.lock().unwrap().move_to_joint_positions(p)?;
+        wait_for_joint_positions(&arm, travel_time).await?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "builtin-components")]
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_arm("fake", &FakeArm::from_config)
+        .is_err()
+    {
+        log::error!("fake type is already registered");
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+#[derive(DoCommand)]
+pub struct FakeArm {
+    joint_positions: Vec<f64>,
+    end_position: Pose,
+}
+
+#[cfg(feature = "builtin-components")]
+impl FakeArm {
+    pub fn new(num_joints: usize) -> Self {
+        Self {
+            joint_positions: vec![0.0; num_joints],
+            end_position: Pose {
+                o_z: 1.0,
+                ..Default::default()
+            },
+        }
+    }
+    pub(crate) fn from_config(cfg: ConfigType, _: Vec<Dependency>) -> Result<ArmType, ArmError> {
+        let mut arm = FakeArm::default();
+        if let Ok(positions) = cfg.get_attribute::<Vec<f64>>("joint_positions") {
+            arm.joint_positions = positions;
+        }
+        Ok(Arc::new(Mutex::new(arm)))
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Default for FakeArm {
+    fn default() -> Self {
+        Self::new(6)
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Arm for FakeArm {
+    fn get_joint_positions(&mut self) -> Result<Vec<f64>, ArmError> {
+        Ok(self.joint_positions.clone())
+    }
+    fn check_joint_positions(&self, positions: &[f64]) -> Result<(), ArmError> {
+        if positions.len() != self.joint_positions.len() {
+            return Err(ArmError::ArmJointCountMismatch(
+                self.joint_positions.len(),
+                positions.len(),
+            ));
+        }
+        if positions.iter().any(|p| !p.is_finite()) {
+            return Err(ArmError::ArmInvalidArgument(
+                "joint positions must be finite",
+            ));
+        }
+        Ok(())
+    }
+    fn move_to_joint_positions(
+        &mut self,
+        positions: Vec<f64>,
+    ) -> Result<Option<Duration>, ArmError> {
+        self.check_joint_positions(&positions)?;
+        self.joint_positions = positions;
+        Ok(None)
+    }
+    fn get_end_position(&mut self) -> Result<Pose, ArmError> {
+        Ok(self.end_position.clone())
+    }
+    fn move_to_position(&mut self, pose: Pose) -> Result<(), ArmError> {
+        self.end_position = pose;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Actuator for FakeArm {
+    fn is_moving(&mut self) -> Result<bool, ActuatorError> {
+        Ok(false)
+    }
+    fn stop(&mut self) -> Result<(), ActuatorError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::{move_through_joint_positions, Arm, ArmError, ArmType, FakeArm};
+    use crate::proto::common::v1::Pose;
+
+    #[test_log::test]
+    fn test_fake_arm() {
+        let mut arm = FakeArm::new(3);
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![0.0; 3]);
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![1.0, 2.0, 3.0]),
+            Ok(None)
+        ));
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![1.0, 2.0, 3.0]);
+
+        // an invalid set of positions leaves every joint where it was
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![1.0]),
+            Err(ArmError::ArmJointCountMismatch(3, 1))
+        ));
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![4.0, f64::NAN, 6.0]),
+            Err(ArmError::ArmInvalidArgument(_))
+        ));
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![1.0, 2.0, 3.0]);
+
+        let pose = Pose {
+            x: 1.0,
+            y: 2.0,
+            z: 3.0,
+            o_z: 1.0,
+            ..Default::default()
+        };
+        assert!(arm.move_to_position(pose.clone()).is_ok());
+        assert_eq!(arm.get_end_position().unwrap(), pose);
+    }
+
+    #[test_log::test]
+    fn test_move_through_joint_positions() {
+        let arm = Arc::new(Mutex::new(FakeArm::new(2)));
+        let arm_type: ArmType = arm.clone();
+
+        assert!(async_io::block_on(move_through_joint_positions(
+            arm_type.clone(),
+            vec![vec![1.0, 2.0], vec![3.0, 4.0]]
+        ))
+        .is_ok());
+        assert_eq!(
+            arm.lock().unwrap().get_joint_positions().unwrap(),
+            vec![3.0, 4.0]
+        );
+
+        // every set is checked before the arm moves
+        assert!(matches!(
+            async_io::block_on(move_through_joint_positions(
+                arm_type,
+                vec![vec![5.0, 6.0], vec![7.0]]
+            )),
+            Err(ArmError::ArmJointCountMismatch(2, 1))
+        ));
+        assert_eq!(
+            arm.lock().unwrap().get_joint_positions().unwrap(),
+            vec![3.0, 4.0]
+        );
+    }
+}
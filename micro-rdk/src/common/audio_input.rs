@@ -0,0 +1,249 @@
+//! Audio inputs provide PCM samples captured by a microphone, through an I2S peripheral or
+//! an ADC sampling continuously into DMA buffers for example. On the ESP32 the `i2s` model
+//! reads a digital microphone on the I2S0 peripheral.
+//!
+//! Samples are signed 16 bits and interleaved when there is more than one channel. Clients
+//! receive them with the streaming `Chunks` RPC, which reads the samples captured since its
+//! previous read every [CHUNK_INTERVAL].
+
+use super::{config::AttributeError, generic::DoCommand};
+use crate::proto::component::audioinput::v1::{AudioChunk, SampleFormat};
+
+#[cfg(feature = "builtin-components")]
+use super::{
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency},
+};
+#[cfg(feature = "builtin-components")]
+use std::time::Instant;
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "audio_input";
+
+/// Interval at which samples are read while a client streams an audio input
+pub const CHUNK_INTERVAL: Duration = Duration::from_millis(50);
+/// Maximum number of frames (one sample per channel) sent in a single chunk
+const MAX_CHUNK_FRAMES: usize = 1024;
+/// Maximum number of chunks sent each time samples are read
+const MAX_CHUNKS_PER_READ: usize = 4;
+
+#[derive(Debug, Error)]
+pub enum AudioInputError {
+    #[error(transparent)]
+    AudioInputConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    AudioInputConfigError(&'static str),
+    #[error("audio read error: {0}")]
+    AudioInputReadError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AudioProperties {
+    pub channel_count: u32,
+    pub sample_rate: u32,
+    /// Delay between a sample being captured and it being available to `read_samples`
+    pub latency: Duration,
+}
+
+pub trait AudioInput: DoCommand {
+    fn get_properties(&mut self) -> Result<AudioProperties, AudioInputError>;
+
+    /// Copies the samples captured since the previous read into `samples` and returns how
+    /// many were copied, always a whole number of frames. Returns 0 rather than blocking
+    /// when no samples are available.
+    fn read_samples(&mut self, samples: &mut [i16]) -> Result<usize, AudioInputError>;
+}
+
+pub type AudioInputType = Arc<Mutex<dyn AudioInput>>;
+
+impl<L> AudioInput for Mutex<L>
+where
+    L: ?Sized + AudioInput,
+{
+    fn get_properties(&mut self) -> Result<AudioProperties, AudioInputError> {
+        self.get_mut().unwrap().get_properties()
+    }
+    fn read_samples(&mut self, samples: &mut [i16]) -> Result<usize, AudioInputError> {
+        self.get_mut().unwrap().read_samples(samples)
+    }
+}
+
+impl<A> AudioInput for Arc<Mutex<A>>
+where
+    A: ?Sized + AudioInput,
+{
+    fn get_properties(&mut self) -> Result<AudioProperties, AudioInputError> {
+        self.lock().unwrap().get_properties()
+    }
+    fn read_samples(&mut self, samples: &mut [i16]) -> Result<usize, AudioInputError> {
+        self.lock().unwrap().read_samples(samples)
+    }
+}
+
+/// Reads the samples available from `audio` and encodes them in `format`, an unspecified
+/// format is encoded as 16 bits integers
+pub(crate) fn read_chunks(
+    audio: &mut dyn AudioInput,
+    format: SampleFormat,
+) -> Result<Vec<AudioChunk>, AudioInputError> {
+    let channels = audio.get_properties()?.channel_count.max(1) as usize;
+    let mut samples = vec![0_i16; MAX_CHUNK_FRAMES * channels];
+    let mut chunks = vec![];
+    while chunks.len() < MAX_CHUNKS_PER_READ {
+        let read = audio.read_samples(&mut samples)?;
+        if read == 0 {
+            break;
+        }
+        let data = match format {
+            SampleFormat::Float32Interleaved => samples[..read]
+                .iter()
+                .flat_map(|s| (*s as f32 / 32768.0).to_le_bytes())
+                .collect(),
+            _ => samples[..read]
+                .iter()
+                .flat_map(|s| s.to_le_bytes())
+                .collect(),
+        };
+        chunks.push(AudioChunk {
+            data,
+            length: (read / channels) as u32,
+        });
+        if read < samples.len() {
+            break;
+        }
+    }
+    Ok(chunks)
+}
+
+#[cfg(feature = "builtin-components")]
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_audio_input("fake", &FakeAudioInput::from_config)
+        .is_err()
+    {
+        log::error!("fake type is already registered");
+    }
+}
+
+/// Produces a sine wave in real time, the same samples on every channel. At most one second
+/// of samples is kept when they aren't read, like a ring of DMA buffers being overrun.
+#[cfg(feature = "builtin-components")]
+#[derive(DoCommand)]
+pub struct FakeAudioInput {
+    frequency_hz: f64,
+    amplitude: f64,
+    sample_rate: u32,
+    channels: u32,
+    started: Option<Instant>,
+    frames_produced: u64,
+}
+
+#[cfg(feature = "builtin-components")]
+impl FakeAudioInput {
+    pub fn new(frequency_hz: f64, amplitude: f64, sample_rate: u32, channels: u32) -> Self {
+        Self {
+            frequency_hz,
+            amplitude: amplitude.clamp(0.0, 1.0),
+            sample_rate,
+            channels,
+            started: None,
+            frames_produced: 0,
+        }
+    }
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<AudioInputType, AudioInputError> {
+        let frequency_hz = cfg.get_attribute::<f64>("frequency_hz").unwrap_or(440.0);
+        let amplitude = cfg.get_attribute::<f64>("amplitude").unwrap_or(0.5);
+        let sample_rate = cfg.get_attribute::<u32>("sample_rate").unwrap_or(16000);
+        let channels = cfg.get_attribute::<u32>("channels").unwrap_or(1);
+        if sample_rate == 0 || channels == 0 {
+            return Err(AudioInputError::AudioInputConfigError(
+                "sample_rate and channels must be positive",
+            ));
+        }
+        Ok(Arc::new(Mutex::new(FakeAudioInput::new(
+            frequency_hz,
+            amplitude,
+            sample_rate,
+            channels,
+        ))))
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl AudioInput for FakeAudioInput {
+    fn get_properties(&mut self) -> Result<AudioProperties, AudioInputError> {
+        Ok(AudioProperties {
+            channel_count: self.channels,
+            sample_rate: self.sample_rate,
+            latency: Duration::ZERO,
+        })
+    }
+
+    fn read_samples(&mut self, samples: &mut [i16]) -> Result<usize, AudioInputError> {
+        let started = *self.started.get_or_insert_with(Instant::now);
+        let captured = (started.elapsed().as_secs_f64() * self.sample_rate as f64) as u64;
+        let backlog = captured.saturating_sub(self.frames_produced);
+        if backlog > self.sample_rate as u64 {
+            self.frames_produced = captured - self.sample_rate as u64;
+        }
+        let channels = self.channels as usize;
+        let frames = (captured - self.frames_produced).min((samples.len() / channels) as u64);
+        for (i, frame) in samples
+            .chunks_exact_mut(channels)
+            .take(frames as usize)
+            .enumerate()
+        {
+            let t = (self.frames_produced + i as u64) as f64 / self.sample_rate as f64;
+            let value = self.amplitude
+                * (2.0 * std::f64::consts::PI * self.frequency_hz * t).sin()
+                * i16::MAX as f64;
+            frame.fill(value as i16);
+        }
+        self.frames_produced += frames;
+        Ok(frames as usize * channels)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{read_chunks, AudioInput, FakeAudioInput};
+    use crate::proto::component::audioinput::v1::SampleFormat;
+
+    #[test_log::test]
+    fn test_fake_audio_input() {
+        let mut audio = FakeAudioInput::new(4000.0, 0.5, 16000, 2);
+        let mut samples = [0_i16; 64];
+        assert_eq!(audio.read_samples(&mut samples).unwrap(), 0);
+
+        std::thread::sleep(Duration::from_millis(10));
+        let read = audio.read_samples(&mut samples).unwrap();
+        assert_eq!(read, 64);
+        // a quarter of the period per sample, same value on both channels
+        assert_eq!(samples[..8], [0, 0, 16383, 16383, 0, 0, -16383, -16383]);
+
+        std::thread::sleep(Duration::from_millis(10));
+        let chunks = read_chunks(&mut audio, SampleFormat::Int16Interleaved).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].data.len(), chunks[0].length as usize * 2 * 2);
+        assert!(chunks[0].length > 0);
+
+        std::thread::sleep(Duration::from_millis(10));
+        let chunks = read_chunks(&mut audio, SampleFormat::Float32Interleaved).unwrap();
+        assert_eq!(chunks[0].data.len(), chunks[0].length as usize * 2 * 4);
+        assert!(chunks[0]
+            .data
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .all(|v| v.abs() <= 0.5));
+    }
+}
@@ -1,17 +1,44 @@
 #![allow(dead_code)]
 #[cfg(feature = "builtin-components")]
-use {super::actuator::ActuatorError, log::*};
+use log::*;
 
 use super::{config::AttributeError, generic::DoCommand, motor::MotorError};
-use crate::common::actuator::Actuator;
+use crate::common::actuator::{Actuator, ActuatorError};
 use crate::proto::common::v1::Vector3;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use thiserror::Error;
 
 pub static COMPONENT_NAME: &str = "base";
 
 pub trait Base: Actuator + DoCommand {
     fn set_power(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError>;
+
+    /// Moves the base in a straight line for `distance_mm` at `mm_per_sec`, the base moves
+    /// backwards when the signs of `distance_mm` and `mm_per_sec` differ.
+    /// Like `Motor::go_for` this doesn't block, it returns the duration after which the caller
+    /// should stop the base, or `None` if the base isn't moving or stops by itself once the
+    /// distance is covered (`is_moving` then turns false).
+    fn move_straight(
+        &mut self,
+        _distance_mm: i64,
+        _mm_per_sec: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        Err(BaseError::BaseMethodUnimplemented("move_straight"))
+    }
+
+    /// Turns the base in place by `angle_deg` at `degs_per_sec`, positive angles turn the base
+    /// counterclockwise.
+    /// Returns the duration after which the caller should stop the base.
+    fn spin(&mut self, _angle_deg: f64, _degs_per_sec: f64) -> Result<Option<Duration>, BaseError> {
+        Err(BaseError::BaseMethodUnimplemented("spin"))
+    }
+
+    /// Sets the linear velocity (in mm/s, only `y` is used) and the angular velocity (in deg/s,
+    /// only `z` is used) of the base, the base keeps moving until stopped.
+    fn set_velocity(&mut self, _lin: &Vector3, _ang: &Vector3) -> Result<(), BaseError> {
+        Err(BaseError::BaseMethodUnimplemented("set_velocity"))
+    }
 }
 
 pub type BaseType = Arc<Mutex<dyn Base>>;
@@ -22,8 +49,14 @@ pub enum BaseError {
     BaseMotorError(#[from] MotorError),
     #[error(transparent)]
     BaseConfigAttributeError(#[from] AttributeError),
+    #[error(transparent)]
+    BaseActuatorError(#[from] ActuatorError),
     #[error("config error: {0}")]
     BaseConfigError(&'static str),
+    #[error("invalid argument: {0}")]
+    BaseInvalidArgument(&'static str),
+    #[error("unimplemented: {0}")]
+    BaseMethodUnimplemented(&'static str),
 }
 
 // TODO(RSDK-5648) - Store power from set_power call on struct and register as "fake" model
@@ -51,6 +84,21 @@ where
     fn set_power(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
         self.get_mut().unwrap().set_power(lin, ang)
     }
+    fn move_straight(
+        &mut self,
+        distance_mm: i64,
+        mm_per_sec: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        self.get_mut()
+            .unwrap()
+            .move_straight(distance_mm, mm_per_sec)
+    }
+    fn spin(&mut self, angle_deg: f64, degs_per_sec: f64) -> Result<Option<Duration>, BaseError> {
+        self.get_mut().unwrap().spin(angle_deg, degs_per_sec)
+    }
+    fn set_velocity(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
+        self.get_mut().unwrap().set_velocity(lin, ang)
+    }
 }
 
 impl<L> Base for Arc<Mutex<L>>
@@ -60,6 +108,19 @@ where
     fn set_power(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
         self.lock().unwrap().set_power(lin, ang)
     }
+    fn move_straight(
+        &mut self,
+        distance_mm: i64,
+        mm_per_sec: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        self.lock().unwrap().move_straight(distance_mm, mm_per_sec)
+    }
+    fn spin(&mut self, angle_deg: f64, degs_per_sec: f64) -> Result<Option<Duration>, BaseError> {
+        self.lock().unwrap().spin(angle_deg, degs_per_sec)
+    }
+    fn set_velocity(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
+        self.lock().unwrap().set_velocity(lin, ang)
+    }
 }
 
 #[cfg(feature = "builtin-components")]
@@ -71,6 +132,25 @@ impl Base for FakeBase {
         );
         Ok(())
     }
+    fn move_straight(
+        &mut self,
+        distance_mm: i64,
+        mm_per_sec: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        debug!("Moving straight {} mm at {} mm/s", distance_mm, mm_per_sec);
+        Ok(None)
+    }
+    fn spin(&mut self, angle_deg: f64, degs_per_sec: f64) -> Result<Option<Duration>, BaseError> {
+        debug!("Spinning {} deg at {} deg/s", angle_deg, degs_per_sec);
+        Ok(None)
+    }
+    fn set_velocity(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
+        debug!(
+            "Setting velocity following lin vec {:?} and ang {:?}",
+            lin, ang
+        );
+        Ok(())
+    }
 }
 
 #[cfg(feature = "builtin-components")]
@@ -151,6 +151,9 @@ pub struct FakeBoard {
     i2cs: HashMap<String, Arc<Mutex<FakeI2CHandle>>>,
     pin_pwms: HashMap<i32, f64>,
     pin_pwm_freq: HashMap<i32, u64>,
+    pin_levels: HashMap<i32, bool>,
+    // every change of a pin's level counts as an interrupt event on that pin
+    interrupt_counts: HashMap<i32, u32>,
 }
 
 impl FakeBoard {
@@ -165,6 +168,8 @@ impl FakeBoard {
             i2cs,
             pin_pwms: HashMap::new(),
             pin_pwm_freq: HashMap::new(),
+            pin_levels: HashMap::new(),
+            interrupt_counts: HashMap::new(),
         }
     }
 
@@ -205,6 +210,8 @@ impl FakeBoard {
             i2cs,
             pin_pwms: HashMap::new(),
             pin_pwm_freq: HashMap::new(),
+            pin_levels: HashMap::new(),
+            interrupt_counts: HashMap::new(),
         })))
     }
 }
@@ -212,12 +219,19 @@ impl FakeBoard {
 impl Board for FakeBoard {
     fn set_gpio_pin_level(&mut self, pin: i32, is_high: bool) -> Result<(), BoardError> {
         info!("set pin {} to {}", pin, is_high);
+        if self.pin_levels.insert(pin, is_high).unwrap_or(true) != is_high {
+            *self.interrupt_counts.entry(pin).or_default() += 1;
+        }
         Ok(())
     }
 
     fn get_gpio_level(&self, pin: i32) -> Result<bool, BoardError> {
         info!("get pin {}", pin);
-        Ok(true)
+        Ok(*self.pin_levels.get(&pin).unwrap_or(&true))
+    }
+
+    fn get_digital_interrupt_value(&self, pin: i32) -> Result<u32, BoardError> {
+        Ok(*self.interrupt_counts.get(&pin).unwrap_or(&0))
     }
 
     fn get_analog_reader_by_name(&self, name: String) -> Result<AnalogReaderType<u16>, BoardError> {
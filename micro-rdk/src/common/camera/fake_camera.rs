@@ -1,5 +1,7 @@
 use crate::common::camera::{Camera, CameraError, CameraType};
+use crate::proto::component::camera::v1::{Format, GetImagesResponse, Image};
 use bytes::Bytes;
+use prost::Message;
 use std::sync::{Arc, Mutex};
 
 use crate::common::{config::ConfigType, registry::ComponentRegistry, registry::Dependency};
@@ -40,6 +42,17 @@ impl Camera for FakeCamera {
     fn get_image(&mut self) -> Result<Bytes, CameraError> {
         Ok(FAKE_JPEG.into())
     }
+    fn get_images(&mut self) -> Result<Bytes, CameraError> {
+        let resp = GetImagesResponse {
+            images: vec![Image {
+                source_name: "fake".to_string(),
+                format: Format::Jpeg.into(),
+                image: FAKE_JPEG.into(),
+            }],
+            response_metadata: None,
+        };
+        Ok(resp.encode_to_vec().into())
+    }
 }
 
 #[cfg(all(test, feature = "native"))]
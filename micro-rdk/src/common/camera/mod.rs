@@ -1,11 +1,11 @@
 use super::{generic::DoCommand, registry::ComponentRegistry};
 use bytes::Bytes;
-use prost::EncodeError;
+use prost::{DecodeError, EncodeError};
 use std::sync::{Arc, Mutex};
 use thiserror::Error;
 
 #[cfg(feature = "builtin-components")]
-mod fake_camera;
+pub(crate) mod fake_camera;
 
 #[allow(unused)]
 pub(crate) fn register_models(registry: &mut ComponentRegistry) {
@@ -37,6 +37,8 @@ pub enum CameraError {
     CameraGenericError(&'static str),
     #[error("{0}")]
     MessageEncodeError(#[from] EncodeError),
+    #[error("{0}")]
+    MessageDecodeError(#[from] DecodeError),
 }
 
 pub trait Camera: DoCommand {
@@ -45,6 +47,7 @@ pub trait Camera: DoCommand {
     fn get_image(&mut self) -> Result<Bytes, CameraError> {
         Err(CameraError::CameraMethodUnimplemented("get_image"))
     }
+    /// Returns the images of the camera's sensors as an encoded `GetImagesResponse`
     fn get_images(&mut self) -> Result<Bytes, CameraError> {
         Err(CameraError::CameraMethodUnimplemented("get_images"))
     }
@@ -5,7 +5,10 @@ use crate::{
     common::system::FirmwareMode,
     google::{self, protobuf::value::Kind as ProtoKind},
     proto::{
-        app::{agent::v1::DeviceAgentConfigResponse, v1::ComponentConfig},
+        app::{
+            agent::v1::DeviceAgentConfigResponse,
+            v1::{ComponentConfig, ServiceConfig},
+        },
         common,
         provisioning::v1::SetNetworkCredentialsRequest,
     },
@@ -474,6 +477,14 @@ impl ResourceName {
         };
         Self { api, name }
     }
+    pub fn new_builtin_service(name: String, subtype: String) -> Self {
+        let api = API {
+            namespace: "rdk".to_owned(),
+            r#type: "service".to_owned(),
+            subtype,
+        };
+        Self { api, name }
+    }
     pub fn get_api(&self) -> &API {
         &self.api
     }
@@ -520,22 +531,29 @@ impl DynamicComponentConfig {
     }
 }
 
+fn attributes_from_proto(
+    attributes: Option<&google::protobuf::Struct>,
+) -> Result<Option<HashMap<String, Kind>>, AttributeError> {
+    let mut attrs_opt: Option<HashMap<String, Kind>> = None;
+    if let Some(cfg_attrs) = attributes {
+        let mut attrs = HashMap::new();
+        for (k, v) in cfg_attrs.fields.iter() {
+            let val: Kind = match &v.kind {
+                None => return Err(AttributeError::KeyNotFound(k.to_string())),
+                Some(inner_v) => inner_v.try_into()?,
+            };
+            let key = k.to_string();
+            attrs.insert(key, val);
+        }
+        attrs_opt = Some(attrs);
+    }
+    Ok(attrs_opt)
+}
+
 impl TryFrom<&ComponentConfig> for DynamicComponentConfig {
     type Error = AttributeError;
     fn try_from(value: &ComponentConfig) -> Result<Self, Self::Error> {
-        let mut attrs_opt: Option<HashMap<String, Kind>> = None;
-        if let Some(cfg_attrs) = value.attributes.as_ref() {
-            let mut attrs = HashMap::new();
-            for (k, v) in cfg_attrs.fields.iter() {
-                let val: Kind = match &v.kind {
-                    None => return Err(AttributeError::KeyNotFound(k.to_string())),
-                    Some(inner_v) => inner_v.try_into()?,
-                };
-                let key = k.to_string();
-                attrs.insert(key, val);
-            }
-            attrs_opt = Some(attrs);
-        }
+        let attrs_opt = attributes_from_proto(value.attributes.as_ref())?;
         #[cfg(feature = "data")]
         let data_collector_configs = if !value.service_configs.is_empty() {
             if let Some(data_service_cfg) = value
@@ -578,6 +596,34 @@ impl TryFrom<&ComponentConfig> for DynamicComponentConfig {
     }
 }
 
+// Services are built from the same dynamic configuration as components, their resource name
+// carries the `service` type of their api (e.g. `rdk:service:generic`)
+impl TryFrom<&ServiceConfig> for DynamicComponentConfig {
+    type Error = AttributeError;
+    fn try_from(value: &ServiceConfig) -> Result<Self, Self::Error> {
+        let api: API = if value.api.is_empty() {
+            // older configs only set the deprecated namespace and type fields
+            let namespace = if value.namespace.is_empty() {
+                "rdk"
+            } else {
+                value.namespace.as_str()
+            };
+            format!("{}:service:{}", namespace, value.r#type)
+                .as_str()
+                .try_into()?
+        } else {
+            value.api.as_str().try_into()?
+        };
+        Ok(Self {
+            name: ResourceName::new(value.name.clone(), api),
+            model: value.model.as_str().try_into()?,
+            attributes: attributes_from_proto(value.attributes.as_ref())?,
+            #[cfg(feature = "data")]
+            data_collector_configs: vec![],
+        })
+    }
+}
+
 #[derive(Debug)]
 pub enum ConfigType<'a> {
     Dynamic(&'a DynamicComponentConfig),
@@ -7,21 +7,32 @@ use crate::{
         config::AgentConfig,
         credentials_storage::RobotConfigurationStorage,
         grpc::ServerError,
+        registry::ComponentRegistry,
+        robot::LocalRobot,
         system::{send_system_event, SystemEvent},
     },
     proto::app::v1::RobotConfig,
 };
 use async_io::Timer;
+use chrono::{DateTime, FixedOffset};
 use futures_lite::{Future, FutureExt};
-use std::{fmt::Debug, pin::Pin, time::Duration};
+use std::{
+    cell::RefCell,
+    fmt::Debug,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 #[cfg(feature = "ota")]
 use crate::common::{exec::Executor, ota};
 
 pub struct ConfigMonitor<Storage> {
     /// revision of running `RobotConfig`
-    config_revision: String,
+    config_revision: RefCell<String>,
     storage: Storage,
+    robot: Arc<Mutex<LocalRobot>>,
+    registry: RefCell<Box<ComponentRegistry>>,
     #[cfg(feature = "ota")]
     executor: Executor,
 }
@@ -35,11 +46,15 @@ where
     pub fn new(
         curr_config: &RobotConfig,
         storage: Storage,
+        robot: Arc<Mutex<LocalRobot>>,
+        registry: Box<ComponentRegistry>,
         #[cfg(feature = "ota")] executor: Executor,
     ) -> Self {
         Self {
-            config_revision: curr_config.revision.to_string(),
+            config_revision: RefCell::new(curr_config.revision.to_string()),
             storage,
+            robot,
+            registry: RefCell::new(registry),
             #[cfg(feature = "ota")]
             executor,
         }
@@ -52,6 +67,28 @@ where
             log::warn!("machine configuration change detected - restarting micro-rdk");
         };
     }
+
+    // Applies the new config to the running robot, returns false if it could only be
+    // applied by restarting
+    fn reconfigure(&self, config: &RobotConfig, build_time: Option<DateTime<FixedOffset>>) -> bool {
+        if let Err(e) = self.robot.lock().unwrap().reconfigure(
+            config,
+            &mut self.registry.borrow_mut(),
+            build_time,
+        ) {
+            log::info!("couldn't reconfigure machine in place: {}", e);
+            return false;
+        }
+        log::info!(
+            "machine reconfigured to revision {} without restarting",
+            config.revision
+        );
+        *self.config_revision.borrow_mut() = config.revision.clone();
+        if let Err(e) = self.storage.store_robot_configuration(config) {
+            log::warn!("failed to store machine config after reconfiguring: {}", e);
+        }
+        true
+    }
 }
 impl<Storage> PeriodicAppClientTask for ConfigMonitor<Storage>
 where
@@ -76,7 +113,7 @@ where
         Box::pin(async move {
             #[allow(unused_mut)]
             let mut reboot = false;
-            let (new_config, _cfg_received_datetime) = app_client
+            let (new_config, cfg_received_datetime) = app_client
                 .get_app_config(None)
                 .or(async {
                     let _ = Timer::after(Duration::from_secs(60)).await;
@@ -111,7 +148,10 @@ where
                     }
                 }
 
-                if config.revision != self.config_revision {
+                let revision_changed = config.revision != *self.config_revision.borrow();
+                // no point in reconfiguring if an update is about to restart the machine
+                if revision_changed && (reboot || !self.reconfigure(config, cfg_received_datetime))
+                {
                     if let Err(e) = self.storage.reset_robot_configuration() {
                         log::warn!(
                             "failed to reset machine config after new config detected: {}",
@@ -52,7 +52,7 @@ use super::server::{IncomingConnectionManager, WebRtcConfiguration};
 use crate::common::provisioning::server::AsNetwork;
 
 #[cfg(feature = "ota")]
-use crate::common::credentials_storage::OtaMetadataStorage;
+use crate::common::{credentials_storage::OtaMetadataStorage, ota::OtaHealthCheck};
 
 pub struct RobotCloudConfig {
     local_fqdn: String,
@@ -196,6 +196,8 @@ pub struct ViamServerBuilder<Storage, State> {
     http2_server_insecure: bool,
     app_client_tasks: Vec<Box<dyn PeriodicAppClientTask>>,
     max_concurrent_connections: usize,
+    #[cfg(feature = "ota")]
+    ota_health_check: OtaHealthCheck,
     _state: PhantomData<State>,
 }
 
@@ -237,6 +239,8 @@ where
             http2_server_insecure: false,
             app_client_tasks: Default::default(),
             max_concurrent_connections: Self::get_default_max_concurrent_connections(),
+            #[cfg(feature = "ota")]
+            ota_health_check: Default::default(),
             _state: PhantomData,
         }
     }
@@ -254,6 +258,8 @@ where
             http2_server_insecure: self.http2_server_insecure,
             app_client_tasks: self.app_client_tasks,
             max_concurrent_connections: self.max_concurrent_connections,
+            #[cfg(feature = "ota")]
+            ota_health_check: self.ota_health_check,
             wifi_manager: Some(wifi_manager),
             _state: PhantomData::<HasNetwork>,
         }
@@ -314,6 +320,13 @@ where
         self
     }
 
+    /// Replaces the checks a freshly updated firmware has to pass before being kept
+    #[cfg(feature = "ota")]
+    pub fn with_ota_health_check(&mut self, ota_health_check: OtaHealthCheck) -> &mut Self {
+        self.ota_health_check = ota_health_check;
+        self
+    }
+
     pub fn with_default_tasks(&mut self) -> &mut Self {
         let restart_monitor = Box::new(RestartMonitor);
         let log_upload = Box::new(LogUploadTask);
@@ -352,6 +365,8 @@ where
             wifi_manager: self.wifi_manager.into(),
             app_client_tasks: self.app_client_tasks,
             max_concurrent_connections: self.max_concurrent_connections,
+            #[cfg(feature = "ota")]
+            ota_health_check: self.ota_health_check,
             network: Some(network),
         }
     }
@@ -386,6 +401,8 @@ where
             wifi_manager: Rc::new(self.wifi_manager),
             app_client_tasks: self.app_client_tasks,
             max_concurrent_connections: self.max_concurrent_connections,
+            #[cfg(feature = "ota")]
+            ota_health_check: self.ota_health_check,
             network: None,
         }
     }
@@ -492,6 +509,8 @@ pub struct ViamServer<Storage, C, M> {
     wifi_manager: Rc<Option<Box<dyn WifiManager>>>,
     app_client_tasks: Vec<Box<dyn PeriodicAppClientTask>>,
     max_concurrent_connections: usize,
+    #[cfg(feature = "ota")]
+    ota_health_check: OtaHealthCheck,
     network: Option<Box<dyn Network>>,
 }
 impl<Storage, C, M> ViamServer<Storage, C, M>
@@ -556,6 +575,11 @@ where
     pub(crate) async fn run(&mut self) -> RunResult {
         log::info!("starting viam server");
 
+        #[cfg(feature = "ota")]
+        self.executor
+            .spawn(self.ota_health_check.clone().run(self.storage.clone()))
+            .detach();
+
         self.storage.log_space_diagnostic();
         // The first step is to check whether or not credentials are populated in
         // storage. If not, we should go straight to provisioning.
@@ -591,6 +615,12 @@ where
                 Ok(metadata) => log::info!("OTA firmware version: {}", metadata.version),
                 Err(e) => log::warn!("OTA firmware metadata is not available: {}", e),
             };
+            if let Ok(version) = self.storage.get_ota_failed_version() {
+                log::warn!(
+                    "OTA firmware version `{}` failed its health check and was rolled back",
+                    version
+                );
+            }
         }
 
         // Since provisioning was run and completed, credentials are properly populated
@@ -742,14 +772,6 @@ where
             }
         }
 
-        let config_monitor_task = Box::new(ConfigMonitor::new(
-            &config,
-            self.storage.clone(),
-            #[cfg(feature = "ota")]
-            self.executor.clone(),
-        ));
-        self.app_client_tasks.push(config_monitor_task);
-
         log::info!("building machine from configuration");
         let mut robot = LocalRobot::from_cloud_config(
             self.executor.clone(),
@@ -769,6 +791,16 @@ where
 
         let robot = Arc::new(Mutex::new(robot));
 
+        let config_monitor_task = Box::new(ConfigMonitor::new(
+            &config,
+            self.storage.clone(),
+            robot.clone(),
+            self.component_registry.clone(),
+            #[cfg(feature = "ota")]
+            self.executor.clone(),
+        ));
+        self.app_client_tasks.push(config_monitor_task);
+
         if self.http2_server.has_http2_server() && !self.http2_server_insecure {
             // Try to obtain and store a fresh TLS certificate. If this fails or we cannot reach
             // app, then we'll end up falling back on whatever TLS certificate was cached. Note:
@@ -885,6 +917,11 @@ where
                 .build()
                 .await
         }
+        // a device booting offline on its cached config reaches app once it reconnects
+        .inspect(|_| {
+            #[cfg(feature = "ota")]
+            self.ota_health_check.set_app_reached();
+        })
         .inspect_err(|e| log::warn!("failed to connect to app server {}: error {}", app_uri, e))
     }
 
@@ -1411,6 +1448,9 @@ mod tests {
     use prost::Message;
     use rustls::client::ServerCertVerifier;
 
+    #[cfg(feature = "ota")]
+    use crate::common::ota::OtaHealthCheck;
+
     const LOCALHOST_URI: &str = "http://localhost:56563";
     type AuthFn = dyn Fn(&AuthenticateRequest) -> bool;
 
@@ -1742,6 +1782,75 @@ mod tests {
         });
     }
 
+    #[cfg(feature = "ota")]
+    #[test_log::test]
+    // A device booting offline on its cached configuration only reaches app once the network
+    // is back, the new firmware must then be marked healthy without a config being fetched
+    fn test_ota_app_reached_after_offline_boot() {
+        let _unused = global_network_test_lock();
+        let ram_storage = RAMStorage::new();
+        let network = match local_ip_address::local_ip().expect("error parsing local IP") {
+            std::net::IpAddr::V4(ip) => ExternallyManagedNetwork::new(ip),
+            _ => panic!("oops expected ipv4"),
+        };
+
+        let creds = CloudConfig {
+            id: "test-offline-boot".to_string(),
+            secret: "".to_string(),
+            app_address: LOCALHOST_URI.to_owned(),
+        };
+        assert!(ram_storage.store_robot_credentials(&creds).is_ok());
+        assert!(ram_storage
+            .store_robot_configuration(&make_sample_config())
+            .is_ok());
+
+        let mdns = NativeMdns::new("".to_owned(), network.get_ip());
+        assert!(mdns.is_ok());
+        let mdns = mdns.unwrap();
+
+        let health_check = OtaHealthCheck::default();
+        let mut viam_server = ViamServerBuilder::new(ram_storage);
+        viam_server
+            .with_http2_server(NativeH2Connector::default(), 12346)
+            .with_max_concurrent_connection(2)
+            .with_default_tasks()
+            .with_ota_health_check(health_check.clone());
+
+        let exec = Executor::new();
+
+        let mut viam_server = viam_server.build(
+            NativeH2Connector::default(),
+            exec.clone(),
+            mdns,
+            Box::new(network),
+        );
+        let cloned_exec = exec.clone();
+
+        let app = AppServerInsecure {
+            auth_fn: Some(Rc::new(Box::new(|req| {
+                assert!(req.entity.contains("test-offline-boot"));
+                true
+            }))),
+            config_fn: Some(Rc::new(Box::new(make_sample_config))),
+            ..Default::default()
+        };
+
+        exec.block_on(async move {
+            let _task = cloned_exec.spawn(async move {
+                let _ = viam_server.run().await;
+                unreachable!()
+            });
+            let _ = Timer::after(Duration::from_millis(500)).await;
+            assert!(!health_check.app_reached());
+
+            let other_clone = cloned_exec.clone();
+            let _fake_server_task =
+                cloned_exec.spawn(async move { run_fake_app_server(other_clone, app).await });
+            let _ = Timer::after(Duration::from_secs(2)).await;
+            assert!(health_check.app_reached());
+        });
+    }
+
     #[test_log::test]
     /// Runs viam server exposing HTTP2 connections, since each HTTP2 connection gets a
     /// max_prio assigned we can't test preemption
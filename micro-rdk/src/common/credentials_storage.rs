@@ -11,7 +11,7 @@ use crate::{
 };
 
 #[cfg(feature = "ota")]
-use crate::common::ota::OtaMetadata;
+use crate::common::ota::{OtaDownloadProgress, OtaMetadata};
 use crate::proto::{app::v1::CertificateResponse, provisioning::v1::CloudConfig};
 use thiserror::Error;
 
@@ -173,6 +173,20 @@ pub trait OtaMetadataStorage {
     fn get_ota_metadata(&self) -> Result<OtaMetadata, Self::Error>;
     fn store_ota_metadata(&self, ota_metadata: &OtaMetadata) -> Result<(), Self::Error>;
     fn reset_ota_metadata(&self) -> Result<(), Self::Error>;
+    /// Ed25519 public key trusted to sign OTA images, see [crate::common::ota]
+    fn has_ota_public_key(&self) -> bool;
+    fn get_ota_public_key(&self) -> Result<Vec<u8>, Self::Error>;
+    fn store_ota_public_key(&self, public_key: &[u8]) -> Result<(), Self::Error>;
+    /// Progress of an interrupted image download, see [OtaDownloadProgress]
+    fn get_ota_download_progress(&self) -> Result<OtaDownloadProgress, Self::Error>;
+    fn store_ota_download_progress(
+        &self,
+        progress: &OtaDownloadProgress,
+    ) -> Result<(), Self::Error>;
+    fn reset_ota_download_progress(&self) -> Result<(), Self::Error>;
+    /// Last version rolled back after failing its health check, see [crate::common::ota::OtaHealthCheck]
+    fn get_ota_failed_version(&self) -> Result<String, Self::Error>;
+    fn store_ota_failed_version(&self, version: &str) -> Result<(), Self::Error>;
 }
 
 pub trait StorageDiagnostic {
@@ -192,6 +206,12 @@ struct RAMCredentialStorageInner {
     tls_cert: Option<TlsCertificate>,
     #[cfg(feature = "ota")]
     ota_metadata: Option<OtaMetadata>,
+    #[cfg(feature = "ota")]
+    ota_public_key: Option<Vec<u8>>,
+    #[cfg(feature = "ota")]
+    ota_download_progress: Option<OtaDownloadProgress>,
+    #[cfg(feature = "ota")]
+    ota_failed_version: Option<String>,
 }
 
 /// Simple CrendentialStorage made for testing purposes
@@ -224,6 +244,12 @@ impl RAMStorage {
             network_settings: None,
             #[cfg(feature = "ota")]
             ota_metadata: None,
+            #[cfg(feature = "ota")]
+            ota_public_key: None,
+            #[cfg(feature = "ota")]
+            ota_download_progress: None,
+            #[cfg(feature = "ota")]
+            ota_failed_version: None,
         })))
     }
 }
@@ -375,6 +401,53 @@ impl OtaMetadataStorage for RAMStorage {
         let _ = self.0.lock().unwrap().ota_metadata.take();
         Ok(())
     }
+    fn has_ota_public_key(&self) -> bool {
+        let inner_ref = self.0.lock().unwrap();
+        inner_ref.ota_public_key.is_some()
+    }
+    fn get_ota_public_key(&self) -> Result<Vec<u8>, Self::Error> {
+        let inner_ref = self.0.lock().unwrap();
+        inner_ref
+            .ota_public_key
+            .clone()
+            .ok_or(RAMStorageError::NotFound)
+    }
+    fn store_ota_public_key(&self, public_key: &[u8]) -> Result<(), Self::Error> {
+        let mut inner_ref = self.0.lock().unwrap();
+        let _ = inner_ref.ota_public_key.insert(public_key.to_vec());
+        Ok(())
+    }
+    fn get_ota_download_progress(&self) -> Result<OtaDownloadProgress, Self::Error> {
+        let inner_ref = self.0.lock().unwrap();
+        inner_ref
+            .ota_download_progress
+            .clone()
+            .ok_or(RAMStorageError::NotFound)
+    }
+    fn store_ota_download_progress(
+        &self,
+        progress: &OtaDownloadProgress,
+    ) -> Result<(), Self::Error> {
+        let mut inner_ref = self.0.lock().unwrap();
+        let _ = inner_ref.ota_download_progress.insert(progress.clone());
+        Ok(())
+    }
+    fn reset_ota_download_progress(&self) -> Result<(), Self::Error> {
+        let _ = self.0.lock().unwrap().ota_download_progress.take();
+        Ok(())
+    }
+    fn get_ota_failed_version(&self) -> Result<String, Self::Error> {
+        let inner_ref = self.0.lock().unwrap();
+        inner_ref
+            .ota_failed_version
+            .clone()
+            .ok_or(RAMStorageError::NotFound)
+    }
+    fn store_ota_failed_version(&self, version: &str) -> Result<(), Self::Error> {
+        let mut inner_ref = self.0.lock().unwrap();
+        let _ = inner_ref.ota_failed_version.insert(version.to_owned());
+        Ok(())
+    }
 }
 #[cfg(feature = "ota")]
 impl<Iterable, Storage: OtaMetadataStorage> OtaMetadataStorage for Iterable
@@ -404,6 +477,54 @@ where
             |val, s| val.or_else(|_| s.store_ota_metadata(ota_metadata)),
         )
     }
+    fn has_ota_public_key(&self) -> bool {
+        self.into_iter().any(OtaMetadataStorage::has_ota_public_key)
+    }
+    fn get_ota_public_key(&self) -> Result<Vec<u8>, Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.get_ota_public_key()),
+        )
+    }
+    fn store_ota_public_key(&self, public_key: &[u8]) -> Result<(), Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.store_ota_public_key(public_key)),
+        )
+    }
+    fn get_ota_download_progress(&self) -> Result<OtaDownloadProgress, Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.get_ota_download_progress()),
+        )
+    }
+    fn store_ota_download_progress(
+        &self,
+        progress: &OtaDownloadProgress,
+    ) -> Result<(), Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.store_ota_download_progress(progress)),
+        )
+    }
+    fn reset_ota_download_progress(&self) -> Result<(), Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or(s.reset_ota_download_progress()),
+        )
+    }
+    fn get_ota_failed_version(&self) -> Result<String, Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.get_ota_failed_version()),
+        )
+    }
+    fn store_ota_failed_version(&self, version: &str) -> Result<(), Self::Error> {
+        self.into_iter().fold(
+            Err::<_, Self::Error>(EmptyStorageCollectionError.into()),
+            |val, s| val.or_else(|_| s.store_ota_failed_version(version)),
+        )
+    }
 }
 
 impl RobotConfigurationStorage for RAMStorage {
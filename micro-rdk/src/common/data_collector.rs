@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     fmt::Display,
+    sync::Mutex,
     time::{Duration, Instant},
 };
 
@@ -9,17 +10,25 @@ use crate::{
     proto::app::data_sync::v1::{sensor_data::Data, MimeType, SensorData, SensorMetadata},
 };
 
+#[cfg(feature = "camera")]
+use super::camera::CameraError;
 use super::{
     analog::AnalogError,
-    board::{Board, BoardError},
+    board::{Board, BoardError, BoardType},
     config::{AttributeError, Kind},
     encoder::{EncoderError, EncoderPositionType},
     motor::MotorError,
     movement_sensor::MovementSensor,
+    power_sensor::{PowerSensor, PowerSupplyType},
     robot::ResourceType,
     sensor::{Readings, SensorError},
     servo::ServoError,
+    switch::SwitchError,
 };
+#[cfg(feature = "camera")]
+use crate::proto::component::camera::v1::{Format, GetImagesResponse};
+#[cfg(feature = "camera")]
+use prost::Message;
 use thiserror::Error;
 
 pub(crate) const DEFAULT_CACHE_SIZE_KB: f64 = 8.0;
@@ -35,6 +44,87 @@ pub struct DataCollectorConfig {
     pub capture_frequency_hz: f32,
     pub capacity: usize,
     pub disabled: bool,
+    pub capture_condition: Option<CaptureCondition>,
+    /// when set, readings are summarized over windows of this duration and only
+    /// the summaries are stored
+    pub aggregation_window: Option<Duration>,
+}
+
+/// A CaptureCondition restricts when a collector stores the data it reads, it is
+/// configured in the "additional_params" of a capture method. Deadband and threshold
+/// conditions look at the numeric fields of a reading (or only at `field` when set,
+/// nested fields are joined with a '.')
+#[derive(Debug, Clone, PartialEq)]
+pub enum CaptureCondition {
+    /// store a reading only when a field changed by more than the deadband since the
+    /// last stored reading
+    Deadband {
+        deadband: f64,
+        field: Option<String>,
+    },
+    /// store a reading only when a field crossed the threshold since the previous reading
+    Threshold {
+        threshold: f64,
+        field: Option<String>,
+    },
+    /// store `samples` consecutive readings whenever an interrupt is detected on `pin`
+    /// of the board named `board`, nothing is read otherwise
+    Trigger {
+        board: String,
+        pin: i32,
+        samples: u32,
+    },
+}
+
+impl CaptureCondition {
+    fn from_additional_params(params: &Kind) -> Result<Option<Self>, AttributeError> {
+        let field: Option<String> = params
+            .get("condition_field")?
+            .map(|f| f.try_into())
+            .transpose()?;
+        let deadband: Option<f64> = params.get("deadband")?.map(|d| d.try_into()).transpose()?;
+        let threshold: Option<f64> = params.get("threshold")?.map(|t| t.try_into()).transpose()?;
+        let trigger_pin: Option<i32> = params
+            .get("trigger_pin")?
+            .map(|p| p.try_into())
+            .transpose()?;
+        match (deadband, threshold, trigger_pin) {
+            (None, None, None) => Ok(None),
+            (Some(deadband), None, None) => {
+                if deadband < 0.0 {
+                    return Err(AttributeError::ValidationError(
+                        "deadband cannot be negative".to_string(),
+                    ));
+                }
+                Ok(Some(Self::Deadband { deadband, field }))
+            }
+            (None, Some(threshold), None) => Ok(Some(Self::Threshold { threshold, field })),
+            (None, None, Some(pin)) => {
+                let board: String = params
+                    .get("trigger_board")?
+                    .ok_or(AttributeError::KeyNotFound("trigger_board".to_string()))?
+                    .try_into()?;
+                let samples: u32 = params
+                    .get("burst_samples")?
+                    .map(|s| s.try_into())
+                    .transpose()?
+                    .unwrap_or(1);
+                if samples == 0 {
+                    return Err(AttributeError::ValidationError(
+                        "burst_samples must be at least 1".to_string(),
+                    ));
+                }
+                Ok(Some(Self::Trigger {
+                    board,
+                    pin,
+                    samples,
+                }))
+            }
+            _ => Err(AttributeError::ValidationError(
+                "only one of deadband, threshold or trigger_pin can be configured".to_string(),
+            )),
+        }
+    }
 }
 
 impl TryFrom<&Kind> for DataCollectorConfig {
@@ -68,6 +158,29 @@ impl TryFrom<&Kind> for DataCollectorConfig {
             ));
         }
         let additional_params = value.get("additional_params")?;
+        let capture_condition = match additional_params {
+            Some(params @ Kind::StructValue(_)) => {
+                CaptureCondition::from_additional_params(params)?
+            }
+            _ => None,
+        };
+        let aggregation_window = match additional_params {
+            Some(params @ Kind::StructValue(_)) => params
+                .get("aggregation_window_secs")?
+                .map(f64::try_from)
+                .transpose()?
+                .map(|w| {
+                    if w > 0.0 {
+                        Ok(Duration::from_secs_f64(w))
+                    } else {
+                        Err(AttributeError::ValidationError(
+                            "aggregation_window_secs must be positive".to_string(),
+                        ))
+                    }
+                })
+                .transpose()?,
+            _ => None,
+        };
         let method = match method_str.as_str() {
             "Readings" => CollectionMethod::Readings,
             "AngularVelocity" => CollectionMethod::AngularVelocity,
@@ -92,15 +205,28 @@ impl TryFrom<&Kind> for DataCollectorConfig {
                 CollectionMethod::Gpios(pin)
             }
             "TicksCount" => CollectionMethod::TicksCount,
+            "IsPowered" => CollectionMethod::IsPowered,
+            "Voltage" => CollectionMethod::Voltage,
+            "Current" => CollectionMethod::Current,
+            "Power" => CollectionMethod::Power,
+            "ReadImage" => CollectionMethod::ReadImage,
+            "GetImages" => CollectionMethod::GetImages,
             _ => {
                 return Err(AttributeError::ConversionImpossibleError);
             }
         };
+        if aggregation_window.is_some() && method.is_binary() {
+            return Err(AttributeError::ValidationError(
+                "binary data cannot be aggregated".to_string(),
+            ));
+        }
         Ok(DataCollectorConfig {
             method,
             capture_frequency_hz,
             capacity,
             disabled,
+            capture_condition,
+            aggregation_window,
         })
     }
 }
@@ -114,14 +240,30 @@ pub enum CollectionMethod {
     AngularVelocity,
     LinearAcceleration,
     LinearVelocity,
-    Position, // also Servo,Motor
+    Position, // also Servo,Motor,Switch
     CompassHeading,
     // Board methods
     Analogs(String),
     Gpios(i32),
     // Encoder methods
     TicksCount,
-    // TODO: RSDK-7127 - Implement collectors for all other applicable components/methods
+    // Motor methods
+    IsPowered,
+    // PowerSensor methods
+    Voltage,
+    Current,
+    Power,
+    // Camera methods
+    ReadImage,
+    GetImages,
+}
+
+impl CollectionMethod {
+    /// Whether the method produces binary data (camera frames) rather than tabular data,
+    /// binary data has to be uploaded one reading per request
+    pub(crate) fn is_binary(&self) -> bool {
+        matches!(self, Self::ReadImage | Self::GetImages)
+    }
 }
 
 impl Display for CollectionMethod {
@@ -139,6 +281,12 @@ impl Display for CollectionMethod {
                 Self::Analogs(_) => "Analogs",
                 Self::Gpios(_) => "Gpios",
                 Self::TicksCount => "TicksCount",
+                Self::IsPowered => "IsPowered",
+                Self::Voltage => "Voltage",
+                Self::Current => "Current",
+                Self::Power => "Power",
+                Self::ReadImage => "ReadImage",
+                Self::GetImages => "GetImages",
             },
             f,
         )
@@ -182,6 +330,265 @@ pub enum DataCollectionError {
     SensorCollectionError(#[from] SensorError),
     #[error(transparent)]
     ServoCollectionError(#[from] ServoError),
+    #[error(transparent)]
+    SwitchCollectionError(#[from] SwitchError),
+    #[cfg(feature = "camera")]
+    #[error(transparent)]
+    CameraCollectionError(#[from] CameraError),
+    #[error("no board available to watch for capture triggers")]
+    MissingTriggerBoard,
+}
+
+fn numeric_fields(prefix: &str, value: &Struct, out: &mut HashMap<String, f64>) {
+    for (key, value) in value.fields.iter() {
+        let key = if prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match &value.kind {
+            Some(ProtoKind::NumberValue(v)) => {
+                let _ = out.insert(key, *v);
+            }
+            Some(ProtoKind::BoolValue(v)) => {
+                let _ = out.insert(key, *v as u8 as f64);
+            }
+            Some(ProtoKind::StructValue(s)) => numeric_fields(&key, s, out),
+            _ => {}
+        }
+    }
+}
+
+/// State kept by a collector to evaluate its CaptureCondition between calls
+struct CaptureFilter {
+    condition: CaptureCondition,
+    trigger_board: Option<BoardType>,
+    last_values: Option<HashMap<String, f64>>,
+    last_interrupt_count: Option<u32>,
+    burst_remaining: u32,
+}
+
+impl CaptureFilter {
+    fn new(condition: CaptureCondition) -> Self {
+        Self {
+            condition,
+            trigger_board: None,
+            last_values: None,
+            last_interrupt_count: None,
+            burst_remaining: 0,
+        }
+    }
+
+    fn is_trigger(&self) -> bool {
+        matches!(self.condition, CaptureCondition::Trigger { .. })
+    }
+
+    /// checks for interrupt events detected since the last poll and returns whether there
+    /// were any, each event adds a burst of samples to the ones still to be read
+    fn poll_interrupts(&mut self) -> Result<bool, DataCollectionError> {
+        let CaptureCondition::Trigger { pin, samples, .. } = self.condition else {
+            return Ok(false);
+        };
+        let board = self
+            .trigger_board
+            .as_ref()
+            .ok_or(DataCollectionError::MissingTriggerBoard)?;
+        let count = board.get_digital_interrupt_value(pin)?;
+        // the first call only records the current count of interrupt events
+        let events = self
+            .last_interrupt_count
+            .replace(count)
+            .map_or(0, |last| count.wrapping_sub(last));
+        self.burst_remaining = self
+            .burst_remaining
+            .saturating_add(events.saturating_mul(samples));
+        Ok(events > 0)
+    }
+
+    /// returns whether a sample should be read, consuming it from the pending bursts
+    fn take_sample(&mut self) -> bool {
+        if !self.is_trigger() {
+            return true;
+        }
+        if self.burst_remaining == 0 {
+            return false;
+        }
+        self.burst_remaining -= 1;
+        true
+    }
+
+    /// returns whether a reading should be stored, the first reading is always kept
+    fn should_keep(&mut self, data: &SensorData) -> bool {
+        let (field, deadband, threshold) = match &self.condition {
+            CaptureCondition::Deadband { deadband, field } => (field, Some(*deadband), None),
+            CaptureCondition::Threshold { threshold, field } => (field, None, Some(*threshold)),
+            CaptureCondition::Trigger { .. } => return true,
+        };
+        let mut values = HashMap::new();
+        match &data.data {
+            Some(Data::Struct(s)) => numeric_fields("", s, &mut values),
+            _ => return true,
+        }
+        if let Some(field) = field {
+            values.retain(|k, _| k == field);
+        }
+        let keep = match self.last_values.as_ref() {
+            None => true,
+            Some(last) => {
+                values.len() != last.len()
+                    || values.iter().any(|(k, v)| match last.get(k) {
+                        None => true,
+                        Some(last) => {
+                            deadband.is_some_and(|d| (v - last).abs() > d)
+                                || threshold.is_some_and(|t| (*v > t) != (*last > t))
+                        }
+                    })
+            }
+        };
+        // a deadband is relative to the last stored reading, a crossing to the previous one
+        if keep || threshold.is_some() {
+            self.last_values = Some(values);
+        }
+        keep
+    }
+}
+
+struct FieldSummary {
+    min: f64,
+    max: f64,
+    sum: f64,
+    last: f64,
+    count: u32,
+}
+
+impl FieldSummary {
+    fn new() -> Self {
+        Self {
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            last: 0.0,
+            count: 0,
+        }
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.last = value;
+        self.count += 1;
+    }
+
+    fn into_value(self) -> Value {
+        let number = |v: f64| Value {
+            kind: Some(ProtoKind::NumberValue(v)),
+        };
+        Value {
+            kind: Some(ProtoKind::StructValue(Struct {
+                fields: HashMap::from([
+                    ("min".to_string(), number(self.min)),
+                    ("max".to_string(), number(self.max)),
+                    ("mean".to_string(), number(self.sum / self.count as f64)),
+                    ("last".to_string(), number(self.last)),
+                    ("count".to_string(), number(self.count as f64)),
+                ]),
+            })),
+        }
+    }
+}
+
+fn timestamp_as_duration(ts: &Timestamp) -> Duration {
+    Duration::new(ts.seconds as u64, ts.nanos as u32)
+}
+
+fn duration_as_timestamp(duration: Duration) -> Timestamp {
+    Timestamp {
+        seconds: duration.as_secs() as i64,
+        nanos: duration.subsec_nanos() as i32,
+    }
+}
+
+/// Summarizes the numeric fields of readings (min, max, mean, last and count) over
+/// a window starting with the first reading, a single SensorData is emitted once the
+/// window's duration has elapsed
+struct Aggregator {
+    window: Duration,
+    // the timestamp of the window's first reading, and when it was aggregated
+    window_start: Option<(Timestamp, Instant)>,
+    summaries: HashMap<String, FieldSummary>,
+}
+
+impl Aggregator {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            window_start: None,
+            summaries: HashMap::new(),
+        }
+    }
+
+    /// adds the readings to the current window and returns the summaries of the windows
+    /// that ended by `now`. Readings are assigned to windows by their timestamps, which are
+    /// not all taken from the same clock, while the current window is closed by `now`
+    fn aggregate(&mut self, readings: Vec<SensorData>, now: Instant) -> Vec<SensorData> {
+        let window = self.window;
+        let mut summaries = vec![];
+        for reading in readings {
+            let (Some(metadata), Some(Data::Struct(data))) = (reading.metadata, reading.data)
+            else {
+                continue;
+            };
+            let requested = metadata.time_requested.unwrap_or_default();
+            // a reading requested once the window has ended belongs to the next one
+            summaries.extend(self.close_window(|start, _| {
+                timestamp_as_duration(&requested) >= timestamp_as_duration(start) + window
+            }));
+            let _ = self.window_start.get_or_insert((requested, now));
+            let mut values = HashMap::new();
+            numeric_fields("", &data, &mut values);
+            for (key, value) in values {
+                self.summaries
+                    .entry(key)
+                    .or_insert_with(FieldSummary::new)
+                    .add(value);
+            }
+        }
+        summaries.extend(self.close_window(|_, opened| now.duration_since(opened) >= window));
+        summaries
+    }
+
+    /// summarizes the current window if `ended` is true for its start
+    fn close_window(
+        &mut self,
+        ended: impl FnOnce(&Timestamp, Instant) -> bool,
+    ) -> Option<SensorData> {
+        let (window_start, opened) = self.window_start.as_ref()?;
+        if !ended(window_start, *opened) {
+            return None;
+        }
+        let window_start = self.window_start.take()?.0;
+        let window_end = timestamp_as_duration(&window_start) + self.window;
+        Some(self.summarize(window_start, duration_as_timestamp(window_end)))
+    }
+
+    fn summarize(&mut self, window_start: Timestamp, window_end: Timestamp) -> SensorData {
+        SensorData {
+            metadata: Some(SensorMetadata {
+                time_requested: Some(window_start),
+                time_received: Some(window_end),
+                annotations: None,
+                mime_type: MimeType::Unspecified.into(),
+            }),
+            data: Some(Data::Struct(Struct {
+                fields: self
+                    .summaries
+                    .drain()
+                    .map(|(key, summary)| (key, summary.into_value()))
+                    .collect(),
+            })),
+        }
+    }
 }
 
 /// A DataCollector represents an association between a data collection method and
@@ -194,6 +601,8 @@ pub struct DataCollector {
     method: CollectionMethod,
     time_interval: Duration,
     capacity: usize,
+    capture_filter: Option<Mutex<CaptureFilter>>,
+    aggregator: Option<Mutex<Aggregator>>,
 }
 
 fn resource_method_pair_is_valid(resource: &ResourceType, method: &CollectionMethod) -> bool {
@@ -206,7 +615,10 @@ fn resource_method_pair_is_valid(resource: &ResourceType, method: &CollectionMet
         }
         ResourceType::Encoder(_) => matches!(method, CollectionMethod::TicksCount),
         ResourceType::Motor(_) => {
-            matches!(method, CollectionMethod::Position)
+            matches!(
+                method,
+                CollectionMethod::Position | CollectionMethod::IsPowered
+            )
         }
         ResourceType::MovementSensor(_) => matches!(
             method,
@@ -219,6 +631,18 @@ fn resource_method_pair_is_valid(resource: &ResourceType, method: &CollectionMet
         ),
         ResourceType::Sensor(_) => matches!(method, CollectionMethod::Readings),
         ResourceType::Servo(_) => matches!(method, CollectionMethod::Position),
+        ResourceType::PowerSensor(_) => matches!(
+            method,
+            CollectionMethod::Voltage | CollectionMethod::Current | CollectionMethod::Power
+        ),
+        ResourceType::Switch(_) => matches!(method, CollectionMethod::Position),
+        #[cfg(feature = "camera")]
+        ResourceType::Camera(_) => {
+            matches!(
+                method,
+                CollectionMethod::ReadImage | CollectionMethod::GetImages
+            )
+        }
         _ => false,
     }
 }
@@ -250,6 +674,8 @@ impl DataCollector {
             method,
             time_interval,
             capacity,
+            capture_filter: None,
+            aggregator: None,
         })
     }
 
@@ -258,13 +684,29 @@ impl DataCollector {
         resource: ResourceType,
         conf: &DataCollectorConfig,
     ) -> Result<Self, DataCollectionError> {
-        Self::new(
+        let mut collector = Self::new(
             name,
             resource,
             conf.method.clone(),
             conf.capture_frequency_hz,
             conf.capacity,
-        )
+        )?;
+        collector.capture_filter = conf
+            .capture_condition
+            .clone()
+            .map(|condition| Mutex::new(CaptureFilter::new(condition)));
+        collector.aggregator = conf
+            .aggregation_window
+            .map(|window| Mutex::new(Aggregator::new(window)));
+        Ok(collector)
+    }
+
+    /// Sets the board watched for interrupts by a `CaptureCondition::Trigger`
+    pub fn with_trigger_board(self, board: BoardType) -> Self {
+        if let Some(filter) = self.capture_filter.as_ref() {
+            filter.lock().unwrap().trigger_board = Some(board);
+        }
+        self
     }
 
     pub fn name(&self) -> String {
@@ -287,13 +729,104 @@ impl DataCollector {
         self.capacity
     }
 
-    /// calls the method associated with the collector and returns the resulting data
+    /// Whether the collector captures data in bursts started by interrupts, such a
+    /// collector should be polled with `poll_trigger` in between its captures
+    pub(crate) fn is_triggered(&self) -> bool {
+        self.capture_filter
+            .as_ref()
+            .is_some_and(|filter| filter.lock().unwrap().is_trigger())
+    }
+
+    /// calls the method associated with the collector and returns the resulting data,
+    /// readings rejected by the collector's CaptureCondition are left out and, when the
+    /// collector aggregates, only the summary of a completed window is returned
     pub(crate) fn call_method(
         &self,
         robot_start_time: Instant,
     ) -> Result<Vec<SensorData>, DataCollectionError> {
+        self.capture(robot_start_time, false)
+    }
+
+    /// checks a triggered collector for interrupt events between two captures, the first
+    /// sample of a burst is read as soon as its event is seen rather than at the next capture
+    pub(crate) fn poll_trigger(
+        &self,
+        robot_start_time: Instant,
+    ) -> Result<Vec<SensorData>, DataCollectionError> {
+        self.capture(robot_start_time, true)
+    }
+
+    fn capture(
+        &self,
+        robot_start_time: Instant,
+        new_events_only: bool,
+    ) -> Result<Vec<SensorData>, DataCollectionError> {
+        let data = self.filtered_read(robot_start_time, new_events_only)?;
+        Ok(match self.aggregator.as_ref() {
+            None => data,
+            // windows are closed by time, also on the ticks where nothing was read
+            Some(aggregator) => aggregator.lock().unwrap().aggregate(data, Instant::now()),
+        })
+    }
+
+    fn filtered_read(
+        &self,
+        robot_start_time: Instant,
+        new_events_only: bool,
+    ) -> Result<Vec<SensorData>, DataCollectionError> {
+        match self.capture_filter.as_ref() {
+            None if new_events_only => Ok(vec![]),
+            None => self.read_data(robot_start_time),
+            Some(filter) => {
+                let mut filter = filter.lock().unwrap();
+                let new_events = filter.poll_interrupts()?;
+                if (new_events_only && !new_events) || !filter.take_sample() {
+                    return Ok(vec![]);
+                }
+                let mut data = self.read_data(robot_start_time)?;
+                data.retain(|reading| filter.should_keep(reading));
+                Ok(data)
+            }
+        }
+    }
+
+    fn read_data(&self, robot_start_time: Instant) -> Result<Vec<SensorData>, DataCollectionError> {
         let reading_requested_ts = robot_start_time.elapsed();
 
+        // each image returned by GetImages is a reading of its own, with its format
+        #[cfg(feature = "camera")]
+        if let (CollectionMethod::GetImages, ResourceType::Camera(res)) =
+            (&self.method, &self.resource)
+        {
+            let images = res.lock().unwrap().get_images()?;
+            let images = GetImagesResponse::decode(images).map_err(CameraError::from)?;
+            let reading_received_ts = robot_start_time.elapsed();
+            return Ok(images
+                .images
+                .into_iter()
+                .map(|image| SensorData {
+                    metadata: Some(SensorMetadata {
+                        time_received: Some(Timestamp {
+                            seconds: reading_received_ts.as_secs() as i64,
+                            nanos: reading_received_ts.subsec_nanos() as i32,
+                        }),
+                        time_requested: Some(Timestamp {
+                            seconds: reading_requested_ts.as_secs() as i64,
+                            nanos: reading_requested_ts.subsec_nanos() as i32,
+                        }),
+                        annotations: None,
+                        mime_type: match Format::try_from(image.format) {
+                            Ok(Format::Jpeg) => MimeType::ImageJpeg,
+                            Ok(Format::Png) => MimeType::ImagePng,
+                            _ => MimeType::Unspecified,
+                        }
+                        .into(),
+                    }),
+                    data: Some(Data::Binary(image.image.to_vec())),
+                })
+                .collect());
+        }
+
         if matches!(self.method, CollectionMethod::Readings) {
             return match self.resource.clone() {
                 ResourceType::Sensor(mut res) => Ok(res.get_readings_sensor_data()?),
@@ -383,6 +916,25 @@ impl DataCollector {
                         )]),
                     })
                 }
+                CollectionMethod::IsPowered => {
+                    let (is_on, power_pct) = res.lock().unwrap().is_powered()?;
+                    Data::Struct(Struct {
+                        fields: HashMap::from([
+                            (
+                                "is_on".to_string(),
+                                Value {
+                                    kind: Some(ProtoKind::BoolValue(is_on)),
+                                },
+                            ),
+                            (
+                                "power_pct".to_string(),
+                                Value {
+                                    kind: Some(ProtoKind::NumberValue(power_pct)),
+                                },
+                            ),
+                        ]),
+                    })
+                }
                 _ => {
                     return Err(DataCollectionError::UnsupportedMethod(
                         self.method.clone(),
@@ -390,6 +942,92 @@ impl DataCollector {
                     ))
                 }
             },
+            ResourceType::PowerSensor(ref mut res) => {
+                let is_ac = |power_supply_type| Value {
+                    kind: Some(ProtoKind::BoolValue(matches!(
+                        power_supply_type,
+                        PowerSupplyType::AC
+                    ))),
+                };
+                match self.method {
+                    CollectionMethod::Voltage => {
+                        let voltage = res.get_voltage()?;
+                        Data::Struct(Struct {
+                            fields: HashMap::from([
+                                (
+                                    "volts".to_string(),
+                                    Value {
+                                        kind: Some(ProtoKind::NumberValue(voltage.volts)),
+                                    },
+                                ),
+                                ("is_ac".to_string(), is_ac(voltage.power_supply_type)),
+                            ]),
+                        })
+                    }
+                    CollectionMethod::Current => {
+                        let current = res.get_current()?;
+                        Data::Struct(Struct {
+                            fields: HashMap::from([
+                                (
+                                    "amperes".to_string(),
+                                    Value {
+                                        kind: Some(ProtoKind::NumberValue(current.amperes)),
+                                    },
+                                ),
+                                ("is_ac".to_string(), is_ac(current.power_supply_type)),
+                            ]),
+                        })
+                    }
+                    CollectionMethod::Power => {
+                        let watts = res.get_power()?;
+                        Data::Struct(Struct {
+                            fields: HashMap::from([(
+                                "watts".to_string(),
+                                Value {
+                                    kind: Some(ProtoKind::NumberValue(watts)),
+                                },
+                            )]),
+                        })
+                    }
+                    _ => {
+                        return Err(DataCollectionError::UnsupportedMethod(
+                            self.method.clone(),
+                            "power_sensor".to_string(),
+                        ))
+                    }
+                }
+            }
+            ResourceType::Switch(res) => match self.method {
+                CollectionMethod::Position => {
+                    let position = res.lock().unwrap().get_position()?;
+                    Data::Struct(Struct {
+                        fields: HashMap::from([(
+                            "position".to_string(),
+                            Value {
+                                kind: Some(ProtoKind::NumberValue(position.into())),
+                            },
+                        )]),
+                    })
+                }
+                _ => {
+                    return Err(DataCollectionError::UnsupportedMethod(
+                        self.method.clone(),
+                        "switch".to_string(),
+                    ))
+                }
+            },
+            #[cfg(feature = "camera")]
+            ResourceType::Camera(res) => match self.method {
+                CollectionMethod::ReadImage => {
+                    Data::Binary(res.lock().unwrap().get_image()?.to_vec())
+                }
+                _ => {
+                    return Err(DataCollectionError::UnsupportedMethod(
+                        self.method.clone(),
+                        "camera".to_string(),
+                    ))
+                }
+            },
             ResourceType::MovementSensor(ref mut res) => match self.method {
                 CollectionMethod::AngularVelocity => res
                     .get_angular_velocity()?
@@ -412,8 +1050,6 @@ impl DataCollector {
                         )]),
                     })
                 }
-                #[allow(unreachable_patterns)]
-                // TODO: RSDK-7127 - remove when methods for other components are implemented
                 _ => {
                     return Err(DataCollectionError::UnsupportedMethod(
                         self.method.clone(),
@@ -424,6 +1060,10 @@ impl DataCollector {
             _ => return Err(DataCollectionError::NoSupportedMethods),
         };
         let reading_received_ts = robot_start_time.elapsed();
+        let mime_type = match data {
+            Data::Binary(_) => MimeType::ImageJpeg,
+            Data::Struct(_) => MimeType::Unspecified,
+        };
         Ok(vec![SensorData {
             metadata: Some(SensorMetadata {
                 time_received: Some(Timestamp {
@@ -435,7 +1075,7 @@ impl DataCollector {
                     nanos: reading_requested_ts.subsec_nanos() as i32,
                 }),
                 annotations: None,
-                mime_type: MimeType::Unspecified.into(),
+                mime_type: mime_type.into(),
             }),
             data: Some(data),
         }])
@@ -457,14 +1097,22 @@ mod tests {
     use std::time::{Duration, Instant};
 
     use super::{
-        CollectionMethod, DataCollectionError, DataCollector, DataCollectorConfig,
-        DEFAULT_CACHE_SIZE_KB,
+        timestamp_as_duration, Aggregator, CaptureCondition, CaptureFilter, CollectionMethod,
+        DataCollectionError, DataCollector, DataCollectorConfig, DEFAULT_CACHE_SIZE_KB,
     };
-    use crate::common::config::{AttributeError, Kind};
+    use crate::common::board::{Board, BoardType, FakeBoard};
+    use crate::common::config::{
+        AttributeError, ConfigType, DynamicComponentConfig, Kind, Model, ResourceName,
+    };
+    use crate::common::motor::{FakeMotor, Motor};
+    use crate::common::power_sensor::{Current, PowerSensor, PowerSupplyType, Voltage};
     use crate::common::robot::ResourceType;
-    use crate::common::sensor::FakeSensor;
+    use crate::common::sensor::{FakeSensor, SensorError};
+    use crate::common::switch::FakeSwitch;
     use crate::google;
-    use crate::proto::app::data_sync::v1::sensor_data::Data;
+    use crate::proto::app::data_sync::v1::{sensor_data::Data, SensorData, SensorMetadata};
+    #[cfg(all(feature = "camera", feature = "builtin-components"))]
+    use crate::{common::camera::fake_camera::FakeCamera, proto::app::data_sync::v1::MimeType};
 
     #[test_log::test]
     fn test_collector_config() -> Result<(), AttributeError> {
@@ -571,6 +1219,305 @@ mod tests {
         Ok(())
     }
 
+    #[test_log::test]
+    fn test_capture_conditions() -> Result<(), AttributeError> {
+        let conf_kind = Kind::StructValue(HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Readings".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(1.0)),
+            (
+                "additional_params".to_string(),
+                Kind::StructValue(HashMap::from([
+                    ("deadband".to_string(), Kind::NumberValue(0.5)),
+                    (
+                        "condition_field".to_string(),
+                        Kind::StringValue("a".to_string()),
+                    ),
+                ])),
+            ),
+        ]));
+        let conf: DataCollectorConfig = (&conf_kind).try_into()?;
+        let condition = conf
+            .capture_condition
+            .expect("capture condition not parsed");
+        assert_eq!(
+            condition,
+            CaptureCondition::Deadband {
+                deadband: 0.5,
+                field: Some("a".to_string())
+            }
+        );
+
+        let reading = |a: f64, b: f64| SensorData {
+            metadata: None,
+            data: Some(Data::Struct(google::protobuf::Struct {
+                fields: HashMap::from([
+                    (
+                        "a".to_string(),
+                        google::protobuf::Value {
+                            kind: Some(google::protobuf::value::Kind::NumberValue(a)),
+                        },
+                    ),
+                    (
+                        "b".to_string(),
+                        google::protobuf::Value {
+                            kind: Some(google::protobuf::value::Kind::NumberValue(b)),
+                        },
+                    ),
+                ]),
+            })),
+        };
+
+        let mut filter = CaptureFilter::new(condition);
+        assert!(!filter.poll_interrupts().unwrap());
+        assert!(filter.take_sample());
+        assert!(filter.should_keep(&reading(1.0, 0.0)));
+        // b isn't watched and a stays within the deadband of the last stored reading
+        assert!(!filter.should_keep(&reading(1.3, 10.0)));
+        assert!(!filter.should_keep(&reading(1.4, 0.0)));
+        assert!(filter.should_keep(&reading(1.6, 0.0)));
+
+        let mut filter = CaptureFilter::new(CaptureCondition::Threshold {
+            threshold: 5.0,
+            field: None,
+        });
+        assert!(filter.should_keep(&reading(1.0, 1.0)));
+        assert!(!filter.should_keep(&reading(4.0, 1.0)));
+        assert!(filter.should_keep(&reading(6.0, 1.0)));
+        assert!(!filter.should_keep(&reading(7.0, 2.0)));
+        assert!(filter.should_keep(&reading(7.0, 5.5)));
+
+        // trigger conditions cannot be evaluated without a board
+        let mut filter = CaptureFilter::new(CaptureCondition::Trigger {
+            board: "board".to_string(),
+            pin: 4,
+            samples: 3,
+        });
+        assert!(matches!(
+            filter.poll_interrupts(),
+            Err(DataCollectionError::MissingTriggerBoard)
+        ));
+
+        let conf_kind = Kind::StructValue(HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Readings".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(1.0)),
+            (
+                "additional_params".to_string(),
+                Kind::StructValue(HashMap::from([
+                    ("deadband".to_string(), Kind::NumberValue(0.5)),
+                    ("trigger_pin".to_string(), Kind::NumberValue(4.0)),
+                ])),
+            ),
+        ]));
+        assert!(matches!(
+            DataCollectorConfig::try_from(&conf_kind),
+            Err(AttributeError::ValidationError(_))
+        ));
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_trigger_capture() -> Result<(), DataCollectionError> {
+        let robot_start_time = Instant::now();
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let resource = ResourceType::Sensor(Arc::new(Mutex::new(FakeSensor::new())));
+        let conf_kind = Kind::StructValue(HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Readings".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(1.0)),
+            (
+                "additional_params".to_string(),
+                Kind::StructValue(HashMap::from([
+                    ("trigger_pin".to_string(), Kind::NumberValue(4.0)),
+                    (
+                        "trigger_board".to_string(),
+                        Kind::StringValue("board".to_string()),
+                    ),
+                    ("burst_samples".to_string(), Kind::NumberValue(2.0)),
+                ])),
+            ),
+        ]));
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        let coll = DataCollector::from_config("fake".to_string(), resource, &conf)?
+            .with_trigger_board(board.clone() as BoardType);
+        assert!(coll.is_triggered());
+
+        // nothing is read until an interrupt event is seen
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+
+        // every level change of the fake board's pin is an interrupt event, two events
+        // between captures are two bursts
+        board.lock().unwrap().set_gpio_pin_level(4, false)?;
+        board.lock().unwrap().set_gpio_pin_level(4, true)?;
+        for _ in 0..4 {
+            assert_eq!(coll.call_method(robot_start_time)?.len(), 1);
+        }
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+
+        // polling in between captures reads the first sample of a burst right away
+        assert!(coll.poll_trigger(robot_start_time)?.is_empty());
+        board.lock().unwrap().set_gpio_pin_level(4, false)?;
+        assert_eq!(coll.poll_trigger(robot_start_time)?.len(), 1);
+        assert!(coll.poll_trigger(robot_start_time)?.is_empty());
+        assert_eq!(coll.call_method(robot_start_time)?.len(), 1);
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_aggregation() -> Result<(), AttributeError> {
+        let conf_kind = Kind::StructValue(HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Readings".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(10.0)),
+            (
+                "additional_params".to_string(),
+                Kind::StructValue(HashMap::from([(
+                    "aggregation_window_secs".to_string(),
+                    Kind::NumberValue(1.0),
+                )])),
+            ),
+        ]));
+        let conf: DataCollectorConfig = (&conf_kind).try_into()?;
+        assert_eq!(conf.aggregation_window, Some(Duration::from_secs(1)));
+
+        let reading = |ms: u64, value: f64| {
+            let ts = google::protobuf::Timestamp {
+                seconds: (ms / 1000) as i64,
+                nanos: ((ms % 1000) * 1_000_000) as i32,
+            };
+            SensorData {
+                metadata: Some(SensorMetadata {
+                    time_requested: Some(ts.clone()),
+                    time_received: Some(ts),
+                    ..Default::default()
+                }),
+                data: Some(Data::Struct(google::protobuf::Struct {
+                    fields: HashMap::from([(
+                        "value".to_string(),
+                        google::protobuf::Value {
+                            kind: Some(google::protobuf::value::Kind::NumberValue(value)),
+                        },
+                    )]),
+                })),
+            }
+        };
+
+        let stat = |summary: &SensorData, name: &str| {
+            let Some(Data::Struct(data)) = &summary.data else {
+                panic!("expected struct data")
+            };
+            let Some(google::protobuf::value::Kind::StructValue(value)) =
+                &data.fields.get("value").unwrap().kind
+            else {
+                panic!("expected a summary of value")
+            };
+            match value.fields.get(name).unwrap().kind {
+                Some(google::protobuf::value::Kind::NumberValue(v)) => v,
+                _ => panic!("{} is not a number", name),
+            }
+        };
+        let window = |summary: &SensorData| {
+            let metadata = summary.metadata.as_ref().unwrap();
+            (
+                timestamp_as_duration(metadata.time_requested.as_ref().unwrap()),
+                timestamp_as_duration(metadata.time_received.as_ref().unwrap()),
+            )
+        };
+        let start = Instant::now();
+        let ms = Duration::from_millis;
+        let at = |t: u64| start + ms(t);
+
+        let mut aggregator = Aggregator::new(Duration::from_secs(1));
+        assert!(aggregator
+            .aggregate(vec![reading(100, 2.0)], at(100))
+            .is_empty());
+        assert!(aggregator
+            .aggregate(vec![reading(600, 6.0)], at(600))
+            .is_empty());
+
+        // the reading that ends a window is summarized with the next one
+        let summary = aggregator.aggregate(vec![reading(1100, 1.0), reading(1200, 5.0)], at(1200));
+        assert_eq!(summary.len(), 1);
+        assert_eq!(window(&summary[0]), (ms(100), ms(1100)));
+        assert_eq!(stat(&summary[0], "min"), 2.0);
+        assert_eq!(stat(&summary[0], "max"), 6.0);
+        assert_eq!(stat(&summary[0], "mean"), 4.0);
+        assert_eq!(stat(&summary[0], "last"), 6.0);
+        assert_eq!(stat(&summary[0], "count"), 2.0);
+
+        // a window is closed once its duration has elapsed since its first reading was
+        // aggregated, even when no reading came in
+        assert!(aggregator.aggregate(vec![], at(2100)).is_empty());
+        let summary = aggregator.aggregate(vec![], at(2200));
+        assert_eq!(summary.len(), 1);
+        assert_eq!(window(&summary[0]), (ms(1100), ms(2100)));
+        assert_eq!(stat(&summary[0], "min"), 1.0);
+        assert_eq!(stat(&summary[0], "last"), 5.0);
+        assert_eq!(stat(&summary[0], "count"), 2.0);
+
+        // no window is open until the next reading
+        assert!(aggregator.aggregate(vec![], at(5000)).is_empty());
+        assert!(aggregator
+            .aggregate(vec![reading(5200, 4.0)], at(5200))
+            .is_empty());
+        let summary = aggregator.aggregate(vec![reading(6300, 3.0)], at(6300));
+        assert_eq!(summary.len(), 1);
+        assert_eq!(window(&summary[0]), (ms(5200), ms(6200)));
+        assert_eq!(stat(&summary[0], "count"), 1.0);
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_collect_aggregated() -> Result<(), DataCollectionError> {
+        let robot_start_time = Instant::now();
+        let resource = ResourceType::Sensor(Arc::new(Mutex::new(FakeSensor::new())));
+        let conf_kind = Kind::StructValue(HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Readings".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(100.0)),
+            (
+                "additional_params".to_string(),
+                Kind::StructValue(HashMap::from([
+                    (
+                        "aggregation_window_secs".to_string(),
+                        Kind::NumberValue(0.05),
+                    ),
+                    ("deadband".to_string(), Kind::NumberValue(1.0)),
+                ])),
+            ),
+        ]));
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        let coll = DataCollector::from_config("fake".to_string(), resource, &conf)?;
+
+        // the fake sensor's readings never change, so only the first one passes the deadband
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+        std::thread::sleep(Duration::from_millis(60));
+        let summary = coll.call_method(robot_start_time)?;
+        assert_eq!(summary.len(), 1);
+        let Some(Data::Struct(data)) = &summary[0].data else {
+            panic!("expected struct data")
+        };
+        assert!(data.fields.contains_key("readings.fake_sensor"));
+        assert!(coll.call_method(robot_start_time)?.is_empty());
+        Ok(())
+    }
+
     #[test_log::test]
     fn test_collect_data() -> Result<(), DataCollectionError> {
         let robot_start_time = Instant::now();
@@ -619,4 +1566,211 @@ mod tests {
         };
         Ok(())
     }
+
+    #[test_log::test]
+    fn test_collect_motor_is_powered() -> Result<(), DataCollectionError> {
+        let robot_start_time = Instant::now();
+        let mut motor = FakeMotor::new();
+        motor.set_power(0.5)?;
+        let resource = ResourceType::Motor(Arc::new(Mutex::new(motor)));
+        let kind_map = HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("IsPowered".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(10.0)),
+        ]);
+        let conf_kind = Kind::StructValue(kind_map);
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        assert!(matches!(conf.method, CollectionMethod::IsPowered));
+
+        let coll = DataCollector::from_config("fake".to_string(), resource.clone(), &conf)?;
+        let data = coll.call_method(robot_start_time)?[0].data.clone();
+        match data {
+            Some(Data::Struct(d)) => {
+                assert_eq!(
+                    d.fields.get("is_on").and_then(|v| v.kind.clone()),
+                    Some(google::protobuf::value::Kind::BoolValue(true))
+                );
+                assert_eq!(
+                    d.fields.get("power_pct").and_then(|v| v.kind.clone()),
+                    Some(google::protobuf::value::Kind::NumberValue(0.5))
+                );
+            }
+            _ => panic!("expected struct data"),
+        };
+
+        let kind_map = HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("Voltage".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(10.0)),
+        ]);
+        let conf_kind = Kind::StructValue(kind_map);
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        assert!(matches!(
+            DataCollector::from_config("fake".to_string(), resource, &conf),
+            Err(DataCollectionError::UnsupportedMethod(
+                CollectionMethod::Voltage,
+                _
+            ))
+        ));
+        Ok(())
+    }
+
+    #[derive(DoCommand, PowerSensorReadings)]
+    struct TestPowerSensor {}
+
+    impl PowerSensor for TestPowerSensor {
+        fn get_voltage(&mut self) -> Result<Voltage, SensorError> {
+            Ok(Voltage {
+                volts: 12.5,
+                power_supply_type: PowerSupplyType::DC,
+            })
+        }
+        fn get_current(&mut self) -> Result<Current, SensorError> {
+            Ok(Current {
+                amperes: 2.0,
+                power_supply_type: PowerSupplyType::AC,
+            })
+        }
+        fn get_power(&mut self) -> Result<f64, SensorError> {
+            Ok(25.0)
+        }
+    }
+
+    fn collect_struct(
+        resource: &ResourceType,
+        method: &str,
+    ) -> Result<HashMap<String, google::protobuf::value::Kind>, DataCollectionError> {
+        let kind_map = HashMap::from([
+            ("method".to_string(), Kind::StringValue(method.to_string())),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(10.0)),
+        ]);
+        let conf = DataCollectorConfig::try_from(&Kind::StructValue(kind_map))
+            .expect("data collector config parse failed");
+        let coll = DataCollector::from_config("test".to_string(), resource.clone(), &conf)?;
+        match coll.call_method(Instant::now())?[0].data.clone() {
+            Some(Data::Struct(d)) => Ok(d
+                .fields
+                .into_iter()
+                .filter_map(|(k, v)| v.kind.map(|kind| (k, kind)))
+                .collect()),
+            _ => panic!("expected struct data"),
+        }
+    }
+
+    #[test_log::test]
+    fn test_collect_power_sensor() -> Result<(), DataCollectionError> {
+        use google::protobuf::value::Kind as ProtoKind;
+        let resource = ResourceType::PowerSensor(Arc::new(Mutex::new(TestPowerSensor {})));
+
+        let voltage = collect_struct(&resource, "Voltage")?;
+        assert_eq!(voltage.get("volts"), Some(&ProtoKind::NumberValue(12.5)));
+        assert_eq!(voltage.get("is_ac"), Some(&ProtoKind::BoolValue(false)));
+
+        let current = collect_struct(&resource, "Current")?;
+        assert_eq!(current.get("amperes"), Some(&ProtoKind::NumberValue(2.0)));
+        assert_eq!(current.get("is_ac"), Some(&ProtoKind::BoolValue(true)));
+
+        let power = collect_struct(&resource, "Power")?;
+        assert_eq!(power.get("watts"), Some(&ProtoKind::NumberValue(25.0)));
+
+        assert!(matches!(
+            collect_struct(&resource, "Readings"),
+            Err(DataCollectionError::UnsupportedMethod(
+                CollectionMethod::Readings,
+                _
+            ))
+        ));
+        Ok(())
+    }
+
+    #[test_log::test]
+    fn test_collect_switch_position() -> Result<(), DataCollectionError> {
+        use google::protobuf::value::Kind as ProtoKind;
+        let cfg = DynamicComponentConfig {
+            name: ResourceName::new_builtin("switch".to_owned(), "switch".to_owned()),
+            model: Model::new_builtin("fake".to_owned()),
+            data_collector_configs: vec![],
+            attributes: Some(HashMap::from([(
+                "position_count".to_owned(),
+                Kind::NumberValue(3.0),
+            )])),
+        };
+        let switch = FakeSwitch::from_config(ConfigType::Dynamic(&cfg), vec![]).unwrap();
+        switch.lock().unwrap().set_position(2).unwrap();
+        let resource = ResourceType::Switch(switch);
+
+        let position = collect_struct(&resource, "Position")?;
+        assert_eq!(position.get("position"), Some(&ProtoKind::NumberValue(2.0)));
+
+        assert!(matches!(
+            collect_struct(&resource, "IsPowered"),
+            Err(DataCollectionError::UnsupportedMethod(
+                CollectionMethod::IsPowered,
+                _
+            ))
+        ));
+        Ok(())
+    }
+
+    #[cfg(all(feature = "camera", feature = "builtin-components"))]
+    #[test_log::test]
+    fn test_collect_camera_image() -> Result<(), DataCollectionError> {
+        let camera = Arc::new(Mutex::new(FakeCamera::new()));
+        let resource = ResourceType::Camera(camera);
+        let kind_map = HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("ReadImage".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(1.0)),
+        ]);
+        let conf_kind = Kind::StructValue(kind_map);
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        let coll = DataCollector::from_config("camera".to_string(), resource, &conf)?;
+        assert!(coll.resource_method_key().method.is_binary());
+
+        let data = coll.call_method(Instant::now())?;
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            data[0].metadata.as_ref().map(|m| m.mime_type),
+            Some(MimeType::ImageJpeg.into())
+        );
+        assert!(matches!(&data[0].data, Some(Data::Binary(image)) if !image.is_empty()));
+        Ok(())
+    }
+
+    #[cfg(all(feature = "camera", feature = "builtin-components"))]
+    #[test_log::test]
+    fn test_collect_camera_images() -> Result<(), DataCollectionError> {
+        let camera = Arc::new(Mutex::new(FakeCamera::new()));
+        let resource = ResourceType::Camera(camera);
+        let kind_map = HashMap::from([
+            (
+                "method".to_string(),
+                Kind::StringValue("GetImages".to_string()),
+            ),
+            ("capture_frequency_hz".to_string(), Kind::NumberValue(1.0)),
+        ]);
+        let conf_kind = Kind::StructValue(kind_map);
+        let conf =
+            DataCollectorConfig::try_from(&conf_kind).expect("data collector config parse failed");
+        let coll = DataCollector::from_config("camera".to_string(), resource, &conf)?;
+        assert!(coll.resource_method_key().method.is_binary());
+
+        let data = coll.call_method(Instant::now())?;
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            data[0].metadata.as_ref().map(|m| m.mime_type),
+            Some(MimeType::ImageJpeg.into())
+        );
+        assert!(matches!(&data[0].data, Some(Data::Binary(image)) if !image.is_empty()));
+        Ok(())
+    }
 }
@@ -7,7 +7,8 @@ use crate::common::data_store::DataStore;
 use crate::google::protobuf::value::Kind;
 use crate::google::protobuf::{Struct, Timestamp};
 use crate::proto::app::data_sync::v1::{
-    DataCaptureUploadRequest, DataType, SensorData, UploadMetadata,
+    sensor_data::Data, DataCaptureUploadMetadata, DataCaptureUploadRequest, DataType, MimeType,
+    SensorData, SensorMetadata, UploadMetadata,
 };
 use crate::proto::app::v1::{RobotConfig, ServiceConfig};
 
@@ -17,7 +18,7 @@ use super::data_store::{DataStoreError, DataStoreReader, WriteMode};
 use super::robot::{LocalRobot, RobotError};
 use super::system::{send_system_event, SystemEvent};
 use async_io::Timer;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use chrono::offset::Local;
 use chrono::Datelike;
 use futures_lite::prelude::Future;
@@ -30,6 +31,10 @@ use thiserror::Error;
 // the smaller amount of available RAM, we've halved it
 static MAX_SENSOR_CONTENTS_SIZE: usize = 32000;
 
+// Collectors triggered by interrupts are checked for new interrupt events this often between
+// captures, so that a burst starts soon after its event whatever the capture frequency
+static TRIGGER_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
 type CollectedReadings = Vec<(
     ResourceMethodKey,
     Result<Vec<SensorData>, DataCollectionError>,
@@ -238,10 +243,23 @@ where
                 );
             }
             loop_counter += 1;
-            Timer::after(self.min_interval).await;
+            self.wait_next_tick(robot_start_time).await;
         }
     }
 
+    // Waits for the next collection tick, polling the collectors triggered by interrupts on
+    // their own timer in the meantime so the ticks of the other collectors are left unchanged
+    async fn wait_next_tick(&self, robot_start_time: Instant) {
+        let next_tick = Instant::now() + self.min_interval;
+        if self.collectors.iter().any(|coll| coll.is_triggered()) {
+            while Instant::now() + TRIGGER_POLL_INTERVAL < next_tick {
+                Timer::after(TRIGGER_POLL_INTERVAL).await;
+                self.poll_triggered_collectors(robot_start_time).await;
+            }
+        }
+        Timer::at(next_tick).await;
+    }
+
     pub async fn collect_data_inner(
         &mut self,
         loop_counter: u64,
@@ -263,6 +281,29 @@ where
         robot_start_time: Instant,
     ) -> Result<(), DataManagerError> {
         let readings = self.collect_readings_for_interval(time_interval_ms, robot_start_time)?;
+        self.store_readings(readings).await;
+        Ok(())
+    }
+
+    // Collectors triggered by interrupts are checked for new interrupt events between
+    // ticks. Errors are left to be reported by the collectors' regular captures rather
+    // than on every poll
+    async fn poll_triggered_collectors(&self, robot_start_time: Instant) {
+        let readings: CollectedReadings = self
+            .collectors
+            .iter()
+            .filter(|coll| coll.is_triggered())
+            .filter_map(|coll| {
+                coll.poll_trigger(robot_start_time)
+                    .ok()
+                    .filter(|data| !data.is_empty())
+                    .map(|data| (coll.resource_method_key(), Ok(data)))
+            })
+            .collect();
+        self.store_readings(readings).await;
+    }
+
+    async fn store_readings(&self, readings: CollectedReadings) {
         let mut store_guard = self.store.lock().await;
         for (collector_key, reading) in readings {
             match reading {
@@ -288,7 +329,6 @@ where
                 }
             }
         }
-        Ok(())
     }
 
     // Here, time_interval_ms is required to be a multiple of the minimum time_interval among the collectors.
@@ -383,12 +423,12 @@ fn get_time_to_subtract(
 
 fn time_correct_reading(
     robot_start_time: Instant,
-    msg: &mut SensorData,
+    metadata: Option<&mut SensorMetadata>,
 ) -> Result<(), DataSyncError> {
     // the timestamps of the stored data are measured as offsets from a starting
     // instant (robot_start_time, acquired from DataSyncTask), so we adjust the
     // timestamps on the parsed message based on the current time (if it is now available)
-    if let Some(metadata) = msg.metadata.as_mut() {
+    if let Some(metadata) = metadata {
         let current_dt = Local::now().fixed_offset();
         // Viam was founded in 2020, so if the current time is set to any time before that
         // we know that settimeofday was never called, or called with an improper datetime
@@ -419,6 +459,90 @@ fn time_correct_reading(
     Ok(())
 }
 
+fn file_extension(mime_type: i32) -> String {
+    match MimeType::try_from(mime_type) {
+        Ok(MimeType::ImageJpeg) => ".jpeg".to_string(),
+        Ok(MimeType::ImagePng) => ".png".to_string(),
+        Ok(MimeType::ApplicationPcd) => ".pcd".to_string(),
+        _ => "".to_string(),
+    }
+}
+
+/// A `SensorData` carrying binary data (such as a camera frame), with the same encoding. The
+/// frame is held in `Bytes` so that a reading decoded from a message read from the store
+/// refers to the store's buffer and the chunks streamed to app are sliced from it
+#[derive(Clone, PartialEq, Message)]
+struct BinarySensorData {
+    #[prost(message, optional, tag = "1")]
+    metadata: Option<SensorMetadata>,
+    #[prost(bytes = "bytes", tag = "3")]
+    binary: Bytes,
+}
+
+impl TryFrom<SensorData> for BinarySensorData {
+    type Error = SensorData;
+    fn try_from(reading: SensorData) -> Result<Self, Self::Error> {
+        match reading.data {
+            Some(Data::Binary(binary)) => Ok(Self {
+                metadata: reading.metadata,
+                binary: binary.into(),
+            }),
+            _ => Err(reading),
+        }
+    }
+}
+
+// Binary readings (such as camera frames) must be uploaded one per request. Readings
+// that don't fit in MAX_SENSOR_CONTENTS_SIZE are sent through the streaming upload API
+async fn upload_binary_reading(
+    app_client: &AppClient,
+    part_id: &str,
+    collector_key: &ResourceMethodKey,
+    reading: BinarySensorData,
+) -> Result<(), AppClientError> {
+    let upload_metadata = UploadMetadata {
+        part_id: part_id.to_string(),
+        component_type: collector_key.component_type.clone(),
+        r#type: DataType::BinarySensor.into(),
+        component_name: collector_key.r_name.clone(),
+        method_name: collector_key.method.to_string(),
+        file_extension: file_extension(
+            reading
+                .metadata
+                .as_ref()
+                .map_or(MimeType::Unspecified.into(), |m| m.mime_type),
+        ),
+        ..Default::default()
+    };
+    if reading.encoded_len() <= MAX_SENSOR_CONTENTS_SIZE {
+        app_client
+            .upload_data(DataCaptureUploadRequest {
+                metadata: Some(upload_metadata),
+                sensor_contents: vec![SensorData {
+                    metadata: reading.metadata,
+                    data: Some(Data::Binary(reading.binary.to_vec())),
+                }],
+            })
+            .await?;
+    } else {
+        app_client
+            .upload_data_streaming(
+                DataCaptureUploadMetadata {
+                    upload_metadata: Some(upload_metadata),
+                    sensor_metadata: reading.metadata,
+                },
+                reading.binary,
+                MAX_SENSOR_CONTENTS_SIZE,
+            )
+            .await?;
+    }
+    #[cfg(feature = "data-upload-hook-unstable")]
+    unsafe {
+        micro_rdk_data_manager_post_upload_hook();
+    }
+    Ok(())
+}
+
 pub struct DataSyncTask<StoreType> {
     store: Rc<AsyncMutex<StoreType>>,
     resource_method_keys: Vec<ResourceMethodKey>,
@@ -440,10 +564,85 @@ where
 
     fn get_time_corrected_reading(&self, raw_msg: BytesMut) -> Result<SensorData, DataSyncError> {
         let mut msg = SensorData::decode(raw_msg)?;
-        time_correct_reading(self.robot_start_time, &mut msg)?;
+        time_correct_reading(self.robot_start_time, msg.metadata.as_mut())?;
         Ok(msg)
     }
 
+    fn get_time_corrected_binary_reading(
+        &self,
+        raw_msg: Bytes,
+    ) -> Result<BinarySensorData, DataSyncError> {
+        let mut msg = BinarySensorData::decode(raw_msg)?;
+        time_correct_reading(self.robot_start_time, msg.metadata.as_mut())?;
+        Ok(msg)
+    }
+
+    // Binary readings can't be batched like tabular ones, so they are read, flushed
+    // and uploaded one at a time
+    async fn sync_binary_data(
+        &self,
+        app_client: &AppClient,
+        collector_key: &ResourceMethodKey,
+        total_messages: usize,
+    ) -> Result<(), AppClientError> {
+        for _ in 0..total_messages {
+            let store_lock = self.store.lock().await;
+            let mut reader = match store_lock.get_reader(collector_key) {
+                Ok(reader) => reader,
+                Err(err) => {
+                    log::error!(
+                        "error acquiring reader for collector key ({:?}): {:?}",
+                        collector_key,
+                        err
+                    );
+                    break;
+                }
+            };
+            let reading = match reader.read_next_message() {
+                Ok(msg) if msg.is_empty() => break,
+                Ok(msg) => {
+                    match self.get_time_corrected_binary_reading(msg.freeze()) {
+                        Ok(reading) => Some(reading),
+                        Err(DataSyncError::NoCurrentTime) => {
+                            log::error!("Could not calculate data timestamps, returning without flushing store");
+                            return Ok(());
+                        }
+                        Err(err) => {
+                            log::error!(
+                                "error decoding reading for collector key ({:?}): {:?}",
+                                collector_key,
+                                err
+                            );
+                            None
+                        }
+                    }
+                }
+                Err(err) => {
+                    log::error!(
+                        "error reading message from store for collector key ({:?}): {:?}",
+                        collector_key,
+                        err
+                    );
+                    break;
+                }
+            };
+            // as for tabular data, the store is flushed before the upload is attempted
+            // (see DataSyncTask::run)
+            reader.flush();
+            std::mem::drop(store_lock);
+
+            if let Some(reading) = reading {
+                if let Err(err) =
+                    upload_binary_reading(app_client, &self.part_id, collector_key, reading).await
+                {
+                    log::error!("error uploading binary data, data lost (1 message)");
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+
     async fn run(&self, app_client: &AppClient) -> Result<(), AppClientError> {
         for collector_key in self.resource_method_keys.iter() {
             // Since a write may occur in between uploading consecutive chunks of data, we want to make
@@ -471,6 +670,11 @@ where
             if total_messages == 0 {
                 continue;
             }
+            if collector_key.method.is_binary() {
+                self.sync_binary_data(app_client, collector_key, total_messages)
+                    .await?;
+                continue;
+            }
             let max_messages_per_chunk = std::cmp::max(10, total_messages.div_ceil(10));
             let mut messages_processed = 0;
 
@@ -717,7 +921,9 @@ impl DataCollectAndSyncTask {
                 let mut readings: Vec<SensorData> = readings
                     .into_iter()
                     .filter_map(|mut readings| {
-                        if let Err(err) = time_correct_reading(robot_start_time, &mut readings) {
+                        if let Err(err) =
+                            time_correct_reading(robot_start_time, readings.metadata.as_mut())
+                        {
                             log::error!(
                                 "unable to time correct reading for collector {:?}, {:?}",
                                 collector_key,
@@ -729,6 +935,34 @@ impl DataCollectAndSyncTask {
                         }
                     })
                     .collect();
+                if collector_key.method.is_binary() {
+                    for reading in readings {
+                        let Ok(reading) = BinarySensorData::try_from(reading) else {
+                            log::error!(
+                                "non binary reading captured for collector {:?}",
+                                collector_key
+                            );
+                            continue;
+                        };
+                        // if we can't upload, don't try again until after the next sleep
+                        if let Err(err) = upload_binary_reading(
+                            app_client,
+                            &self.part_id,
+                            &collector_key,
+                            reading,
+                        )
+                        .await
+                        {
+                            log::error!(
+                                "error uploading binary data: {}, collector: {:?}",
+                                err,
+                                collector_key
+                            );
+                            break;
+                        }
+                    }
+                    continue;
+                }
                 readings.reverse();
                 if !readings.is_empty() {
                     let mut readings_to_upload: Vec<SensorData> = vec![];
@@ -767,7 +1001,8 @@ impl DataCollectAndSyncTask {
                         }
                     }
                 } else {
-                    log::warn!(
+                    // readings can be left out by the collector's capture condition
+                    log::debug!(
                         "no readings captured for collector {:?}, skipping upload",
                         collector_key
                     );
@@ -850,8 +1085,10 @@ mod tests {
     use crate::common::data_collector::DataCollectionError;
     use crate::common::data_store::{DataStoreReader, WriteMode};
     use crate::common::{
+        board::{Board, BoardType, FakeBoard},
         data_collector::{
-            CollectionMethod, DataCollector, ResourceMethodKey, DEFAULT_CACHE_SIZE_KB,
+            CaptureCondition, CollectionMethod, DataCollector, DataCollectorConfig,
+            ResourceMethodKey, DEFAULT_CACHE_SIZE_KB,
         },
         data_store::{DataStore, DataStoreError},
         robot::ResourceType,
@@ -863,6 +1100,37 @@ mod tests {
     use crate::google::protobuf::value::Kind;
     use crate::proto::app::data_sync::v1::{sensor_data::Data, SensorData};
 
+    #[cfg(feature = "native")]
+    use {
+        super::{upload_binary_reading, BinarySensorData, DataSyncTask, MAX_SENSOR_CONTENTS_SIZE},
+        crate::common::{
+            app_client::{encode_request, AppClient, AppClientBuilder},
+            credentials_storage::RobotCredentials,
+            exec::Executor,
+            grpc::{GrpcBody, GrpcResponse},
+            grpc_client::GrpcClient,
+        },
+        crate::native::tcp::NativeStream,
+        crate::proto::{
+            app::data_sync::v1::{
+                streaming_data_capture_upload_request::UploadPacket, DataCaptureUploadRequest,
+                DataCaptureUploadResponse, MimeType, SensorMetadata,
+                StreamingDataCaptureUploadRequest,
+            },
+            rpc::v1::AuthenticateResponse,
+        },
+        async_io::Async,
+        bytes::{Buf, Bytes},
+        futures_util::lock::Mutex as AsyncMutex,
+        http_body_util::BodyExt,
+        hyper::{
+            body::Incoming, header::CONTENT_TYPE, server::conn::http2, service::service_fn,
+            Response,
+        },
+        std::collections::VecDeque,
+        std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream},
+    };
+
     #[derive(DoCommand)]
     struct TestSensorFailure {}
 
@@ -1364,4 +1632,357 @@ mod tests {
             assert_eq!(read_data, expected_data);
         });
     }
+
+    #[test_log::test]
+    fn test_poll_triggered_collectors() {
+        let robot_start_time = Instant::now();
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let conf = DataCollectorConfig {
+            method: CollectionMethod::Readings,
+            capture_frequency_hz: 1.0,
+            capacity: (DEFAULT_CACHE_SIZE_KB * 1000.0) as usize,
+            disabled: false,
+            capture_condition: Some(CaptureCondition::Trigger {
+                board: "board".to_string(),
+                pin: 4,
+                samples: 1,
+            }),
+            aggregation_window: None,
+        };
+        let resource = ResourceType::Sensor(Arc::new(Mutex::new(TestSensor {})));
+        let coll = DataCollector::from_config("r1".to_string(), resource, &conf)
+            .unwrap()
+            .with_trigger_board(board.clone() as BoardType);
+
+        // a collector at 3 Hz keeps its own tick, triggers are polled on their own timer
+        let conf = DataCollectorConfig {
+            capture_frequency_hz: 3.0,
+            capture_condition: None,
+            ..conf
+        };
+        let resource = ResourceType::Sensor(Arc::new(Mutex::new(TestSensor {})));
+        let other = DataCollector::from_config("r2".to_string(), resource, &conf).unwrap();
+
+        let store = ReadSavingStore::new();
+        let stored = store.store.clone();
+        let mut manager =
+            DataManager::new(vec![coll, other], store, None, "boop".to_string()).unwrap();
+        assert_eq!(manager.min_interval_ms(), 333);
+        assert_eq!(manager.collection_intervals(), vec![333, 999]);
+
+        async_io::block_on(async move {
+            assert!(manager
+                .collect_and_store_readings(999, robot_start_time)
+                .await
+                .is_ok());
+            assert_eq!(stored.borrow().len(), 0);
+
+            // an event between two captures is read on the next poll
+            board.lock().unwrap().set_gpio_pin_level(4, false).unwrap();
+            for _ in 0..3 {
+                manager.poll_triggered_collectors(robot_start_time).await;
+                assert_eq!(stored.borrow().len(), 1);
+            }
+
+            // an event seen by a capture isn't read twice
+            board.lock().unwrap().set_gpio_pin_level(4, true).unwrap();
+            assert!(manager
+                .collect_and_store_readings(999, robot_start_time)
+                .await
+                .is_ok());
+            manager.poll_triggered_collectors(robot_start_time).await;
+            assert_eq!(stored.borrow().len(), 2);
+        });
+    }
+
+    #[cfg(feature = "native")]
+    type ReceivedRequests = Rc<RefCell<Vec<(String, Bytes)>>>;
+
+    // Records the path and body of every upload request, and answers each call with
+    // an empty response
+    #[cfg(feature = "native")]
+    async fn run_fake_data_sync_server(
+        listener: Async<TcpListener>,
+        exec: Executor,
+        requests: ReceivedRequests,
+    ) {
+        loop {
+            let (incoming, _peer) = listener.accept().await.unwrap();
+            let requests = requests.clone();
+            let service = service_fn(move |req: hyper::Request<Incoming>| {
+                let requests = requests.clone();
+                async move {
+                    let path = req.uri().path().to_string();
+                    let body = req.into_body().collect().await?.to_bytes();
+                    let resp = if path == "/proto.rpc.v1.AuthService/Authenticate" {
+                        encode_request(AuthenticateResponse {
+                            access_token: "fake".to_string(),
+                        })
+                    } else {
+                        requests.borrow_mut().push((path, body));
+                        encode_request(DataCaptureUploadResponse::default())
+                    };
+                    let mut body = GrpcBody::new();
+                    body.put_data(resp.unwrap());
+                    Ok::<_, hyper::Error>(
+                        Response::builder()
+                            .status(200)
+                            .header(CONTENT_TYPE, "application/grpc")
+                            .body(body)
+                            .unwrap(),
+                    )
+                }
+            });
+            let conn = http2::Builder::new(exec.clone())
+                .serve_connection(NativeStream::LocalPlain(incoming), service);
+            if conn.await.is_err() {
+                break;
+            }
+        }
+    }
+
+    #[cfg(feature = "native")]
+    fn start_fake_data_sync_server(exec: &Executor) -> (u16, ReceivedRequests) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let listener: Async<TcpListener> = listener.try_into().unwrap();
+        let requests = ReceivedRequests::default();
+        let cloned_requests = requests.clone();
+        let cloned_exec = exec.clone();
+        exec.spawn(async move {
+            run_fake_data_sync_server(listener, cloned_exec, cloned_requests).await
+        })
+        .detach();
+        (port, requests)
+    }
+
+    #[cfg(feature = "native")]
+    async fn connect_app_client(port: u16, exec: Executor) -> AppClient {
+        let stream = Async::<TcpStream>::connect(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+            .await
+            .unwrap();
+        let uri = "http://localhost".parse().unwrap();
+        let grpc_client = GrpcClient::new(NativeStream::LocalPlain(stream), exec, uri)
+            .await
+            .unwrap();
+        let creds = RobotCredentials::new(
+            "robot".to_string(),
+            "secret".to_string(),
+            "http://localhost".to_string(),
+        )
+        .unwrap();
+        AppClientBuilder::new(Box::new(grpc_client), creds)
+            .build()
+            .await
+            .unwrap()
+    }
+
+    // splits a request body in the length prefixed messages it is made of
+    #[cfg(feature = "native")]
+    fn grpc_messages(mut body: Bytes) -> Vec<Bytes> {
+        let mut messages = vec![];
+        while body.has_remaining() {
+            let _compressed = body.get_u8();
+            let len = body.get_u32() as usize;
+            messages.push(body.split_to(len));
+        }
+        messages
+    }
+
+    #[cfg(feature = "native")]
+    fn binary_reading(len: usize) -> SensorData {
+        SensorData {
+            metadata: Some(SensorMetadata {
+                mime_type: MimeType::ImageJpeg.into(),
+                ..Default::default()
+            }),
+            data: Some(Data::Binary((0..len).map(|i| i as u8).collect())),
+        }
+    }
+
+    #[cfg(feature = "native")]
+    fn check_binary_upload(request: &(String, Bytes), expected: &SensorData) {
+        let Some(Data::Binary(expected_image)) = expected.data.as_ref() else {
+            panic!("expected a binary reading");
+        };
+        let messages = grpc_messages(request.1.clone());
+        if request.0 == "/viam.app.datasync.v1.DataSyncService/DataCaptureUpload" {
+            assert_eq!(messages.len(), 1);
+            let req = DataCaptureUploadRequest::decode(messages[0].clone()).unwrap();
+            assert_eq!(req.metadata.unwrap().file_extension, ".jpeg");
+            assert_eq!(req.sensor_contents.len(), 1);
+            assert_eq!(req.sensor_contents[0].data, expected.data);
+            return;
+        }
+        assert_eq!(
+            request.0,
+            "/viam.app.datasync.v1.DataSyncService/StreamingDataCaptureUpload"
+        );
+        let mut packets = messages.into_iter().map(|msg| {
+            StreamingDataCaptureUploadRequest::decode(msg)
+                .unwrap()
+                .upload_packet
+        });
+        let Some(Some(UploadPacket::Metadata(metadata))) = packets.next() else {
+            panic!("the first packet of a streaming upload should be the metadata");
+        };
+        assert_eq!(metadata.upload_metadata.unwrap().file_extension, ".jpeg");
+        assert_eq!(
+            metadata.sensor_metadata.map(|m| m.mime_type),
+            Some(MimeType::ImageJpeg.into())
+        );
+        let mut image = vec![];
+        for packet in packets {
+            let Some(UploadPacket::Data(chunk)) = packet else {
+                panic!("expected a data packet");
+            };
+            assert!(!chunk.is_empty() && chunk.len() <= MAX_SENSOR_CONTENTS_SIZE);
+            image.extend_from_slice(&chunk);
+        }
+        assert_eq!(&image, expected_image);
+    }
+
+    #[cfg(feature = "native")]
+    #[test_log::test]
+    fn test_upload_binary_reading() {
+        let exec = Executor::new();
+        let (port, requests) = start_fake_data_sync_server(&exec);
+        let coll_key = ResourceMethodKey {
+            r_name: "camera".to_string(),
+            component_type: "rdk:component:camera".to_string(),
+            method: CollectionMethod::ReadImage,
+        };
+        let small = binary_reading(1000);
+        let large = binary_reading(3 * MAX_SENSOR_CONTENTS_SIZE + 100);
+
+        let cloned_exec = exec.clone();
+        exec.block_on(async {
+            let app_client = connect_app_client(port, cloned_exec).await;
+            for reading in [&small, &large] {
+                let reading = BinarySensorData::try_from(reading.clone()).unwrap();
+                assert!(
+                    upload_binary_reading(&app_client, "part", &coll_key, reading)
+                        .await
+                        .is_ok()
+                );
+            }
+        });
+
+        let requests = requests.borrow();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].0,
+            "/viam.app.datasync.v1.DataSyncService/DataCaptureUpload"
+        );
+        check_binary_upload(&requests[0], &small);
+        assert_eq!(
+            requests[1].0,
+            "/viam.app.datasync.v1.DataSyncService/StreamingDataCaptureUpload"
+        );
+        let messages = grpc_messages(requests[1].1.clone());
+        // the metadata followed by three full chunks and the remaining 100 bytes
+        assert_eq!(messages.len(), 5);
+        check_binary_upload(&requests[1], &large);
+    }
+
+    #[cfg(feature = "native")]
+    struct BinaryStoreReader {
+        messages: Rc<RefCell<VecDeque<SensorData>>>,
+    }
+
+    #[cfg(feature = "native")]
+    impl DataStoreReader for BinaryStoreReader {
+        fn read_next_message(&mut self) -> Result<BytesMut, DataStoreError> {
+            match self.messages.borrow_mut().pop_front() {
+                Some(msg) => {
+                    let mut res = BytesMut::with_capacity(msg.encoded_len());
+                    msg.encode(&mut res)?;
+                    Ok(res)
+                }
+                None => Ok(BytesMut::with_capacity(0)),
+            }
+        }
+        fn messages_remaining(&self) -> Result<usize, DataStoreError> {
+            Ok(self.messages.borrow().len())
+        }
+        fn flush(self) {}
+    }
+
+    #[cfg(feature = "native")]
+    struct BinaryStore {
+        messages: Rc<RefCell<VecDeque<SensorData>>>,
+    }
+
+    #[cfg(feature = "native")]
+    impl DataStore for BinaryStore {
+        type Reader = BinaryStoreReader;
+        fn write_message(
+            &mut self,
+            _collector_key: &ResourceMethodKey,
+            message: SensorData,
+            _write_mode: WriteMode,
+        ) -> Result<(), DataStoreError> {
+            self.messages.borrow_mut().push_back(message);
+            Ok(())
+        }
+        fn from_resource_method_settings(
+            _collector_settings: Vec<(ResourceMethodKey, usize)>,
+        ) -> Result<Self, DataStoreError> {
+            Ok(Self {
+                messages: Default::default(),
+            })
+        }
+        fn get_reader(
+            &self,
+            _collector_key: &ResourceMethodKey,
+        ) -> Result<Self::Reader, DataStoreError> {
+            Ok(BinaryStoreReader {
+                messages: self.messages.clone(),
+            })
+        }
+    }
+
+    #[cfg(feature = "native")]
+    #[test_log::test]
+    fn test_sync_binary_data() {
+        let exec = Executor::new();
+        let (port, requests) = start_fake_data_sync_server(&exec);
+        let coll_key = ResourceMethodKey {
+            r_name: "camera".to_string(),
+            component_type: "rdk:component:camera".to_string(),
+            method: CollectionMethod::ReadImage,
+        };
+        let readings = [
+            binary_reading(1000),
+            binary_reading(2 * MAX_SENSOR_CONTENTS_SIZE),
+            binary_reading(2000),
+        ];
+        let mut store = BinaryStore::from_resource_method_settings(vec![]).unwrap();
+        for reading in readings.iter() {
+            assert!(store
+                .write_message(&coll_key, reading.clone(), WriteMode::PreserveOrFail)
+                .is_ok());
+        }
+        let messages = store.messages.clone();
+        let sync_task = DataSyncTask {
+            store: Rc::new(AsyncMutex::new(store)),
+            resource_method_keys: vec![coll_key],
+            sync_interval: Duration::from_secs(1),
+            part_id: "part".to_string(),
+            robot_start_time: Instant::now(),
+        };
+
+        let cloned_exec = exec.clone();
+        exec.block_on(async {
+            let app_client = connect_app_client(port, cloned_exec).await;
+            assert!(sync_task.run(&app_client).await.is_ok());
+        });
+
+        assert!(messages.borrow().is_empty());
+        let requests = requests.borrow();
+        assert_eq!(requests.len(), readings.len());
+        for (request, reading) in requests.iter().zip(readings.iter()) {
+            check_binary_upload(request, reading);
+        }
+    }
 }
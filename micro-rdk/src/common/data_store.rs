@@ -52,6 +52,12 @@ pub enum DataStoreError {
     BufferInUse(ResourceMethodKey),
     #[error("unimplemented")]
     Unimplemented,
+    #[error("data partition unavailable: {0}")]
+    PartitionUnavailable(String),
+    #[error("data partition error: {0}")]
+    PartitionError(String),
+    #[error("data partition too small for {0} collectors")]
+    PartitionTooSmall(usize),
 }
 
 /// A trait for an entity that is capable of reading from a store region without consuming
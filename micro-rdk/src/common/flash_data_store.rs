@@ -0,0 +1,1155 @@
+//! A DataStore persisting captured data to a flash partition (or a file standing in for one on
+//! native), so readings that weren't synced yet survive reboots, deep sleep and power loss.
+//!
+//! The partition is split into sector sized segments. Every collector may use a number of
+//! segments proportional to its configured capacity, taken from a pool of free segments shared
+//! by all collectors. Records are only ever appended, a segment is erased right before being
+//! reused and released segments go to the back of the pool so erase cycles are spread over the
+//! partition. Synced records are marked as consumed in place by clearing bits of their header,
+//! which flash allows without erasing.
+//!
+//! Segment layout: | magic (u32) | sequence number (u32) | collector hash (u32) | fragments.. |
+//! Fragment layout: | payload length (u32) | state (u8) | flags (u8) | payload |
+//!
+//! A record larger than the room left in a segment is split in fragments, the first one flagged
+//! as starting the record and all but the last one as continuing at the start of the next
+//! segment of the collector. The first fragment is written with an erased state which is only
+//! set to valid once the whole record is written, a fragment left with an erased state was torn
+//! by a power loss and closes its segment. Segments are attributed to collectors by the hash of
+//! their key, segments of collectors that aren't configured anymore are discarded.
+//!
+//! Records captured before the clock was set are flagged as holding timestamps relative to the
+//! boot that wrote them. They can't be dated after a reboot, so the first time the store is
+//! opened in a boot such records left by an earlier boot are dropped.
+//!
+//! The store is used when available: on ESP32 the partition table needs a data partition
+//! labelled `data_store` (any subtype), e.g. `data_store, data, 0x40, , 0x80000,`, on native
+//! the `MICRO_RDK_DATA_STORE_PATH` environment variable points to the backing file.
+
+use crate::{
+    common::app_client::VIAM_FOUNDING_YEAR, google::protobuf::Timestamp,
+    proto::app::data_sync::v1::SensorData,
+};
+use bytes::BytesMut;
+use chrono::{offset::Local, Datelike};
+use prost::Message;
+use scopeguard::defer;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    rc::Rc,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::{
+    data_collector::ResourceMethodKey,
+    data_store::{DataStore, DataStoreError, DataStoreReader, WriteMode},
+};
+
+/// Storage with the semantics of NOR flash: writes can only clear bits, a sector has to be
+/// erased (set to 0xFF) before its content can be written again.
+pub trait DataPartition {
+    /// Opens the partition reserved for data capture, returns
+    /// [DataStoreError::PartitionUnavailable] when the platform doesn't provide one
+    fn open() -> Result<Self, DataStoreError>
+    where
+        Self: Sized;
+    /// Size in bytes of the smallest erasable unit
+    fn sector_size(&self) -> usize;
+    fn size(&self) -> usize;
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), DataStoreError>;
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), DataStoreError>;
+    fn erase_sector(&mut self, sector: usize) -> Result<(), DataStoreError>;
+}
+
+#[cfg(feature = "esp32")]
+pub type PersistentDataStore = FlashDataStore<crate::esp32::data_partition::EspDataPartition>;
+#[cfg(all(feature = "native", not(feature = "esp32")))]
+pub type PersistentDataStore = FlashDataStore<crate::native::data_partition::FileDataPartition>;
+
+const SEGMENT_MAGIC: u32 = 0x5344_5246;
+const SEGMENT_HEADER_LEN: usize = 12;
+const RECORD_HEADER_LEN: usize = 6;
+const RECORD_STATE_OFFSET: usize = 4;
+const RECORD_FLAGS_OFFSET: usize = 5;
+const ERASED: u8 = 0xFF;
+const RECORD_VALID: u8 = 0x0F;
+const RECORD_CONSUMED: u8 = 0x00;
+// the fragment is the first one of its record
+const FRAGMENT_STARTS: u8 = 0x01;
+// the record goes on at the start of the next segment
+const FRAGMENT_CONTINUES: u8 = 0x02;
+// the timestamps of the record are offsets from the start of the boot that wrote it
+const BOOT_RELATIVE: u8 = 0x04;
+// a collector needs a segment to write to while the previous one waits to be synced
+const MIN_SEGMENTS_PER_COLLECTOR: usize = 2;
+
+// set once the store was opened in this boot, records with relative timestamps found after
+// that were written by this boot
+static OPENED_THIS_BOOT: AtomicBool = AtomicBool::new(false);
+
+fn collector_hash(key: &ResourceMethodKey) -> u32 {
+    // FNV-1a
+    key.to_string().bytes().fold(0x811c_9dc5_u32, |hash, byte| {
+        (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[..4].try_into().unwrap())
+}
+
+/// Timestamps of captured readings are offsets from the robot start, which are meaningless
+/// after a reboot. When the clock is set they are converted to absolute times before being
+/// persisted (data sync leaves timestamps ahead of the robot uptime untouched). Returns true
+/// when the reading keeps relative timestamps because the clock isn't set yet.
+fn anchor_timestamps(message: &mut SensorData) -> bool {
+    let Some(metadata) = message.metadata.as_mut() else {
+        return false;
+    };
+    let now = Local::now().fixed_offset();
+    if now.year() < VIAM_FOUNDING_YEAR {
+        return metadata.time_received.is_some() || metadata.time_requested.is_some();
+    }
+    let to_duration = |ts: &Timestamp| chrono::Duration::new(ts.seconds, ts.nanos as u32);
+    // the reading was just received, so the robot started `time_received` ago
+    let Some(robot_start) = metadata
+        .time_received
+        .as_ref()
+        .and_then(to_duration)
+        .and_then(|elapsed| now.checked_sub_signed(elapsed))
+    else {
+        return false;
+    };
+    if robot_start.year() < VIAM_FOUNDING_YEAR {
+        // timestamps are already absolute
+        return false;
+    }
+    for ts in [&mut metadata.time_received, &mut metadata.time_requested]
+        .into_iter()
+        .flatten()
+    {
+        if let Some(dt) =
+            to_duration(&*ts).and_then(|offset| robot_start.checked_add_signed(offset))
+        {
+            *ts = Timestamp {
+                seconds: dt.timestamp(),
+                nanos: dt.timestamp_subsec_nanos() as i32,
+            };
+        }
+    }
+    false
+}
+
+#[derive(Clone, Default)]
+struct Segment {
+    sector: usize,
+    seq: u32,
+    // offset of the first record not consumed yet
+    read_offset: usize,
+    write_offset: usize,
+    messages: usize,
+    // set when a write was torn, nothing else can be appended to the segment
+    closed: bool,
+    // records with relative timestamps from an earlier boot dropped by the scan
+    expired: usize,
+}
+
+impl Segment {
+    // records with relative timestamps are dropped when `new_boot` is set, they were written
+    // by an earlier boot
+    fn scan<P: DataPartition>(
+        partition: &mut P,
+        sector: usize,
+        seq: u32,
+        new_boot: bool,
+    ) -> Result<Self, DataStoreError> {
+        let sector_size = partition.sector_size();
+        let base = sector * sector_size;
+        let mut segment = Self {
+            sector,
+            seq,
+            ..Default::default()
+        };
+        let mut offset = SEGMENT_HEADER_LEN;
+        let mut first_unconsumed = None;
+        while offset + RECORD_HEADER_LEN <= sector_size {
+            let mut header = [0_u8; RECORD_HEADER_LEN];
+            partition.read(base + offset, &mut header)?;
+            if header == [ERASED; RECORD_HEADER_LEN] {
+                break;
+            }
+            let len = read_u32(&header) as usize;
+            let mut state = header[RECORD_STATE_OFFSET];
+            let flags = header[RECORD_FLAGS_OFFSET];
+            if len > sector_size - offset - RECORD_HEADER_LEN
+                || flags & !(FRAGMENT_STARTS | FRAGMENT_CONTINUES | BOOT_RELATIVE) != 0
+                || (state != RECORD_VALID && state != RECORD_CONSUMED)
+            {
+                segment.closed = true;
+                break;
+            }
+            if new_boot && state == RECORD_VALID && flags & BOOT_RELATIVE != 0 {
+                partition.write(base + offset + RECORD_STATE_OFFSET, &[RECORD_CONSUMED])?;
+                state = RECORD_CONSUMED;
+                segment.expired += 1;
+            }
+            // the rest of a record is consumed along with its first fragment
+            if state == RECORD_VALID && flags & FRAGMENT_STARTS != 0 {
+                segment.messages += 1;
+                let _ = first_unconsumed.get_or_insert(offset);
+            }
+            offset += RECORD_HEADER_LEN + len;
+        }
+        segment.write_offset = offset;
+        segment.read_offset = first_unconsumed.unwrap_or(offset);
+        Ok(segment)
+    }
+
+    fn is_consumed(&self) -> bool {
+        self.read_offset >= self.write_offset
+    }
+
+    fn base(&self, sector_size: usize) -> usize {
+        self.sector * sector_size
+    }
+}
+
+/// The segments used by a collector
+struct Region {
+    hash: u32,
+    // the most segments the collector may use
+    quota: usize,
+    // segments holding data, oldest first
+    segments: VecDeque<Segment>,
+    next_seq: u32,
+    // sectors no collector uses, shared by all regions
+    free: Rc<RefCell<VecDeque<usize>>>,
+}
+
+impl Region {
+    fn messages(&self) -> usize {
+        self.segments.iter().map(|segment| segment.messages).sum()
+    }
+
+    // frees the oldest segments once all their records were synced, the segment being
+    // written to is kept
+    fn release_consumed(&mut self) {
+        while self.segments.len() > 1 && self.segments[0].is_consumed() {
+            let segment = self.segments.pop_front().unwrap();
+            self.free.borrow_mut().push_back(segment.sector);
+        }
+    }
+
+    fn start_segment<P: DataPartition>(
+        &mut self,
+        partition: &mut P,
+        key: &ResourceMethodKey,
+        write_mode: WriteMode,
+    ) -> Result<(), DataStoreError> {
+        if self.segments.len() >= self.quota {
+            if !matches!(write_mode, WriteMode::OverwriteOldest) {
+                return Err(DataStoreError::DataBufferFull(key.clone()));
+            }
+            let oldest = self.segments.pop_front().unwrap();
+            log::warn!(
+                "persistent store full for {}, dropping {} unsynced messages",
+                key,
+                oldest.messages
+            );
+            self.free.borrow_mut().push_back(oldest.sector);
+        }
+        let sector = self
+            .free
+            .borrow_mut()
+            .pop_front()
+            .ok_or(DataStoreError::DataBufferFull(key.clone()))?;
+        let mut header = Vec::with_capacity(SEGMENT_HEADER_LEN);
+        header.extend_from_slice(&SEGMENT_MAGIC.to_le_bytes());
+        header.extend_from_slice(&self.next_seq.to_le_bytes());
+        header.extend_from_slice(&self.hash.to_le_bytes());
+        let started = partition
+            .erase_sector(sector)
+            .and_then(|_| partition.write(sector * partition.sector_size(), &header));
+        if let Err(err) = started {
+            self.free.borrow_mut().push_back(sector);
+            return Err(err);
+        }
+        self.segments.push_back(Segment {
+            sector,
+            seq: self.next_seq,
+            read_offset: SEGMENT_HEADER_LEN,
+            write_offset: SEGMENT_HEADER_LEN,
+            ..Default::default()
+        });
+        self.next_seq = self.next_seq.wrapping_add(1);
+        Ok(())
+    }
+
+    fn append<P: DataPartition>(
+        &mut self,
+        partition: &mut P,
+        key: &ResourceMethodKey,
+        payload: &[u8],
+        boot_relative: bool,
+        write_mode: WriteMode,
+    ) -> Result<(), DataStoreError> {
+        let sector_size = partition.sector_size();
+        let fragment_capacity = sector_size - SEGMENT_HEADER_LEN - RECORD_HEADER_LEN;
+        // a record never needs every segment of the collector so writing it can't drop its
+        // own first fragment
+        let max_payload_len = (self.quota - 1) * fragment_capacity;
+        if payload.len() > max_payload_len {
+            return Err(DataStoreError::DataTooLarge(
+                key.clone(),
+                payload.len(),
+                max_payload_len,
+            ));
+        }
+        self.release_consumed();
+        let head_room = self
+            .segments
+            .back()
+            .filter(|segment| !segment.closed)
+            .map_or(0, |segment| {
+                (sector_size - segment.write_offset).saturating_sub(RECORD_HEADER_LEN)
+            });
+        let in_new_segments = if head_room > 0 {
+            payload.len().saturating_sub(head_room)
+        } else {
+            payload.len().max(1)
+        };
+        let new_segments = in_new_segments.div_ceil(fragment_capacity);
+        if new_segments > self.quota - self.segments.len()
+            && !matches!(write_mode, WriteMode::OverwriteOldest)
+        {
+            return Err(DataStoreError::DataBufferFull(key.clone()));
+        }
+
+        let mut first = None;
+        let mut rest = payload;
+        let written = loop {
+            let room = if first.is_none() && head_room > 0 {
+                head_room
+            } else if let Err(err) = self.start_segment(partition, key, write_mode) {
+                break Err(err);
+            } else {
+                fragment_capacity
+            };
+            let (chunk, remaining) = rest.split_at(room.min(rest.len()));
+            rest = remaining;
+            let mut flags = 0;
+            if first.is_none() {
+                flags |= FRAGMENT_STARTS;
+                if boot_relative {
+                    flags |= BOOT_RELATIVE;
+                }
+            }
+            if !rest.is_empty() {
+                flags |= FRAGMENT_CONTINUES;
+            }
+            let segment = self.segments.back_mut().unwrap();
+            let offset = segment.write_offset;
+            let mut fragment = Vec::with_capacity(RECORD_HEADER_LEN + chunk.len());
+            fragment.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            // the first fragment commits the record once everything else is written
+            fragment.push(if first.is_none() {
+                ERASED
+            } else {
+                RECORD_VALID
+            });
+            fragment.push(flags);
+            fragment.extend_from_slice(chunk);
+            if let Err(err) = partition.write(segment.base(sector_size) + offset, &fragment) {
+                segment.closed = true;
+                break Err(err);
+            }
+            segment.write_offset += fragment.len();
+            match first {
+                None => first = Some((segment.seq, offset)),
+                // nothing to read before the end of a continuation
+                Some(_) if segment.read_offset == offset => {
+                    segment.read_offset = segment.write_offset
+                }
+                Some(_) => {}
+            }
+            if rest.is_empty() {
+                break Ok(());
+            }
+        };
+        let Some((seq, offset)) = first else {
+            return written;
+        };
+        let segment = self
+            .segments
+            .iter_mut()
+            .find(|segment| segment.seq == seq)
+            .unwrap();
+        let committed = written.and_then(|_| {
+            partition.write(
+                segment.base(sector_size) + offset + RECORD_STATE_OFFSET,
+                &[RECORD_VALID],
+            )
+        });
+        if let Err(err) = committed {
+            segment.closed = true;
+            return Err(err);
+        }
+        segment.messages += 1;
+        Ok(())
+    }
+}
+
+/// A DataStore backed by a [DataPartition], see the module documentation for the layout.
+/// Like [super::data_store::DefaultDataStore] it should only be initialized once and is not
+/// thread-safe. The partition is split between collectors proportionally to their capacity,
+/// the 64 KB limit of the RAM store doesn't apply.
+pub struct FlashDataStore<P> {
+    partition: Rc<RefCell<P>>,
+    regions: Vec<Rc<RefCell<Region>>>,
+    buffer_usages: Vec<Rc<AtomicBool>>,
+    collector_keys: Vec<ResourceMethodKey>,
+}
+
+impl<P: DataPartition> FlashDataStore<P> {
+    pub fn new(
+        partition: P,
+        collector_settings: Vec<(ResourceMethodKey, usize)>,
+    ) -> Result<Self, DataStoreError> {
+        let new_boot = !OPENED_THIS_BOOT.swap(true, Ordering::Relaxed);
+        Self::open(partition, collector_settings, new_boot)
+    }
+
+    fn open(
+        mut partition: P,
+        collector_settings: Vec<(ResourceMethodKey, usize)>,
+        new_boot: bool,
+    ) -> Result<Self, DataStoreError> {
+        if collector_settings.is_empty() {
+            return Err(DataStoreError::NoCollectors);
+        }
+        let num_collectors = collector_settings.len();
+        let sector_size = partition.sector_size();
+        let sectors = partition.size() / sector_size;
+        let total_capacity = collector_settings
+            .iter()
+            .map(|(_, capacity)| *capacity)
+            .sum::<usize>()
+            .max(1);
+        let quotas: Vec<usize> = collector_settings
+            .iter()
+            .map(|(_, capacity)| {
+                (sectors * capacity / total_capacity).max(MIN_SEGMENTS_PER_COLLECTOR)
+            })
+            .collect();
+        if quotas.iter().sum::<usize>() > sectors {
+            return Err(DataStoreError::PartitionTooSmall(num_collectors));
+        }
+
+        // segments are found by collector wherever they are, so the data of a collector
+        // survives other collectors being added or removed
+        let mut headers = Vec::new();
+        for sector in 0..sectors {
+            let mut header = [0_u8; SEGMENT_HEADER_LEN];
+            partition.read(sector * sector_size, &mut header)?;
+            if read_u32(&header[0..]) == SEGMENT_MAGIC {
+                headers.push((sector, read_u32(&header[4..]), read_u32(&header[8..])));
+            }
+        }
+        let free = Rc::new(RefCell::new(VecDeque::new()));
+        let mut used = vec![false; sectors];
+        let mut regions = Vec::new();
+        let mut buffer_usages = Vec::new();
+        let mut collector_keys = vec![];
+        for ((collector_key, _), quota) in collector_settings.into_iter().zip(quotas) {
+            let hash = collector_hash(&collector_key);
+            let mut segments = headers
+                .iter()
+                .filter(|(_, _, segment_hash)| *segment_hash == hash)
+                .map(|(sector, seq, _)| Segment::scan(&mut partition, *sector, *seq, new_boot))
+                .collect::<Result<Vec<_>, _>>()?;
+            let expired: usize = segments.iter().map(|segment| segment.expired).sum();
+            if expired > 0 {
+                log::warn!(
+                    "dropping {} messages of {} captured before the clock was set in an earlier boot",
+                    expired,
+                    collector_key
+                );
+            }
+            segments.sort_by_key(|segment| segment.seq);
+            // the collector's share shrank, keep its newest segments
+            let dropped = segments.len().saturating_sub(quota);
+            let dropped_messages: usize = segments
+                .drain(..dropped)
+                .map(|segment| segment.messages)
+                .sum();
+            if dropped_messages > 0 {
+                log::warn!(
+                    "persistent store shrank for {}, dropping {} unsynced messages",
+                    collector_key,
+                    dropped_messages
+                );
+            }
+            for segment in segments.iter() {
+                used[segment.sector] = true;
+            }
+            let next_seq = segments
+                .last()
+                .map_or(0, |segment| segment.seq.wrapping_add(1));
+            let mut region = Region {
+                hash,
+                quota,
+                segments: segments.into(),
+                next_seq,
+                free: free.clone(),
+            };
+            region.release_consumed();
+            let messages = region.messages();
+            if messages > 0 {
+                log::info!(
+                    "recovered {} unsynced messages for {} from persistent store",
+                    messages,
+                    collector_key
+                );
+            }
+            regions.push(Rc::new(RefCell::new(region)));
+            buffer_usages.push(Rc::new(AtomicBool::new(false)));
+            collector_keys.push(collector_key);
+        }
+        // segments released by the regions above went to the back, the others come first
+        let released: Vec<usize> = free.borrow_mut().drain(..).collect();
+        free.borrow_mut()
+            .extend((0..sectors).filter(|sector| !used[*sector]).chain(released));
+        Ok(Self {
+            partition: Rc::new(RefCell::new(partition)),
+            regions,
+            buffer_usages,
+            collector_keys,
+        })
+    }
+
+    fn get_index_for_collector(
+        &self,
+        collector_key: &ResourceMethodKey,
+    ) -> Result<usize, DataStoreError> {
+        self.collector_keys
+            .iter()
+            .position(|key| key == collector_key)
+            .ok_or(DataStoreError::UnknownCollectorKey(collector_key.clone()))
+    }
+
+    fn buffer_in_use(&self, buffer_index: usize) -> bool {
+        self.buffer_usages[buffer_index].load(Ordering::Relaxed)
+    }
+
+    fn register_buffer_usage(&self, buffer_index: usize) {
+        self.buffer_usages[buffer_index].store(true, Ordering::Relaxed);
+    }
+
+    fn unregister_buffer_usage(&self, buffer_index: usize) {
+        self.buffer_usages[buffer_index].store(false, Ordering::Relaxed);
+    }
+}
+
+impl<P: DataPartition> DataStore for FlashDataStore<P> {
+    type Reader = FlashDataStoreReader<P>;
+
+    fn write_message(
+        &mut self,
+        collector_key: &ResourceMethodKey,
+        mut message: SensorData,
+        write_mode: WriteMode,
+    ) -> Result<(), DataStoreError> {
+        let buffer_index = self.get_index_for_collector(collector_key)?;
+        if self.buffer_in_use(buffer_index) {
+            return Err(DataStoreError::BufferInUse(collector_key.clone()));
+        } else {
+            self.register_buffer_usage(buffer_index);
+        }
+        defer! {
+            self.unregister_buffer_usage(buffer_index);
+        }
+        let boot_relative = anchor_timestamps(&mut message);
+        self.regions[buffer_index].borrow_mut().append(
+            &mut *self.partition.borrow_mut(),
+            collector_key,
+            &message.encode_to_vec(),
+            boot_relative,
+            write_mode,
+        )
+    }
+
+    fn from_resource_method_settings(
+        settings: Vec<(ResourceMethodKey, usize)>,
+    ) -> Result<Self, DataStoreError> {
+        Self::new(P::open()?, settings)
+    }
+
+    fn get_reader(
+        &self,
+        collector_key: &ResourceMethodKey,
+    ) -> Result<Self::Reader, DataStoreError> {
+        let buffer_index = self.get_index_for_collector(collector_key)?;
+        if self.buffer_in_use(buffer_index) {
+            return Err(DataStoreError::BufferInUse(collector_key.clone()));
+        }
+        self.register_buffer_usage(buffer_index);
+        Ok(FlashDataStoreReader {
+            partition: self.partition.clone(),
+            region: self.regions[buffer_index].clone(),
+            position: (0, 0),
+            read: vec![],
+            buffer_registration: self.buffer_usages[buffer_index].clone(),
+        })
+    }
+}
+
+pub struct FlashDataStoreReader<P> {
+    partition: Rc<RefCell<P>>,
+    region: Rc<RefCell<Region>>,
+    // next record to read as an index in the region's segments and an offset
+    position: (usize, usize),
+    // records returned so far as the segment index and offset of their first fragment, and
+    // the segment index and offset where they end. They are consumed on flush
+    read: Vec<(usize, usize, usize, usize)>,
+    buffer_registration: Rc<AtomicBool>,
+}
+
+impl<P: DataPartition> FlashDataStoreReader<P> {
+    // reads the header of the fragment at `offset` in a segment, returns its payload length,
+    // state and flags
+    fn read_fragment_header(
+        partition: &P,
+        segment: &Segment,
+        offset: usize,
+    ) -> Result<(usize, u8, u8), DataStoreError> {
+        let mut header = [0_u8; RECORD_HEADER_LEN];
+        partition.read(segment.base(partition.sector_size()) + offset, &mut header)?;
+        let len = read_u32(&header) as usize;
+        if offset + RECORD_HEADER_LEN + len > segment.write_offset {
+            return Err(DataStoreError::DataIntegrityError);
+        }
+        Ok((
+            len,
+            header[RECORD_STATE_OFFSET],
+            header[RECORD_FLAGS_OFFSET],
+        ))
+    }
+}
+
+impl<P: DataPartition> DataStoreReader for FlashDataStoreReader<P> {
+    fn read_next_message(&mut self) -> Result<BytesMut, DataStoreError> {
+        let region = self.region.borrow();
+        let partition = self.partition.borrow();
+        let sector_size = partition.sector_size();
+        loop {
+            let Some(segment) = region.segments.get(self.position.0) else {
+                return Ok(BytesMut::with_capacity(0));
+            };
+            let offset = self.position.1.max(segment.read_offset);
+            if offset >= segment.write_offset {
+                self.position = (self.position.0 + 1, 0);
+                continue;
+            }
+            let (len, state, mut flags) = Self::read_fragment_header(&partition, segment, offset)?;
+            self.position.1 = offset + RECORD_HEADER_LEN + len;
+            // the rest of a record whose first fragment was dropped with an older segment
+            if flags & FRAGMENT_STARTS == 0 {
+                continue;
+            }
+            match state {
+                RECORD_CONSUMED => continue,
+                RECORD_VALID => {}
+                _ => return Err(DataStoreError::DataIntegrityError),
+            }
+            let start = (self.position.0, offset);
+            let mut msg_bytes = BytesMut::zeroed(len);
+            partition.read(
+                segment.base(sector_size) + offset + RECORD_HEADER_LEN,
+                &mut msg_bytes,
+            )?;
+            let mut seq = segment.seq;
+            while flags & FRAGMENT_CONTINUES != 0 {
+                let next = region
+                    .segments
+                    .get(self.position.0 + 1)
+                    .filter(|next| next.seq == seq.wrapping_add(1))
+                    .ok_or(DataStoreError::DataIntegrityError)?;
+                let len;
+                (len, _, flags) = Self::read_fragment_header(&partition, next, SEGMENT_HEADER_LEN)?;
+                if flags & FRAGMENT_STARTS != 0 {
+                    return Err(DataStoreError::DataIntegrityError);
+                }
+                let payload_offset = SEGMENT_HEADER_LEN + RECORD_HEADER_LEN;
+                let mut fragment = BytesMut::zeroed(len);
+                partition.read(next.base(sector_size) + payload_offset, &mut fragment)?;
+                msg_bytes.unsplit(fragment);
+                seq = next.seq;
+                self.position = (self.position.0 + 1, payload_offset + len);
+            }
+            self.read
+                .push((start.0, start.1, self.position.0, self.position.1));
+            return Ok(msg_bytes);
+        }
+    }
+    fn messages_remaining(&self) -> Result<usize, DataStoreError> {
+        Ok(self.region.borrow().messages() - self.read.len())
+    }
+    fn flush(self) {
+        let mut region = self.region.borrow_mut();
+        let mut partition = self.partition.borrow_mut();
+        let sector_size = partition.sector_size();
+        for (first, offset, last, end) in self.read.iter().copied() {
+            let segment = &mut region.segments[first];
+            if let Err(err) = partition.write(
+                segment.base(sector_size) + offset + RECORD_STATE_OFFSET,
+                &[RECORD_CONSUMED],
+            ) {
+                log::error!("failed to mark synced message as consumed: {:?}", err);
+            }
+            segment.messages = segment.messages.saturating_sub(1);
+            // a record continuing in the next segment fills the rest of the previous ones
+            for idx in first..last {
+                let segment = &mut region.segments[idx];
+                segment.read_offset = segment.write_offset;
+            }
+            region.segments[last].read_offset = end;
+        }
+        region.release_consumed();
+    }
+}
+
+impl<P> Drop for FlashDataStoreReader<P> {
+    fn drop(&mut self) {
+        self.buffer_registration.store(false, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use crate::{
+        common::{
+            data_collector::{CollectionMethod, ResourceMethodKey},
+            data_store::{DataStore, DataStoreError, DataStoreReader, WriteMode},
+            flash_data_store::{DataPartition, FlashDataStore},
+        },
+        google::protobuf::{value::Kind, Struct, Timestamp, Value},
+        proto::app::data_sync::v1::{sensor_data::Data, SensorData, SensorMetadata},
+    };
+    use prost::Message;
+
+    const SECTOR_SIZE: usize = 256;
+
+    /// Emulates NOR flash in RAM, clones share the same content to simulate reboots
+    #[derive(Clone)]
+    struct RamPartition(Rc<RefCell<Vec<u8>>>);
+
+    impl RamPartition {
+        fn new(sectors: usize) -> Self {
+            Self(Rc::new(RefCell::new(vec![0xFF; sectors * SECTOR_SIZE])))
+        }
+    }
+
+    impl DataPartition for RamPartition {
+        fn open() -> Result<Self, DataStoreError> {
+            Err(DataStoreError::PartitionUnavailable("ram".to_string()))
+        }
+        fn sector_size(&self) -> usize {
+            SECTOR_SIZE
+        }
+        fn size(&self) -> usize {
+            self.0.borrow().len()
+        }
+        fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), DataStoreError> {
+            buf.copy_from_slice(&self.0.borrow()[offset..offset + buf.len()]);
+            Ok(())
+        }
+        fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), DataStoreError> {
+            let mut flash = self.0.borrow_mut();
+            for (dst, src) in flash[offset..offset + data.len()].iter_mut().zip(data) {
+                *dst &= *src;
+            }
+            Ok(())
+        }
+        fn erase_sector(&mut self, sector: usize) -> Result<(), DataStoreError> {
+            self.0.borrow_mut()[sector * SECTOR_SIZE..(sector + 1) * SECTOR_SIZE].fill(0xFF);
+            Ok(())
+        }
+    }
+
+    fn key(name: &str) -> ResourceMethodKey {
+        ResourceMethodKey {
+            r_name: name.to_string(),
+            component_type: "rdk:component:sensor".to_string(),
+            method: CollectionMethod::Readings,
+        }
+    }
+
+    fn reading(value: f64) -> SensorData {
+        SensorData {
+            metadata: Some(SensorMetadata {
+                time_received: Some(Timestamp {
+                    seconds: 5,
+                    nanos: 0,
+                }),
+                ..Default::default()
+            }),
+            data: Some(Data::Struct(Struct {
+                fields: HashMap::from([(
+                    "value".to_string(),
+                    Value {
+                        kind: Some(Kind::NumberValue(value)),
+                    },
+                )]),
+            })),
+        }
+    }
+
+    fn read_values<P: DataPartition>(
+        store: &FlashDataStore<P>,
+        key: &ResourceMethodKey,
+    ) -> Vec<f64> {
+        let mut reader = store.get_reader(key).unwrap();
+        let mut values = vec![];
+        loop {
+            let msg = reader.read_next_message().unwrap();
+            if msg.is_empty() {
+                break;
+            }
+            let data = SensorData::decode(msg).unwrap().data;
+            let Some(Data::Struct(data)) = data else {
+                panic!("unexpected data")
+            };
+            let Some(Kind::NumberValue(value)) = data.fields["value"].kind else {
+                panic!("unexpected value")
+            };
+            values.push(value);
+        }
+        values
+    }
+
+    #[test_log::test]
+    fn test_messages_survive_reopening() {
+        let partition = RamPartition::new(16);
+        let settings = vec![(key("a"), 1000), (key("b"), 1000)];
+        let mut store = FlashDataStore::new(partition.clone(), settings.clone()).unwrap();
+        for i in 0..20 {
+            store
+                .write_message(&key("a"), reading(i as f64), WriteMode::PreserveOrFail)
+                .unwrap();
+        }
+        store
+            .write_message(&key("b"), reading(100.0), WriteMode::PreserveOrFail)
+            .unwrap();
+
+        // sync the first 5 messages of "a"
+        {
+            let mut reader = store.get_reader(&key("a")).unwrap();
+            assert_eq!(reader.messages_remaining().unwrap(), 20);
+            for _ in 0..5 {
+                assert!(!reader.read_next_message().unwrap().is_empty());
+            }
+            assert_eq!(reader.messages_remaining().unwrap(), 15);
+            reader.flush();
+        }
+        assert!(matches!(
+            store.write_message(&key("c"), reading(0.0), WriteMode::PreserveOrFail),
+            Err(DataStoreError::UnknownCollectorKey(_))
+        ));
+        drop(store);
+
+        let store = FlashDataStore::new(partition.clone(), settings).unwrap();
+        let expected: Vec<f64> = (5..20).map(|i| i as f64).collect();
+        assert_eq!(read_values(&store, &key("a")), expected);
+        assert_eq!(read_values(&store, &key("b")), vec![100.0]);
+    }
+
+    #[test_log::test]
+    fn test_collectors_changes_keep_data() {
+        let partition = RamPartition::new(16);
+        let mut store =
+            FlashDataStore::new(partition.clone(), vec![(key("a"), 1000), (key("b"), 1000)])
+                .unwrap();
+        for i in 0..20 {
+            store
+                .write_message(&key("a"), reading(i as f64), WriteMode::PreserveOrFail)
+                .unwrap();
+        }
+        store
+            .write_message(&key("b"), reading(100.0), WriteMode::PreserveOrFail)
+            .unwrap();
+        drop(store);
+        let expected: Vec<f64> = (0..20).map(|i| i as f64).collect();
+
+        // a collector is added, the others keep their data
+        let mut store = FlashDataStore::new(
+            partition.clone(),
+            vec![(key("c"), 1000), (key("a"), 1000), (key("b"), 1000)],
+        )
+        .unwrap();
+        assert_eq!(read_values(&store, &key("a")), expected);
+        assert_eq!(read_values(&store, &key("b")), vec![100.0]);
+        for i in 0..20 {
+            store
+                .write_message(&key("c"), reading(i as f64), WriteMode::PreserveOrFail)
+                .unwrap();
+        }
+        drop(store);
+
+        // a collector is removed, its segments are reused by the others
+        let mut store =
+            FlashDataStore::new(partition.clone(), vec![(key("a"), 1000), (key("c"), 1000)])
+                .unwrap();
+        assert_eq!(read_values(&store, &key("a")), expected);
+        assert_eq!(read_values(&store, &key("c")), expected);
+        let mut written = 0;
+        while store
+            .write_message(&key("c"), reading(0.0), WriteMode::PreserveOrFail)
+            .is_ok()
+        {
+            written += 1;
+        }
+        assert!(written > 20);
+        drop(store);
+
+        let store =
+            FlashDataStore::new(partition, vec![(key("a"), 1000), (key("c"), 1000)]).unwrap();
+        assert_eq!(read_values(&store, &key("a")), expected);
+        assert_eq!(read_values(&store, &key("c")).len(), 20 + written);
+    }
+
+    fn binary(len: usize, fill: u8) -> SensorData {
+        SensorData {
+            data: Some(Data::Binary(vec![fill; len])),
+            ..Default::default()
+        }
+    }
+
+    fn read_binaries<P: DataPartition>(
+        store: &FlashDataStore<P>,
+        key: &ResourceMethodKey,
+    ) -> Vec<Vec<u8>> {
+        let mut reader = store.get_reader(key).unwrap();
+        let mut binaries = vec![];
+        loop {
+            let msg = reader.read_next_message().unwrap();
+            if msg.is_empty() {
+                break;
+            }
+            let Some(Data::Binary(data)) = SensorData::decode(msg).unwrap().data else {
+                panic!("unexpected data")
+            };
+            binaries.push(data);
+        }
+        binaries
+    }
+
+    #[test_log::test]
+    fn test_records_span_segments() {
+        let partition = RamPartition::new(8);
+        let settings = vec![(key("a"), 1000)];
+        let mut store = FlashDataStore::new(partition.clone(), settings.clone()).unwrap();
+        // images larger than a sector, with a small record in between
+        store
+            .write_message(&key("a"), binary(600, 1), WriteMode::PreserveOrFail)
+            .unwrap();
+        store
+            .write_message(&key("a"), binary(10, 2), WriteMode::PreserveOrFail)
+            .unwrap();
+        store
+            .write_message(&key("a"), binary(300, 3), WriteMode::PreserveOrFail)
+            .unwrap();
+        assert_eq!(store.regions[0].borrow().segments.len(), 4);
+        drop(store);
+
+        let mut store = FlashDataStore::new(partition.clone(), settings.clone()).unwrap();
+        assert_eq!(
+            read_binaries(&store, &key("a")),
+            vec![vec![1; 600], vec![2; 10], vec![3; 300]]
+        );
+
+        // sync the first two, the segments only holding them are released
+        {
+            let mut reader = store.get_reader(&key("a")).unwrap();
+            assert_eq!(reader.messages_remaining().unwrap(), 3);
+            assert_eq!(reader.read_next_message().unwrap().len(), 600 + 3);
+            assert!(!reader.read_next_message().unwrap().is_empty());
+            reader.flush();
+        }
+        assert_eq!(read_binaries(&store, &key("a")), vec![vec![3; 300]]);
+        assert_eq!(store.regions[0].borrow().segments.len(), 2);
+
+        // the store was too full, the oldest image is dropped and the rest of it is skipped
+        for fill in 4..8 {
+            store
+                .write_message(&key("a"), binary(600, fill), WriteMode::OverwriteOldest)
+                .unwrap();
+        }
+        let binaries = read_binaries(&store, &key("a"));
+        assert_eq!(binaries.last(), Some(&vec![7; 600]));
+        assert!(binaries.iter().all(|binary| binary.len() == 600));
+        drop(store);
+        let store = FlashDataStore::new(partition, settings).unwrap();
+        assert_eq!(read_binaries(&store, &key("a")), binaries);
+    }
+
+    #[test_log::test]
+    fn test_spanning_record_needs_room() {
+        let partition = RamPartition::new(4);
+        let mut store = FlashDataStore::new(partition.clone(), vec![(key("a"), 1000)]).unwrap();
+        store
+            .write_message(&key("a"), binary(500, 1), WriteMode::PreserveOrFail)
+            .unwrap();
+        // nothing is written when the record doesn't fit in the room left
+        assert!(matches!(
+            store.write_message(&key("a"), binary(500, 2), WriteMode::PreserveOrFail),
+            Err(DataStoreError::DataBufferFull(_))
+        ));
+        assert_eq!(read_binaries(&store, &key("a")), vec![vec![1; 500]]);
+        // a record can't take every segment of the collector
+        assert!(matches!(
+            store.write_message(&key("a"), binary(1000, 2), WriteMode::OverwriteOldest),
+            Err(DataStoreError::DataTooLarge(_, _, _))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_torn_spanning_record_is_discarded() {
+        let partition = RamPartition::new(8);
+        let settings = vec![(key("a"), 1000)];
+        let mut store = FlashDataStore::new(partition.clone(), settings.clone()).unwrap();
+        store
+            .write_message(&key("a"), binary(10, 1), WriteMode::PreserveOrFail)
+            .unwrap();
+        let (sector, offset) = {
+            let region = store.regions[0].borrow();
+            let head = region.segments.back().unwrap();
+            (head.sector, head.write_offset)
+        };
+        store
+            .write_message(&key("a"), binary(600, 2), WriteMode::PreserveOrFail)
+            .unwrap();
+        drop(store);
+
+        // power was lost before the first fragment was marked valid
+        {
+            let mut flash = partition.0.borrow_mut();
+            flash[sector * SECTOR_SIZE + offset + 4] = 0xFF;
+        }
+        let mut store = FlashDataStore::new(partition.clone(), settings).unwrap();
+        assert_eq!(read_binaries(&store, &key("a")), vec![vec![1; 10]]);
+        store
+            .write_message(&key("a"), binary(10, 3), WriteMode::PreserveOrFail)
+            .unwrap();
+        assert_eq!(
+            read_binaries(&store, &key("a")),
+            vec![vec![1; 10], vec![3; 10]]
+        );
+    }
+
+    #[test_log::test]
+    fn test_full_store() {
+        let partition = RamPartition::new(2);
+        let mut store = FlashDataStore::new(partition.clone(), vec![(key("a"), 1000)]).unwrap();
+        let mut written = 0;
+        while store
+            .write_message(
+                &key("a"),
+                reading(written as f64),
+                WriteMode::PreserveOrFail,
+            )
+            .is_ok()
+        {
+            written += 1;
+        }
+        assert!(written > 2);
+        assert_eq!(read_values(&store, &key("a")).len(), written);
+
+        // the oldest segment is dropped to make room
+        store
+            .write_message(&key("a"), reading(-1.0), WriteMode::OverwriteOldest)
+            .unwrap();
+        let values = read_values(&store, &key("a"));
+        assert!(values.len() < written);
+        assert_eq!(values.last(), Some(&-1.0));
+
+        let too_large = SensorData {
+            data: Some(Data::Binary(vec![0; SECTOR_SIZE])),
+            ..Default::default()
+        };
+        assert!(matches!(
+            store.write_message(&key("a"), too_large, WriteMode::OverwriteOldest),
+            Err(DataStoreError::DataTooLarge(_, _, _))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_torn_write_is_discarded() {
+        let mut partition = RamPartition::new(4);
+        let settings = vec![(key("a"), 1000)];
+        let mut store = FlashDataStore::new(partition.clone(), settings.clone()).unwrap();
+        for i in 0..3 {
+            store
+                .write_message(&key("a"), reading(i as f64), WriteMode::PreserveOrFail)
+                .unwrap();
+        }
+        let write_offset = store.regions[0].borrow().segments[0].write_offset;
+        drop(store);
+
+        // power was lost after writing the length of a record but before marking it valid
+        partition.write(write_offset, &[10, 0, 0, 0]).unwrap();
+
+        let mut store = FlashDataStore::new(partition, settings).unwrap();
+        assert_eq!(read_values(&store, &key("a")), vec![0.0, 1.0, 2.0]);
+        store
+            .write_message(&key("a"), reading(3.0), WriteMode::PreserveOrFail)
+            .unwrap();
+        // the record went to a new segment
+        assert_eq!(store.regions[0].borrow().segments.len(), 2);
+        assert_eq!(read_values(&store, &key("a")), vec![0.0, 1.0, 2.0, 3.0]);
+    }
+
+    #[test_log::test]
+    fn test_relative_timestamps_expire_on_reboot() {
+        let partition = RamPartition::new(8);
+        let settings = vec![(key("a"), 1000)];
+        let mut store = FlashDataStore::open(partition.clone(), settings.clone(), true).unwrap();
+        // a reading captured before the clock was set, then one anchored to the clock
+        store.regions[0]
+            .borrow_mut()
+            .append(
+                &mut *store.partition.borrow_mut(),
+                &key("a"),
+                &reading(1.0).encode_to_vec(),
+                true,
+                WriteMode::PreserveOrFail,
+            )
+            .unwrap();
+        store
+            .write_message(&key("a"), reading(2.0), WriteMode::PreserveOrFail)
+            .unwrap();
+        drop(store);
+
+        // the store is opened again in the same boot when the config changes
+        let store = FlashDataStore::open(partition.clone(), settings.clone(), false).unwrap();
+        assert_eq!(read_values(&store, &key("a")), vec![1.0, 2.0]);
+        drop(store);
+
+        // after a reboot the relative timestamps can't be dated anymore
+        let store = FlashDataStore::open(partition.clone(), settings.clone(), true).unwrap();
+        assert_eq!(read_values(&store, &key("a")), vec![2.0]);
+        drop(store);
+        let store = FlashDataStore::open(partition, settings, true).unwrap();
+        assert_eq!(read_values(&store, &key("a")), vec![2.0]);
+        assert_eq!(
+            store
+                .get_reader(&key("a"))
+                .unwrap()
+                .messages_remaining()
+                .unwrap(),
+            1
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use super::{
+    actuator::{Actuator, ActuatorError},
+    config::AttributeError,
+    generic::DoCommand,
+};
+
+#[cfg(feature = "builtin-components")]
+use super::{
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency},
+};
+
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "gantry";
+
+#[derive(Debug, Error)]
+pub enum GantryError {
+    #[error(transparent)]
+    GantryActuatorError(#[from] ActuatorError),
+    #[error(transparent)]
+    GantryConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    GantryConfigError(&'static str),
+    #[error("expected {0} axes, got {1}")]
+    GantryAxisCountMismatch(usize, usize),
+    #[error("invalid argument: {0}")]
+    GantryInvalidArgument(&'static str),
+    #[error("unimplemented: {0}")]
+    GantryMethodUnimplemented(&'static str),
+}
+
+pub trait Gantry: Actuator + DoCommand {
+    /// Returns the position of each axis in millimeters
+    fn get_position(&mut self) -> Result<Vec<f64>, GantryError>;
+
+    /// Moves each axis to the given position in millimeters. `speeds_mm_per_sec` may be
+    /// empty to move at the default speed, otherwise it has one speed per axis
+    fn move_to_position(
+        &mut self,
+        positions_mm: Vec<f64>,
+        speeds_mm_per_sec: Vec<f64>,
+    ) -> Result<(), GantryError>;
+
+    /// Returns the length of each axis in millimeters
+    fn get_lengths(&mut self) -> Result<Vec<f64>, GantryError>;
+
+    /// Runs the homing sequence of the gantry, returns whether the gantry was homed
+    fn home(&mut self) -> Result<bool, GantryError> {
+        Err(GantryError::GantryMethodUnimplemented("home"))
+    }
+}
+
+pub type GantryType = Arc<Mutex<dyn Gantry>>;
+
+impl<L> Gantry for Mutex<L>
+where
+    L: ?Sized + Gantry,
+{
+    fn get_position(&mut self) -> Result<Vec<f64>, GantryError> {
+        self.get_mut().unwrap().get_position()
+    }
+    fn move_to_position(
+        &mut self,
+        positions_mm: Vec<f64>,
+        speeds_mm_per_sec: Vec<f64>,
+    ) -> Result<(), GantryError> {
+        self.get_mut()
+            .unwrap()
+            .move_to_position(positions_mm, speeds_mm_per_sec)
+    }
+    fn get_lengths(&mut self) -> Result<Vec<f64>, GantryError> {
+        self.get_mut().unwrap().get_lengths()
+    }
+    fn home(&mut self) -> Result<bool, GantryError> {
+        self.get_mut().unwrap().home()
+    }
+}
+
+impl<A> Gantry for Arc<Mutex<A>>
+where
+    A: ?Sized + Gantry,
+{
+    fn get_position(&mut self) -> Result<Vec<f64>, GantryError> {
+        self.lock().unwrap().get_position()
+    }
+    fn move_to_position(
+        &mut self,
+        positions_mm: Vec<f64>,
+        speeds_mm_per_sec: Vec<f64>,
+    ) -> Result<(), GantryError> {
+        self.lock()
+            .unwrap()
+            .move_to_position(positions_mm, speeds_mm_per_sec)
+    }
+    fn get_lengths(&mut self) -> Result<Vec<f64>, GantryError> {
+        self.lock().unwrap().get_lengths()
+    }
+    fn home(&mut self) -> Result<bool, GantryError> {
+        self.lock().unwrap().home()
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_gantry("fake", &FakeGantry::from_config)
+        .is_err()
+    {
+        log::error!("fake type is already registered");
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+#[derive(DoCommand)]
+pub struct FakeGantry {
+    positions_mm: Vec<f64>,
+    lengths_mm: Vec<f64>,
+}
+
+#[cfg(feature = "builtin-components")]
+impl FakeGantry {
+    pub fn new(lengths_mm: Vec<f64>) -> Self {
+        Self {
+            positions_mm: vec![0.0; lengths_mm.len()],
+            lengths_mm,
+        }
+    }
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<GantryType, GantryError> {
+        let gantry = match cfg.get_attribute::<Vec<f64>>("lengths_mm") {
+            Ok(lengths_mm) => FakeGantry::new(lengths_mm),
+            Err(_) => FakeGantry::default(),
+        };
+        Ok(Arc::new(Mutex::new(gantry)))
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Default for FakeGantry {
+    fn default() -> Self {
+        Self::new(vec![100.0])
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Gantry for FakeGantry {
+    fn get_position(&mut self) -> Result<Vec<f64>, GantryError> {
+        Ok(self.positions_mm.clone())
+    }
+    fn move_to_position(
+        &mut self,
+        positions_mm: Vec<f64>,
+        _speeds_mm_per_sec: Vec<f64>,
+    ) -> Result<(), GantryError> {
+        if positions_mm.len() != self.lengths_mm.len() {
+            return Err(GantryError::GantryAxisCountMismatch(
+                self.lengths_mm.len(),
+                positions_mm.len(),
+            ));
+        }
+        if positions_mm
+            .iter()
+            .zip(self.lengths_mm.iter())
+            .any(|(pos, len)| !(0.0..=*len).contains(pos))
+        {
+            return Err(GantryError::GantryInvalidArgument(
+                "position out of the range of the axis",
+            ));
+        }
+        self.positions_mm = positions_mm;
+        Ok(())
+    }
+    fn get_lengths(&mut self) -> Result<Vec<f64>, GantryError> {
+        Ok(self.lengths_mm.clone())
+    }
+    fn home(&mut self) -> Result<bool, GantryError> {
+        self.positions_mm.iter_mut().for_each(|pos| *pos = 0.0);
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Actuator for FakeGantry {
+    fn is_moving(&mut self) -> Result<bool, ActuatorError> {
+        Ok(false)
+    }
+    fn stop(&mut self) -> Result<(), ActuatorError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FakeGantry, Gantry, GantryError};
+
+    #[test_log::test]
+    fn test_fake_gantry() {
+        let mut gantry = FakeGantry::new(vec![100.0, 50.0]);
+        assert_eq!(gantry.get_lengths().unwrap(), vec![100.0, 50.0]);
+        assert_eq!(gantry.get_position().unwrap(), vec![0.0, 0.0]);
+
+        gantry.move_to_position(vec![20.0, 50.0], vec![]).unwrap();
+        assert_eq!(gantry.get_position().unwrap(), vec![20.0, 50.0]);
+
+        // an invalid position leaves the gantry where it is
+        assert!(matches!(
+            gantry.move_to_position(vec![20.0], vec![]),
+            Err(GantryError::GantryAxisCountMismatch(2, 1))
+        ));
+        assert!(matches!(
+            gantry.move_to_position(vec![10.0, 60.0], vec![]),
+            Err(GantryError::GantryInvalidArgument(_))
+        ));
+        assert!(matches!(
+            gantry.move_to_position(vec![-1.0, 10.0], vec![]),
+            Err(GantryError::GantryInvalidArgument(_))
+        ));
+        assert_eq!(gantry.get_position().unwrap(), vec![20.0, 50.0]);
+
+        assert!(gantry.home().unwrap());
+        assert_eq!(gantry.get_position().unwrap(), vec![0.0, 0.0]);
+    }
+}
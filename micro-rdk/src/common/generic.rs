@@ -31,6 +31,16 @@ pub(crate) fn register_models(registry: &mut ComponentRegistry) {
     {
         log::error!("model fake is already registered")
     }
+    if registry
+        .register_service(
+            COMPONENT_NAME,
+            "fake",
+            &FakeGenericComponent::service_from_config,
+        )
+        .is_err()
+    {
+        log::error!("model fake is already registered for service")
+    }
 }
 
 pub trait DoCommand {
@@ -76,6 +86,15 @@ impl<L> GenericComponent for Mutex<L> where L: ?Sized + GenericComponent {}
 
 impl<A> GenericComponent for Arc<Mutex<A>> where A: ?Sized + GenericComponent {}
 
+/// A service implementing the `rdk:service:generic` API, only exposing DoCommand
+pub trait GenericService: DoCommand {}
+
+pub type GenericServiceType = Arc<Mutex<dyn GenericService>>;
+
+impl<L> GenericService for Mutex<L> where L: ?Sized + GenericService {}
+
+impl<A> GenericService for Arc<Mutex<A>> where A: ?Sized + GenericService {}
+
 #[cfg(feature = "builtin-components")]
 pub struct FakeGenericComponent {}
 
@@ -87,11 +106,20 @@ impl FakeGenericComponent {
     ) -> Result<GenericComponentType, GenericError> {
         Ok(Arc::new(Mutex::new(FakeGenericComponent {})))
     }
+    pub(crate) fn service_from_config(
+        _: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<GenericServiceType, GenericError> {
+        Ok(Arc::new(Mutex::new(FakeGenericComponent {})))
+    }
 }
 
 #[cfg(feature = "builtin-components")]
 impl GenericComponent for FakeGenericComponent {}
 
+#[cfg(feature = "builtin-components")]
+impl GenericService for FakeGenericComponent {}
+
 #[cfg(feature = "builtin-components")]
 impl DoCommand for FakeGenericComponent {
     fn do_command(
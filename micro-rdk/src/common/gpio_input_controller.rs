@@ -0,0 +1,553 @@
+//! An input controller reading buttons on GPIO pins and axes (such as the potentiometers of
+//! a joystick) on analog readers of the board, it lets a handheld pendant act as a teleop
+//! controller for other machines.
+//!
+//! Pins and analog readers are only sampled when the events are queried, which a client
+//! streaming the events does every few milliseconds, so a press shorter than that interval
+//! can be missed. The last events of each control are kept so changes sampled by other
+//! queries (such as `GetEvents`) between two polls of a stream are all streamed.
+//!
+//! ```json
+//! "buttons": [
+//!     { "control": "ButtonSouth", "pin": 12, "invert": true, "debounce_msec": 20 }
+//! ],
+//! "axes": [
+//!     { "control": "AbsoluteX", "analog_reader": "x", "min": 0, "max": 4095,
+//!       "bidirectional": true, "deadzone": 40, "min_change": 10 }
+//! ]
+//! ```
+//!
+//! A button is pressed when its pin is high, or low when `invert` is set, and a change is
+//! only reported once the pin kept its new level for `debounce_msec`. Axes report positions
+//! between -1 and 1 (0 and 1 when `bidirectional` is false), raw values within `deadzone` of
+//! the center (or of `min`) report 0 and raw changes smaller than `min_change` are ignored.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime},
+};
+
+use super::{
+    analog::{AnalogReader, AnalogReaderType},
+    board::{Board, BoardType},
+    config::{AttributeError, ConfigType, Kind},
+    input_controller::{
+        is_after, timestamp, EventType, InputController, InputControllerError, InputControllerType,
+    },
+    registry::{get_board_from_dependencies, ComponentRegistry, Dependency},
+};
+use crate::proto::component::inputcontroller::v1::Event;
+
+const DEFAULT_DEBOUNCE_MSEC: u32 = 5;
+/// Number of events kept for each control
+const QUEUED_EVENTS: usize = 16;
+
+fn queue_event(events: &mut VecDeque<Event>, event: Event) {
+    if events.len() == QUEUED_EVENTS {
+        let _ = events.pop_front();
+    }
+    events.push_back(event);
+}
+
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_input_controller("gpio", &GpioInputController::<BoardType>::from_config)
+        .is_err()
+    {
+        log::error!("gpio model is already registered")
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct ButtonConfig {
+    pub(crate) control: String,
+    pub(crate) pin: i32,
+    pub(crate) invert: bool,
+    pub(crate) debounce: Duration,
+}
+
+impl TryFrom<&Kind> for ButtonConfig {
+    type Error = AttributeError;
+    fn try_from(value: &Kind) -> Result<Self, Self::Error> {
+        let control: String = value
+            .get("control")?
+            .ok_or(AttributeError::KeyNotFound("control".to_string()))?
+            .try_into()?;
+        let pin: i32 = value
+            .get("pin")?
+            .ok_or(AttributeError::KeyNotFound("pin".to_string()))?
+            .try_into()?;
+        let invert = match value.get("invert")? {
+            Some(invert) => invert.try_into()?,
+            None => false,
+        };
+        let debounce_msec: u32 = match value.get("debounce_msec")? {
+            Some(debounce) => debounce.try_into()?,
+            None => DEFAULT_DEBOUNCE_MSEC,
+        };
+        Ok(Self {
+            control,
+            pin,
+            invert,
+            debounce: Duration::from_millis(debounce_msec as u64),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub(crate) struct AxisConfig {
+    pub(crate) control: String,
+    pub(crate) analog_reader: String,
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+    pub(crate) bidirectional: bool,
+    pub(crate) deadzone: f64,
+    pub(crate) min_change: f64,
+    pub(crate) invert: bool,
+}
+
+impl TryFrom<&Kind> for AxisConfig {
+    type Error = AttributeError;
+    fn try_from(value: &Kind) -> Result<Self, Self::Error> {
+        let control: String = value
+            .get("control")?
+            .ok_or(AttributeError::KeyNotFound("control".to_string()))?
+            .try_into()?;
+        let analog_reader: String = value
+            .get("analog_reader")?
+            .ok_or(AttributeError::KeyNotFound("analog_reader".to_string()))?
+            .try_into()?;
+        let number = |key: &str, default: f64| -> Result<f64, AttributeError> {
+            match value.get(key)? {
+                Some(val) => val.try_into(),
+                None => Ok(default),
+            }
+        };
+        let flag = |key: &str, default: bool| -> Result<bool, AttributeError> {
+            match value.get(key)? {
+                Some(val) => val.try_into(),
+                None => Ok(default),
+            }
+        };
+        let config = Self {
+            control,
+            analog_reader,
+            min: number("min", 0.0)?,
+            max: number("max", u16::MAX as f64)?,
+            bidirectional: flag("bidirectional", true)?,
+            deadzone: number("deadzone", 0.0)?,
+            min_change: number("min_change", 0.0)?,
+            invert: flag("invert", false)?,
+        };
+        if config.max <= config.min {
+            return Err(AttributeError::ValidationError(format!(
+                "axis {} max must be greater than min",
+                config.control
+            )));
+        }
+        if config.deadzone < 0.0 || config.min_change < 0.0 {
+            return Err(AttributeError::ValidationError(format!(
+                "axis {} deadzone and min_change cannot be negative",
+                config.control
+            )));
+        }
+        Ok(config)
+    }
+}
+
+impl AxisConfig {
+    /// Converts a raw reading of the analog reader to the position of the axis
+    pub(crate) fn scale(&self, raw: f64) -> f64 {
+        let raw = raw.clamp(self.min, self.max);
+        let position = if self.bidirectional {
+            let center = (self.min + self.max) / 2.0;
+            if (raw - center).abs() <= self.deadzone {
+                0.0
+            } else {
+                (raw - center) / (self.max - center)
+            }
+        } else if raw - self.min <= self.deadzone {
+            0.0
+        } else {
+            (raw - self.min) / (self.max - self.min)
+        };
+        match (self.invert, self.bidirectional) {
+            (false, _) => position,
+            (true, true) => -position,
+            (true, false) => 1.0 - position,
+        }
+    }
+}
+
+struct Button {
+    config: ButtonConfig,
+    pressed: Option<bool>,
+    /// when the pin started reading the opposite of `pressed`
+    changed_at: Option<Instant>,
+    events: VecDeque<Event>,
+}
+
+impl Button {
+    fn sample<B: Board>(&mut self, board: &B, now: Instant) -> Result<(), InputControllerError> {
+        let pressed = board.get_gpio_level(self.config.pin)? != self.config.invert;
+        let current = match self.pressed {
+            Some(current) => current,
+            None => {
+                // the initial state of the button isn't an event
+                self.pressed = Some(pressed);
+                return Ok(());
+            }
+        };
+        if pressed == current {
+            self.changed_at = None;
+            return Ok(());
+        }
+        let changed_at = *self.changed_at.get_or_insert(now);
+        if now.duration_since(changed_at) < self.config.debounce {
+            return Ok(());
+        }
+        self.pressed = Some(pressed);
+        self.changed_at = None;
+        let (event, value) = if pressed {
+            (EventType::ButtonPress, 1.0)
+        } else {
+            (EventType::ButtonRelease, 0.0)
+        };
+        queue_event(
+            &mut self.events,
+            Event {
+                time: Some(timestamp(SystemTime::now())),
+                event: event.as_str().to_string(),
+                control: self.config.control.clone(),
+                value,
+            },
+        );
+        Ok(())
+    }
+}
+
+struct Axis {
+    config: AxisConfig,
+    reader: AnalogReaderType<u16>,
+    raw: Option<f64>,
+    position: Option<f64>,
+    events: VecDeque<Event>,
+}
+
+impl Axis {
+    fn sample(&mut self) -> Result<(), InputControllerError> {
+        let raw = self.reader.read()? as f64;
+        if self
+            .raw
+            .is_some_and(|prev| (raw - prev).abs() < self.config.min_change)
+        {
+            return Ok(());
+        }
+        let _ = self.raw.insert(raw);
+        let position = self.config.scale(raw);
+        let previous = self.position.replace(position);
+        if previous.is_none() || previous == Some(position) {
+            return Ok(());
+        }
+        queue_event(
+            &mut self.events,
+            Event {
+                time: Some(timestamp(SystemTime::now())),
+                event: EventType::PositionChangeAbs.as_str().to_string(),
+                control: self.config.control.clone(),
+                value: position,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[derive(DoCommand)]
+pub struct GpioInputController<B> {
+    board: B,
+    buttons: Vec<Button>,
+    axes: Vec<Axis>,
+}
+
+impl<B> GpioInputController<B>
+where
+    B: Board,
+{
+    pub(crate) fn new(
+        board: B,
+        buttons: Vec<ButtonConfig>,
+        axes: Vec<AxisConfig>,
+    ) -> Result<Self, InputControllerError> {
+        if buttons.is_empty() && axes.is_empty() {
+            return Err(InputControllerError::InputControllerConfigError(
+                "at least one button or axis must be configured",
+            ));
+        }
+        let mut controls = HashSet::new();
+        for control in buttons
+            .iter()
+            .map(|b| &b.control)
+            .chain(axes.iter().map(|a| &a.control))
+        {
+            if !controls.insert(control) {
+                return Err(InputControllerError::InputControllerDuplicateControl(
+                    control.clone(),
+                ));
+            }
+        }
+        let axes = axes
+            .into_iter()
+            .map(|config| {
+                Ok(Axis {
+                    reader: board.get_analog_reader_by_name(config.analog_reader.clone())?,
+                    config,
+                    raw: None,
+                    position: None,
+                    events: VecDeque::new(),
+                })
+            })
+            .collect::<Result<Vec<Axis>, InputControllerError>>()?;
+        let buttons = buttons
+            .into_iter()
+            .map(|config| Button {
+                config,
+                pressed: None,
+                changed_at: None,
+                events: VecDeque::new(),
+            })
+            .collect();
+        let mut controller = Self {
+            board,
+            buttons,
+            axes,
+        };
+        controller.sample()?;
+        Ok(controller)
+    }
+
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        dependencies: Vec<Dependency>,
+    ) -> Result<InputControllerType, InputControllerError> {
+        let board = get_board_from_dependencies(dependencies).ok_or(
+            InputControllerError::InputControllerConfigError("missing board attribute"),
+        )?;
+        let buttons = match cfg.get_attribute::<Vec<ButtonConfig>>("buttons") {
+            Ok(buttons) => buttons,
+            Err(AttributeError::KeyNotFound(_)) => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        let axes = match cfg.get_attribute::<Vec<AxisConfig>>("axes") {
+            Ok(axes) => axes,
+            Err(AttributeError::KeyNotFound(_)) => vec![],
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Arc::new(Mutex::new(GpioInputController::new(
+            board, buttons, axes,
+        )?)))
+    }
+
+    fn sample(&mut self) -> Result<(), InputControllerError> {
+        let now = Instant::now();
+        for button in self.buttons.iter_mut() {
+            button.sample(&self.board, now)?;
+        }
+        for axis in self.axes.iter_mut() {
+            axis.sample()?;
+        }
+        Ok(())
+    }
+}
+
+impl<B> InputController for GpioInputController<B>
+where
+    B: Board,
+{
+    fn get_controls(&mut self) -> Result<Vec<String>, InputControllerError> {
+        Ok(self
+            .buttons
+            .iter()
+            .map(|b| b.config.control.clone())
+            .chain(self.axes.iter().map(|a| a.config.control.clone()))
+            .collect())
+    }
+
+    fn get_events(&mut self) -> Result<Vec<Event>, InputControllerError> {
+        self.sample()?;
+        Ok(self
+            .buttons
+            .iter()
+            .filter_map(|b| b.events.back().cloned())
+            .chain(self.axes.iter().filter_map(|a| a.events.back().cloned()))
+            .collect())
+    }
+
+    fn get_events_since(&mut self, since: SystemTime) -> Result<Vec<Event>, InputControllerError> {
+        self.sample()?;
+        let since = timestamp(since);
+        Ok(self
+            .buttons
+            .iter()
+            .flat_map(|b| b.events.iter())
+            .chain(self.axes.iter().flat_map(|a| a.events.iter()))
+            .filter(|event| is_after(event, &since))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, SystemTime},
+    };
+
+    use super::{AxisConfig, ButtonConfig, GpioInputController};
+    use crate::{
+        common::{
+            board::{Board, BoardType, FakeBoard},
+            input_controller::{events_since, InputController, InputControllerError},
+        },
+        proto::component::inputcontroller::v1::stream_events_request,
+    };
+
+    fn button(control: &str, pin: i32, debounce: Duration) -> ButtonConfig {
+        ButtonConfig {
+            control: control.to_string(),
+            pin,
+            invert: false,
+            debounce,
+        }
+    }
+
+    #[test_log::test]
+    fn test_gpio_buttons() {
+        let mut board: BoardType = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let mut controller = GpioInputController::new(
+            board.clone(),
+            vec![
+                button("ButtonSouth", 12, Duration::ZERO),
+                button("ButtonEast", 13, Duration::from_millis(50)),
+            ],
+            vec![],
+        )
+        .unwrap();
+        assert_eq!(
+            controller.get_controls().unwrap(),
+            vec!["ButtonSouth".to_string(), "ButtonEast".to_string()]
+        );
+        assert!(controller.get_events().unwrap().is_empty());
+
+        let since = SystemTime::now();
+        board.set_gpio_pin_level(12, false).unwrap();
+        board.set_gpio_pin_level(13, false).unwrap();
+        let events = controller.get_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].control, "ButtonSouth");
+        assert_eq!(events[0].event, "ButtonRelease");
+
+        // the second button is still bouncing
+        std::thread::sleep(Duration::from_millis(60));
+        let events = controller.get_events().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].control, "ButtonEast");
+        assert_eq!(events[1].event, "ButtonRelease");
+
+        let registrations = vec![stream_events_request::Events {
+            control: "ButtonEast".to_string(),
+            events: vec!["AllEvents".to_string()],
+            cancelled_events: vec![],
+        }];
+        let events =
+            events_since(&mut controller, &registrations, since, SystemTime::now()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].control, "ButtonEast");
+        let now = SystemTime::now();
+        let events = events_since(&mut controller, &registrations, now, now).unwrap();
+        assert!(events.is_empty());
+
+        assert!(matches!(
+            GpioInputController::new(
+                board.clone(),
+                vec![
+                    button("ButtonSouth", 12, Duration::ZERO),
+                    button("ButtonSouth", 13, Duration::ZERO)
+                ],
+                vec![],
+            ),
+            Err(InputControllerError::InputControllerDuplicateControl(_))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_axis_scale() {
+        let mut axis = AxisConfig {
+            control: "AbsoluteX".to_string(),
+            analog_reader: "x".to_string(),
+            min: 0.0,
+            max: 1000.0,
+            bidirectional: true,
+            deadzone: 50.0,
+            min_change: 0.0,
+            invert: false,
+        };
+        assert_eq!(axis.scale(0.0), -1.0);
+        assert_eq!(axis.scale(530.0), 0.0);
+        assert_eq!(axis.scale(750.0), 0.5);
+        assert_eq!(axis.scale(2000.0), 1.0);
+        axis.invert = true;
+        assert_eq!(axis.scale(750.0), -0.5);
+        axis.bidirectional = false;
+        assert_eq!(axis.scale(30.0), 1.0);
+        assert_eq!(axis.scale(250.0), 0.75);
+    }
+
+    #[test_log::test]
+    fn test_gpio_button_events_queued() {
+        let mut board: BoardType = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let mut controller = GpioInputController::new(
+            board.clone(),
+            vec![button("ButtonSouth", 12, Duration::ZERO)],
+            vec![],
+        )
+        .unwrap();
+        let registrations = vec![stream_events_request::Events {
+            control: "ButtonSouth".to_string(),
+            events: vec!["ButtonPress".to_string(), "ButtonRelease".to_string()],
+            cancelled_events: vec![],
+        }];
+
+        // a release and a press sampled between two polls are both reported, in order
+        let since = SystemTime::now();
+        board.set_gpio_pin_level(12, false).unwrap();
+        assert_eq!(controller.get_events().unwrap().len(), 1);
+        board.set_gpio_pin_level(12, true).unwrap();
+        let events = controller.get_events().unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "ButtonPress");
+
+        let until = SystemTime::now();
+        let events = events_since(&mut controller, &registrations, since, until).unwrap();
+        assert_eq!(
+            events.iter().map(|e| e.event.as_str()).collect::<Vec<_>>(),
+            vec!["ButtonRelease", "ButtonPress"]
+        );
+
+        // events sampled during a poll are later than the poll and left for the next one
+        board.set_gpio_pin_level(12, false).unwrap();
+        let polled_at = SystemTime::now();
+        let events = events_since(&mut controller, &registrations, until, polled_at).unwrap();
+        assert!(events.is_empty());
+        let events = events_since(
+            &mut controller,
+            &registrations,
+            polled_at,
+            SystemTime::now(),
+        )
+        .unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "ButtonRelease");
+    }
+}
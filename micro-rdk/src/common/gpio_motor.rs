@@ -38,7 +38,9 @@
 //!
 
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use async_executor::Task;
 
 use super::actuator::{Actuator, ActuatorError};
 use super::board::{Board, BoardType};
@@ -46,11 +48,13 @@ use super::config::ConfigType;
 use super::encoder::{
     Encoder, EncoderPositionType, EncoderType, COMPONENT_NAME as EncoderCompName,
 };
-use super::math_utils::go_for_math;
+use super::exec::Executor;
+use super::math_utils::{go_for_math, UtilsInvalidArg};
 use super::motor::{
     Motor, MotorError, MotorPinType, MotorPinsConfig, MotorSupportedProperties, MotorType,
     COMPONENT_NAME as MotorCompName,
 };
+use super::pid::{PidConfig, PidController};
 use super::registry::{get_board_from_dependencies, ComponentRegistry, Dependency, ResourceKey};
 use super::robot::Resource;
 
@@ -98,6 +102,7 @@ pub(crate) fn gpio_motor_from_config(
     } else {
         return Err(MotorError::ConfigError("Motor, missing 'pin' attribute"));
     };
+    let control = MotorControlConfig::from_config(&cfg)?;
     let motor = match motor_type {
         MotorPinType::PwmAB => PwmABMotor::<BoardType>::from_config(cfg, board.clone())?.clone(),
         MotorPinType::PwmDirection => {
@@ -106,7 +111,10 @@ pub(crate) fn gpio_motor_from_config(
         MotorPinType::AB => AbMotor::<BoardType>::from_config(cfg, board.clone())?.clone(),
     };
     if let Some(enc) = enc {
-        let enc_motor = EncodedMotor::new(motor, enc.clone());
+        let mut enc_motor = EncodedMotor::new(motor, enc.clone());
+        if let Some(control) = control {
+            enc_motor = enc_motor.with_control(control);
+        }
         return Ok(Arc::new(Mutex::new(enc_motor)));
     }
     Ok(motor)
@@ -118,26 +126,256 @@ pub(crate) fn gpio_motor_from_config(
 // of forcing the user to supply a PWM frequency in the motor config)
 const MOTOR_PWM_FREQUENCY: u64 = 1000;
 
+// Default rate at which the power of a closed loop motor may change, in power per second
+const DEFAULT_RAMP_RATE: f64 = 0.2;
+const DEFAULT_CONTROL_FREQUENCY_HZ: f64 = 20.0;
+
+/// Closed loop control of an encoded motor, enabled by the `control_parameters` attribute.
+///
+/// ```json
+/// "ticks_per_rotation": 600,
+/// "control_parameters": { "kp": 0.002, "ki": 0.05, "kd": 0.0 },
+/// "ramp_rate": 0.2,
+/// "control_frequency_hz": 20
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct MotorControlConfig {
+    pub pid: PidConfig,
+    pub ticks_per_rotation: f64,
+    /// maximum change of power per second
+    pub ramp_rate: f64,
+    pub control_frequency_hz: f64,
+}
+
+impl MotorControlConfig {
+    pub(crate) fn from_config(cfg: &ConfigType) -> Result<Option<Self>, MotorError> {
+        if !cfg.has_attribute("control_parameters") {
+            return Ok(None);
+        }
+        let pid = cfg
+            .get_attribute::<PidConfig>("control_parameters")
+            .map_err(|_| MotorError::ConfigError("invalid 'control_parameters' attribute"))?;
+        let ticks_per_rotation =
+            cfg.get_attribute::<f64>("ticks_per_rotation")
+                .or(Err(MotorError::ConfigError(
+                    "closed loop control requires 'ticks_per_rotation'",
+                )))?;
+        if ticks_per_rotation <= 0.0 {
+            return Err(MotorError::ConfigError(
+                "'ticks_per_rotation' must be positive",
+            ));
+        }
+        let ramp_rate = cfg
+            .get_attribute::<f64>("ramp_rate")
+            .unwrap_or(DEFAULT_RAMP_RATE);
+        if ramp_rate <= 0.0 {
+            return Err(MotorError::ConfigError("'ramp_rate' must be positive"));
+        }
+        let control_frequency_hz = cfg
+            .get_attribute::<f64>("control_frequency_hz")
+            .unwrap_or(DEFAULT_CONTROL_FREQUENCY_HZ);
+        if control_frequency_hz <= 0.0 {
+            return Err(MotorError::ConfigError(
+                "'control_frequency_hz' must be positive",
+            ));
+        }
+        Ok(Some(Self {
+            pid,
+            ticks_per_rotation,
+            ramp_rate,
+            control_frequency_hz,
+        }))
+    }
+}
+
+// State shared between an encoded motor and its control loop
+struct ControlState {
+    target_rpm: f64,
+    // position (in ticks) at which the loop stops the motor, none when running indefinitely
+    goal_ticks: Option<f64>,
+    power: f64,
+    pid: PidController,
+}
+
+struct MotorControl {
+    config: MotorControlConfig,
+    state: Arc<Mutex<ControlState>>,
+    // dropping the task cancels the control loop
+    task: Option<Task<()>>,
+}
+
 #[derive(DoCommand)]
 pub struct EncodedMotor<M, Enc> {
     motor: M,
     enc: Enc,
+    control: Option<MotorControl>,
 }
 
 impl<M, Enc> EncodedMotor<M, Enc>
 where
+    M: Motor + Clone + 'static,
+    Enc: Encoder + Clone + 'static,
+{
+    pub fn new(motor: M, enc: Enc) -> Self {
+        Self {
+            motor,
+            enc,
+            control: None,
+        }
+    }
+
+    /// Drives `set_rpm` and `go_for` with a PID loop fed by the encoder instead of open loop
+    /// power. The loop runs as a task on the executor while the motor is moving.
+    pub fn with_control(mut self, config: MotorControlConfig) -> Self {
+        let _ = self.control.insert(MotorControl {
+            config,
+            state: Arc::new(Mutex::new(ControlState {
+                target_rpm: 0.0,
+                goal_ticks: None,
+                power: 0.0,
+                pid: PidController::new(config.pid, -1.0, 1.0),
+            })),
+            task: None,
+        });
+        self
+    }
+
+    fn get_ticks(&self) -> Result<f64, MotorError> {
+        Ok(self.enc.get_position(EncoderPositionType::TICKS)?.value as f64)
+    }
+
+    fn cancel_control(&mut self) {
+        if let Some(control) = self.control.as_mut() {
+            let _ = control.task.take();
+            let mut state = control.state.lock().unwrap();
+            state.target_rpm = 0.0;
+            state.goal_ticks = None;
+            state.power = 0.0;
+            state.pid.reset();
+        }
+    }
+
+    fn run_control(&mut self, target_rpm: f64, goal_ticks: Option<f64>) {
+        let motor = self.motor.clone();
+        let enc = self.enc.clone();
+        let Some(control) = self.control.as_mut() else {
+            return;
+        };
+        {
+            let mut state = control.state.lock().unwrap();
+            state.target_rpm = target_rpm;
+            state.goal_ticks = goal_ticks;
+        }
+        if control
+            .task
+            .as_ref()
+            .is_some_and(|task| !task.is_finished())
+        {
+            return;
+        }
+        let state = control.state.clone();
+        let config = control.config;
+        control.task = Some(
+            Executor::new().spawn(async move { control_loop(motor, enc, state, config).await }),
+        );
+    }
+}
+
+async fn control_loop<M, Enc>(
+    mut motor: M,
+    enc: Enc,
+    state: Arc<Mutex<ControlState>>,
+    config: MotorControlConfig,
+) where
     M: Motor,
     Enc: Encoder,
 {
-    pub fn new(motor: M, enc: Enc) -> Self {
-        Self { motor, enc }
+    let period = Duration::from_secs_f64(1.0 / config.control_frequency_hz);
+    let read_ticks = || {
+        enc.get_position(EncoderPositionType::TICKS)
+            .map(|pos| pos.value as f64)
+    };
+    let mut last_ticks = match read_ticks() {
+        Ok(ticks) => ticks,
+        Err(err) => {
+            log::error!("motor control loop couldn't read encoder: {:?}", err);
+            let _ = motor.stop();
+            return;
+        }
+    };
+    let mut last_time = Instant::now();
+    loop {
+        async_io::Timer::after(period).await;
+        let ticks = match read_ticks() {
+            Ok(ticks) => ticks,
+            Err(err) => {
+                log::error!("motor control loop couldn't read encoder: {:?}", err);
+                let _ = motor.stop();
+                return;
+            }
+        };
+        let now = Instant::now();
+        let dt = now - last_time;
+        last_time = now;
+        if dt.is_zero() {
+            continue;
+        }
+        let step = control_step(
+            &mut motor,
+            &mut state.lock().unwrap(),
+            &config,
+            ticks,
+            ticks - last_ticks,
+            dt,
+        );
+        last_ticks = ticks;
+        match step {
+            Ok(true) => {}
+            Ok(false) => return,
+            Err(err) => {
+                log::error!("motor control loop couldn't set power: {:?}", err);
+                let _ = motor.stop();
+                return;
+            }
+        }
+    }
+}
+
+/// One iteration of the control loop: the encoder is at `ticks` and moved by `delta_ticks`
+/// during `dt`. Returns false once the goal is reached and the motor stopped.
+fn control_step<M: Motor>(
+    motor: &mut M,
+    state: &mut ControlState,
+    config: &MotorControlConfig,
+    ticks: f64,
+    delta_ticks: f64,
+    dt: Duration,
+) -> Result<bool, MotorError> {
+    if let Some(goal) = state.goal_ticks {
+        // the goal is reached once the remaining distance is behind us
+        if (goal - ticks) * state.target_rpm.signum() <= 0.0 {
+            state.goal_ticks = None;
+            state.target_rpm = 0.0;
+            state.power = 0.0;
+            state.pid.reset();
+            let _ = motor.stop();
+            return Ok(false);
+        }
     }
+    let rpm = delta_ticks / config.ticks_per_rotation / dt.as_secs_f64() * 60.0;
+    let target_rpm = state.target_rpm;
+    let target = state.pid.update(target_rpm, rpm, dt);
+    let max_step = config.ramp_rate * dt.as_secs_f64();
+    state.power =
+        (state.power + (target - state.power).clamp(-max_step, max_step)).clamp(-1.0, 1.0);
+    motor.set_power(state.power)?;
+    Ok(true)
 }
 
 impl<M, Enc> Motor for EncodedMotor<M, Enc>
 where
-    M: Motor,
-    Enc: Encoder,
+    M: Motor + Clone + 'static,
+    Enc: Encoder + Clone + 'static,
 {
     fn get_position(&mut self) -> Result<i32, MotorError> {
         Ok(self
@@ -148,11 +386,38 @@ where
 
     /// Accepts percentage as a float, e.g. `0.5` equals `50%` power.
     fn set_power(&mut self, pct: f64) -> Result<(), MotorError> {
+        self.cancel_control();
         self.motor.set_power(pct)
     }
+
+    /// With closed loop control the motor is stopped by the control loop once the encoder
+    /// reports the requested revolutions, no duration is returned and the motor reports it is
+    /// moving until then.
     fn go_for(&mut self, rpm: f64, revolutions: f64) -> Result<Option<Duration>, MotorError> {
-        self.motor.go_for(rpm, revolutions)
+        let ticks_per_rotation = match self.control.as_ref() {
+            Some(control) => control.config.ticks_per_rotation,
+            None => return self.motor.go_for(rpm, revolutions),
+        };
+        if rpm.is_nan() || revolutions.is_nan() {
+            return Err(UtilsInvalidArg.into());
+        }
+        if revolutions == 0.0 {
+            self.run_control(rpm, None);
+            return Ok(None);
+        }
+        if rpm == 0.0 {
+            return Err(UtilsInvalidArg.into());
+        }
+        let dir = (rpm * revolutions).signum();
+        let goal = self.get_ticks()? + revolutions.abs() * dir * ticks_per_rotation;
+        self.run_control(rpm.abs() * dir, Some(goal));
+        Ok(None)
     }
+
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        self.motor.is_powered()
+    }
+
     fn get_properties(&mut self) -> MotorSupportedProperties {
         MotorSupportedProperties {
             position_reporting: true,
@@ -162,13 +427,18 @@ where
 
 impl<M, Enc> Actuator for EncodedMotor<M, Enc>
 where
-    M: Motor,
-    Enc: Encoder,
+    M: Motor + Clone + 'static,
+    Enc: Encoder + Clone + 'static,
 {
     fn is_moving(&mut self) -> Result<bool, ActuatorError> {
+        // under closed loop control the motor moves as long as the control loop runs
+        if let Some(task) = self.control.as_ref().and_then(|c| c.task.as_ref()) {
+            return Ok(!task.is_finished());
+        }
         self.motor.is_moving()
     }
     fn stop(&mut self) -> Result<(), ActuatorError> {
+        self.cancel_control();
         self.motor.stop()
     }
 }
@@ -182,6 +452,7 @@ pub(crate) struct PwmABMotor<B> {
     pwm_pin: i32,
     max_rpm: f64,
     dir_flip: bool,
+    power: f64,
 }
 
 impl<B> PwmABMotor<B>
@@ -203,6 +474,7 @@ where
             pwm_pin,
             max_rpm,
             dir_flip,
+            power: 0.0,
         };
         // we start with this because we want to reserve a timer and PWM channel early
         // for boards where these are a limited resource
@@ -261,6 +533,7 @@ where
             self.board.set_gpio_pin_level(self.b_pin, false)?;
         }
         self.board.set_pwm_duty(self.pwm_pin, pct)?;
+        self.power = pct;
         Ok(())
     }
 
@@ -268,6 +541,10 @@ where
         Err(MotorError::MissingEncoder)
     }
 
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Ok((self.power != 0.0, self.power))
+    }
+
     fn go_for(
         &mut self,
         rpm: f64,
@@ -308,6 +585,7 @@ pub(crate) struct PwmDirectionMotor<B> {
     pwm_pin: i32,
     max_rpm: f64,
     dir_flip: bool,
+    power: f64,
 }
 
 impl<B> PwmDirectionMotor<B>
@@ -327,6 +605,7 @@ where
             pwm_pin,
             max_rpm,
             dir_flip,
+            power: 0.0,
         };
         // we start with this because we want to reserve a timer and PWM channel early
         // for boards where these are a limited resource
@@ -365,6 +644,7 @@ where
         let set_high = (pct > 0.0) && !self.dir_flip;
         self.board.set_gpio_pin_level(self.dir_pin, set_high)?;
         self.board.set_pwm_duty(self.pwm_pin, pct)?;
+        self.power = pct;
         Ok(())
     }
 
@@ -372,6 +652,10 @@ where
         Err(MotorError::MissingEncoder)
     }
 
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Ok((self.power != 0.0, self.power))
+    }
+
     fn go_for(&mut self, rpm: f64, revolutions: f64) -> Result<Option<Duration>, MotorError> {
         let (pwr, dur) =
             go_for_math(self.max_rpm, rpm, revolutions).map_err(MotorError::InvalidArgument)?;
@@ -414,6 +698,7 @@ pub(crate) struct AbMotor<B> {
     dir_flip: bool,
     is_on: bool,
     pwm_pin: i32,
+    power: f64,
 }
 
 impl<B> AbMotor<B>
@@ -435,6 +720,7 @@ where
             dir_flip,
             is_on: false,
             pwm_pin: a_pin,
+            power: 0.0,
         };
         // we start with this because we want to reserve a timer and PWM channel early
         // for boards where these are a limited resource
@@ -487,6 +773,7 @@ where
         self.board.set_gpio_pin_level(high_pin, true)?;
         self.board.set_pwm_duty(pwm_pin, pct)?;
         self.is_on = true;
+        self.power = pct;
         Ok(())
     }
 
@@ -494,6 +781,10 @@ where
         Err(MotorError::MissingEncoder)
     }
 
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Ok((self.is_on, self.power))
+    }
+
     fn go_for(&mut self, rpm: f64, revolutions: f64) -> Result<Option<Duration>, MotorError> {
         let (pwr, dur) = go_for_math(self.max_rpm, rpm, revolutions)?;
         self.set_power(pwr)?;
@@ -523,6 +814,187 @@ where
         self.board.set_gpio_pin_level(self.a_pin, false)?;
         self.board.set_gpio_pin_level(self.b_pin, false)?;
         self.is_on = false;
+        self.power = 0.0;
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use crate::common::{
+        actuator::Actuator,
+        board::{Board, FakeBoard},
+        encoder::{EncoderType, FakeEncoder},
+        gpio_motor::{
+            control_step, AbMotor, ControlState, EncodedMotor, MotorControlConfig, PwmABMotor,
+            PwmDirectionMotor,
+        },
+        motor::{Motor, MotorType},
+        pid::{PidConfig, PidController},
+    };
+
+    const PWM_PIN: i32 = 3;
+    // one control period at 100Hz
+    const DT: Duration = Duration::from_millis(10);
+
+    fn control_config() -> MotorControlConfig {
+        MotorControlConfig {
+            // integral only, so the output holds once the error is zero
+            pid: PidConfig {
+                kp: 0.0,
+                ki: 0.01,
+                kd: 0.0,
+            },
+            ticks_per_rotation: 1.0,
+            ramp_rate: 10.0,
+            control_frequency_hz: 100.0,
+        }
+    }
+
+    fn control_state(target_rpm: f64, goal_ticks: Option<f64>) -> ControlState {
+        ControlState {
+            target_rpm,
+            goal_ticks,
+            power: 0.0,
+            pid: PidController::new(control_config().pid, -1.0, 1.0),
+        }
+    }
+
+    fn pwm_motor(board: Arc<Mutex<FakeBoard>>) -> MotorType {
+        Arc::new(Mutex::new(
+            PwmABMotor::new(1, 2, PWM_PIN, 100.0, false, board).unwrap(),
+        ))
+    }
+
+    fn closed_loop_motor(board: Arc<Mutex<FakeBoard>>) -> EncodedMotor<MotorType, EncoderType> {
+        let enc: EncoderType = Arc::new(Mutex::new(FakeEncoder::new()));
+        EncodedMotor::new(pwm_motor(board), enc).with_control(control_config())
+    }
+
+    #[test_log::test]
+    fn test_is_powered_signed() {
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let motors: Vec<MotorType> = vec![
+            pwm_motor(board.clone()),
+            Arc::new(Mutex::new(
+                PwmDirectionMotor::new(4, 5, 100.0, false, board.clone()).unwrap(),
+            )),
+            Arc::new(Mutex::new(
+                AbMotor::new(6, 7, 100.0, false, board.clone()).unwrap(),
+            )),
+        ];
+        for mut motor in motors {
+            assert_eq!(motor.is_powered().unwrap(), (false, 0.0));
+            motor.set_power(0.5).unwrap();
+            assert_eq!(motor.is_powered().unwrap(), (true, 0.5));
+            motor.set_power(-0.25).unwrap();
+            assert_eq!(motor.is_powered().unwrap(), (true, -0.25));
+            motor.stop().unwrap();
+            assert_eq!(motor.is_powered().unwrap(), (false, 0.0));
+        }
+    }
+
+    #[test_log::test]
+    fn test_control_step_speed() {
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let mut motor = pwm_motor(board.clone());
+        let config = control_config();
+        let mut state = control_state(60.0, None);
+
+        // a stalled motor gets more power every period, at most ramp_rate per second
+        let mut last_power = 0.0;
+        for _ in 0..5 {
+            assert!(control_step(&mut motor, &mut state, &config, 0.0, 0.0, DT).unwrap());
+            assert!(state.power > last_power);
+            assert!(state.power - last_power <= config.ramp_rate * DT.as_secs_f64() + 1e-9);
+            last_power = state.power;
+        }
+        assert_eq!(board.get_pwm_duty(PWM_PIN), last_power);
+
+        // at the target speed (one tick per second is 60 rpm) the power holds
+        assert!(control_step(&mut motor, &mut state, &config, 0.01, 0.01, DT).unwrap());
+        assert!((state.power - last_power).abs() < 1e-9);
+
+        // too fast, the power goes down
+        assert!(control_step(&mut motor, &mut state, &config, 1.01, 1.0, DT).unwrap());
+        assert!(state.power < last_power);
+    }
+
+    #[test_log::test]
+    fn test_control_step_goal() {
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let mut motor = pwm_motor(board.clone());
+        let config = control_config();
+
+        let mut state = control_state(60.0, Some(5.0));
+        assert!(control_step(&mut motor, &mut state, &config, 3.0, 0.0, DT).unwrap());
+        assert!(board.get_pwm_duty(PWM_PIN) > 0.0);
+        assert!(!control_step(&mut motor, &mut state, &config, 5.0, 2.0, DT).unwrap());
+        assert_eq!(board.get_pwm_duty(PWM_PIN), 0.0);
+        assert_eq!(state.goal_ticks, None);
+        assert_eq!(state.target_rpm, 0.0);
+
+        // backwards, the goal is below the current position
+        let mut state = control_state(-60.0, Some(-5.0));
+        assert!(control_step(&mut motor, &mut state, &config, -3.0, 0.0, DT).unwrap());
+        assert!(state.power < 0.0);
+        assert!(!control_step(&mut motor, &mut state, &config, -6.0, -3.0, DT).unwrap());
+        assert_eq!(board.get_pwm_duty(PWM_PIN), 0.0);
+    }
+
+    #[test_log::test]
+    fn test_closed_loop_go_for() {
+        let board = Arc::new(Mutex::new(FakeBoard::new(vec![])));
+        let mut motor = closed_loop_motor(board.clone());
+
+        assert!(motor.go_for(60.0, 0.0).is_ok());
+        assert_eq!(
+            motor
+                .control
+                .as_ref()
+                .unwrap()
+                .state
+                .lock()
+                .unwrap()
+                .goal_ticks,
+            None
+        );
+        assert!(motor.go_for(0.0, 5.0).is_err());
+
+        // the control loop stops the motor at the goal, there's no duration to wait for
+        assert!(matches!(motor.go_for(60.0, 5.0), Ok(None)));
+        assert!(motor.is_moving().unwrap());
+        {
+            let state = motor.control.as_ref().unwrap().state.lock().unwrap();
+            assert_eq!(state.target_rpm, 60.0);
+            assert!(state.goal_ticks.is_some());
+        }
+
+        // negative revolutions go backwards whatever the sign of rpm
+        let start = motor.get_ticks().unwrap();
+        assert!(motor.go_for(60.0, -5.0).is_ok());
+        {
+            let state = motor.control.as_ref().unwrap().state.lock().unwrap();
+            assert_eq!(state.target_rpm, -60.0);
+            assert!(state.goal_ticks.unwrap() < start);
+        }
+
+        assert!(motor.stop().is_ok());
+        assert!(motor.control.as_ref().unwrap().task.is_none());
+        assert_eq!(
+            motor
+                .control
+                .as_ref()
+                .unwrap()
+                .state
+                .lock()
+                .unwrap()
+                .target_rpm,
+            0.0
+        );
+        assert_eq!(board.get_pwm_duty(PWM_PIN), 0.0);
+    }
+}
@@ -0,0 +1,137 @@
+use super::{
+    actuator::{Actuator, ActuatorError},
+    config::AttributeError,
+    generic::DoCommand,
+};
+
+#[cfg(feature = "builtin-components")]
+use super::{
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency},
+};
+
+use std::sync::{Arc, Mutex};
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "gripper";
+
+#[derive(Debug, Error)]
+pub enum GripperError {
+    #[error(transparent)]
+    GripperActuatorError(#[from] ActuatorError),
+    #[error(transparent)]
+    GripperConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    GripperConfigError(&'static str),
+}
+
+pub trait Gripper: Actuator + DoCommand {
+    /// Opens the gripper
+    fn open(&mut self) -> Result<(), GripperError>;
+
+    /// Closes the gripper until it grabs something or is fully closed, returns whether
+    /// something was grabbed
+    fn grab(&mut self) -> Result<bool, GripperError>;
+}
+
+pub type GripperType = Arc<Mutex<dyn Gripper>>;
+
+impl<L> Gripper for Mutex<L>
+where
+    L: ?Sized + Gripper,
+{
+    fn open(&mut self) -> Result<(), GripperError> {
+        self.get_mut().unwrap().open()
+    }
+    fn grab(&mut self) -> Result<bool, GripperError> {
+        self.get_mut().unwrap().grab()
+    }
+}
+
+impl<A> Gripper for Arc<Mutex<A>>
+where
+    A: ?Sized + Gripper,
+{
+    fn open(&mut self) -> Result<(), GripperError> {
+        self.lock().unwrap().open()
+    }
+    fn grab(&mut self) -> Result<bool, GripperError> {
+        self.lock().unwrap().grab()
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_gripper("fake", &FakeGripper::from_config)
+        .is_err()
+    {
+        log::error!("fake type is already registered");
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+#[derive(DoCommand)]
+pub struct FakeGripper {
+    is_open: bool,
+}
+
+#[cfg(feature = "builtin-components")]
+impl FakeGripper {
+    pub fn new() -> Self {
+        Self { is_open: true }
+    }
+    pub(crate) fn from_config(
+        _: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<GripperType, GripperError> {
+        Ok(Arc::new(Mutex::new(FakeGripper::default())))
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Default for FakeGripper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Gripper for FakeGripper {
+    fn open(&mut self) -> Result<(), GripperError> {
+        self.is_open = true;
+        Ok(())
+    }
+    fn grab(&mut self) -> Result<bool, GripperError> {
+        self.is_open = false;
+        Ok(true)
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl Actuator for FakeGripper {
+    fn is_moving(&mut self) -> Result<bool, ActuatorError> {
+        Ok(false)
+    }
+    fn stop(&mut self) -> Result<(), ActuatorError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FakeGripper, Gripper};
+    use crate::common::actuator::Actuator;
+
+    #[test_log::test]
+    fn test_fake_gripper() {
+        let mut gripper = FakeGripper::new();
+        assert!(gripper.is_open);
+        assert!(gripper.grab().unwrap());
+        assert!(!gripper.is_open);
+        assert!(!gripper.is_moving().unwrap());
+        gripper.open().unwrap();
+        assert!(gripper.is_open);
+        assert!(gripper.stop().is_ok());
+    }
+}
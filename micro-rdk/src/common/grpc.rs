@@ -1,22 +1,31 @@
 use core::fmt;
 use std::{
+    collections::{BTreeMap, HashMap},
     convert::Infallible,
     fmt::Debug,
     marker::PhantomData,
-    sync::{Arc, Mutex},
-    time::{Duration, Instant},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
 };
 
 use crate::{
     common::{
-        analog::AnalogReader, board::Board, motor::Motor, robot::LocalRobot,
+        actuator::Actuator, analog::AnalogReader, arm, audio_input, base::BaseError, board::Board,
+        exec::Executor, gantry::GantryError, input_controller, motor::Motor, robot::LocalRobot,
         webrtc::grpc::WebRtcGrpcService,
     },
-    google::rpc::Status,
+    google::{self, rpc::Status},
     proto::{self, component, robot, rpc::webrtc::v1::CallResponse},
 };
 use bytes::BufMut;
 use futures_lite::{Future, StreamExt};
+use futures_util::{
+    future::{AbortHandle, Abortable},
+    FutureExt,
+};
 use http_body_util::{combinators::BoxBody, BodyExt, StreamBody};
 use hyper::{
     body::{self, Body, Bytes, Frame},
@@ -26,6 +35,7 @@ use hyper::{
 };
 use log::*;
 use prost::Message;
+use scopeguard::ScopeGuard;
 use std::{
     pin::Pin,
     task::{Context, Poll},
@@ -34,6 +44,26 @@ use thiserror::Error;
 
 use super::webrtc::signaling_server::SignalingServer;
 
+/// Produces the messages of a server streaming RPC each time it's polled, given when it was
+/// last polled (`None` the first time) and the time of this poll, taken before polling so
+/// nothing that happens while the messages are sent is missed by the next poll. Returns the
+/// messages and when to poll it again.
+pub(crate) type StreamPoller =
+    Box<dyn FnMut(Option<SystemTime>, SystemTime) -> Result<(Vec<Bytes>, Instant), ServerError>>;
+
+/// Server streaming RPCs, they are served by polling a [StreamPoller]
+pub(crate) fn is_server_streaming_rpc(path: &str) -> bool {
+    matches!(
+        path,
+        "/viam.component.inputcontroller.v1.InputControllerService/StreamEvents"
+            | "/viam.component.audioinput.v1.AudioInputService/Chunks"
+    )
+}
+
+/// Response of a unary RPC. Most handlers resolve immediately, but some (like motor GoFor) have
+/// to wait on the executor before they can answer, so the future must not borrow the server.
+pub type UnaryRpcFuture = Pin<Box<dyn Future<Output = Result<Bytes, ServerError>>>>;
+
 #[derive(Clone, Debug)]
 pub struct GrpcBody {
     _marker: PhantomData<*const ()>,
@@ -188,12 +218,22 @@ impl<'a> GrpcServerInner<'a> {
         Ok(rest)
     }
 
-    pub(crate) fn handle_rpc_stream(
-        &mut self,
-        _: &str,
-        _: &[u8],
-    ) -> Result<(Bytes, std::time::Instant), ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnavailable))
+    /// Decodes the request of a server streaming RPC and looks its resource up, the poller
+    /// returned produces the messages of the stream
+    pub(crate) fn stream_poller(
+        &self,
+        path: &str,
+        payload: &[u8],
+    ) -> Result<StreamPoller, ServerError> {
+        match path {
+            "/viam.component.inputcontroller.v1.InputControllerService/StreamEvents" => {
+                self.input_controller_stream_events(payload)
+            }
+            "/viam.component.audioinput.v1.AudioInputService/Chunks" => {
+                self.audio_input_chunks(payload)
+            }
+            _ => Err(ServerError::from(GrpcError::RpcUnavailable)),
+        }
     }
 
     pub(crate) fn handle_request(
@@ -208,13 +248,42 @@ impl<'a> GrpcServerInner<'a> {
         // demand a better system.
         match path {
             "/proto.rpc.webrtc.v1.SignalingService/Call" => self.signaling_service_call(payload),
-            _ => Box::pin(futures_lite::stream::once(
-                self.handle_unary_request(path, payload),
-            )),
+            path if is_server_streaming_rpc(path) => self.polled_stream(path, payload),
+            _ => {
+                let mut fut = self.handle_unary_request(path, payload);
+                if let Some(res) = (&mut fut).now_or_never() {
+                    return Box::pin(futures_lite::stream::once(res));
+                }
+                // the handler needs to wait, run it as a task on the executor so the response
+                // stream stays Send + Sync. Dropping the stream (the client went away) cancels
+                // the task
+                Box::pin(futures_lite::stream::once_future(
+                    Executor::new().spawn(fut),
+                ))
+            }
         }
     }
 
-    pub(crate) fn handle_unary_request(
+    pub(crate) fn handle_unary_request(mut self, path: &str, payload: &[u8]) -> UnaryRpcFuture {
+        let fut = match path {
+            "/viam.component.motor.v1.MotorService/GoFor" => self.motor_go_for(payload),
+            "/viam.component.motor.v1.MotorService/GoTo" => self.motor_go_to(payload),
+            "/viam.component.base.v1.BaseService/MoveStraight" => self.base_move_straight(payload),
+            "/viam.component.base.v1.BaseService/Spin" => self.base_spin(payload),
+            "/viam.component.arm.v1.ArmService/MoveToJointPositions" => {
+                self.arm_move_to_joint_positions(payload)
+            }
+            "/viam.component.arm.v1.ArmService/MoveThroughJointPositions" => {
+                self.arm_move_through_joint_positions(payload)
+            }
+            _ => Ok(Box::pin(futures_lite::future::ready(
+                self.handle_blocking_unary_request(path, payload),
+            )) as UnaryRpcFuture),
+        };
+        fut.unwrap_or_else(|err| Box::pin(futures_lite::future::ready(Err(err))))
+    }
+
+    fn handle_blocking_unary_request(
         mut self,
         path: &str,
         payload: &[u8],
@@ -222,8 +291,6 @@ impl<'a> GrpcServerInner<'a> {
         match path {
             "/viam.component.base.v1.BaseService/SetPower" => self.base_set_power(payload),
             "/viam.component.base.v1.BaseService/Stop" => self.base_stop(payload),
-            "/viam.component.base.v1.BaseService/MoveStraight" => self.base_move_straight(payload),
-            "/viam.component.base.v1.BaseService/Spin" => self.base_spin(payload),
             "/viam.component.base.v1.BaseService/SetVelocity" => self.base_set_velocity(payload),
             "/viam.component.base.v1.BaseService/IsMoving" => self.base_is_moving(payload),
             "/viam.component.board.v1.BoardService/GetDigitalInterruptValue" => {
@@ -251,6 +318,15 @@ impl<'a> GrpcServerInner<'a> {
             "/viam.component.generic.v1.GenericService/DoCommand" => {
                 self.generic_component_do_command(payload)
             }
+            "/viam.service.generic.v1.GenericService/DoCommand" => {
+                self.generic_service_do_command(payload)
+            }
+            "/viam.service.sensors.v1.SensorsService/GetSensors" => {
+                self.sensors_service_get_sensors(payload)
+            }
+            "/viam.service.sensors.v1.SensorsService/GetReadings" => {
+                self.sensors_service_get_readings(payload)
+            }
             #[cfg(feature = "camera")]
             "/viam.component.camera.v1.CameraService/GetImage" => self.camera_get_image(payload),
             #[cfg(feature = "camera")]
@@ -263,8 +339,6 @@ impl<'a> GrpcServerInner<'a> {
             "/viam.component.motor.v1.MotorService/GetProperties" => {
                 self.motor_get_properties(payload)
             }
-            "/viam.component.motor.v1.MotorService/GoFor" => self.motor_go_for(payload),
-            "/viam.component.motor.v1.MotorService/GoTo" => self.motor_go_to(payload),
             "/viam.component.motor.v1.MotorService/IsPowered" => self.motor_is_powered(payload),
             "/viam.component.motor.v1.MotorService/IsMoving" => self.motor_is_moving(payload),
             "/viam.component.motor.v1.MotorService/ResetZeroPosition" => {
@@ -363,6 +437,59 @@ impl<'a> GrpcServerInner<'a> {
                 self.switch_get_num_positions(payload)
             }
             "/viam.component.switch.v1.SwitchService/DoCommand" => self.switch_do_command(payload),
+            "/viam.component.arm.v1.ArmService/GetEndPosition" => {
+                self.arm_get_end_position(payload)
+            }
+            "/viam.component.arm.v1.ArmService/MoveToPosition" => {
+                self.arm_move_to_position(payload)
+            }
+            "/viam.component.arm.v1.ArmService/GetJointPositions" => {
+                self.arm_get_joint_positions(payload)
+            }
+            "/viam.component.arm.v1.ArmService/Stop" => self.arm_stop(payload),
+            "/viam.component.arm.v1.ArmService/IsMoving" => self.arm_is_moving(payload),
+            "/viam.component.arm.v1.ArmService/DoCommand" => self.arm_do_command(payload),
+            "/viam.component.gripper.v1.GripperService/Open" => self.gripper_open(payload),
+            "/viam.component.gripper.v1.GripperService/Grab" => self.gripper_grab(payload),
+            "/viam.component.gripper.v1.GripperService/Stop" => self.gripper_stop(payload),
+            "/viam.component.gripper.v1.GripperService/IsMoving" => self.gripper_is_moving(payload),
+            "/viam.component.gripper.v1.GripperService/DoCommand" => {
+                self.gripper_do_command(payload)
+            }
+            "/viam.component.gantry.v1.GantryService/GetPosition" => {
+                self.gantry_get_position(payload)
+            }
+            "/viam.component.gantry.v1.GantryService/MoveToPosition" => {
+                self.gantry_move_to_position(payload)
+            }
+            "/viam.component.gantry.v1.GantryService/Home" => self.gantry_home(payload),
+            "/viam.component.gantry.v1.GantryService/GetLengths" => {
+                self.gantry_get_lengths(payload)
+            }
+            "/viam.component.gantry.v1.GantryService/Stop" => self.gantry_stop(payload),
+            "/viam.component.gantry.v1.GantryService/IsMoving" => self.gantry_is_moving(payload),
+            "/viam.component.gantry.v1.GantryService/DoCommand" => self.gantry_do_command(payload),
+            "/viam.component.inputcontroller.v1.InputControllerService/GetControls" => {
+                self.input_controller_get_controls(payload)
+            }
+            "/viam.component.inputcontroller.v1.InputControllerService/GetEvents" => {
+                self.input_controller_get_events(payload)
+            }
+            "/viam.component.inputcontroller.v1.InputControllerService/DoCommand" => {
+                self.input_controller_do_command(payload)
+            }
+            "/viam.component.posetracker.v1.PoseTrackerService/GetPoses" => {
+                self.pose_tracker_get_poses(payload)
+            }
+            "/viam.component.posetracker.v1.PoseTrackerService/DoCommand" => {
+                self.pose_tracker_do_command(payload)
+            }
+            "/viam.component.audioinput.v1.AudioInputService/Properties" => {
+                self.audio_input_properties(payload)
+            }
+            "/viam.component.audioinput.v1.AudioInputService/DoCommand" => {
+                self.audio_input_do_command(payload)
+            }
             _ => Err(ServerError::from(GrpcError::RpcUnimplemented)),
         }
     }
@@ -397,35 +524,70 @@ impl<'a> GrpcServerInner<'a> {
         GrpcServerInner::encode_message(props)
     }
 
-    fn motor_go_for(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        // TODO: internal go_for can't wait without blocking executor, must be waited from here.
-        // requires refactoring this function (and its callers) to be async
-        /*
+    fn motor_go_for(&mut self, message: &[u8]) -> Result<UnaryRpcFuture, ServerError> {
         let req = component::motor::v1::GoForRequest::decode(message)
             .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
         let motor = match self.robot.lock().unwrap().get_motor_by_name(req.name) {
             Some(m) => m,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
-        let mut motor = motor.lock().unwrap();
-
-        if let Some(dur) =  motor.go_for(req.rpm, req.revolutions).map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err)))? {
-            // async wait for duration
-        }
-        motor.lock().unwrap();
-
+        let dur = motor
+            .lock()
+            .unwrap()
+            .go_for(req.rpm, req.revolutions)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
         let resp = component::motor::v1::GoForResponse {};
-        GrpcServerInner::encode_message(resp)
-        */
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+        if dur.is_none() && req.revolutions != 0.0 {
+            return Ok(Box::pin(wait_until_stopped(motor, resp)));
+        }
+        Ok(Box::pin(wait_then_stop(motor, dur, resp)))
     }
 
-    fn motor_go_to(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn motor_go_to(&mut self, message: &[u8]) -> Result<UnaryRpcFuture, ServerError> {
+        let req = component::motor::v1::GoToRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let motor = match self.robot.lock().unwrap().get_motor_by_name(req.name) {
+            Some(m) => m,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let (revolutions, dur) = {
+            let mut motor = motor.lock().unwrap();
+            let pos = motor
+                .get_position()
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+            let revolutions = req.position_revolutions - pos as f64;
+            // go_for would run indefinitely with 0 revolutions, we are already there
+            if revolutions == 0.0 {
+                (revolutions, None)
+            } else {
+                // the direction is given by the sign of revolutions
+                let dur = motor
+                    .go_for(req.rpm.abs(), revolutions)
+                    .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+                (revolutions, dur)
+            }
+        };
+        let resp = component::motor::v1::GoToResponse {};
+        if dur.is_none() && revolutions != 0.0 {
+            return Ok(Box::pin(wait_until_stopped(motor, resp)));
+        }
+        Ok(Box::pin(wait_then_stop(motor, dur, resp)))
     }
 
-    fn motor_is_powered(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn motor_is_powered(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::motor::v1::IsPoweredRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let motor = match self.robot.lock().unwrap().get_motor_by_name(req.name) {
+            Some(m) => m,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let (is_on, power_pct) = motor
+            .lock()
+            .unwrap()
+            .is_powered()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::motor::v1::IsPoweredResponse { is_on, power_pct };
+        GrpcServerInner::encode_message(resp)
     }
 
     fn motor_is_moving(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
@@ -481,6 +643,7 @@ impl<'a> GrpcServerInner<'a> {
             Some(m) => m,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
+        cancel_pending_stop(&motor);
         motor
             .lock()
             .unwrap()
@@ -497,6 +660,7 @@ impl<'a> GrpcServerInner<'a> {
             Some(m) => m,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
+        cancel_pending_stop(&motor);
         motor
             .set_rpm(req.rpm)
             .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
@@ -511,6 +675,7 @@ impl<'a> GrpcServerInner<'a> {
             Some(m) => m,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
+        cancel_pending_stop(&motor);
         motor
             .lock()
             .unwrap()
@@ -859,6 +1024,81 @@ impl<'a> GrpcServerInner<'a> {
         GrpcServerInner::encode_message(resp)
     }
 
+    fn generic_service_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let service = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_generic_service_by_name(req.name)
+        {
+            Some(s) => s,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = service
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    // the sensors service messages are deprecated, but SDKs still use them to read every
+    // sensor of a machine in a single request
+    #[allow(deprecated)]
+    fn sensors_service_get_sensors(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let _ = proto::service::sensors::v1::GetSensorsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let sensor_names = self.robot.lock().unwrap().get_sensor_names();
+        let resp = proto::service::sensors::v1::GetSensorsResponse { sensor_names };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    // A sensor that is missing or fails to be read doesn't fail the whole request, its
+    // readings hold a single "error" entry instead
+    #[allow(deprecated)]
+    fn sensors_service_get_readings(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::service::sensors::v1::GetReadingsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let sensors: Vec<_> = {
+            let robot = self.robot.lock().unwrap();
+            req.sensor_names
+                .into_iter()
+                .map(|name| {
+                    let sensor = robot.get_readings_by_name(&name);
+                    (name, sensor)
+                })
+                .collect()
+        };
+        let readings = sensors
+            .into_iter()
+            .map(|(name, sensor)| {
+                let readings = sensor
+                    .ok_or_else(|| format!("{}:{} not found", name.subtype, name.name))
+                    .and_then(|mut sensor| {
+                        sensor.get_generic_readings().map_err(|err| err.to_string())
+                    })
+                    .unwrap_or_else(|err| {
+                        log::warn!("sensors service couldn't read {}: {}", name.name, err);
+                        HashMap::from([(
+                            "error".to_owned(),
+                            google::protobuf::Value {
+                                kind: Some(google::protobuf::value::Kind::StringValue(err)),
+                            },
+                        )])
+                    });
+                proto::service::sensors::v1::Readings {
+                    name: Some(name),
+                    readings,
+                }
+            })
+            .collect();
+        let resp = proto::service::sensors::v1::GetReadingsResponse { readings };
+        GrpcServerInner::encode_message(resp)
+    }
+
     fn sensor_get_readings(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
         let req = proto::common::v1::GetReadingsRequest::decode(message)
             .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
@@ -1035,12 +1275,48 @@ impl<'a> GrpcServerInner<'a> {
         GrpcServerInner::encode_message(resp)
     }
 
-    fn movement_sensor_get_accuracy(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn movement_sensor_get_accuracy(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::movement_sensor::v1::GetAccuracyRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let m_sensor = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_movement_sensor_by_name(req.name)
+        {
+            Some(b) => b,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let accuracy = m_sensor
+            .lock()
+            .unwrap()
+            .get_accuracy()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::movement_sensor::v1::GetAccuracyResponse::from(accuracy);
+        GrpcServerInner::encode_message(resp)
     }
 
-    fn movement_sensor_get_orientation(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn movement_sensor_get_orientation(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::movement_sensor::v1::GetOrientationRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let m_sensor = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_movement_sensor_by_name(req.name)
+        {
+            Some(b) => b,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let orientation = m_sensor
+            .lock()
+            .unwrap()
+            .get_orientation()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::movement_sensor::v1::GetOrientationResponse {
+            orientation: Some(orientation.into()),
+        };
+        GrpcServerInner::encode_message(resp)
     }
 
     fn movement_sensor_get_readings(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
@@ -1086,16 +1362,61 @@ impl<'a> GrpcServerInner<'a> {
         GrpcServerInner::encode_message(resp)
     }
 
-    fn base_move_straight(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn base_move_straight(&mut self, message: &[u8]) -> Result<UnaryRpcFuture, ServerError> {
+        let req = component::base::v1::MoveStraightRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let base = match self.robot.lock().unwrap().get_base_by_name(req.name) {
+            Some(b) => b,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let dur = base
+            .lock()
+            .unwrap()
+            .move_straight(req.distance_mm, req.mm_per_sec)
+            .map_err(ServerError::from)?;
+        let resp = component::base::v1::MoveStraightResponse {};
+        if dur.is_none() && req.distance_mm != 0 {
+            return Ok(Box::pin(wait_until_stopped(base, resp)));
+        }
+        Ok(Box::pin(wait_then_stop(base, dur, resp)))
     }
 
-    fn base_spin(&mut self, _message: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn base_spin(&mut self, message: &[u8]) -> Result<UnaryRpcFuture, ServerError> {
+        let req = component::base::v1::SpinRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let base = match self.robot.lock().unwrap().get_base_by_name(req.name) {
+            Some(b) => b,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let dur = base
+            .lock()
+            .unwrap()
+            .spin(req.angle_deg, req.degs_per_sec)
+            .map_err(ServerError::from)?;
+        let resp = component::base::v1::SpinResponse {};
+        if dur.is_none() && req.angle_deg != 0.0 {
+            return Ok(Box::pin(wait_until_stopped(base, resp)));
+        }
+        Ok(Box::pin(wait_then_stop(base, dur, resp)))
     }
 
-    fn base_set_velocity(&mut self, _: &[u8]) -> Result<Bytes, ServerError> {
-        Err(ServerError::from(GrpcError::RpcUnimplemented))
+    fn base_set_velocity(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::base::v1::SetVelocityRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let base = match self.robot.lock().unwrap().get_base_by_name(req.name) {
+            Some(b) => b,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        cancel_pending_stop(&base);
+        base.lock()
+            .unwrap()
+            .set_velocity(
+                &req.linear.unwrap_or_default(),
+                &req.angular.unwrap_or_default(),
+            )
+            .map_err(ServerError::from)?;
+        let resp = component::base::v1::SetVelocityResponse {};
+        GrpcServerInner::encode_message(resp)
     }
 
     fn base_is_moving(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
@@ -1122,13 +1443,14 @@ impl<'a> GrpcServerInner<'a> {
             Some(b) => b,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
+        cancel_pending_stop(&base);
         base.lock()
             .unwrap()
             .set_power(
                 &req.linear.unwrap_or_default(),
                 &req.angular.unwrap_or_default(),
             )
-            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+            .map_err(ServerError::from)?;
         let resp = component::base::v1::SetPowerResponse {};
         GrpcServerInner::encode_message(resp)
     }
@@ -1141,6 +1463,7 @@ impl<'a> GrpcServerInner<'a> {
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
 
+        cancel_pending_stop(&base);
         base.lock()
             .unwrap()
             .stop()
@@ -1385,100 +1708,714 @@ impl<'a> GrpcServerInner<'a> {
         GrpcServerInner::encode_message(resp)
     }
 
-    // robot_get_operations returns an empty response since operations are not yet
-    // supported on micro-rdk
-    fn robot_get_operations(&mut self, _: &[u8]) -> Result<Bytes, ServerError> {
-        let operation = robot::v1::GetOperationsResponse::default();
-        GrpcServerInner::encode_message(operation)
-    }
-
-    // robot_shutdown will not return anything because will restart
-    fn robot_shutdown(&mut self, _: &[u8]) -> ! {
-        crate::common::runtime::terminate()
-    }
-
-    fn robot_get_cloud_metadata(&mut self) -> Result<Bytes, ServerError> {
-        let resp = self
-            .robot
+    fn arm_get_end_position(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::arm::v1::GetEndPositionRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let pose = arm
             .lock()
             .unwrap()
-            .get_cloud_metadata()
-            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+            .get_end_position()
+            .map_err(ServerError::from)?;
+        let resp = component::arm::v1::GetEndPositionResponse { pose: Some(pose) };
         GrpcServerInner::encode_message(resp)
     }
 
-    #[cfg(feature = "camera")]
-    fn camera_get_image(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
-        // TODO: Modify camera methods (ie `get_image`, `render_frame`) to return a data structure that can be passed into
-        // `encode_message`, rather than re-implementing `encode_message` here. See
-        // https://viam.atlassian.net/browse/RSDK-824
-        let req = component::camera::v1::GetImageRequest::decode(message)
+    fn arm_move_to_position(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::arm::v1::MoveToPositionRequest::decode(message)
             .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
-
-        let camera = self
-            .robot
-            .lock()
-            .unwrap()
-            .get_camera_by_name(req.name)
-            .ok_or(GrpcError::RpcUnavailable)?;
-
-        let image = camera
-            .lock()
-            .unwrap()
-            .get_image()
-            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
-
-        let resp = component::camera::v1::GetImageResponse {
-            mime_type: "image/jpeg".to_string(),
-            image,
+        let pose = req
+            .to
+            .ok_or(ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
+        arm.lock()
+            .unwrap()
+            .move_to_position(pose)
+            .map_err(ServerError::from)?;
+        let resp = component::arm::v1::MoveToPositionResponse {};
         GrpcServerInner::encode_message(resp)
     }
 
-    #[cfg(feature = "camera")]
-    fn camera_render_frame(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
-        let req = component::camera::v1::RenderFrameRequest::decode(message)
+    fn arm_get_joint_positions(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::arm::v1::GetJointPositionsRequest::decode(message)
             .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
-
-        let camera = self
-            .robot
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let values = arm
             .lock()
             .unwrap()
-            .get_camera_by_name(req.name)
-            .ok_or(GrpcError::RpcUnavailable)?;
+            .get_joint_positions()
+            .map_err(ServerError::from)?;
+        let resp = component::arm::v1::GetJointPositionsResponse {
+            positions: Some(component::arm::v1::JointPositions { values }),
+        };
+        GrpcServerInner::encode_message(resp)
+    }
 
-        let image = camera
+    fn arm_move_to_joint_positions(
+        &mut self,
+        message: &[u8],
+    ) -> Result<UnaryRpcFuture, ServerError> {
+        let req = component::arm::v1::MoveToJointPositionsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let positions = req
+            .positions
+            .ok_or(ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let travel_time = arm
             .lock()
             .unwrap()
-            .get_image()
-            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+            .move_to_joint_positions(positions.values)
+            .map_err(ServerError::from)?;
+        Ok(Box::pin(async move {
+            arm::wait_for_joint_positions(&arm, travel_time)
+                .await
+                .map_err(ServerError::from)?;
+            GrpcServerInner::encode_message(component::arm::v1::MoveToJointPositionsResponse {})
+        }))
+    }
 
-        let msg = crate::google::api::HttpBody {
-            content_type: "image/jpeg".to_string(),
-            data: image.to_vec(),
-            ..Default::default()
+    fn arm_move_through_joint_positions(
+        &mut self,
+        message: &[u8],
+    ) -> Result<UnaryRpcFuture, ServerError> {
+        let req = component::arm::v1::MoveThroughJointPositionsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
-        GrpcServerInner::encode_message(msg)
+        let positions = req.positions.into_iter().map(|p| p.values).collect();
+        Ok(Box::pin(async move {
+            arm::move_through_joint_positions(arm, positions)
+                .await
+                .map_err(ServerError::from)?;
+            GrpcServerInner::encode_message(
+                component::arm::v1::MoveThroughJointPositionsResponse {},
+            )
+        }))
     }
 
-    #[cfg(feature = "camera")]
-    fn camera_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
-        let req = proto::common::v1::DoCommandRequest::decode(message)
+    fn arm_stop(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::arm::v1::StopRequest::decode(message)
             .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
-        let camera = match self.robot.lock().unwrap().get_camera_by_name(req.name) {
-            Some(m) => m,
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
             None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
         };
-        let res = camera
-            .lock()
+        arm.lock()
             .unwrap()
-            .do_command(req.command)
-            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
-        let resp = proto::common::v1::DoCommandResponse { result: res };
+            .stop()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::arm::v1::StopResponse {};
         GrpcServerInner::encode_message(resp)
     }
 
-    fn get_version(&mut self) -> Result<Bytes, ServerError> {
+    fn arm_is_moving(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::arm::v1::IsMovingRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let resp = component::arm::v1::IsMovingResponse {
+            is_moving: arm
+                .lock()
+                .unwrap()
+                .is_moving()
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn arm_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let arm = match self.robot.lock().unwrap().get_arm_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = arm
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gripper_open(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gripper::v1::OpenRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gripper = match self.robot.lock().unwrap().get_gripper_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        gripper
+            .lock()
+            .unwrap()
+            .open()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::gripper::v1::OpenResponse {};
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gripper_grab(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gripper::v1::GrabRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gripper = match self.robot.lock().unwrap().get_gripper_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let success = gripper
+            .lock()
+            .unwrap()
+            .grab()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::gripper::v1::GrabResponse {
+            success,
+            extra: None,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gripper_stop(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gripper::v1::StopRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gripper = match self.robot.lock().unwrap().get_gripper_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        gripper
+            .lock()
+            .unwrap()
+            .stop()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::gripper::v1::StopResponse {};
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gripper_is_moving(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gripper::v1::IsMovingRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gripper = match self.robot.lock().unwrap().get_gripper_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let resp = component::gripper::v1::IsMovingResponse {
+            is_moving: gripper
+                .lock()
+                .unwrap()
+                .is_moving()
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gripper_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gripper = match self.robot.lock().unwrap().get_gripper_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = gripper
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_get_position(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::GetPositionRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let positions_mm = gantry
+            .lock()
+            .unwrap()
+            .get_position()
+            .map_err(ServerError::from)?;
+        let resp = component::gantry::v1::GetPositionResponse { positions_mm };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_move_to_position(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::MoveToPositionRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        gantry
+            .lock()
+            .unwrap()
+            .move_to_position(req.positions_mm, req.speeds_mm_per_sec)
+            .map_err(ServerError::from)?;
+        let resp = component::gantry::v1::MoveToPositionResponse {};
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_home(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::HomeRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let homed = gantry.lock().unwrap().home().map_err(ServerError::from)?;
+        let resp = component::gantry::v1::HomeResponse { homed };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_get_lengths(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::GetLengthsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let lengths_mm = gantry
+            .lock()
+            .unwrap()
+            .get_lengths()
+            .map_err(ServerError::from)?;
+        let resp = component::gantry::v1::GetLengthsResponse { lengths_mm };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_stop(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::StopRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        gantry
+            .lock()
+            .unwrap()
+            .stop()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::gantry::v1::StopResponse {};
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_is_moving(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::gantry::v1::IsMovingRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let resp = component::gantry::v1::IsMovingResponse {
+            is_moving: gantry
+                .lock()
+                .unwrap()
+                .is_moving()
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn gantry_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let gantry = match self.robot.lock().unwrap().get_gantry_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = gantry
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn input_controller_get_controls(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::inputcontroller::v1::GetControlsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let controller = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_input_controller_by_name(req.controller)
+        {
+            Some(c) => c,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let controls = controller
+            .lock()
+            .unwrap()
+            .get_controls()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::inputcontroller::v1::GetControlsResponse { controls };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn input_controller_get_events(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::inputcontroller::v1::GetEventsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let controller = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_input_controller_by_name(req.controller)
+        {
+            Some(c) => c,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let events = controller
+            .lock()
+            .unwrap()
+            .get_events()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::inputcontroller::v1::GetEventsResponse { events };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn input_controller_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let controller = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_input_controller_by_name(req.name)
+        {
+            Some(c) => c,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = controller
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn input_controller_stream_events(&self, message: &[u8]) -> Result<StreamPoller, ServerError> {
+        let req = component::inputcontroller::v1::StreamEventsRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let controller = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_input_controller_by_name(req.controller)
+        {
+            Some(c) => c,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        Ok(Box::new(
+            move |since: Option<SystemTime>, now: SystemTime| {
+                let messages = input_controller::events_since(
+                    &mut *controller.lock().unwrap(),
+                    &req.events,
+                    since.unwrap_or(now),
+                    now,
+                )
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?
+                .into_iter()
+                .map(|event| {
+                    GrpcServerInner::encode_message(
+                        component::inputcontroller::v1::StreamEventsResponse { event: Some(event) },
+                    )
+                })
+                .collect::<Result<Vec<Bytes>, ServerError>>()?;
+                Ok((
+                    messages,
+                    Instant::now() + input_controller::STREAM_EVENTS_INTERVAL,
+                ))
+            },
+        ))
+    }
+
+    fn pose_tracker_get_poses(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::posetracker::v1::GetPosesRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let tracker = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_pose_tracker_by_name(req.name)
+        {
+            Some(t) => t,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let body_poses = tracker
+            .lock()
+            .unwrap()
+            .get_poses(req.body_names)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::posetracker::v1::GetPosesResponse { body_poses };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn pose_tracker_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let tracker = match self
+            .robot
+            .lock()
+            .unwrap()
+            .get_pose_tracker_by_name(req.name)
+        {
+            Some(t) => t,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = tracker
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn audio_input_properties(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::audioinput::v1::PropertiesRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let audio = match self.robot.lock().unwrap().get_audio_input_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let props = audio
+            .lock()
+            .unwrap()
+            .get_properties()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = component::audioinput::v1::PropertiesResponse {
+            channel_count: props.channel_count,
+            latency: Some(google::protobuf::Duration {
+                seconds: props.latency.as_secs() as i64,
+                nanos: props.latency.subsec_nanos() as i32,
+            }),
+            sample_rate: props.sample_rate,
+            sample_size: std::mem::size_of::<i16>() as u32,
+            is_big_endian: false,
+            is_float: false,
+            is_interleaved: true,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    // the first message of the stream describes the format of the chunks that follow
+    fn audio_input_chunks(&self, message: &[u8]) -> Result<StreamPoller, ServerError> {
+        let req = component::audioinput::v1::ChunksRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let format = match component::audioinput::v1::SampleFormat::try_from(req.sample_format) {
+            Ok(component::audioinput::v1::SampleFormat::Float32Interleaved) => {
+                component::audioinput::v1::SampleFormat::Float32Interleaved
+            }
+            Ok(_) => component::audioinput::v1::SampleFormat::Int16Interleaved,
+            Err(_) => return Err(ServerError::from(GrpcError::RpcInvalidArgument)),
+        };
+        let audio = match self.robot.lock().unwrap().get_audio_input_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        Ok(Box::new(move |since: Option<SystemTime>, _| {
+            let mut audio = audio.lock().unwrap();
+            let mut messages = vec![];
+            if since.is_none() {
+                let props = audio
+                    .get_properties()
+                    .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+                let info = component::audioinput::v1::AudioChunkInfo {
+                    sample_format: format.into(),
+                    channels: props.channel_count,
+                    sampling_rate: props.sample_rate as i64,
+                };
+                messages.push(GrpcServerInner::encode_message(
+                    component::audioinput::v1::ChunksResponse {
+                        r#type: Some(component::audioinput::v1::chunks_response::Type::Info(info)),
+                    },
+                )?);
+            }
+            for chunk in audio_input::read_chunks(&mut *audio, format)
+                .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?
+            {
+                messages.push(GrpcServerInner::encode_message(
+                    component::audioinput::v1::ChunksResponse {
+                        r#type: Some(component::audioinput::v1::chunks_response::Type::Chunk(
+                            chunk,
+                        )),
+                    },
+                )?);
+            }
+            Ok((messages, Instant::now() + audio_input::CHUNK_INTERVAL))
+        }))
+    }
+
+    fn audio_input_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let audio = match self.robot.lock().unwrap().get_audio_input_by_name(req.name) {
+            Some(a) => a,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = audio
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    // over HTTP2 a task on the executor polls the stream for as long as the client keeps it
+    // open, WebRTC keeps the poller with the call and polls it between requests instead
+    fn polled_stream(
+        self,
+        path: &str,
+        payload: &[u8],
+    ) -> Pin<Box<dyn futures_lite::Stream<Item = Result<Bytes, ServerError>> + Sync + Send>> {
+        let (sender, receiver) = async_channel::bounded::<Result<Bytes, ServerError>>(1);
+        match self.stream_poller(path, payload) {
+            Ok(mut poller) => {
+                Executor::new()
+                    .spawn(async move {
+                        let mut since = None;
+                        while !sender.is_closed() {
+                            let now = SystemTime::now();
+                            let polled = poller(since, now);
+                            let _ = since.insert(now);
+                            match polled {
+                                Ok((messages, next)) => {
+                                    for msg in messages {
+                                        if sender.send(Ok(msg)).await.is_err() {
+                                            return;
+                                        }
+                                    }
+                                    async_io::Timer::at(next).await;
+                                }
+                                Err(err) => {
+                                    let _ = sender.send(Err(err)).await;
+                                    return;
+                                }
+                            }
+                        }
+                    })
+                    .detach();
+            }
+            Err(err) => {
+                let _ = sender.send_blocking(Err(err));
+            }
+        }
+        Box::pin(receiver)
+    }
+
+    // robot_get_operations returns an empty response since operations are not yet
+    // supported on micro-rdk
+    fn robot_get_operations(&mut self, _: &[u8]) -> Result<Bytes, ServerError> {
+        let operation = robot::v1::GetOperationsResponse::default();
+        GrpcServerInner::encode_message(operation)
+    }
+
+    // robot_shutdown will not return anything because will restart
+    fn robot_shutdown(&mut self, _: &[u8]) -> ! {
+        crate::common::runtime::terminate()
+    }
+
+    fn robot_get_cloud_metadata(&mut self) -> Result<Bytes, ServerError> {
+        let resp = self
+            .robot
+            .lock()
+            .unwrap()
+            .get_cloud_metadata()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+        GrpcServerInner::encode_message(resp)
+    }
+
+    #[cfg(feature = "camera")]
+    fn camera_get_image(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        // TODO: Modify camera methods (ie `get_image`, `render_frame`) to return a data structure that can be passed into
+        // `encode_message`, rather than re-implementing `encode_message` here. See
+        // https://viam.atlassian.net/browse/RSDK-824
+        let req = component::camera::v1::GetImageRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+
+        let camera = self
+            .robot
+            .lock()
+            .unwrap()
+            .get_camera_by_name(req.name)
+            .ok_or(GrpcError::RpcUnavailable)?;
+
+        let image = camera
+            .lock()
+            .unwrap()
+            .get_image()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+
+        let resp = component::camera::v1::GetImageResponse {
+            mime_type: "image/jpeg".to_string(),
+            image,
+        };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    #[cfg(feature = "camera")]
+    fn camera_render_frame(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = component::camera::v1::RenderFrameRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+
+        let camera = self
+            .robot
+            .lock()
+            .unwrap()
+            .get_camera_by_name(req.name)
+            .ok_or(GrpcError::RpcUnavailable)?;
+
+        let image = camera
+            .lock()
+            .unwrap()
+            .get_image()
+            .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+
+        let msg = crate::google::api::HttpBody {
+            content_type: "image/jpeg".to_string(),
+            data: image.to_vec(),
+            ..Default::default()
+        };
+        GrpcServerInner::encode_message(msg)
+    }
+
+    #[cfg(feature = "camera")]
+    fn camera_do_command(&mut self, message: &[u8]) -> Result<Bytes, ServerError> {
+        let req = proto::common::v1::DoCommandRequest::decode(message)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let camera = match self.robot.lock().unwrap().get_camera_by_name(req.name) {
+            Some(m) => m,
+            None => return Err(ServerError::from(GrpcError::RpcUnavailable)),
+        };
+        let res = camera
+            .lock()
+            .unwrap()
+            .do_command(req.command)
+            .map_err(|_| ServerError::from(GrpcError::RpcInvalidArgument))?;
+        let resp = proto::common::v1::DoCommandResponse { result: res };
+        GrpcServerInner::encode_message(resp)
+    }
+
+    fn get_version(&mut self) -> Result<Bytes, ServerError> {
         let resp = proto::robot::v1::GetVersionResponse {
             platform: "viam-micro-server".to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
@@ -1570,30 +2507,146 @@ impl<'a> GrpcServerInner<'a> {
     }
 }
 
+/// Timed movements waiting to stop their actuator, keyed by the actuator's address. Each one is
+/// tagged with an id so it only ever removes its own entry.
+static PENDING_STOPS: Mutex<BTreeMap<usize, (usize, AbortHandle)>> = Mutex::new(BTreeMap::new());
+static NEXT_MOVEMENT_ID: AtomicUsize = AtomicUsize::new(0);
+static IS_MOVING_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+fn actuator_key<A: ?Sized>(actuator: &Arc<Mutex<A>>) -> usize {
+    Arc::as_ptr(actuator) as *const () as usize
+}
+
+/// Cancels the timer of a movement still running on `actuator`, another command now drives it.
+/// The cancelled movement answers right away without stopping the actuator.
+fn cancel_pending_stop<A: ?Sized>(actuator: &Arc<Mutex<A>>) {
+    if let Some((_, timer)) = PENDING_STOPS
+        .lock()
+        .unwrap()
+        .remove(&actuator_key(actuator))
+    {
+        timer.abort();
+    }
+}
+
+fn forget_pending_stop(key: usize, id: usize) {
+    let mut pending = PENDING_STOPS.lock().unwrap();
+    if pending.get(&key).is_some_and(|(owner, _)| *owner == id) {
+        let _ = pending.remove(&key);
+    }
+}
+
+/// Waits on the executor for the duration returned by a movement (e.g. `Motor::go_for`) then
+/// stops the actuator. It replaces the timer of any movement still running on the same
+/// actuator. Dropping the future before the duration elapsed (the stream was reset or the
+/// connection closed) stops the actuator.
+fn wait_then_stop<A: Actuator + ?Sized + 'static, M: Message + 'static>(
+    actuator: Arc<Mutex<A>>,
+    dur: Option<Duration>,
+    resp: M,
+) -> impl Future<Output = Result<Bytes, ServerError>> {
+    stop_when_done(
+        actuator,
+        dur.map(|dur| async move {
+            let _ = async_io::Timer::after(dur).await;
+        }),
+        resp,
+    )
+}
+
+/// Like [wait_then_stop] for a movement the actuator ends by itself (e.g. `Motor::go_for` under
+/// closed loop control returns no duration), waits until the actuator is no longer moving.
+fn wait_until_stopped<A: Actuator + ?Sized + 'static, M: Message + 'static>(
+    actuator: Arc<Mutex<A>>,
+    resp: M,
+) -> impl Future<Output = Result<Bytes, ServerError>> {
+    let moving = actuator.clone();
+    stop_when_done(
+        actuator,
+        Some(async move {
+            while moving.lock().unwrap().is_moving().unwrap_or(false) {
+                let _ = async_io::Timer::after(IS_MOVING_POLL_INTERVAL).await;
+            }
+        }),
+        resp,
+    )
+}
+
+fn stop_when_done<A, M, F>(
+    actuator: Arc<Mutex<A>>,
+    done: Option<F>,
+    resp: M,
+) -> impl Future<Output = Result<Bytes, ServerError>>
+where
+    A: Actuator + ?Sized + 'static,
+    M: Message + 'static,
+    F: Future<Output = ()> + 'static,
+{
+    cancel_pending_stop(&actuator);
+    // register the wait now rather than on the first poll, a command handled in between
+    // must be able to cancel it
+    let timer = done.map(|done| {
+        let key = actuator_key(&actuator);
+        let id = NEXT_MOVEMENT_ID.fetch_add(1, Ordering::Relaxed);
+        let (handle, registration) = AbortHandle::new_pair();
+        let _ = PENDING_STOPS.lock().unwrap().insert(key, (id, handle));
+        let actuator = scopeguard::guard(actuator, move |actuator| {
+            forget_pending_stop(key, id);
+            let _ = actuator.lock().unwrap().stop();
+        });
+        (actuator, key, id, Abortable::new(done, registration))
+    });
+    async move {
+        if let Some((actuator, key, id, timer)) = timer {
+            let cancelled = timer.await.is_err();
+            let actuator = ScopeGuard::into_inner(actuator);
+            if !cancelled {
+                forget_pending_stop(key, id);
+                actuator
+                    .lock()
+                    .unwrap()
+                    .stop()
+                    .map_err(|err| ServerError::new(GrpcError::RpcInternal, Some(err.into())))?;
+            }
+        }
+        GrpcServerInner::encode_message(resp)
+    }
+}
+
 impl<R> WebRtcGrpcService for GrpcServer<R>
 where
     R: GrpcResponse + 'static,
 {
-    fn unary_rpc(&mut self, method: &str, data: &Bytes) -> Result<Bytes, ServerError> {
+    fn unary_rpc(&mut self, method: &str, data: &Bytes) -> UnaryRpcFuture {
         let grpc = GrpcServerInner {
             robot: &self.robot,
             signaling_server: &self.signaling_server,
         };
-        grpc.handle_unary_request(method, data)
-            .map(|mut b| b.split_off(5))
+        let fut = grpc.handle_unary_request(method, data);
+        Box::pin(async move { fut.await.map(|mut b| b.split_off(5)) })
+    }
+    fn is_server_stream_rpc(&self, method: &str) -> bool {
+        is_server_streaming_rpc(method)
     }
     fn server_stream_rpc(
         &mut self,
         method: &str,
         data: &Bytes,
-    ) -> Result<(Bytes, Instant), ServerError> {
+    ) -> Result<StreamPoller, ServerError> {
         log::debug!("stream req is {:?}, ", method);
-        let mut grpc = GrpcServerInner {
+        let grpc = GrpcServerInner {
             robot: &self.robot,
             signaling_server: &self.signaling_server,
         };
-        grpc.handle_rpc_stream(method, data)
-            .map(|mut dur| (dur.0.split_off(5), dur.1))
+        let mut poller = grpc.stream_poller(method, data)?;
+        Ok(Box::new(move |since, now| {
+            poller(since, now).map(|(messages, next)| {
+                (
+                    messages.into_iter().map(|mut m| m.split_off(5)).collect(),
+                    next,
+                )
+            })
+        }))
     }
 }
 
@@ -1761,6 +2814,40 @@ impl From<InvalidUri> for ServerError {
     }
 }
 
+impl From<BaseError> for ServerError {
+    fn from(value: BaseError) -> Self {
+        let grpc_error = match value {
+            BaseError::BaseInvalidArgument(_) => GrpcError::RpcInvalidArgument,
+            _ => GrpcError::RpcInternal,
+        };
+        Self::new(grpc_error, Some(value.into()))
+    }
+}
+
+impl From<arm::ArmError> for ServerError {
+    fn from(value: arm::ArmError) -> Self {
+        let grpc_error = match value {
+            arm::ArmError::ArmInvalidArgument(_) | arm::ArmError::ArmJointCountMismatch(..) => {
+                GrpcError::RpcInvalidArgument
+            }
+            _ => GrpcError::RpcInternal,
+        };
+        Self::new(grpc_error, Some(value.into()))
+    }
+}
+
+impl From<GantryError> for ServerError {
+    fn from(value: GantryError) -> Self {
+        let grpc_error = match value {
+            GantryError::GantryInvalidArgument(_) | GantryError::GantryAxisCountMismatch(..) => {
+                GrpcError::RpcInvalidArgument
+            }
+            _ => GrpcError::RpcInternal,
+        };
+        Self::new(grpc_error, Some(value.into()))
+    }
+}
+
 impl From<GrpcError> for ServerError {
     fn from(grpc_error: GrpcError) -> Self {
         Self {
@@ -1778,3 +2865,750 @@ impl fmt::Display for ServerError {
         }
     }
 }
+
+#[cfg(all(test, feature = "builtin-components"))]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::{Duration, Instant, SystemTime},
+    };
+
+    use async_io::Timer;
+    use bytes::Bytes;
+    use futures_lite::future::block_on;
+    use futures_util::FutureExt;
+    use prost::Message;
+
+    use super::{
+        actuator_key, wait_until_stopped, GrpcBody, GrpcError, GrpcServer, UnaryRpcFuture,
+        PENDING_STOPS,
+    };
+    use crate::{
+        common::{
+            board::Board,
+            config::{DynamicComponentConfig, Kind, Model, ResourceName},
+            motor::{FakeMotor, Motor},
+            registry::ComponentRegistry,
+            robot::LocalRobot,
+            webrtc::grpc::WebRtcGrpcService,
+        },
+        google::protobuf::value::Kind as Value,
+        proto::component::{
+            arm::v1::{
+                GetJointPositionsRequest, GetJointPositionsResponse, JointPositions,
+                MoveThroughJointPositionsRequest, MoveToJointPositionsRequest,
+            },
+            audioinput::v1::{
+                chunks_response, ChunksRequest, ChunksResponse, PropertiesRequest,
+                PropertiesResponse, SampleFormat,
+            },
+            gantry::v1::{
+                GetLengthsRequest, GetLengthsResponse, GetPositionRequest, GetPositionResponse,
+                HomeRequest, HomeResponse, MoveToPositionRequest,
+            },
+            gripper::v1::{GrabRequest, GrabResponse, OpenRequest},
+            inputcontroller::v1::{
+                stream_events_request, Event, GetEventsRequest, StreamEventsRequest,
+                StreamEventsResponse,
+            },
+            motor::v1::{GoForRequest, GoForResponse, SetPowerRequest},
+            posetracker::v1::{GetPosesRequest, GetPosesResponse},
+        },
+        proto::service::sensors::v1::{
+            GetReadingsRequest, GetReadingsResponse, GetSensorsRequest, GetSensorsResponse,
+        },
+    };
+
+    fn component(
+        name: &str,
+        subtype: &str,
+        model: &str,
+        attributes: Option<HashMap<String, Kind>>,
+    ) -> Option<DynamicComponentConfig> {
+        Some(DynamicComponentConfig {
+            name: ResourceName::new_builtin(name.to_owned(), subtype.to_owned()),
+            model: Model::new_builtin(model.to_owned()),
+            attributes,
+            #[cfg(feature = "data")]
+            data_collector_configs: vec![],
+        })
+    }
+
+    fn setup_robot_with(conf: Vec<Option<DynamicComponentConfig>>) -> Arc<Mutex<LocalRobot>> {
+        let mut robot = LocalRobot::default();
+        let mut registry: Box<ComponentRegistry> = Box::default();
+        robot.process_components(conf, &mut registry).unwrap();
+        Arc::new(Mutex::new(robot))
+    }
+
+    fn setup_robot() -> Arc<Mutex<LocalRobot>> {
+        setup_robot_with(vec![component("motor", "motor", "fake", None)])
+    }
+
+    fn motor_power(robot: &Arc<Mutex<LocalRobot>>) -> f64 {
+        let motor = robot
+            .lock()
+            .unwrap()
+            .get_motor_by_name("motor".to_owned())
+            .unwrap();
+        let (_, power) = motor.lock().unwrap().is_powered().unwrap();
+        power
+    }
+
+    // whether a movement of the motor is waiting to stop it
+    fn has_pending_stop(robot: &Arc<Mutex<LocalRobot>>) -> bool {
+        let motor = robot
+            .lock()
+            .unwrap()
+            .get_motor_by_name("motor".to_owned())
+            .unwrap();
+        PENDING_STOPS
+            .lock()
+            .unwrap()
+            .contains_key(&actuator_key(&motor))
+    }
+
+    // the fake motor has a max rpm of 100, a movement at `rpm` powers it to `rpm / 100`
+    fn go_for(server: &mut GrpcServer<GrpcBody>, rpm: f64, revolutions: f64) -> UnaryRpcFuture {
+        let req = GoForRequest {
+            name: "motor".to_owned(),
+            rpm,
+            revolutions,
+            extra: None,
+        };
+        server.unary_rpc(
+            "/viam.component.motor.v1.MotorService/GoFor",
+            &Bytes::from(req.encode_to_vec()),
+        )
+    }
+
+    #[test_log::test]
+    fn test_go_for_stops_after_duration() {
+        let robot = setup_robot();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        // 0.1 revolution at 100 rpm takes 60ms
+        let mut fut = go_for(&mut server, 100.0, 0.1);
+        assert!((&mut fut).now_or_never().is_none());
+        assert_eq!(motor_power(&robot), 1.0);
+        assert!(block_on(fut).is_ok());
+        assert_eq!(motor_power(&robot), 0.0);
+    }
+
+    #[test_log::test]
+    fn test_overlapping_go_for() {
+        let robot = setup_robot();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        // a movement replaces a running one, which answers right away, the first movement
+        // takes 10 minutes so the second one can only answer on its own timer
+        let mut first = go_for(&mut server, 10.0, 100.0);
+        assert!((&mut first).now_or_never().is_none());
+        let mut second = go_for(&mut server, 100.0, 0.1);
+        assert!((&mut second).now_or_never().is_none());
+        assert!(matches!(first.now_or_never(), Some(Ok(_))));
+        assert!(has_pending_stop(&robot));
+        assert_eq!(motor_power(&robot), 1.0);
+        assert!(block_on(second).is_ok());
+        assert!(!has_pending_stop(&robot));
+        assert_eq!(motor_power(&robot), 0.0);
+
+        // the timer of a replaced movement must not stop the next one
+        let mut first = go_for(&mut server, 100.0, 0.1);
+        assert!((&mut first).now_or_never().is_none());
+        let mut second = go_for(&mut server, 50.0, 100.0);
+        assert!((&mut second).now_or_never().is_none());
+        assert!(matches!(first.now_or_never(), Some(Ok(_))));
+        assert!(has_pending_stop(&robot));
+        assert_eq!(motor_power(&robot), 0.5);
+        assert!((&mut second).now_or_never().is_none());
+        drop(second);
+        assert!(!has_pending_stop(&robot));
+        assert_eq!(motor_power(&robot), 0.0);
+    }
+
+    #[test_log::test]
+    fn test_cancelled_go_for() {
+        let robot = setup_robot();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        // dropping the call (the stream was reset) stops the motor
+        let mut fut = go_for(&mut server, 50.0, 1.0);
+        assert!((&mut fut).now_or_never().is_none());
+        assert_eq!(motor_power(&robot), 0.5);
+        drop(fut);
+        assert_eq!(motor_power(&robot), 0.0);
+
+        // another command on the motor cancels the movement's timer without stopping it
+        let mut fut = go_for(&mut server, 50.0, 100.0);
+        assert!((&mut fut).now_or_never().is_none());
+        let req = SetPowerRequest {
+            name: "motor".to_owned(),
+            power_pct: 0.3,
+            extra: None,
+        };
+        let set_power = server.unary_rpc(
+            "/viam.component.motor.v1.MotorService/SetPower",
+            &Bytes::from(req.encode_to_vec()),
+        );
+        assert!(matches!(set_power.now_or_never(), Some(Ok(_))));
+        assert!(matches!(fut.now_or_never(), Some(Ok(_))));
+        assert!(!has_pending_stop(&robot));
+        assert_eq!(motor_power(&robot), 0.3);
+    }
+
+    fn joint_positions(server: &mut GrpcServer<GrpcBody>, name: &str) -> Vec<f64> {
+        let req = GetJointPositionsRequest {
+            name: name.to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.arm.v1.ArmService/GetJointPositions",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        GetJointPositionsResponse::decode(resp)
+            .unwrap()
+            .positions
+            .unwrap()
+            .values
+    }
+
+    fn move_to_joint_positions(
+        server: &mut GrpcServer<GrpcBody>,
+        name: &str,
+        values: Vec<f64>,
+    ) -> UnaryRpcFuture {
+        let req = MoveToJointPositionsRequest {
+            name: name.to_owned(),
+            positions: Some(JointPositions { values }),
+            extra: None,
+        };
+        server.unary_rpc(
+            "/viam.component.arm.v1.ArmService/MoveToJointPositions",
+            &Bytes::from(req.encode_to_vec()),
+        )
+    }
+
+    fn move_through_joint_positions(
+        server: &mut GrpcServer<GrpcBody>,
+        name: &str,
+        positions: Vec<Vec<f64>>,
+    ) -> UnaryRpcFuture {
+        let req = MoveThroughJointPositionsRequest {
+            name: name.to_owned(),
+            positions: positions
+                .into_iter()
+                .map(|values| JointPositions { values })
+                .collect(),
+            options: None,
+            extra: None,
+        };
+        server.unary_rpc(
+            "/viam.component.arm.v1.ArmService/MoveThroughJointPositions",
+            &Bytes::from(req.encode_to_vec()),
+        )
+    }
+
+    fn setup_arms() -> Arc<Mutex<LocalRobot>> {
+        let servos = Kind::VecValue(vec![
+            Kind::StringValue("base".to_owned()),
+            Kind::StringValue("elbow".to_owned()),
+        ]);
+        setup_robot_with(vec![
+            component("arm", "arm", "fake", None),
+            component("base", "servo", "fake", None),
+            component("elbow", "servo", "fake", None),
+            component(
+                "chain",
+                "arm",
+                "servo_chain",
+                Some(HashMap::from([("servos".to_owned(), servos)])),
+            ),
+        ])
+    }
+
+    #[test_log::test]
+    fn test_wait_until_stopped() {
+        // a movement the motor ends by itself answers once the motor no longer moves
+        let motor = Arc::new(Mutex::new(FakeMotor::new()));
+        motor.lock().unwrap().set_power(0.5).unwrap();
+        let mut fut = Box::pin(wait_until_stopped(motor.clone(), GoForResponse {}));
+        assert!((&mut fut).now_or_never().is_none());
+        assert!((&mut fut).now_or_never().is_none());
+        motor.lock().unwrap().set_power(0.0).unwrap();
+        assert!(block_on(fut).is_ok());
+    }
+
+    #[test_log::test]
+    fn test_arm_move_to_joint_positions() {
+        let robot = setup_arms();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        // the fake arm reports it is not moving, the call answers right away
+        let positions = vec![10.0, 20.0, 30.0, 40.0, 50.0, 60.0];
+        let fut = move_to_joint_positions(&mut server, "arm", positions.clone());
+        assert!(matches!(fut.now_or_never(), Some(Ok(_))));
+        assert_eq!(joint_positions(&mut server, "arm"), positions);
+
+        // an invalid set of positions moves none of the joints
+        let fut = move_to_joint_positions(&mut server, "arm", vec![0.0; 5]);
+        assert!(matches!(
+            fut.now_or_never(),
+            Some(Err(err)) if err.status_code() == GrpcError::RpcInvalidArgument as i32
+        ));
+        let fut =
+            move_to_joint_positions(&mut server, "arm", vec![0.0, 0.0, 0.0, 0.0, 0.0, f64::NAN]);
+        assert!(matches!(
+            fut.now_or_never(),
+            Some(Err(err)) if err.status_code() == GrpcError::RpcInvalidArgument as i32
+        ));
+        assert_eq!(joint_positions(&mut server, "arm"), positions);
+
+        // the servo chain answers once its estimated travel time elapsed, 90deg at 300deg/s
+        let start = Instant::now();
+        let mut fut = move_to_joint_positions(&mut server, "chain", vec![90.0, 45.0]);
+        assert!((&mut fut).now_or_never().is_none());
+        assert_eq!(joint_positions(&mut server, "chain"), vec![90.0, 45.0]);
+        assert!(block_on(fut).is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+
+        let fut = move_to_joint_positions(&mut server, "chain", vec![90.0, 200.0]);
+        assert!(matches!(
+            fut.now_or_never(),
+            Some(Err(err)) if err.status_code() == GrpcError::RpcInvalidArgument as i32
+        ));
+        assert_eq!(joint_positions(&mut server, "chain"), vec![90.0, 45.0]);
+    }
+
+    #[test_log::test]
+    fn test_arm_move_through_joint_positions() {
+        let robot = setup_arms();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        // every pose is reached in turn, 60deg then 30deg at 300deg/s
+        let start = Instant::now();
+        let fut = move_through_joint_positions(
+            &mut server,
+            "chain",
+            vec![vec![60.0, 0.0], vec![60.0, 30.0]],
+        );
+        assert!(block_on(fut).is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(joint_positions(&mut server, "chain"), vec![60.0, 30.0]);
+
+        // the arm doesn't move when any of the poses is invalid
+        let fut = move_through_joint_positions(
+            &mut server,
+            "chain",
+            vec![vec![0.0, 0.0], vec![0.0, 181.0]],
+        );
+        assert!(matches!(
+            fut.now_or_never(),
+            Some(Err(err)) if err.status_code() == GrpcError::RpcInvalidArgument as i32
+        ));
+        assert_eq!(joint_positions(&mut server, "chain"), vec![60.0, 30.0]);
+
+        let fut =
+            move_through_joint_positions(&mut server, "arm", vec![vec![1.0; 6], vec![2.0; 6]]);
+        assert!(matches!(fut.now_or_never(), Some(Ok(_))));
+        assert_eq!(joint_positions(&mut server, "arm"), vec![2.0; 6]);
+    }
+
+    #[test_log::test]
+    fn test_gripper() {
+        let robot = setup_robot_with(vec![component("gripper", "gripper", "fake", None)]);
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let req = GrabRequest {
+            name: "gripper".to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.gripper.v1.GripperService/Grab",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(GrabResponse::decode(resp).unwrap().success);
+
+        let req = OpenRequest {
+            name: "gripper".to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.gripper.v1.GripperService/Open",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never();
+        assert!(matches!(resp, Some(Ok(_))));
+
+        let req = OpenRequest {
+            name: "missing".to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.gripper.v1.GripperService/Open",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never();
+        assert!(matches!(resp, Some(Err(_))));
+    }
+
+    #[test_log::test]
+    fn test_gantry() {
+        let lengths = Kind::VecValue(vec![Kind::NumberValue(100.0), Kind::NumberValue(50.0)]);
+        let robot = setup_robot_with(vec![component(
+            "gantry",
+            "gantry",
+            "fake",
+            Some(HashMap::from([("lengths_mm".to_owned(), lengths)])),
+        )]);
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let position = |server: &mut GrpcServer<GrpcBody>| {
+            let req = GetPositionRequest {
+                name: "gantry".to_owned(),
+                extra: None,
+            };
+            let resp = server
+                .unary_rpc(
+                    "/viam.component.gantry.v1.GantryService/GetPosition",
+                    &Bytes::from(req.encode_to_vec()),
+                )
+                .now_or_never()
+                .unwrap()
+                .unwrap();
+            GetPositionResponse::decode(resp).unwrap().positions_mm
+        };
+
+        let req = GetLengthsRequest {
+            name: "gantry".to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.gantry.v1.GantryService/GetLengths",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            GetLengthsResponse::decode(resp).unwrap().lengths_mm,
+            vec![100.0, 50.0]
+        );
+
+        let move_to = |server: &mut GrpcServer<GrpcBody>, positions_mm: Vec<f64>| {
+            let req = MoveToPositionRequest {
+                name: "gantry".to_owned(),
+                positions_mm,
+                speeds_mm_per_sec: vec![],
+                extra: None,
+            };
+            server
+                .unary_rpc(
+                    "/viam.component.gantry.v1.GantryService/MoveToPosition",
+                    &Bytes::from(req.encode_to_vec()),
+                )
+                .now_or_never()
+                .unwrap()
+        };
+        assert!(move_to(&mut server, vec![25.0, 10.0]).is_ok());
+        assert_eq!(position(&mut server), vec![25.0, 10.0]);
+        assert!(move_to(&mut server, vec![25.0])
+            .is_err_and(|err| err.status_code() == GrpcError::RpcInvalidArgument as i32));
+        assert!(move_to(&mut server, vec![25.0, 60.0])
+            .is_err_and(|err| err.status_code() == GrpcError::RpcInvalidArgument as i32));
+        assert_eq!(position(&mut server), vec![25.0, 10.0]);
+
+        let req = HomeRequest {
+            name: "gantry".to_owned(),
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.gantry.v1.GantryService/Home",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        assert!(HomeResponse::decode(resp).unwrap().homed);
+        assert_eq!(position(&mut server), vec![0.0, 0.0]);
+    }
+
+    #[test_log::test]
+    fn test_input_controller_stream_events() {
+        let button = Kind::StructValue(HashMap::from([
+            (
+                "control".to_owned(),
+                Kind::StringValue("ButtonSouth".to_owned()),
+            ),
+            ("pin".to_owned(), Kind::NumberValue(12.0)),
+            ("debounce_msec".to_owned(), Kind::NumberValue(0.0)),
+        ]));
+        let robot = setup_robot_with(vec![
+            component("board", "board", "fake", None),
+            component(
+                "pendant",
+                "input_controller",
+                "gpio",
+                Some(HashMap::from([(
+                    "buttons".to_owned(),
+                    Kind::VecValue(vec![button]),
+                )])),
+            ),
+        ]);
+        let mut board = robot
+            .lock()
+            .unwrap()
+            .get_board_by_name("board".to_owned())
+            .unwrap();
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let req = StreamEventsRequest {
+            controller: "pendant".to_owned(),
+            events: vec![stream_events_request::Events {
+                control: "ButtonSouth".to_owned(),
+                events: vec!["AllEvents".to_owned()],
+                cancelled_events: vec![],
+            }],
+            extra: None,
+        };
+        let path = "/viam.component.inputcontroller.v1.InputControllerService/StreamEvents";
+        // the poller is built once for the whole stream
+        let mut poller = server
+            .server_stream_rpc(path, &Bytes::from(req.encode_to_vec()))
+            .unwrap();
+        let mut poll = |since: Option<SystemTime>, now: SystemTime| -> Vec<Event> {
+            let (messages, _) = poller(since, now).unwrap();
+            messages
+                .into_iter()
+                .map(|m| StreamEventsResponse::decode(m).unwrap().event.unwrap())
+                .collect()
+        };
+        let get_events = |server: &mut GrpcServer<GrpcBody>| {
+            let req = GetEventsRequest {
+                controller: "pendant".to_owned(),
+                extra: None,
+            };
+            let resp = server.unary_rpc(
+                "/viam.component.inputcontroller.v1.InputControllerService/GetEvents",
+                &Bytes::from(req.encode_to_vec()),
+            );
+            assert!(matches!(resp.now_or_never(), Some(Ok(_))));
+        };
+
+        let first = SystemTime::now();
+        assert!(poll(None, first).is_empty());
+
+        // a release and a press sampled by GetEvents calls between two polls are both streamed
+        board.set_gpio_pin_level(12, false).unwrap();
+        get_events(&mut server);
+        board.set_gpio_pin_level(12, true).unwrap();
+        get_events(&mut server);
+        let second = SystemTime::now();
+        let events = poll(Some(first), second);
+        assert_eq!(
+            events.iter().map(|e| e.event.as_str()).collect::<Vec<_>>(),
+            vec!["ButtonRelease", "ButtonPress"]
+        );
+
+        // an event sampled while polling is streamed by the next poll
+        board.set_gpio_pin_level(12, false).unwrap();
+        let third = SystemTime::now();
+        assert!(poll(Some(second), third).is_empty());
+        let events = poll(Some(third), SystemTime::now());
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].event, "ButtonRelease");
+
+        let req = StreamEventsRequest {
+            controller: "missing".to_owned(),
+            events: vec![],
+            extra: None,
+        };
+        assert!(server
+            .server_stream_rpc(path, &Bytes::from(req.encode_to_vec()))
+            .is_err());
+    }
+
+    #[test_log::test]
+    fn test_pose_tracker_get_poses() {
+        let bodies = Kind::VecValue(vec![
+            Kind::StringValue("hand".to_owned()),
+            Kind::StringValue("head".to_owned()),
+        ]);
+        let robot = setup_robot_with(vec![component(
+            "tracker",
+            "pose_tracker",
+            "fake",
+            Some(HashMap::from([("bodies".to_owned(), bodies)])),
+        )]);
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let mut get_poses = |body_names: Vec<&str>| {
+            let req = GetPosesRequest {
+                name: "tracker".to_owned(),
+                body_names: body_names.into_iter().map(str::to_owned).collect(),
+                extra: None,
+            };
+            server
+                .unary_rpc(
+                    "/viam.component.posetracker.v1.PoseTrackerService/GetPoses",
+                    &Bytes::from(req.encode_to_vec()),
+                )
+                .now_or_never()
+                .unwrap()
+                .map(|resp| GetPosesResponse::decode(resp).unwrap().body_poses)
+        };
+        let poses = get_poses(vec![]).unwrap();
+        assert_eq!(poses.len(), 2);
+        assert_eq!(poses["head"].reference_frame, "world");
+        let poses = get_poses(vec!["hand"]).unwrap();
+        assert_eq!(poses.len(), 1);
+        assert!(poses.contains_key("hand"));
+        assert!(get_poses(vec!["foot"]).is_err());
+    }
+
+    #[test_log::test]
+    fn test_audio_input_chunks() {
+        let robot = setup_robot_with(vec![component(
+            "microphone",
+            "audio_input",
+            "fake",
+            Some(HashMap::from([
+                ("sample_rate".to_owned(), Kind::NumberValue(8000.0)),
+                ("channels".to_owned(), Kind::NumberValue(2.0)),
+            ])),
+        )]);
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let req = PropertiesRequest {
+            name: "microphone".to_owned(),
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.component.audioinput.v1.AudioInputService/Properties",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let props = PropertiesResponse::decode(resp).unwrap();
+        assert_eq!(props.channel_count, 2);
+        assert_eq!(props.sample_rate, 8000);
+        assert_eq!(props.sample_size, 2);
+        assert!(props.is_interleaved);
+
+        let req = ChunksRequest {
+            name: "microphone".to_owned(),
+            sample_format: SampleFormat::Int16Interleaved.into(),
+        };
+        let mut poller = server
+            .server_stream_rpc(
+                "/viam.component.audioinput.v1.AudioInputService/Chunks",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .unwrap();
+
+        // the stream starts with the format of the chunks
+        let first = SystemTime::now();
+        let (messages, _) = poller(None, first).unwrap();
+        let mut messages = messages
+            .into_iter()
+            .map(|m| ChunksResponse::decode(m).unwrap().r#type.unwrap());
+        match messages.next() {
+            Some(chunks_response::Type::Info(info)) => {
+                assert_eq!(info.channels, 2);
+                assert_eq!(info.sampling_rate, 8000);
+                assert_eq!(
+                    info.sample_format,
+                    i32::from(SampleFormat::Int16Interleaved)
+                );
+            }
+            other => panic!("expected the chunk info, got {:?}", other),
+        }
+        assert!(messages.next().is_none());
+
+        // then carries the samples captured since the previous poll
+        block_on(Timer::after(Duration::from_millis(20)));
+        let (messages, _) = poller(Some(first), SystemTime::now()).unwrap();
+        assert!(!messages.is_empty());
+        for message in messages {
+            match ChunksResponse::decode(message).unwrap().r#type {
+                Some(chunks_response::Type::Chunk(chunk)) => {
+                    assert!(chunk.length > 0);
+                    assert_eq!(chunk.data.len(), chunk.length as usize * 2 * 2);
+                }
+                other => panic!("expected a chunk, got {:?}", other),
+            }
+        }
+    }
+
+    #[test_log::test]
+    #[allow(deprecated)]
+    fn test_sensors_service_partial_readings() {
+        let robot = setup_robot_with(vec![component(
+            "sensor",
+            "sensor",
+            "fake",
+            Some(HashMap::from([(
+                "fake_value".to_owned(),
+                Kind::NumberValue(12.5),
+            )])),
+        )]);
+        let mut server = GrpcServer::new(robot.clone(), GrpcBody::new());
+
+        let req = GetSensorsRequest::default();
+        let resp = server
+            .unary_rpc(
+                "/viam.service.sensors.v1.SensorsService/GetSensors",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let sensor_names = GetSensorsResponse::decode(resp).unwrap().sensor_names;
+        assert_eq!(sensor_names.len(), 1);
+        assert_eq!(sensor_names[0].name, "sensor");
+
+        // the missing sensor gets an error entry, the other one is still read
+        let missing = ResourceName::new_builtin("missing".to_owned(), "sensor".to_owned())
+            .to_proto_resource_name();
+        let req = GetReadingsRequest {
+            name: "builtin".to_owned(),
+            sensor_names: vec![sensor_names[0].clone(), missing],
+            extra: None,
+        };
+        let resp = server
+            .unary_rpc(
+                "/viam.service.sensors.v1.SensorsService/GetReadings",
+                &Bytes::from(req.encode_to_vec()),
+            )
+            .now_or_never()
+            .unwrap()
+            .unwrap();
+        let readings = GetReadingsResponse::decode(resp).unwrap().readings;
+        assert_eq!(readings.len(), 2);
+        assert_eq!(readings[0].name.as_ref().unwrap().name, "sensor");
+        assert_eq!(
+            readings[0].readings["fake_sensor"].kind,
+            Some(Value::NumberValue(12.5))
+        );
+        assert_eq!(readings[1].name.as_ref().unwrap().name, "missing");
+        assert_eq!(readings[1].readings.len(), 1);
+        assert!(matches!(
+            &readings[1].readings["error"].kind,
+            Some(Value::StringValue(err)) if err.contains("not found")
+        ));
+    }
+}
@@ -0,0 +1,156 @@
+use super::{analog::AnalogError, board::BoardError, config::AttributeError, generic::DoCommand};
+use crate::{
+    google::protobuf::Timestamp,
+    proto::component::inputcontroller::v1::{stream_events_request, Event},
+};
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "input_controller";
+
+/// Interval at which the controller is sampled while a client streams its events
+pub(crate) const STREAM_EVENTS_INTERVAL: Duration = Duration::from_millis(20);
+
+#[derive(Debug, Error)]
+pub enum InputControllerError {
+    #[error(transparent)]
+    InputControllerBoardError(#[from] BoardError),
+    #[error(transparent)]
+    InputControllerAnalogError(#[from] AnalogError),
+    #[error(transparent)]
+    InputControllerConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    InputControllerConfigError(&'static str),
+    #[error("control {0} is configured more than once")]
+    InputControllerDuplicateControl(String),
+    #[error("unimplemented: {0}")]
+    InputControllerMethodUnimplemented(&'static str),
+}
+
+/// Types of events reported by an input controller, the string representations match
+/// the ones used by the Viam SDKs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventType {
+    AllEvents,
+    ButtonPress,
+    ButtonRelease,
+    PositionChangeAbs,
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::AllEvents => "AllEvents",
+            Self::ButtonPress => "ButtonPress",
+            Self::ButtonRelease => "ButtonRelease",
+            Self::PositionChangeAbs => "PositionChangeAbs",
+        }
+    }
+}
+
+pub trait InputController: DoCommand {
+    /// Returns the names of the controls (such as `ButtonSouth` or `AbsoluteX`) the
+    /// controller provides
+    fn get_controls(&mut self) -> Result<Vec<String>, InputControllerError>;
+
+    /// Returns the most recent event of each control
+    fn get_events(&mut self) -> Result<Vec<Event>, InputControllerError>;
+
+    /// Returns the events of every control that happened after `since`. Controllers that only
+    /// keep the most recent event of each control report the ones of `get_events`
+    fn get_events_since(&mut self, since: SystemTime) -> Result<Vec<Event>, InputControllerError> {
+        let since = timestamp(since);
+        Ok(self
+            .get_events()?
+            .into_iter()
+            .filter(|event| is_after(event, &since))
+            .collect())
+    }
+}
+
+pub type InputControllerType = Arc<Mutex<dyn InputController>>;
+
+impl<L> InputController for Mutex<L>
+where
+    L: ?Sized + InputController,
+{
+    fn get_controls(&mut self) -> Result<Vec<String>, InputControllerError> {
+        self.get_mut().unwrap().get_controls()
+    }
+    fn get_events(&mut self) -> Result<Vec<Event>, InputControllerError> {
+        self.get_mut().unwrap().get_events()
+    }
+    fn get_events_since(&mut self, since: SystemTime) -> Result<Vec<Event>, InputControllerError> {
+        self.get_mut().unwrap().get_events_since(since)
+    }
+}
+
+impl<A> InputController for Arc<Mutex<A>>
+where
+    A: ?Sized + InputController,
+{
+    fn get_controls(&mut self) -> Result<Vec<String>, InputControllerError> {
+        self.lock().unwrap().get_controls()
+    }
+    fn get_events(&mut self) -> Result<Vec<Event>, InputControllerError> {
+        self.lock().unwrap().get_events()
+    }
+    fn get_events_since(&mut self, since: SystemTime) -> Result<Vec<Event>, InputControllerError> {
+        self.lock().unwrap().get_events_since(since)
+    }
+}
+
+pub(crate) fn timestamp(time: SystemTime) -> Timestamp {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    Timestamp {
+        seconds: since_epoch.as_secs() as i64,
+        nanos: since_epoch.subsec_nanos() as i32,
+    }
+}
+
+/// Whether `event` happened after `since`
+pub(crate) fn is_after(event: &Event, since: &Timestamp) -> bool {
+    event
+        .time
+        .as_ref()
+        .is_some_and(|t| (t.seconds, t.nanos) > (since.seconds, since.nanos))
+}
+
+/// Returns the events of `controller` that happened after `since` and up to `until` (the
+/// time of the poll) and that a client registered for with `registrations`, oldest first.
+/// Events sampled while polling are later than `until` and are left for the next poll.
+pub(crate) fn events_since(
+    controller: &mut dyn InputController,
+    registrations: &[stream_events_request::Events],
+    since: SystemTime,
+    until: SystemTime,
+) -> Result<Vec<Event>, InputControllerError> {
+    let until = timestamp(until);
+    let mut events: Vec<Event> = controller
+        .get_events_since(since)?
+        .into_iter()
+        .filter(|event| !is_after(event, &until))
+        .filter(|event| {
+            registrations.iter().any(|reg| {
+                reg.control == event.control
+                    && !reg.cancelled_events.contains(&event.event)
+                    && reg
+                        .events
+                        .iter()
+                        .any(|e| e == &event.event || e == EventType::AllEvents.as_str())
+            })
+        })
+        .collect();
+    events.sort_by_key(|event| {
+        event
+            .time
+            .as_ref()
+            .map(|t| (t.seconds, t.nanos))
+            .unwrap_or_default()
+    });
+    Ok(events)
+}
@@ -101,6 +101,107 @@ impl From<Vector3> for Value {
     }
 }
 
+// Tolerance used to detect an orientation pointing along the z axis, for which the angle of the
+// orientation vector can't be derived the usual way
+const ANGLE_EPSILON: f64 = 1e-4;
+
+/// An orientation vector: (o_x, o_y, o_z) is the unit vector the z axis of the component points
+/// to and theta (in degrees) is the rotation of the component around that vector
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orientation {
+    pub o_x: f64,
+    pub o_y: f64,
+    pub o_z: f64,
+    pub theta: f64,
+}
+
+impl Default for Orientation {
+    fn default() -> Self {
+        Orientation {
+            o_x: 0.0,
+            o_y: 0.0,
+            o_z: 1.0,
+            theta: 0.0,
+        }
+    }
+}
+
+impl Orientation {
+    /// Builds an orientation from roll, pitch and yaw (in radians) applied in that order around
+    /// the x, y and z axes of a right handed frame with z pointing up
+    pub fn from_euler_angles(roll: f64, pitch: f64, yaw: f64) -> Self {
+        let (sr, cr) = roll.sin_cos();
+        let (sp, cp) = pitch.sin_cos();
+        let (sy, cy) = yaw.sin_cos();
+        // rotation matrix Rz(yaw) * Ry(pitch) * Rx(roll)
+        let r = [
+            [cy * cp, cy * sp * sr - sy * cr, cy * sp * cr + sy * sr],
+            [sy * cp, sy * sp * sr + cy * cr, sy * sp * cr - cy * sr],
+            [-sp, cp * sr, cp * cr],
+        ];
+        // the orientation vector is the rotation Rz(lon) * Ry(lat) * Rz(theta), the image of the
+        // z axis being the last column of the matrix
+        let theta = if 1.0 - r[2][2].abs() > ANGLE_EPSILON {
+            r[2][1].atan2(-r[2][0])
+        } else if r[2][2] > 0.0 {
+            r[1][0].atan2(r[0][0])
+        } else {
+            r[1][0].atan2(r[1][1])
+        };
+        Orientation {
+            o_x: r[0][2],
+            o_y: r[1][2],
+            o_z: r[2][2],
+            theta: theta.to_degrees(),
+        }
+    }
+}
+
+impl From<Orientation> for common::v1::Orientation {
+    fn from(orientation: Orientation) -> Self {
+        common::v1::Orientation {
+            o_x: orientation.o_x,
+            o_y: orientation.o_y,
+            o_z: orientation.o_z,
+            theta: orientation.theta,
+        }
+    }
+}
+
+impl From<Orientation> for Value {
+    fn from(value: Orientation) -> Self {
+        let fields = HashMap::from([
+            (
+                "o_x".to_string(),
+                Value {
+                    kind: Some(Kind::NumberValue(value.o_x)),
+                },
+            ),
+            (
+                "o_y".to_string(),
+                Value {
+                    kind: Some(Kind::NumberValue(value.o_y)),
+                },
+            ),
+            (
+                "o_z".to_string(),
+                Value {
+                    kind: Some(Kind::NumberValue(value.o_z)),
+                },
+            ),
+            (
+                "theta".to_string(),
+                Value {
+                    kind: Some(Kind::NumberValue(value.theta)),
+                },
+            ),
+        ]);
+        Self {
+            kind: Some(Kind::StructValue(Struct { fields })),
+        }
+    }
+}
+
 // If revolutions is 0, the returned wait duration will be 0 representing that
 // the motor should run indefinitely.
 pub(crate) fn go_for_math(
@@ -218,4 +319,58 @@ mod tests {
         assert_eq!(pwr, -0.5);
         assert_eq!(dur, Some(Duration::from_secs(30)));
     }
+
+    #[test_log::test]
+    fn test_orientation_from_euler_angles() {
+        let close = |a: Orientation, b: Orientation| {
+            (a.o_x - b.o_x).abs() < 1e-9
+                && (a.o_y - b.o_y).abs() < 1e-9
+                && (a.o_z - b.o_z).abs() < 1e-9
+                && (a.theta - b.theta).abs() < 1e-9
+        };
+        assert!(close(
+            Orientation::from_euler_angles(0.0, 0.0, 0.0),
+            Orientation::default()
+        ));
+        // yawing only rotates around the z axis
+        assert!(close(
+            Orientation::from_euler_angles(0.0, 0.0, 90_f64.to_radians()),
+            Orientation {
+                o_x: 0.0,
+                o_y: 0.0,
+                o_z: 1.0,
+                theta: 90.0
+            }
+        ));
+        // pitching tilts the z axis toward x
+        assert!(close(
+            Orientation::from_euler_angles(0.0, 90_f64.to_radians(), 0.0),
+            Orientation {
+                o_x: 1.0,
+                o_y: 0.0,
+                o_z: 0.0,
+                theta: 0.0
+            }
+        ));
+        // rolling tilts the z axis toward -y
+        assert!(close(
+            Orientation::from_euler_angles(90_f64.to_radians(), 0.0, 0.0),
+            Orientation {
+                o_x: 0.0,
+                o_y: -1.0,
+                o_z: 0.0,
+                theta: 90.0
+            }
+        ));
+        // upside down
+        assert!(close(
+            Orientation::from_euler_angles(180_f64.to_radians(), 0.0, 0.0),
+            Orientation {
+                o_x: 0.0,
+                o_y: 0.0,
+                o_z: -1.0,
+                theta: 180.0
+            }
+        ));
+    }
 }
@@ -0,0 +1,448 @@
+//! A movement sensor merging the outputs of other movement sensors, so an IMU, a compass and a
+//! GPS can be exposed as a single component.
+//!
+//! The sensors are listed, in order of priority, under the `sensors` attribute. Each method is
+//! routed to the first sensor declaring it supported in its properties.
+//!
+//! Orientation can also be estimated from the angular velocity and linear acceleration of the
+//! merged sensors by setting `complementary_filter_alpha` (between 0 and 1, typically around
+//! 0.98). The angular velocity is integrated and corrected by the tilt derived from gravity,
+//! alpha being the weight given to the integrated angles. The yaw is corrected by the compass
+//! heading when one of the sensors reports it. The filter is updated each time the orientation
+//! is read, so it should be polled regularly (e.g. by capturing its readings).
+//!
+//! ```ignore
+//! {
+//!     "sensors": ["imu", "gps"],
+//!     "complementary_filter_alpha": 0.98
+//! }
+//! ```
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use super::{
+    config::ConfigType,
+    math_utils::{Orientation, Vector3},
+    movement_sensor::{
+        GeoPosition, MovementSensor, MovementSensorAccuracy, MovementSensorSupportedMethods,
+        MovementSensorType, COMPONENT_NAME as MovementSensorCompName,
+    },
+    registry::{ComponentRegistry, Dependency, ResourceKey},
+    robot::Resource,
+    sensor::SensorError,
+};
+
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_movement_sensor("merged", &MergedMovementSensor::from_config)
+        .is_err()
+    {
+        log::error!("merged model is already registered")
+    }
+    if registry
+        .register_dependency_getter(
+            MovementSensorCompName,
+            "merged",
+            &MergedMovementSensor::dependencies_from_config,
+        )
+        .is_err()
+    {
+        log::error!("failed to register dependency getter for merged model")
+    }
+}
+
+// Past this delay the integrated angles are considered stale and the filter restarts from the
+// tilt measured by the accelerometer
+const MAX_FILTER_STEP: Duration = Duration::from_secs(1);
+
+struct ComplementaryFilter {
+    alpha: f64,
+    // roll, pitch and yaw in radians
+    angles: Option<(f64, f64, f64)>,
+    last_update: Instant,
+}
+
+// Returns the angle closest to `target` differing from `angle` by a multiple of a full turn, so
+// angles on both sides of ±π can be blended
+fn unwrap_angle(angle: f64, target: f64) -> f64 {
+    use std::f64::consts::{PI, TAU};
+    angle + ((target - angle + PI) / TAU).floor() * TAU
+}
+
+impl ComplementaryFilter {
+    fn new(alpha: f64) -> Self {
+        Self {
+            alpha,
+            angles: None,
+            last_update: Instant::now(),
+        }
+    }
+
+    // `angular_velocity` is in degrees per second, `heading` in degrees clockwise from north
+    fn update(
+        &mut self,
+        angular_velocity: Vector3,
+        linear_acceleration: Vector3,
+        heading: Option<f64>,
+        now: Instant,
+    ) -> Orientation {
+        let acc = linear_acceleration;
+        let roll_acc = acc.y.atan2(acc.z);
+        let pitch_acc = (-acc.x).atan2((acc.y * acc.y + acc.z * acc.z).sqrt());
+        // the heading is clockwise around a z axis pointing up
+        let yaw_ref = heading.map(|h| -h.to_radians());
+
+        let dt = now.saturating_duration_since(self.last_update);
+        self.last_update = now;
+        let (roll, pitch, yaw) = match self.angles {
+            Some((roll, pitch, yaw)) if dt <= MAX_FILTER_STEP => {
+                let dt = dt.as_secs_f64();
+                let roll = roll + angular_velocity.x.to_radians() * dt;
+                let pitch = pitch + angular_velocity.y.to_radians() * dt;
+                let yaw = yaw + angular_velocity.z.to_radians() * dt;
+                let blend = |integrated: f64, reference: f64| {
+                    self.alpha * integrated
+                        + (1.0 - self.alpha) * unwrap_angle(reference, integrated)
+                };
+                (
+                    blend(roll, roll_acc),
+                    blend(pitch, pitch_acc),
+                    yaw_ref.map_or(yaw, |yaw_ref| blend(yaw, yaw_ref)),
+                )
+            }
+            _ => (roll_acc, pitch_acc, yaw_ref.unwrap_or_default()),
+        };
+        let _ = self.angles.insert((roll, pitch, yaw));
+        Orientation::from_euler_angles(roll, pitch, yaw)
+    }
+}
+
+#[derive(DoCommand, MovementSensorReadings)]
+pub struct MergedMovementSensor {
+    position: Option<MovementSensorType>,
+    linear_velocity: Option<MovementSensorType>,
+    angular_velocity: Option<MovementSensorType>,
+    linear_acceleration: Option<MovementSensorType>,
+    compass_heading: Option<MovementSensorType>,
+    orientation: Option<MovementSensorType>,
+    accuracy: Option<MovementSensorType>,
+    filter: Option<ComplementaryFilter>,
+}
+
+impl MergedMovementSensor {
+    /// Merges `sensors`, each method being routed to the first sensor supporting it
+    pub fn new(sensors: Vec<MovementSensorType>) -> Self {
+        let props: Vec<(MovementSensorSupportedMethods, &MovementSensorType)> =
+            sensors.iter().map(|s| (s.get_properties(), s)).collect();
+        let route = |supported: fn(&MovementSensorSupportedMethods) -> bool| {
+            props
+                .iter()
+                .find(|(p, _)| supported(p))
+                .map(|(_, s)| (*s).clone())
+        };
+        Self {
+            position: route(|p| p.position_supported),
+            linear_velocity: route(|p| p.linear_velocity_supported),
+            angular_velocity: route(|p| p.angular_velocity_supported),
+            linear_acceleration: route(|p| p.linear_acceleration_supported),
+            compass_heading: route(|p| p.compass_heading_supported),
+            orientation: route(|p| p.orientation_supported),
+            accuracy: route(|p| p.accuracy_supported),
+            filter: None,
+        }
+    }
+
+    /// Estimates the orientation with a complementary filter instead of reading it from a
+    /// sensor, requires a sensor reporting angular velocity and one reporting linear acceleration
+    pub fn with_complementary_filter(mut self, alpha: f64) -> Result<Self, SensorError> {
+        if !(0.0..=1.0).contains(&alpha) {
+            return Err(SensorError::ConfigError(
+                "complementary_filter_alpha must be between 0 and 1",
+            ));
+        }
+        if self.angular_velocity.is_none() || self.linear_acceleration.is_none() {
+            return Err(SensorError::ConfigError(
+                "complementary filter requires angular velocity and linear acceleration",
+            ));
+        }
+        let _ = self.filter.insert(ComplementaryFilter::new(alpha));
+        Ok(self)
+    }
+
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        deps: Vec<Dependency>,
+    ) -> Result<MovementSensorType, SensorError> {
+        let names = cfg.get_attribute::<Vec<String>>("sensors")?;
+        let sensors = names
+            .iter()
+            .map(|name| {
+                deps.iter()
+                    .find_map(|Dependency(key, res)| match res {
+                        Resource::MovementSensor(s) if key.1 == *name => Some(s.clone()),
+                        _ => None,
+                    })
+                    .ok_or(SensorError::ConfigError(
+                        "merged movement sensor dependency couldn't be found",
+                    ))
+            })
+            .collect::<Result<Vec<MovementSensorType>, SensorError>>()?;
+        let mut sensor = Self::new(sensors);
+        if let Ok(alpha) = cfg.get_attribute::<f64>("complementary_filter_alpha") {
+            sensor = sensor.with_complementary_filter(alpha)?;
+        }
+        Ok(Arc::new(Mutex::new(sensor)))
+    }
+
+    pub(crate) fn dependencies_from_config(cfg: ConfigType) -> Vec<ResourceKey> {
+        cfg.get_attribute::<Vec<String>>("sensors")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| ResourceKey::new(MovementSensorCompName, name))
+            .collect()
+    }
+}
+
+impl MovementSensor for MergedMovementSensor {
+    fn get_position(&mut self) -> Result<GeoPosition, SensorError> {
+        self.position
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented("get_position"))?
+            .get_position()
+    }
+
+    fn get_linear_velocity(&mut self) -> Result<Vector3, SensorError> {
+        self.linear_velocity
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented(
+                "get_linear_velocity",
+            ))?
+            .get_linear_velocity()
+    }
+
+    fn get_angular_velocity(&mut self) -> Result<Vector3, SensorError> {
+        self.angular_velocity
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented(
+                "get_angular_velocity",
+            ))?
+            .get_angular_velocity()
+    }
+
+    fn get_linear_acceleration(&mut self) -> Result<Vector3, SensorError> {
+        self.linear_acceleration
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented(
+                "get_linear_acceleration",
+            ))?
+            .get_linear_acceleration()
+    }
+
+    fn get_compass_heading(&mut self) -> Result<f64, SensorError> {
+        self.compass_heading
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented(
+                "get_compass_heading",
+            ))?
+            .get_compass_heading()
+    }
+
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        if self.filter.is_some() {
+            let angular_velocity = self.get_angular_velocity()?;
+            let linear_acceleration = self.get_linear_acceleration()?;
+            let heading = match self.compass_heading.as_mut() {
+                Some(s) => Some(s.get_compass_heading()?),
+                None => None,
+            };
+            // checked above
+            let filter = self.filter.as_mut().unwrap();
+            return Ok(filter.update(
+                angular_velocity,
+                linear_acceleration,
+                heading,
+                Instant::now(),
+            ));
+        }
+        self.orientation
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented("get_orientation"))?
+            .get_orientation()
+    }
+
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        self.accuracy
+            .as_mut()
+            .ok_or(SensorError::SensorMethodUnimplemented("get_accuracy"))?
+            .get_accuracy()
+    }
+
+    fn get_properties(&self) -> MovementSensorSupportedMethods {
+        MovementSensorSupportedMethods {
+            position_supported: self.position.is_some(),
+            linear_velocity_supported: self.linear_velocity.is_some(),
+            angular_velocity_supported: self.angular_velocity.is_some(),
+            linear_acceleration_supported: self.linear_acceleration.is_some(),
+            compass_heading_supported: self.compass_heading.is_some(),
+            orientation_supported: self.orientation.is_some() || self.filter.is_some(),
+            accuracy_supported: self.accuracy.is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::{Duration, Instant},
+    };
+
+    use crate::common::{
+        math_utils::Vector3,
+        movement_sensor::{
+            FakeMovementSensor, GeoPosition, MovementSensor, MovementSensorSupportedMethods,
+            MovementSensorType,
+        },
+        sensor::SensorError,
+    };
+
+    use super::{ComplementaryFilter, MergedMovementSensor};
+
+    #[derive(DoCommand, MovementSensorReadings)]
+    struct TestImu {
+        angular_velocity: Vector3,
+        linear_acceleration: Vector3,
+    }
+
+    impl MovementSensor for TestImu {
+        fn get_position(&mut self) -> Result<GeoPosition, SensorError> {
+            Err(SensorError::SensorMethodUnimplemented("get_position"))
+        }
+        fn get_linear_velocity(&mut self) -> Result<Vector3, SensorError> {
+            Err(SensorError::SensorMethodUnimplemented(
+                "get_linear_velocity",
+            ))
+        }
+        fn get_angular_velocity(&mut self) -> Result<Vector3, SensorError> {
+            Ok(self.angular_velocity)
+        }
+        fn get_linear_acceleration(&mut self) -> Result<Vector3, SensorError> {
+            Ok(self.linear_acceleration)
+        }
+        fn get_compass_heading(&mut self) -> Result<f64, SensorError> {
+            Err(SensorError::SensorMethodUnimplemented(
+                "get_compass_heading",
+            ))
+        }
+        fn get_properties(&self) -> MovementSensorSupportedMethods {
+            MovementSensorSupportedMethods {
+                position_supported: false,
+                linear_velocity_supported: false,
+                angular_velocity_supported: true,
+                linear_acceleration_supported: true,
+                compass_heading_supported: false,
+                orientation_supported: false,
+                accuracy_supported: false,
+            }
+        }
+    }
+
+    fn test_imu() -> TestImu {
+        TestImu {
+            angular_velocity: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0,
+            },
+            linear_acceleration: Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 9.81,
+            },
+        }
+    }
+
+    #[test_log::test]
+    fn test_methods_are_routed() {
+        let imu: MovementSensorType = Arc::new(Mutex::new(test_imu()));
+        let gps: MovementSensorType = Arc::new(Mutex::new(FakeMovementSensor::new()));
+        let mut merged = MergedMovementSensor::new(vec![imu, gps]);
+
+        let props = merged.get_properties();
+        assert!(props.position_supported);
+        assert!(props.angular_velocity_supported);
+        assert!(props.linear_acceleration_supported);
+        assert!(props.compass_heading_supported);
+        assert!(!props.linear_velocity_supported);
+        assert!(!props.orientation_supported);
+
+        // both sensors report linear acceleration, the first one listed wins
+        assert_eq!(merged.get_linear_acceleration().unwrap().z, 9.81);
+        assert_eq!(merged.get_position().unwrap().lat, 27.33);
+        assert_eq!(merged.get_compass_heading().unwrap(), 42.0);
+        assert!(merged.get_linear_velocity().is_err());
+        assert!(merged.get_orientation().is_err());
+
+        let mut merged = merged.with_complementary_filter(0.98).unwrap();
+        assert!(merged.get_properties().orientation_supported);
+        assert!(merged.get_orientation().is_ok());
+
+        let gps: MovementSensorType = Arc::new(Mutex::new(FakeMovementSensor::new()));
+        assert!(MergedMovementSensor::new(vec![gps])
+            .with_complementary_filter(0.98)
+            .is_err());
+    }
+
+    #[test_log::test]
+    fn test_complementary_filter() {
+        let mut filter = ComplementaryFilter::new(0.9);
+        let start = Instant::now();
+        let level = Vector3 {
+            x: 0.0,
+            y: 0.0,
+            z: 9.81,
+        };
+        let still = Vector3::new();
+
+        // starts from the tilt measured by the accelerometer and the compass heading
+        let orientation = filter.update(still, level, Some(90.0), start);
+        assert!((orientation.o_z - 1.0).abs() < 1e-9);
+        assert!((orientation.theta + 90.0).abs() < 1e-9);
+
+        // rotating at 10 deg/s around z for a second without a compass
+        let orientation = filter.update(
+            Vector3 {
+                x: 0.0,
+                y: 0.0,
+                z: 10.0,
+            },
+            level,
+            None,
+            start + Duration::from_secs(1),
+        );
+        assert!((orientation.theta + 80.0).abs() < 1e-9);
+
+        // a constant tilt is eventually reached despite the gyro reporting no rotation
+        let tilted = Vector3 {
+            x: 0.0,
+            y: 9.81 * 30_f64.to_radians().sin(),
+            z: 9.81 * 30_f64.to_radians().cos(),
+        };
+        let mut now = start + Duration::from_secs(1);
+        for _ in 0..200 {
+            now += Duration::from_millis(100);
+            filter.update(still, tilted, None, now);
+        }
+        let (roll, pitch, _) = filter.angles.unwrap();
+        assert!((roll - 30_f64.to_radians()).abs() < 1e-6);
+        assert!(pitch.abs() < 1e-6);
+
+        // a stale filter restarts from the accelerometer
+        filter.update(still, level, None, now + Duration::from_secs(5));
+        let (roll, _, _) = filter.angles.unwrap();
+        assert!(roll.abs() < 1e-9);
+    }
+}
@@ -3,12 +3,18 @@
 //!
 //! # Components
 //! - [actuator]
+//! - [arm]
+//! - [audio_input]
 //! - [base]
 //! - [board]
 //! - [camera]
 //! - [encoder]
+//! - [gantry]
+//! - [gripper]
+//! - [input_controller]
 //! - [motor]
 //! - [movement_sensor]
+//! - [pose_tracker]
 //! - [sensor]
 //! - [servo]
 //!
@@ -16,21 +22,27 @@
 //! - [grpc]
 //! - [grpc_client]
 //! - [i2c]
+//! - [pid]
 //! - [webrtc]
 //! - [conn]
 //!
 //!
 //! General Purpose Drivers
 //! - [adxl345]
+//! - [gpio_input_controller]
 //! - [gpio_motor]
 //! - [ina]
+//! - [merged_movement_sensor]
 //! - [mpu6050]
+//! - [servo_arm]
 
 pub mod actuator;
 #[cfg(feature = "builtin-components")]
 pub mod adxl345;
 pub mod analog;
 pub mod app_client;
+pub mod arm;
+pub mod audio_input;
 pub mod base;
 pub mod board;
 pub mod button;
@@ -42,24 +54,33 @@ pub mod credentials_storage;
 pub mod digital_interrupt;
 pub mod encoder;
 pub mod exec;
+pub mod gantry;
 pub mod generic;
 #[cfg(feature = "builtin-components")]
+pub mod gpio_input_controller;
+#[cfg(feature = "builtin-components")]
 pub mod gpio_motor;
 #[cfg(feature = "builtin-components")]
 pub mod gpio_servo;
+pub mod gripper;
 pub mod grpc;
 pub mod grpc_client;
 pub mod i2c;
 #[cfg(feature = "builtin-components")]
 pub mod ina;
+pub mod input_controller;
 pub mod log;
 pub mod math_utils;
+#[cfg(feature = "builtin-components")]
+pub mod merged_movement_sensor;
 pub mod motor;
 pub mod movement_sensor;
 #[cfg(feature = "builtin-components")]
 pub mod mpu6050;
 #[cfg(feature = "ota")]
 pub mod ota;
+pub mod pid;
+pub mod pose_tracker;
 pub mod power_sensor;
 pub mod registry;
 pub mod restart_monitor;
@@ -67,6 +88,8 @@ pub mod robot;
 pub mod runtime;
 pub mod sensor;
 pub mod servo;
+#[cfg(feature = "builtin-components")]
+pub mod servo_arm;
 pub mod status;
 pub mod switch;
 pub mod system;
@@ -98,5 +121,7 @@ pub mod data_collector;
 pub mod data_manager;
 #[cfg(feature = "data")]
 pub mod data_store;
+#[cfg(feature = "data")]
+pub mod flash_data_store;
 
 pub mod provisioning;
@@ -103,6 +103,9 @@ pub trait Motor: Actuator + DoCommand {
     /// This method will return an error if position reporting is not supported.
     /// If revolutions is 0, this will run the motor at rpm indefinitely.
     /// If revolutions != 0, this will block until the number of revolutions has been completed or another operation comes in.
+    /// Returns the duration after which the caller should stop the motor, or `None` when the
+    /// motor runs indefinitely or stops by itself once the revolutions are completed (`is_moving`
+    /// then turns false).
     fn go_for(&mut self, rpm: f64, revolutions: f64) -> Result<Option<Duration>, MotorError>;
 
     // Instructs the motor to turn at the specified RPM. The default behavior is to call go_for
@@ -113,6 +116,12 @@ pub trait Motor: Actuator + DoCommand {
         Ok(())
     }
 
+    /// Reports whether the motor is powered along with the percentage of power applied,
+    /// between `-1.0` and `1.0` for motors that know their direction.
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Err(MotorError::MotorMethodUnimplemented("is_powered"))
+    }
+
     /// Returns an instance of MotorSupportedProperties indicating the optional properties
     /// supported by this motor
     fn get_properties(&mut self) -> MotorSupportedProperties;
@@ -257,6 +266,9 @@ where
     fn set_rpm(&mut self, rpm: f64) -> Result<(), MotorError> {
         self.get_mut().unwrap().set_rpm(rpm)
     }
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        self.get_mut().unwrap().is_powered()
+    }
     fn get_properties(&mut self) -> MotorSupportedProperties {
         self.get_mut().unwrap().get_properties()
     }
@@ -278,6 +290,9 @@ where
     fn set_rpm(&mut self, rpm: f64) -> Result<(), MotorError> {
         self.lock().unwrap().set_rpm(rpm)
     }
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        self.lock().unwrap().is_powered()
+    }
     fn get_properties(&mut self) -> MotorSupportedProperties {
         self.lock().unwrap().get_properties()
     }
@@ -302,6 +317,9 @@ impl Motor for FakeMotor {
         self.set_power(pwr)?;
         Ok(dur)
     }
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Ok((self.power != 0.0, self.power))
+    }
     fn get_properties(&mut self) -> MotorSupportedProperties {
         MotorSupportedProperties {
             position_reporting: true,
@@ -382,6 +400,9 @@ impl Motor for FakeMotorWithDependency {
     fn go_for(&mut self, _: f64, _: f64) -> Result<Option<Duration>, MotorError> {
         Err(MotorError::MotorMethodUnimplemented("go_for"))
     }
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        Ok((self.power != 0.0, self.power))
+    }
     fn get_properties(&mut self) -> MotorSupportedProperties {
         MotorSupportedProperties {
             position_reporting: true,
@@ -17,7 +17,7 @@ use std::{
 use crate::{
     common::{
         generic::DoCommand,
-        math_utils::Vector3,
+        math_utils::{Orientation, Vector3},
         sensor::{GenericReadingsResult, Readings, SensorError},
     },
     google::protobuf::{value::Kind, Struct, Value},
@@ -37,14 +37,16 @@ pub(crate) fn register_models(registry: &mut ComponentRegistry) {
 }
 
 // A local struct representation of the supported methods indicated by the
-// GetProperties method of the Movement Sensor API. TODO: add a boolean for
-// orientation when it is supportable.
+// GetProperties method of the Movement Sensor API. GetProperties doesn't report
+// accuracy, the flag is only used locally.
 pub struct MovementSensorSupportedMethods {
     pub position_supported: bool,
     pub linear_velocity_supported: bool,
     pub angular_velocity_supported: bool,
     pub linear_acceleration_supported: bool,
     pub compass_heading_supported: bool,
+    pub orientation_supported: bool,
+    pub accuracy_supported: bool,
 }
 
 impl From<MovementSensorSupportedMethods> for movement_sensor::v1::GetPropertiesResponse {
@@ -55,7 +57,30 @@ impl From<MovementSensorSupportedMethods> for movement_sensor::v1::GetProperties
             angular_velocity_supported: props.angular_velocity_supported,
             linear_acceleration_supported: props.linear_acceleration_supported,
             compass_heading_supported: props.compass_heading_supported,
-            orientation_supported: false,
+            orientation_supported: props.orientation_supported,
+        }
+    }
+}
+
+// The accuracy of the measurements reported by a movement sensor, as returned by the
+// GetAccuracy method of the Movement Sensor API
+#[derive(Clone, Debug, Default)]
+pub struct MovementSensorAccuracy {
+    pub accuracy: HashMap<String, f32>,
+    pub position_hdop: Option<f32>,
+    pub position_vdop: Option<f32>,
+    pub position_nmea_gga_fix: Option<i32>,
+    pub compass_degrees_error: Option<f32>,
+}
+
+impl From<MovementSensorAccuracy> for movement_sensor::v1::GetAccuracyResponse {
+    fn from(acc: MovementSensorAccuracy) -> movement_sensor::v1::GetAccuracyResponse {
+        movement_sensor::v1::GetAccuracyResponse {
+            accuracy: acc.accuracy,
+            position_hdop: acc.position_hdop,
+            position_vdop: acc.position_vdop,
+            position_nmea_gga_fix: acc.position_nmea_gga_fix,
+            compass_degrees_error: acc.compass_degrees_error,
         }
     }
 }
@@ -155,14 +180,21 @@ impl From<GeoPosition> for movement_sensor::v1::GetPositionResponse {
     }
 }
 
-// A trait for implementing a movement sensor component driver. TODO: add
-// get_orientation and get_accuracy if/when they become supportable.
+// A trait for implementing a movement sensor component driver. Orientation and
+// accuracy are optional, drivers supporting them override the default
+// implementations and declare them in get_properties.
 pub trait MovementSensor: Readings + DoCommand {
     fn get_position(&mut self) -> Result<GeoPosition, SensorError>;
     fn get_linear_velocity(&mut self) -> Result<Vector3, SensorError>;
     fn get_angular_velocity(&mut self) -> Result<Vector3, SensorError>;
     fn get_linear_acceleration(&mut self) -> Result<Vector3, SensorError>;
     fn get_compass_heading(&mut self) -> Result<f64, SensorError>;
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        Err(SensorError::SensorMethodUnimplemented("get_orientation"))
+    }
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        Err(SensorError::SensorMethodUnimplemented("get_accuracy"))
+    }
     fn get_properties(&self) -> MovementSensorSupportedMethods;
 }
 
@@ -202,6 +234,9 @@ pub fn get_movement_sensor_generic_readings(
             },
         );
     }
+    if supported_methods.orientation_supported {
+        res.insert("orientation".to_string(), ms.get_orientation()?.into());
+    }
     Ok(res)
 }
 
@@ -281,6 +316,8 @@ impl MovementSensor for FakeMovementSensor {
             linear_velocity_supported: false,
             angular_velocity_supported: false,
             compass_heading_supported: true,
+            orientation_supported: false,
+            accuracy_supported: false,
         }
     }
 
@@ -325,6 +362,14 @@ where
         self.get_mut().unwrap().get_compass_heading()
     }
 
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        self.get_mut().unwrap().get_orientation()
+    }
+
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        self.get_mut().unwrap().get_accuracy()
+    }
+
     fn get_properties(&self) -> MovementSensorSupportedMethods {
         self.lock().unwrap().get_properties()
     }
@@ -354,6 +399,14 @@ where
         self.lock().unwrap().get_compass_heading()
     }
 
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        self.lock().unwrap().get_orientation()
+    }
+
+    fn get_accuracy(&mut self) -> Result<MovementSensorAccuracy, SensorError> {
+        self.lock().unwrap().get_accuracy()
+    }
+
     fn get_properties(&self) -> MovementSensorSupportedMethods {
         self.lock().unwrap().get_properties()
     }
@@ -4,10 +4,12 @@
 //! description of the I2C registers is at
 //! https://download.datasheets.com/pdfs/2015/3/19/8/3/59/59/invse_/manual/5rm-mpu-6000a-00v4.2.pdf
 //!
-//! We support reading the accelerometer, gyroscope, and thermometer data off of the chip. We do not
-//! yet support using the digital interrupt pin to notify on events (freefall, collision, etc.),
-//! nor do we yet support using the secondary I2C connection to add an external clock or
-//! magnetometer.
+//! We support reading the accelerometer, gyroscope, and thermometer data off of the chip. The
+//! orientation is derived from the direction of gravity measured by the accelerometer, it is only
+//! meaningful while the sensor isn't accelerating and, without a magnetometer, its yaw is always 0.
+//! We do not yet support using the digital interrupt pin to notify on events (freefall,
+//! collision, etc.), nor do we yet support using the secondary I2C connection to add an external
+//! clock or magnetometer.
 //!
 //! The chip has two possible I2C addresses, which can be selected by wiring the AD0 pin to either
 //! hot or ground:
@@ -16,7 +18,7 @@
 //!
 
 use crate::common::i2c::I2cHandleType;
-use crate::common::math_utils::Vector3;
+use crate::common::math_utils::{Orientation, Vector3};
 use crate::common::movement_sensor::{MovementSensor, MovementSensorSupportedMethods};
 
 use super::board::Board;
@@ -137,6 +139,13 @@ fn get_linear_acceleration_from_reading(reading: &[u8; 14]) -> Vector3 {
     Vector3 { x, y, z }
 }
 
+fn get_orientation_from_reading(reading: &[u8; 14]) -> Orientation {
+    let acc = get_linear_acceleration_from_reading(reading);
+    let roll = acc.y.atan2(acc.z);
+    let pitch = (-acc.x).atan2((acc.y * acc.y + acc.z * acc.z).sqrt());
+    Orientation::from_euler_angles(roll, pitch, 0.0)
+}
+
 impl MovementSensor for MPU6050 {
     fn get_properties(&self) -> MovementSensorSupportedMethods {
         MovementSensorSupportedMethods {
@@ -145,6 +154,8 @@ impl MovementSensor for MPU6050 {
             angular_velocity_supported: true,
             linear_acceleration_supported: true,
             compass_heading_supported: false,
+            orientation_supported: true,
+            accuracy_supported: false,
         }
     }
 
@@ -164,6 +175,14 @@ impl MovementSensor for MPU6050 {
         Ok(get_linear_acceleration_from_reading(&result))
     }
 
+    fn get_orientation(&mut self) -> Result<Orientation, SensorError> {
+        let register_write: [u8; 1] = [READING_START_REGISTER];
+        let mut result: [u8; 14] = [0; 14];
+        self.i2c_handle
+            .write_read_i2c(self.i2c_address, &register_write, &mut result)?;
+        Ok(get_orientation_from_reading(&result))
+    }
+
     fn get_position(&mut self) -> Result<super::movement_sensor::GeoPosition, SensorError> {
         Err(SensorError::SensorMethodUnimplemented("get_posiiton"))
     }
@@ -183,7 +202,10 @@ impl MovementSensor for MPU6050 {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_angular_velocity_from_reading, get_linear_acceleration_from_reading};
+    use super::{
+        get_angular_velocity_from_reading, get_linear_acceleration_from_reading,
+        get_orientation_from_reading,
+    };
 
     #[test_log::test]
     fn test_read_linear_acceleration() {
@@ -215,4 +237,22 @@ mod tests {
         assert_eq!(ang_vel.y, -246.09375);
         assert_eq!(ang_vel.z, 31.25);
     }
+
+    #[test_log::test]
+    fn test_read_orientation() {
+        // lying flat, gravity is measured along +z
+        let reading: [u8; 14] = [0, 0, 0, 0, 64, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let orientation = get_orientation_from_reading(&reading);
+        assert!(orientation.o_x.abs() < 1e-9);
+        assert!(orientation.o_y.abs() < 1e-9);
+        assert!((orientation.o_z - 1.0).abs() < 1e-9);
+        assert!(orientation.theta.abs() < 1e-9);
+
+        // nose pointing down, gravity is measured along -x
+        let reading: [u8; 14] = [192, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        let orientation = get_orientation_from_reading(&reading);
+        assert!((orientation.o_x - 1.0).abs() < 1e-9);
+        assert!(orientation.o_y.abs() < 1e-9);
+        assert!(orientation.o_z.abs() < 1e-9);
+    }
 }
@@ -27,12 +27,22 @@
 /// }
 /// ```
 ///
+/// The image can be checked before it is marked bootable with two optional attributes:
+/// - `sha256`: hex encoded SHA-256 digest of the image
+/// - `signature`: hex encoded Ed25519 signature of that SHA-256 digest
+///
+/// When a trusted public key has been provisioned in NVS (see [OtaMetadataStorage]) a
+/// `signature` is required, and an image that doesn't match is discarded. Both are computed
+/// while the image is streamed to flash so it never needs to be held in memory.
+///
 /// The sdkconfig options relevant to OTA, currently default as of esp-idf v4
 /// and should be reviewed when upgrading to esp-idf v5
 /// - CONFIG_BOOTLOADER_FACTORY_RESET=NO
 ///   - clear data partitions and boot from factory partition
-/// - CONFIG_BOOTLOADER_APP_ROLLBACK_ENABLE=NO
+/// - CONFIG_BOOTLOADER_APP_ROLLBACK_ENABLE=YES
 ///   - after updating the app, bootloader runs a new app with the "ESP_OTA_IMG_PENDING_VERIFY" state set. If the image is not marked as verified, will boot to previous ota slot
+///   - the image is marked as verified once it passes the [OtaHealthCheck], a version that
+///     fails it is rolled back and stored so it isn't downloaded again
 ///
 use crate::{
     common::{
@@ -49,24 +59,44 @@ use crate::{
 use crate::esp32::esp_idf_svc::{
     ota::{EspFirmwareInfoLoad, EspOta, FirmwareInfo},
     sys::{
-        esp_app_desc_t, esp_image_header_t, esp_image_segment_header_t,
-        esp_ota_get_next_update_partition, esp_partition_t,
+        esp, esp_app_desc_t, esp_image_header_t, esp_image_segment_header_t, esp_ota_abort,
+        esp_ota_begin, esp_ota_end, esp_ota_get_next_update_partition,
+        esp_ota_get_running_partition, esp_ota_get_state_partition, esp_ota_handle_t,
+        esp_ota_img_states_t, esp_ota_img_states_t_ESP_OTA_IMG_PENDING_VERIFY,
+        esp_ota_mark_app_invalid_rollback_and_reboot, esp_ota_mark_app_valid_cancel_rollback,
+        esp_ota_resume, esp_ota_set_boot_partition, esp_ota_write, esp_partition_read,
+        esp_partition_t, OTA_WITH_SEQUENTIAL_WRITES,
     },
 };
+use async_executor::Task;
 use async_io::Timer;
-#[cfg(not(feature = "esp32"))]
-use futures_lite::AsyncWriteExt;
+use ed25519_dalek::{Signature, VerifyingKey};
 use futures_lite::{FutureExt, StreamExt};
 use futures_util::TryFutureExt;
 use http_body_util::{BodyExt, Empty};
-use hyper::{body::Bytes, client::conn::http2, Request};
+use hyper::{body::Bytes, client::conn::http2, header, Request, StatusCode};
 use once_cell::sync::Lazy;
-use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    cell::Cell,
+    fmt::Write,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 const CONN_RETRY_SECS: u64 = 1;
 const NUM_RETRY_CONN: usize = 5;
+/// attempts in a row to resume a download without receiving anything before starting over
+const MAX_RESUME_ATTEMPTS: usize = 3;
 const DOWNLOAD_TIMEOUT_SECS: u64 = 30;
+/// bytes downloaded between two saves of the download progress
+const PROGRESS_STORE_INTERVAL: usize = 64 * 1024;
+/// a download is resumed from the start of a flash sector, so the sector is erased before
+/// being written again
+#[cfg(feature = "esp32")]
+const FLASH_SECTOR_SIZE: usize = 4096;
 
 /// https://github.com/esp-rs/esp-idf-svc/blob/4ccf3182b32129b55082b5810d837a1cf5bc1a08/src/ota.rs#L94
 /// https://github.com/espressif/esp-idf/commit/3b9cb25fe18c5a6ed64ddd6a1dc4d0ce0b6cdc2a
@@ -96,10 +126,22 @@ pub(crate) enum ConfigError {
     MissingAttribute(String),
     #[error("value missing for field `{0}`")]
     MissingValue(String),
+    #[error("`{0}` is not a valid hex encoding of {1} bytes")]
+    InvalidHex(String, usize),
+    #[error("trusted OTA public key is invalid: {0}")]
+    InvalidPublicKey(String),
     #[error("{0}")]
     Other(String),
 }
 
+#[derive(Error, Debug)]
+pub(crate) enum VerificationError {
+    #[error("image sha256 digest {0} does not match expected digest {1}")]
+    DigestMismatch(String, String),
+    #[error("image signature doesn't match the trusted public key: {0}")]
+    InvalidSignature(String),
+}
+
 #[derive(Error, Debug)]
 pub(crate) enum DownloadError {
     #[error("resolving next frame took longer than {0} seconds")]
@@ -135,6 +177,10 @@ pub(crate) enum OtaError<S: OtaMetadataStorage> {
     StorageError(<S as OtaMetadataStorage>::Error),
     #[error("error writing firmware to update partition: {0}")]
     WriteError(String),
+    #[error("Bad Request - Status: {0}")]
+    BadStatus(StatusCode),
+    #[error(transparent)]
+    VerificationError(#[from] VerificationError),
     #[error("{0}")]
     Other(String),
 }
@@ -155,6 +201,174 @@ impl OtaMetadata {
     }
 }
 
+/// Progress of a download, persisted so that a dropped connection or a reboot resumes it with a
+/// `Range` request instead of starting over.
+#[derive(Clone, Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OtaDownloadProgress {
+    pub(crate) version: String,
+    pub(crate) url: String,
+    /// `ETag` of the image, or its `Last-Modified` date when the server doesn't send one. It is
+    /// sent back in `If-Range` so the server sends the whole image if it has changed since.
+    pub(crate) etag: Option<String>,
+    pub(crate) file_len: usize,
+    pub(crate) nwritten: usize,
+    /// attempts to resume the download that failed before receiving anything
+    pub(crate) resume_attempts: usize,
+}
+
+/// Writes the image to the next OTA partition.
+#[cfg(feature = "esp32")]
+struct OtaWriter {
+    partition: *const esp_partition_t,
+    handle: esp_ota_handle_t,
+}
+
+#[cfg(feature = "esp32")]
+impl OtaWriter {
+    /// Starts writing the image at `offset`, returns the offset it actually resumed from.
+    fn begin(offset: usize) -> Result<(Self, usize), String> {
+        let partition = unsafe { esp_ota_get_next_update_partition(std::ptr::null()) };
+        if partition.is_null() {
+            return Err("failed to obtain a handle to the next OTA update partition".to_string());
+        }
+        let offset = offset - offset % FLASH_SECTOR_SIZE;
+        let mut handle: esp_ota_handle_t = 0;
+        if offset == 0 {
+            esp!(unsafe {
+                esp_ota_begin(partition, OTA_WITH_SEQUENTIAL_WRITES as usize, &mut handle)
+            })
+        } else {
+            esp!(unsafe {
+                esp_ota_resume(
+                    partition,
+                    OTA_WITH_SEQUENTIAL_WRITES as usize,
+                    offset,
+                    &mut handle,
+                )
+            })
+        }
+        .map_err(|e| e.to_string())?;
+        Ok((Self { partition, handle }, offset))
+    }
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        esp!(unsafe { esp_ota_write(self.handle, data.as_ptr() as *const _, data.len()) })
+            .map_err(|e| e.to_string())
+    }
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), String> {
+        esp!(unsafe {
+            esp_partition_read(
+                self.partition,
+                offset,
+                buf.as_mut_ptr() as *mut _,
+                buf.len(),
+            )
+        })
+        .map_err(|e| e.to_string())
+    }
+    /// Releases the handle, what was written is kept so the download can be resumed.
+    fn abort(self) -> Result<(), String> {
+        esp!(unsafe { esp_ota_abort(self.handle) }).map_err(|e| e.to_string())
+    }
+    /// Validates the image and marks the partition bootable.
+    fn complete(self) -> Result<(), String> {
+        esp!(unsafe { esp_ota_end(self.handle) }).map_err(|e| e.to_string())?;
+        esp!(unsafe { esp_ota_set_boot_partition(self.partition) }).map_err(|e| e.to_string())
+    }
+}
+
+/// Keeps the image in memory, so only a connection dropped during an update can be resumed.
+#[cfg(not(feature = "esp32"))]
+struct OtaWriter(Vec<u8>);
+
+#[cfg(not(feature = "esp32"))]
+impl OtaWriter {
+    fn begin(_offset: usize) -> Result<(Self, usize), String> {
+        Ok((Self(Vec::new()), 0))
+    }
+    fn write(&mut self, data: &[u8]) -> Result<(), String> {
+        self.0.extend_from_slice(data);
+        Ok(())
+    }
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), String> {
+        let src = self
+            .0
+            .get(offset..offset + buf.len())
+            .ok_or_else(|| format!("{} bytes at {} were not written", buf.len(), offset))?;
+        buf.copy_from_slice(src);
+        Ok(())
+    }
+    fn abort(self) -> Result<(), String> {
+        Ok(())
+    }
+    fn complete(self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+fn decode_hex<const N: usize>(attribute: &str, value: &str) -> Result<[u8; N], ConfigError> {
+    let err = || ConfigError::InvalidHex(attribute.to_string(), N);
+    if value.len() != N * 2 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(err());
+    }
+    let mut bytes = [0_u8; N];
+    for (byte, chunk) in bytes.iter_mut().zip(value.as_bytes().chunks(2)) {
+        // chunks of hex digits are valid utf8
+        *byte = u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16).map_err(|_| err())?;
+    }
+    Ok(bytes)
+}
+
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut s, b| {
+            let _ = write!(s, "{:02x}", b);
+            s
+        })
+}
+
+/// Hashes the image as it is downloaded and checks the digest, and its signature when one
+/// is expected, once the download completes.
+pub(crate) struct ImageVerifier {
+    hasher: Sha256,
+    sha256: Option<[u8; 32]>,
+    signature: Option<(VerifyingKey, Signature)>,
+}
+
+impl ImageVerifier {
+    pub(crate) fn new(
+        sha256: Option<[u8; 32]>,
+        signature: Option<(VerifyingKey, Signature)>,
+    ) -> Self {
+        Self {
+            hasher: Sha256::new(),
+            sha256,
+            signature,
+        }
+    }
+
+    pub(crate) fn update(&mut self, data: &[u8]) {
+        self.hasher.update(data);
+    }
+
+    pub(crate) fn finalize(self) -> Result<(), VerificationError> {
+        let digest: [u8; 32] = self.hasher.finalize().into();
+        if let Some(expected) = self.sha256 {
+            if digest != expected {
+                return Err(VerificationError::DigestMismatch(
+                    encode_hex(&digest),
+                    encode_hex(&expected),
+                ));
+            }
+        }
+        if let Some((key, signature)) = self.signature {
+            key.verify_strict(&digest, &signature)
+                .map_err(|e| VerificationError::InvalidSignature(e.to_string()))?;
+        }
+        Ok(())
+    }
+}
+
 pub(crate) struct OtaService<S: OtaMetadataStorage> {
     exec: Executor,
     connector: OtaConnector,
@@ -163,6 +377,8 @@ pub(crate) struct OtaService<S: OtaMetadataStorage> {
     pending_version: String,
     max_size: usize,
     address: usize,
+    sha256: Option<[u8; 32]>,
+    signature: Option<(VerifyingKey, Signature)>,
 }
 
 impl<S: OtaMetadataStorage> OtaService<S> {
@@ -229,6 +445,52 @@ impl<S: OtaMetadataStorage> OtaService<S> {
             )));
         }
 
+        let optional_string = |key: &str| -> Result<Option<String>, ConfigError> {
+            match kind.fields.get(key).and_then(|val| val.kind.as_ref()) {
+                None => Ok(None),
+                Some(val) => match Kind::try_from(val)? {
+                    Kind::StringValue(s) => Ok(Some(s)),
+                    other => Err(ConfigError::Other(format!(
+                        "invalid `{}` value: {:?}",
+                        key, other
+                    ))),
+                },
+            }
+        };
+
+        let sha256 = optional_string("sha256")?
+            .map(|s| decode_hex::<32>("sha256", &s))
+            .transpose()?;
+        let signature = optional_string("signature")?
+            .map(|s| decode_hex::<64>("signature", &s))
+            .transpose()?
+            .map(|bytes| Signature::from_bytes(&bytes));
+
+        // once a trusted key is provisioned, unsigned images are refused
+        let signature = match (storage.has_ota_public_key(), signature) {
+            (false, None) => None,
+            (false, Some(_)) => {
+                return Err(ConfigError::Other(
+                    "`signature` is set but no trusted OTA public key is stored".to_string(),
+                )
+                .into())
+            }
+            (true, None) => {
+                return Err(ConfigError::MissingAttribute("signature".to_string()).into())
+            }
+            (true, Some(signature)) => {
+                let key = storage
+                    .get_ota_public_key()
+                    .map_err(OtaError::StorageError)?;
+                let key: [u8; 32] = key.as_slice().try_into().map_err(|_| {
+                    ConfigError::InvalidPublicKey(format!("expected 32 bytes, found {}", key.len()))
+                })?;
+                let key = VerifyingKey::from_bytes(&key)
+                    .map_err(|e| ConfigError::InvalidPublicKey(e.to_string()))?;
+                Some((key, signature))
+            }
+        };
+
         let connector = OtaConnector::default();
 
         #[cfg(not(feature = "esp32"))]
@@ -260,10 +522,23 @@ impl<S: OtaMetadataStorage> OtaService<S> {
             pending_version,
             max_size,
             address,
+            sha256,
+            signature,
         })
     }
 
     pub(crate) fn needs_update(&self) -> bool {
+        if self
+            .storage
+            .get_ota_failed_version()
+            .is_ok_and(|version| version == self.pending_version)
+        {
+            log::debug!(
+                "version `{}` was rolled back after failing its health check, skipping update",
+                self.pending_version
+            );
+            return false;
+        }
         self.stored_metadata().unwrap_or_default().version != self.pending_version
     }
 
@@ -293,17 +568,14 @@ impl<S: OtaMetadataStorage> OtaService<S> {
         Ok(uri)
     }
 
-    /// Attempts to perform an OTA update.
-    /// On success, returns an `Ok(true)` or `Ok(false)` indicating if a reboot is necessary.
-    pub(crate) async fn update(&mut self) -> Result<bool, OtaError<S>> {
-        // TODO(RSDK-10331): refactor OTA update logic to work with state machines
-        if !(self.needs_update()) {
-            return Ok(false);
-        }
-
+    /// Connects to the image url, following redirections, and requests the image from `offset`.
+    /// Returns the response along with the task driving the connection.
+    async fn send_request(
+        &self,
+        offset: usize,
+        etag: Option<&str>,
+    ) -> Result<(hyper::Response<hyper::body::Incoming>, Box<Task<()>>), OtaError<S>> {
         let mut uri = self.parse_uri(&self.url)?;
-        let mut response: hyper::Response<hyper::body::Incoming>;
-        let mut conn;
 
         let mut num_tries = 0;
         loop {
@@ -361,26 +633,31 @@ impl<S: OtaMetadataStorage> OtaService<S> {
 
             // underlying Task that drives the request IO
             // boxed to prevent stack overflow
-            conn = Some(Box::new(self.exec.spawn(async move {
+            let conn = Box::new(self.exec.spawn(async move {
                 if let Err(err) = inner_conn.await {
                     log::error!("connection failed: {:?}", err);
                 }
-            })));
+            }));
 
-            log::info!("ota connected, beginning download");
-            let request = Request::builder()
-                .method("GET")
-                .uri(&uri)
+            log::info!("ota connected, requesting firmware from byte {}", offset);
+            let mut request = Request::builder().method("GET").uri(&uri);
+            if offset > 0 {
+                request = request.header(header::RANGE, format!("bytes={}-", offset));
+                if let Some(etag) = etag {
+                    request = request.header(header::IF_RANGE, etag);
+                }
+            }
+            let request = request
                 .body(Empty::<Bytes>::new())
                 .map_err(|e| OtaError::Other(e.to_string()))?;
-            response = sender
+            let response = sender
                 .send_request(request)
                 .await
                 .map_err(|e| OtaError::Other(e.to_string()))?;
 
             let status = response.status();
             match (status.is_success(), status.is_redirection()) {
-                (true, false) => break,
+                (true, false) => return Ok((response, conn)),
                 (false, true) => {
                     log::info!("OTA connection received a redirection...");
                     let headers = response.headers();
@@ -406,191 +683,131 @@ impl<S: OtaMetadataStorage> OtaService<S> {
                     );
 
                     uri = self.parse_uri(new_uri)?;
-                    drop(conn.take());
                     continue;
                 }
-                _ => {
-                    return Err(OtaError::Other(format!(
-                        "Bad Request - Status: {}",
-                        response.status()
-                    )))
-                }
+                _ => return Err(OtaError::BadStatus(status)),
             };
         }
+    }
 
-        let headers = response.headers();
-        log::debug!("ota response headers: {:?}", headers);
-
-        if !headers.contains_key(hyper::header::CONTENT_LENGTH) {
-            return Err(OtaError::Other(
-                "response header missing content length".to_string(),
-            ));
-        }
-        let file_len = headers[hyper::header::CONTENT_LENGTH]
-            .to_str()
-            .map_err(|e| OtaError::Other(e.to_string()))?
-            .parse::<usize>()
-            .map_err(|e| OtaError::Other(e.to_string()))?;
+    /// Returns the progress of a previous download of the pending version, unless it failed to
+    /// resume too many times in a row
+    fn stored_progress(&self) -> OtaDownloadProgress {
+        self.storage
+            .get_ota_download_progress()
+            .ok()
+            .filter(|p| p.version == self.pending_version && p.url == self.url)
+            .filter(|p| {
+                let resumable = p.resume_attempts < MAX_RESUME_ATTEMPTS;
+                if !resumable {
+                    log::warn!(
+                        "download of version `{}` failed to resume {} times, starting over",
+                        p.version,
+                        p.resume_attempts
+                    );
+                }
+                resumable
+            })
+            .unwrap_or_else(|| OtaDownloadProgress {
+                version: self.pending_version.clone(),
+                url: self.url.clone(),
+                ..Default::default()
+            })
+    }
 
-        if file_len > self.max_size {
-            return Err(OtaError::InvalidImageSizeLarge(file_len, self.max_size));
-        }
-        if file_len < *FIRMWARE_HEADER_SIZE {
-            return Err(OtaError::InvalidImageSizeSmall(
-                file_len,
-                *FIRMWARE_HEADER_SIZE,
-            ));
+    /// Attempts to perform an OTA update.
+    /// On success, returns an `Ok(true)` or `Ok(false)` indicating if a reboot is necessary.
+    pub(crate) async fn update(&mut self) -> Result<bool, OtaError<S>> {
+        // TODO(RSDK-10331): refactor OTA update logic to work with state machines
+        if !(self.needs_update()) {
+            return Ok(false);
         }
 
-        #[cfg(feature = "esp32")]
-        let (mut ota, running_fw_info) = {
-            let ota = EspOta::new().map_err(|e| {
-                OtaError::UpdateError(format!("failed to initiate ota partition handle: {}", e))
-            })?;
-            let fw_info = ota
-                .get_running_slot()
-                .map_err(|e| {
-                    OtaError::UpdateError(format!(
-                        "failed to get handle to running ota partition: {}",
-                        e
-                    ))
-                })?
-                .firmware;
-            (ota, fw_info)
-        };
-        #[cfg(feature = "esp32")]
-        let mut update_handle = ota.initiate_update().map_err(|_| OtaError::InitError)?;
-        #[cfg(not(feature = "esp32"))]
-        let mut update_handle = Vec::new();
-        let mut nwritten: usize = 0;
-        let mut total_downloaded: usize = 0;
-
-        #[allow(unused_mut)]
-        let mut got_info = false;
+        let mut progress = self.stored_progress();
 
-        log::info!("writing new firmware to address `{:#x}`", self.address,);
-        let mut stream = response.into_data_stream();
-
-        loop {
-            match stream
-                .try_next()
-                .map_err(DownloadError::Network)
-                .or(async {
-                    async_io::Timer::after(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS)).await;
-                    Err(DownloadError::Timeout(DOWNLOAD_TIMEOUT_SECS as usize))
-                })
-                .await
-            {
-                Ok(Some(data)) => {
-                    total_downloaded += data.len();
-
-                    if !got_info {
-                        if total_downloaded < *FIRMWARE_HEADER_SIZE {
-                            log::error!("initial frame too small to retrieve esp_app_desc_t");
-                        } else {
-                            #[cfg(feature = "esp32")]
-                            {
-                                log::info!("verifying new ota firmware");
-                                let mut new_fw = FirmwareInfo {
-                                    version: Default::default(),
-                                    released: Default::default(),
-                                    description: None,
-                                    signature: None,
-                                    download_id: None,
-                                };
-                                let loader = EspFirmwareInfoLoad {};
-                                // We *infer* that the binary is a valid by verifying
-                                // the presence of the firmware header in the first bytes,
-                                // however this does not imply validity of the image as a whole.
-                                let loaded = loader
-                                    .fetch(&data, &mut new_fw)
-                                    .map_err(|e| OtaError::InvalidFirmware(e.to_string()))?;
-                                if loaded {
-                                    log::debug!(
-                                        "current firmware app description: {:?}",
-                                        running_fw_info
-                                    );
-                                    log::debug!("new firmware app description: {:?}", new_fw);
-                                    got_info = true;
-                                } else {
-                                    log::error!(
-                                        "unable to validate image header in first {} bytes, terminating download",
-                                        total_downloaded
-                                    );
-                                    break;
-                                }
-                            }
-                        }
-                    }
+        let (mut writer, offset) = OtaWriter::begin(progress.nwritten).map_err(|e| {
+            OtaError::UpdateError(format!("failed to initiate ota partition handle: {}", e))
+        })?;
+        progress.nwritten = offset;
+        let resumed_from = offset;
+        let mut verifier = ImageVerifier::new(self.sha256, self.signature);
+        if offset > 0 {
+            log::info!(
+                "resuming download of version `{}` at {}/{} bytes",
+                self.pending_version,
+                offset,
+                progress.file_len
+            );
+            // the digest covers the whole image, so feed it what was written before
+            let mut buf = vec![0_u8; 4096];
+            let mut read = 0;
+            while read < offset {
+                let len = buf.len().min(offset - read);
+                if let Err(e) = writer.read(read, &mut buf[..len]) {
+                    log::error!("failed to read back firmware, restarting download: {}", e);
+                    let _ = writer.abort();
+                    let _ = self.storage.reset_ota_download_progress();
+                    return Err(OtaError::UpdateError(e));
+                }
+                verifier.update(&buf[..len]);
+                read += len;
+            }
+        }
 
-                    if data.len() + nwritten > self.max_size {
-                        log::error!("file is larger than expected, aborting");
-                        #[cfg(feature = "esp32")]
-                        update_handle
-                            .abort()
-                            .map_err(|e| OtaError::AbortError(format!("{:?}", e)))?;
-                        return Err(OtaError::InvalidImageSizeLarge(
-                            data.len() + nwritten,
-                            self.max_size,
-                        ));
-                    }
+        let res = self
+            .download(&mut writer, &mut verifier, &mut progress)
+            .await;
 
-                    // TODO(RSDK-9271) add async writer for ota
-                    #[cfg(feature = "esp32")]
-                    update_handle
-                        .write(&data)
-                        .map_err(|e| OtaError::WriteError(e.to_string()))?;
-                    #[cfg(not(feature = "esp32"))]
-                    let _n = update_handle
-                        .write(&data)
-                        .await
-                        .map_err(|e| OtaError::WriteError(e.to_string()))?;
-                    // TODO change back to 'n' after impl async writer
-                    nwritten += data.len();
-                    log::info!(
-                        "updating next OTA partition at {:#x}: {}/{} bytes written",
-                        self.address,
-                        nwritten,
-                        file_len
-                    );
-                }
-                Ok(None) => break,
-                Err(e) => {
-                    #[cfg(feature = "esp32")]
-                    update_handle
-                        .abort()
-                        .map_err(|e| OtaError::AbortError(format!("{:?}", e)))?;
-                    return Err(OtaError::DownloadError(e));
-                }
+        if let Err(e) = res {
+            if progress.nwritten > resumed_from {
+                progress.resume_attempts = 0;
+            } else if resumed_from > 0 {
+                progress.resume_attempts += 1;
+            }
+            // progress is cleared when the download can't be resumed
+            if progress.nwritten > 0 {
+                log::info!(
+                    "storing OTA progress to resume later: {}/{} bytes",
+                    progress.nwritten,
+                    progress.file_len
+                );
+                let _ = self
+                    .storage
+                    .store_ota_download_progress(&progress)
+                    .inspect_err(|e| log::error!("failed to store OTA progress: {}", e));
+            } else {
+                let _ = self.storage.reset_ota_download_progress();
             }
+            writer
+                .abort()
+                .map_err(|e| OtaError::AbortError(format!("{:?}", e)))?;
+            return Err(e);
         }
 
-        drop(conn);
-        log::info!("firmware download complete: {} bytes", nwritten);
+        let _ = self
+            .storage
+            .reset_ota_download_progress()
+            .inspect_err(|e| log::warn!("failed to clear OTA progress: {}", e));
 
-        if nwritten != file_len {
-            log::error!("wrote {} bytes, expected to write {}", nwritten, file_len);
+        if let Err(e) = verifier.finalize() {
+            log::error!("failed to verify new firmware: {}", e);
             log::error!("aborting ota");
-            #[cfg(feature = "esp32")]
-            update_handle
+            writer
                 .abort()
                 .map_err(|e| OtaError::AbortError(format!("{:?}", e)))?;
 
-            return Err(OtaError::Other(
-                "nbytes written did not match file size".to_string(),
-            ));
+            return Err(OtaError::VerificationError(e));
         }
+        log::info!("new firmware verified");
 
         #[cfg(feature = "esp32")]
-        {
-            log::info!(
-                "setting device to use new firmware at `{:#x}`",
-                self.address
-            );
-            update_handle
-                .complete()
-                .map_err(|e| OtaError::UpdateError(format!("{:?}", e)))
-        }?;
+        log::info!(
+            "setting device to use new firmware at `{:#x}`",
+            self.address
+        );
+        writer
+            .complete()
+            .map_err(|e| OtaError::UpdateError(format!("{:?}", e)))?;
 
         log::info!("updating firmware metadata in NVS");
         self.storage
@@ -622,4 +839,755 @@ impl<S: OtaMetadataStorage> OtaService<S> {
 
         Ok(true)
     }
+
+    /// Downloads the rest of the image described by `progress` to `writer`. A dropped connection
+    /// or a stalled download is resumed from where it stopped, giving up after
+    /// `NUM_RETRY_CONN` attempts in a row without receiving anything. On errors that can't be
+    /// resumed from, `progress` is reset.
+    async fn download(
+        &self,
+        writer: &mut OtaWriter,
+        verifier: &mut ImageVerifier,
+        progress: &mut OtaDownloadProgress,
+    ) -> Result<(), OtaError<S>> {
+        #[cfg(feature = "esp32")]
+        let running_fw_info = EspOta::new()
+            .and_then(|ota| ota.get_running_slot())
+            .map(|slot| slot.firmware)
+            .map_err(|e| {
+                OtaError::UpdateError(format!(
+                    "failed to get handle to running ota partition: {}",
+                    e
+                ))
+            })?;
+
+        let mut num_failures = 0;
+        loop {
+            let (response, conn) = match self
+                .send_request(progress.nwritten, progress.etag.as_deref())
+                .await
+            {
+                Ok(res) => res,
+                // 416 when the stored progress doesn't match the image anymore, other client
+                // errors won't go away by asking for the same range again
+                Err(OtaError::BadStatus(status))
+                    if progress.nwritten > 0 && status.is_client_error() =>
+                {
+                    log::warn!(
+                        "server refused to resume the download ({}), restarting it",
+                        status
+                    );
+                    progress.nwritten = 0;
+                    progress.etag = None;
+                    return Err(OtaError::BadStatus(status));
+                }
+                Err(e) => return Err(e),
+            };
+
+            let headers = response.headers();
+            log::debug!("ota response headers: {:?}", headers);
+            let header_str = |name: header::HeaderName| {
+                headers
+                    .get(name)
+                    .and_then(|val| val.to_str().ok())
+                    .map(str::to_string)
+            };
+
+            let etag = header_str(header::ETAG).or_else(|| header_str(header::LAST_MODIFIED));
+            if response.status() == StatusCode::PARTIAL_CONTENT {
+                // Content-Range: bytes <start>-<end>/<total>
+                let range = header_str(header::CONTENT_RANGE).and_then(|range| {
+                    let (start, total) = range.strip_prefix("bytes ")?.split_once('/')?;
+                    let start = start.split_once('-')?.0.parse::<usize>().ok()?;
+                    Some((start, total.parse::<usize>().ok()?))
+                });
+                if range != Some((progress.nwritten, progress.file_len)) {
+                    let err = OtaError::Other(format!(
+                        "unexpected content range {:?}, expected {}/{}",
+                        range, progress.nwritten, progress.file_len
+                    ));
+                    progress.nwritten = 0;
+                    return Err(err);
+                }
+                log::info!("resuming download at byte {}", progress.nwritten);
+            } else {
+                if progress.nwritten > 0 {
+                    // the image changed or the server doesn't support ranges
+                    log::warn!("server sent the whole image, restarting download");
+                    progress.nwritten = 0;
+                    return Err(OtaError::Other("download couldn't be resumed".to_string()));
+                }
+                let file_len = header_str(header::CONTENT_LENGTH)
+                    .ok_or_else(|| {
+                        OtaError::Other("response header missing content length".to_string())
+                    })?
+                    .parse::<usize>()
+                    .map_err(|e| OtaError::Other(e.to_string()))?;
+
+                if file_len > self.max_size {
+                    return Err(OtaError::InvalidImageSizeLarge(file_len, self.max_size));
+                }
+                if file_len < *FIRMWARE_HEADER_SIZE {
+                    return Err(OtaError::InvalidImageSizeSmall(
+                        file_len,
+                        *FIRMWARE_HEADER_SIZE,
+                    ));
+                }
+                progress.file_len = file_len;
+            }
+            if etag.is_some() {
+                progress.etag = etag;
+            }
+
+            log::info!("writing new firmware to address `{:#x}`", self.address,);
+            let mut stream = response.into_data_stream();
+            let mut last_stored = progress.nwritten;
+            let mut got_info = progress.nwritten > 0;
+
+            let res = loop {
+                match stream
+                    .try_next()
+                    .map_err(DownloadError::Network)
+                    .or(async {
+                        async_io::Timer::after(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS)).await;
+                        Err(DownloadError::Timeout(DOWNLOAD_TIMEOUT_SECS as usize))
+                    })
+                    .await
+                {
+                    Ok(Some(data)) => {
+                        num_failures = 0;
+                        if !got_info {
+                            if progress.nwritten + data.len() < *FIRMWARE_HEADER_SIZE {
+                                log::error!("initial frame too small to retrieve esp_app_desc_t");
+                            } else {
+                                #[cfg(feature = "esp32")]
+                                {
+                                    log::info!("verifying new ota firmware");
+                                    let mut new_fw = FirmwareInfo {
+                                        version: Default::default(),
+                                        released: Default::default(),
+                                        description: None,
+                                        signature: None,
+                                        download_id: None,
+                                    };
+                                    let loader = EspFirmwareInfoLoad {};
+                                    // We *infer* that the binary is a valid by verifying
+                                    // the presence of the firmware header in the first bytes,
+                                    // however this does not imply validity of the image as a whole.
+                                    let loaded = loader
+                                        .fetch(&data, &mut new_fw)
+                                        .map_err(|e| OtaError::InvalidFirmware(e.to_string()))?;
+                                    if !loaded {
+                                        log::error!(
+                                            "unable to validate image header in first {} bytes, terminating download",
+                                            data.len()
+                                        );
+                                        return Err(OtaError::InvalidFirmware(
+                                            "firmware header not found".to_string(),
+                                        ));
+                                    }
+                                    log::debug!(
+                                        "current firmware app description: {:?}",
+                                        running_fw_info
+                                    );
+                                    log::debug!("new firmware app description: {:?}", new_fw);
+                                }
+                                got_info = true;
+                            }
+                        }
+
+                        if data.len() + progress.nwritten > progress.file_len {
+                            log::error!("file is larger than expected, aborting");
+                            let len = data.len() + progress.nwritten;
+                            progress.nwritten = 0;
+                            return Err(OtaError::Other(format!(
+                                "received {} bytes, expected {}",
+                                len, progress.file_len
+                            )));
+                        }
+
+                        verifier.update(&data);
+
+                        // TODO(RSDK-9271) add async writer for ota
+                        if let Err(e) = writer.write(&data) {
+                            progress.nwritten = 0;
+                            return Err(OtaError::WriteError(e));
+                        }
+                        progress.nwritten += data.len();
+                        log::info!(
+                            "updating next OTA partition at {:#x}: {}/{} bytes written",
+                            self.address,
+                            progress.nwritten,
+                            progress.file_len
+                        );
+
+                        if progress.nwritten - last_stored >= PROGRESS_STORE_INTERVAL {
+                            last_stored = progress.nwritten;
+                            let _ = self
+                                .storage
+                                .store_ota_download_progress(progress)
+                                .inspect_err(|e| log::warn!("failed to store OTA progress: {}", e));
+                        }
+                    }
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            };
+            drop(conn);
+
+            let err = match res {
+                Ok(()) if progress.nwritten == progress.file_len => {
+                    log::info!("firmware download complete: {} bytes", progress.nwritten);
+                    return Ok(());
+                }
+                Ok(()) => OtaError::Other("download ended early".to_string()),
+                Err(e) => OtaError::DownloadError(e),
+            };
+            log::warn!(
+                "download interrupted at {}/{} bytes: {}",
+                progress.nwritten,
+                progress.file_len,
+                err
+            );
+            num_failures += 1;
+            if num_failures >= NUM_RETRY_CONN {
+                return Err(err);
+            }
+            log::info!("resuming download in {} seconds", CONN_RETRY_SECS);
+            Timer::after(Duration::from_secs(CONN_RETRY_SECS)).await;
+        }
+    }
+}
+
+/// Returns true when the running image was just installed and still has to be verified, which
+/// only happens when rollback is enabled in the bootloader.
+#[cfg(feature = "esp32")]
+fn image_pending_verify() -> bool {
+    let mut state: esp_ota_img_states_t = 0;
+    let partition = unsafe { esp_ota_get_running_partition() };
+    esp!(unsafe { esp_ota_get_state_partition(partition, &mut state) })
+        .is_ok_and(|_| state == esp_ota_img_states_t_ESP_OTA_IMG_PENDING_VERIFY)
+}
+#[cfg(not(feature = "esp32"))]
+fn image_pending_verify() -> bool {
+    false
+}
+
+/// Decides whether a newly installed firmware is kept. After an update the image boots
+/// pending verification: it is marked valid once the server has reached app and the
+/// additional checks pass. If that doesn't happen before the timeout, the version is
+/// recorded as failed in [OtaMetadataStorage] and the device reboots into the previous image.
+#[derive(Clone)]
+pub struct OtaHealthCheck {
+    timeout: Duration,
+    checks: Vec<Rc<dyn Fn() -> bool>>,
+    app_reached: Rc<Cell<bool>>,
+}
+
+impl Default for OtaHealthCheck {
+    fn default() -> Self {
+        Self::new(Duration::from_secs(300))
+    }
+}
+
+impl OtaHealthCheck {
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            checks: Vec::new(),
+            app_reached: Default::default(),
+        }
+    }
+
+    /// Adds a check the new firmware has to pass before being kept
+    pub fn with_check(mut self, check: impl Fn() -> bool + 'static) -> Self {
+        self.checks.push(Rc::new(check));
+        self
+    }
+
+    /// To be called once the server is connected to app
+    pub(crate) fn set_app_reached(&self) {
+        self.app_reached.set(true);
+    }
+
+    #[cfg(test)]
+    pub(crate) fn app_reached(&self) -> bool {
+        self.app_reached.get()
+    }
+
+    /// Waits for the firmware to reach app and pass the checks, returns false on timeout
+    async fn wait_healthy(&self) -> bool {
+        let start = Instant::now();
+        loop {
+            if self.app_reached.get() && self.checks.iter().all(|check| check()) {
+                return true;
+            }
+            if start.elapsed() >= self.timeout {
+                return false;
+            }
+            Timer::after(Duration::from_secs(1)).await;
+        }
+    }
+
+    fn record_failure<S: OtaMetadataStorage>(storage: &S) -> String {
+        let version = storage
+            .get_ota_metadata()
+            .map(|metadata| metadata.version)
+            .unwrap_or_default();
+        if let Err(e) = storage.store_ota_failed_version(&version) {
+            log::error!(
+                "failed to store the version failing its health check: {}",
+                e
+            );
+        }
+        // the previous image will be running, whichever version it was
+        if let Err(e) = storage.reset_ota_metadata() {
+            log::error!("failed to reset OTA metadata: {}", e);
+        }
+        version
+    }
+
+    pub(crate) async fn run<S: OtaMetadataStorage>(self, storage: S) {
+        if !image_pending_verify() {
+            return;
+        }
+        log::warn!(
+            "new firmware is pending verification, it will be rolled back unless it reaches app within {} seconds",
+            self.timeout.as_secs()
+        );
+        if self.wait_healthy().await {
+            #[cfg(feature = "esp32")]
+            if let Err(e) = esp!(unsafe { esp_ota_mark_app_valid_cancel_rollback() }) {
+                log::error!("failed to mark new firmware as valid: {}", e);
+                return;
+            }
+            log::info!("new firmware passed its health check and was marked as valid");
+            return;
+        }
+
+        let version = Self::record_failure(&storage);
+        log::error!(
+            "firmware version `{}` failed its health check, rolling back to the previous image",
+            version
+        );
+        #[cfg(feature = "esp32")]
+        {
+            let e = esp!(unsafe { esp_ota_mark_app_invalid_rollback_and_reboot() });
+            log::error!("failed to roll back firmware: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        cell::RefCell, collections::HashMap, convert::Infallible, net::TcpListener, rc::Rc,
+        time::Duration,
+    };
+
+    use async_io::{Async, Timer};
+    use ed25519_dalek::{Signer, SigningKey};
+    use futures_lite::{stream, StreamExt};
+    use http_body_util::{combinators::UnsyncBoxBody, BodyExt, Full, StreamBody};
+    use hyper::{
+        body::{Bytes, Frame, Incoming},
+        header,
+        server::conn::http2,
+        service::service_fn,
+        Request, Response, StatusCode,
+    };
+    use sha2::{Digest, Sha256};
+
+    use crate::{
+        common::{
+            credentials_storage::{OtaMetadataStorage, RAMStorage},
+            exec::Executor,
+            ota::{
+                decode_hex, encode_hex, ImageVerifier, OtaDownloadProgress, OtaError,
+                OtaHealthCheck, OtaMetadata, OtaService, VerificationError, MAX_RESUME_ATTEMPTS,
+            },
+        },
+        google::protobuf::{value::Kind, Struct, Value},
+        native::tcp::NativeStream,
+        proto::app::v1::ServiceConfig,
+    };
+
+    const ETAG: &str = "\"image-v1\"";
+
+    // Serves `image` on any path, honoring `Range` and `If-Range` like ota-dev-server does.
+    // The first response is cut after `cut_at` bytes to simulate a dropped connection, when
+    // `range_status` is set requests with a `Range` fail with it. The `Range` of every request
+    // is recorded in `ranges`.
+    async fn serve_image(
+        exec: Executor,
+        listener: Async<TcpListener>,
+        image: Bytes,
+        cut_at: usize,
+        range_status: Option<StatusCode>,
+        ranges: Rc<RefCell<Vec<Option<String>>>>,
+    ) {
+        loop {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (image, ranges) = (image.clone(), ranges.clone());
+            let srv = service_fn(move |req: Request<Incoming>| {
+                let header_str = |name: header::HeaderName| {
+                    req.headers()
+                        .get(name)
+                        .map(|val| val.to_str().unwrap().to_string())
+                };
+                let range = header_str(header::RANGE);
+                let if_range = header_str(header::IF_RANGE);
+                let first = ranges.borrow().is_empty();
+                ranges.borrow_mut().push(range.clone());
+                let image = image.clone();
+                async move {
+                    if let Some(status) = range_status.filter(|_| range.is_some()) {
+                        let body: UnsyncBoxBody<Bytes, std::io::Error> = Full::new(Bytes::new())
+                            .map_err(|e| match e {})
+                            .boxed_unsync();
+                        return Ok::<_, Infallible>(
+                            Response::builder().status(status).body(body).unwrap(),
+                        );
+                    }
+                    let start = range
+                        .filter(|_| if_range.as_deref() == Some(ETAG))
+                        .map(|range| {
+                            range
+                                .strip_prefix("bytes=")
+                                .unwrap()
+                                .trim_end_matches('-')
+                                .parse::<usize>()
+                                .unwrap()
+                        });
+                    let response = Response::builder().header(header::ETAG, ETAG);
+                    let (response, data) = match start {
+                        Some(start) => (
+                            response
+                                .status(StatusCode::PARTIAL_CONTENT)
+                                .header(
+                                    header::CONTENT_RANGE,
+                                    format!("bytes {}-{}/{}", start, image.len() - 1, image.len()),
+                                )
+                                .header(header::CONTENT_LENGTH, image.len() - start),
+                            image.slice(start..),
+                        ),
+                        None => (
+                            response
+                                .status(StatusCode::OK)
+                                .header(header::CONTENT_LENGTH, image.len()),
+                            image,
+                        ),
+                    };
+                    let body: UnsyncBoxBody<Bytes, std::io::Error> = if first {
+                        let data = data.slice(..cut_at);
+                        StreamBody::new(stream::once(Ok(Frame::data(data))).chain(
+                            stream::once_future(async {
+                                Timer::after(Duration::from_millis(100)).await;
+                                Err(std::io::Error::other("connection dropped"))
+                            }),
+                        ))
+                        .boxed_unsync()
+                    } else {
+                        Full::new(data).map_err(|e| match e {}).boxed_unsync()
+                    };
+                    Ok(response.body(body).unwrap())
+                }
+            });
+            exec.spawn(
+                http2::Builder::new(exec.clone())
+                    .serve_connection(NativeStream::LocalPlain(stream), srv),
+            )
+            .detach();
+        }
+    }
+
+    fn ota_service_config(url: String, sha256: String) -> ServiceConfig {
+        let string_value = |s: String| Value {
+            kind: Some(Kind::StringValue(s)),
+        };
+        ServiceConfig {
+            name: "OTA".to_string(),
+            model: "rdk:builtin:ota_service".to_string(),
+            attributes: Some(Struct {
+                fields: HashMap::from([
+                    ("url".to_string(), string_value(url)),
+                    ("version".to_string(), string_value("1.0".to_string())),
+                    ("sha256".to_string(), string_value(sha256)),
+                ]),
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn start_server(
+        exec: &Executor,
+        image: Bytes,
+        cut_at: usize,
+        range_status: Option<StatusCode>,
+    ) -> (String, Rc<RefCell<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/ota.bin", listener.local_addr().unwrap());
+        let listener: Async<TcpListener> = listener.try_into().unwrap();
+        let ranges: Rc<RefCell<Vec<Option<String>>>> = Default::default();
+        exec.spawn(serve_image(
+            exec.clone(),
+            listener,
+            image,
+            cut_at,
+            range_status,
+            ranges.clone(),
+        ))
+        .detach();
+        (url, ranges)
+    }
+
+    #[test_log::test]
+    fn test_resume_dropped_download() {
+        let exec = Executor::new();
+        let image: Bytes = (0..200_000).map(|i| (i % 251) as u8).collect();
+        let sha256 = encode_hex(&Sha256::digest(&image));
+        let (url, ranges) = start_server(&exec, image, 50_000, None);
+
+        let storage = RAMStorage::new();
+        let ota = OtaService::from_config(
+            &ota_service_config(url, sha256),
+            storage.clone(),
+            exec.clone(),
+        );
+        assert!(ota.is_ok());
+        let mut ota = ota.ok().unwrap();
+
+        assert!(matches!(exec.block_on(ota.update()), Ok(true)));
+
+        // the second request only asked for what wasn't received yet
+        assert_eq!(
+            *ranges.borrow(),
+            vec![None, Some("bytes=50000-".to_string())]
+        );
+        assert_eq!(storage.get_ota_metadata().unwrap().version, "1.0");
+        assert!(storage.get_ota_download_progress().is_err());
+    }
+
+    #[test_log::test]
+    fn test_download_progress_is_stored() {
+        let exec = Executor::new();
+        let image: Bytes = (0..200_000).map(|i| (i % 251) as u8).collect();
+        let sha256 = encode_hex(&Sha256::digest(&image));
+        let (url, _) = start_server(&exec, image, 50_000, Some(StatusCode::SERVICE_UNAVAILABLE));
+
+        let storage = RAMStorage::new();
+        let ota = OtaService::from_config(
+            &ota_service_config(url.clone(), sha256),
+            storage.clone(),
+            exec.clone(),
+        );
+        assert!(ota.is_ok());
+        let mut ota = ota.ok().unwrap();
+
+        assert!(exec.block_on(ota.update()).is_err());
+        assert!(!storage.has_ota_metadata());
+
+        let progress = storage.get_ota_download_progress();
+        assert!(progress.is_ok());
+        let progress = progress.unwrap();
+        assert_eq!(progress.version, "1.0");
+        assert_eq!(progress.url, url);
+        assert_eq!(progress.etag.as_deref(), Some(ETAG));
+        assert_eq!(progress.file_len, 200_000);
+        assert_eq!(progress.nwritten, 50_000);
+        assert_eq!(progress.resume_attempts, 0);
+    }
+
+    #[test_log::test]
+    fn test_refused_range_restarts_download() {
+        let exec = Executor::new();
+        let image: Bytes = (0..200_000).map(|i| (i % 251) as u8).collect();
+        let sha256 = encode_hex(&Sha256::digest(&image));
+        let (url, ranges) = start_server(
+            &exec,
+            image,
+            50_000,
+            Some(StatusCode::RANGE_NOT_SATISFIABLE),
+        );
+
+        let storage = RAMStorage::new();
+        let ota = OtaService::from_config(
+            &ota_service_config(url, sha256),
+            storage.clone(),
+            exec.clone(),
+        );
+        assert!(ota.is_ok());
+        let mut ota = ota.ok().unwrap();
+
+        // the stale progress is dropped rather than resumed again
+        assert!(matches!(
+            exec.block_on(ota.update()),
+            Err(OtaError::BadStatus(StatusCode::RANGE_NOT_SATISFIABLE))
+        ));
+        assert!(storage.get_ota_download_progress().is_err());
+
+        assert!(matches!(exec.block_on(ota.update()), Ok(true)));
+        assert_eq!(
+            *ranges.borrow(),
+            vec![None, Some("bytes=50000-".to_string()), None]
+        );
+        assert_eq!(storage.get_ota_metadata().unwrap().version, "1.0");
+    }
+
+    #[test_log::test]
+    fn test_resume_attempts_are_capped() {
+        let exec = Executor::new();
+        let url = "http://127.0.0.1:1/ota.bin".to_string();
+        let storage = RAMStorage::new();
+        let service = OtaService::from_config(
+            &ota_service_config(url.clone(), encode_hex(&[0; 32])),
+            storage.clone(),
+            exec,
+        )
+        .ok()
+        .unwrap();
+
+        let mut progress = OtaDownloadProgress {
+            version: "1.0".to_string(),
+            url,
+            etag: Some(ETAG.to_string()),
+            file_len: 200_000,
+            nwritten: 50_000,
+            resume_attempts: MAX_RESUME_ATTEMPTS - 1,
+        };
+        storage.store_ota_download_progress(&progress).unwrap();
+        assert_eq!(service.stored_progress(), progress);
+
+        progress.resume_attempts = MAX_RESUME_ATTEMPTS;
+        storage.store_ota_download_progress(&progress).unwrap();
+        assert_eq!(service.stored_progress().nwritten, 0);
+        assert_eq!(service.stored_progress().etag, None);
+    }
+
+    #[test_log::test]
+    fn test_decode_hex() {
+        let bytes = decode_hex::<4>("sha256", "00ff10Ab");
+        assert!(bytes.is_ok());
+        assert_eq!(bytes.unwrap(), [0x00, 0xff, 0x10, 0xab]);
+        assert_eq!(encode_hex(&[0x00, 0xff, 0x10, 0xab]), "00ff10ab");
+
+        assert!(decode_hex::<4>("sha256", "00ff10").is_err());
+        assert!(decode_hex::<4>("sha256", "00ff10zz").is_err());
+        assert!(decode_hex::<2>("sha256", "+1ff").is_err());
+        assert!(decode_hex::<2>("sha256", "é1f").is_err());
+    }
+
+    #[test_log::test]
+    fn test_image_verifier() {
+        let image: Vec<u8> = (0..4096).map(|i| (i % 251) as u8).collect();
+        let digest: [u8; 32] = Sha256::digest(&image).into();
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let signature = signing_key.sign(&digest);
+
+        let verify = |sha256, signature, image: &[u8]| {
+            let mut verifier = ImageVerifier::new(sha256, signature);
+            for chunk in image.chunks(1000) {
+                verifier.update(chunk);
+            }
+            verifier.finalize()
+        };
+
+        assert!(verify(None, None, &image).is_ok());
+        assert!(verify(
+            Some(digest),
+            Some((signing_key.verifying_key(), signature)),
+            &image
+        )
+        .is_ok());
+
+        let mut tampered = image.clone();
+        tampered[2048] ^= 1;
+        assert!(matches!(
+            verify(Some(digest), None, &tampered),
+            Err(VerificationError::DigestMismatch(_, _))
+        ));
+        assert!(matches!(
+            verify(
+                None,
+                Some((signing_key.verifying_key(), signature)),
+                &tampered
+            ),
+            Err(VerificationError::InvalidSignature(_))
+        ));
+
+        let other_key = SigningKey::from_bytes(&[8; 32]);
+        assert!(matches!(
+            verify(None, Some((other_key.verifying_key(), signature)), &image),
+            Err(VerificationError::InvalidSignature(_))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_health_check() {
+        let exec = Executor::new();
+
+        let health_check = OtaHealthCheck::new(Duration::from_secs(2));
+        health_check.set_app_reached();
+        assert!(exec.block_on(health_check.wait_healthy()));
+
+        let health_check = OtaHealthCheck::new(Duration::from_secs(2));
+        assert!(!exec.block_on(health_check.wait_healthy()));
+
+        let health_check = OtaHealthCheck::new(Duration::from_secs(2)).with_check(|| false);
+        health_check.set_app_reached();
+        assert!(!exec.block_on(health_check.wait_healthy()));
+
+        let ready = Rc::new(RefCell::new(false));
+        let health_check = OtaHealthCheck::new(Duration::from_secs(5)).with_check({
+            let ready = ready.clone();
+            move || *ready.borrow()
+        });
+        let (reached, ready_task) = (health_check.clone(), ready.clone());
+        exec.spawn(async move {
+            Timer::after(Duration::from_millis(500)).await;
+            reached.set_app_reached();
+            *ready_task.borrow_mut() = true;
+        })
+        .detach();
+        assert!(exec.block_on(health_check.wait_healthy()));
+    }
+
+    #[test_log::test]
+    fn test_failed_version_is_skipped() {
+        let storage = RAMStorage::new();
+        storage
+            .store_ota_metadata(&OtaMetadata {
+                version: "2.0".to_string(),
+            })
+            .unwrap();
+        let failed = OtaHealthCheck::record_failure(&storage);
+        assert_eq!(failed, "2.0");
+        assert_eq!(storage.get_ota_failed_version().unwrap(), "2.0");
+        assert!(!storage.has_ota_metadata());
+
+        let exec = Executor::new();
+        let config_with_version = |version: &str| {
+            let mut config = ota_service_config(
+                "http://127.0.0.1:1/ota.bin".to_string(),
+                encode_hex(&[0; 32]),
+            );
+            config.attributes.as_mut().unwrap().fields.insert(
+                "version".to_string(),
+                Value {
+                    kind: Some(Kind::StringValue(version.to_string())),
+                },
+            );
+            config
+        };
+        let service =
+            OtaService::from_config(&config_with_version("2.0"), storage.clone(), exec.clone())
+                .ok()
+                .unwrap();
+        assert!(!service.needs_update());
+
+        let service = OtaService::from_config(&config_with_version("2.1"), storage, exec)
+            .ok()
+            .unwrap();
+        assert!(service.needs_update());
+    }
 }
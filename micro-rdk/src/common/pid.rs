@@ -0,0 +1,186 @@
+//! A PID controller to close the loop on actuators reporting their state, such as motors
+//! paired with an encoder.
+//!
+//! The gains are read from a `control_parameters` attribute:
+//! ```json
+//! "control_parameters": { "kp": 0.5, "ki": 1.0, "kd": 0.0 }
+//! ```
+
+use std::time::Duration;
+
+use super::config::{AttributeError, Kind};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PidConfig {
+    pub kp: f64,
+    pub ki: f64,
+    pub kd: f64,
+}
+
+impl TryFrom<&Kind> for PidConfig {
+    type Error = AttributeError;
+    fn try_from(value: &Kind) -> Result<Self, Self::Error> {
+        let gain = |key: &str| -> Result<f64, AttributeError> {
+            match value.get(key)? {
+                Some(val) => val.try_into(),
+                None => Ok(0.0),
+            }
+        };
+        let config = Self {
+            kp: gain("kp")?,
+            ki: gain("ki")?,
+            kd: gain("kd")?,
+        };
+        if [config.kp, config.ki, config.kd]
+            .iter()
+            .any(|g| !g.is_finite())
+        {
+            return Err(AttributeError::ValidationError(
+                "control_parameters gains must be finite".to_string(),
+            ));
+        }
+        Ok(config)
+    }
+}
+
+/// Computes a command in `[min_output, max_output]` from the error between a setpoint and a
+/// measurement. The integral term is clamped so it can't wind up past the output limits
+/// while the actuator is saturated.
+#[derive(Clone, Debug)]
+pub struct PidController {
+    config: PidConfig,
+    min_output: f64,
+    max_output: f64,
+    integral: f64,
+    prev_error: Option<f64>,
+}
+
+impl PidController {
+    pub fn new(config: PidConfig, min_output: f64, max_output: f64) -> Self {
+        Self {
+            config,
+            min_output,
+            max_output,
+            integral: 0.0,
+            prev_error: None,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.integral = 0.0;
+        self.prev_error = None;
+    }
+
+    pub fn update(&mut self, setpoint: f64, measurement: f64, dt: Duration) -> f64 {
+        let dt = dt.as_secs_f64();
+        let error = setpoint - measurement;
+        if dt <= 0.0 {
+            return (self.config.kp * error + self.config.ki * self.integral)
+                .clamp(self.min_output, self.max_output);
+        }
+        if self.config.ki != 0.0 {
+            let (lo, hi) = (
+                self.min_output / self.config.ki,
+                self.max_output / self.config.ki,
+            );
+            self.integral = (self.integral + error * dt).clamp(lo.min(hi), lo.max(hi));
+        }
+        let derivative = self.prev_error.map_or(0.0, |prev| (error - prev) / dt);
+        self.prev_error = Some(error);
+        (self.config.kp * error + self.config.ki * self.integral + self.config.kd * derivative)
+            .clamp(self.min_output, self.max_output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use crate::common::{
+        config::Kind,
+        pid::{PidConfig, PidController},
+    };
+
+    #[test_log::test]
+    fn test_pid_config() {
+        let kind = Kind::StructValue(HashMap::from([
+            ("kp".to_owned(), Kind::NumberValue(0.5)),
+            ("ki".to_owned(), Kind::StringValue("2".to_owned())),
+        ]));
+        let config = PidConfig::try_from(&kind);
+        assert!(config.is_ok());
+        assert_eq!(
+            config.unwrap(),
+            PidConfig {
+                kp: 0.5,
+                ki: 2.0,
+                kd: 0.0
+            }
+        );
+
+        let kind = Kind::StructValue(HashMap::from([(
+            "kd".to_owned(),
+            Kind::NumberValue(f64::NAN),
+        )]));
+        assert!(PidConfig::try_from(&kind).is_err());
+        assert!(PidConfig::try_from(&Kind::NumberValue(1.0)).is_err());
+    }
+
+    #[test_log::test]
+    fn test_pid_proportional() {
+        let mut pid = PidController::new(
+            PidConfig {
+                kp: 0.1,
+                ..Default::default()
+            },
+            -1.0,
+            1.0,
+        );
+        assert_eq!(pid.update(5.0, 0.0, Duration::from_millis(50)), 0.5);
+        assert_eq!(pid.update(-5.0, 0.0, Duration::from_millis(50)), -0.5);
+        // output is saturated
+        assert_eq!(pid.update(50.0, 0.0, Duration::from_millis(50)), 1.0);
+    }
+
+    #[test_log::test]
+    fn test_pid_integral_anti_windup() {
+        let mut pid = PidController::new(
+            PidConfig {
+                ki: 1.0,
+                ..Default::default()
+            },
+            -1.0,
+            1.0,
+        );
+        for _ in 0..100 {
+            let _ = pid.update(10.0, 0.0, Duration::from_secs(1));
+        }
+        // the integral didn't accumulate past the output limit, so it recovers right away
+        let out = pid.update(-1.0, 0.0, Duration::from_millis(500));
+        assert!((out - 0.5).abs() < 1e-9);
+
+        pid.reset();
+        assert_eq!(pid.update(0.0, 0.0, Duration::from_secs(1)), 0.0);
+    }
+
+    #[test_log::test]
+    fn test_pid_converges() {
+        // first order plant: the speed follows the command with a gain of 100 rpm per unit
+        let mut pid = PidController::new(
+            PidConfig {
+                kp: 0.002,
+                ki: 0.05,
+                kd: 0.0,
+            },
+            -1.0,
+            1.0,
+        );
+        let dt = Duration::from_millis(50);
+        let mut speed = 0.0;
+        for _ in 0..400 {
+            let command = pid.update(60.0, speed, dt);
+            speed += (command * 100.0 - speed) * 0.2;
+        }
+        assert!((speed - 60.0).abs() < 0.5);
+    }
+}
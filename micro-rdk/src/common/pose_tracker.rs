@@ -0,0 +1,156 @@
+use super::{config::AttributeError, generic::DoCommand};
+use crate::proto::common::v1::PoseInFrame;
+
+#[cfg(feature = "builtin-components")]
+use super::{
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency},
+};
+#[cfg(feature = "builtin-components")]
+use crate::proto::common::v1::Pose;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use thiserror::Error;
+
+pub static COMPONENT_NAME: &str = "pose_tracker";
+
+#[derive(Debug, Error)]
+pub enum PoseTrackerError {
+    #[error(transparent)]
+    PoseTrackerConfigAttributeError(#[from] AttributeError),
+    #[error("config error: {0}")]
+    PoseTrackerConfigError(&'static str),
+    #[error("body {0} is not tracked")]
+    PoseTrackerBodyNotFound(String),
+}
+
+pub trait PoseTracker: DoCommand {
+    /// Returns the pose of each of the bodies named in `body_names`, or of every tracked
+    /// body when `body_names` is empty
+    fn get_poses(
+        &mut self,
+        body_names: Vec<String>,
+    ) -> Result<HashMap<String, PoseInFrame>, PoseTrackerError>;
+}
+
+pub type PoseTrackerType = Arc<Mutex<dyn PoseTracker>>;
+
+impl<L> PoseTracker for Mutex<L>
+where
+    L: ?Sized + PoseTracker,
+{
+    fn get_poses(
+        &mut self,
+        body_names: Vec<String>,
+    ) -> Result<HashMap<String, PoseInFrame>, PoseTrackerError> {
+        self.get_mut().unwrap().get_poses(body_names)
+    }
+}
+
+impl<A> PoseTracker for Arc<Mutex<A>>
+where
+    A: ?Sized + PoseTracker,
+{
+    fn get_poses(
+        &mut self,
+        body_names: Vec<String>,
+    ) -> Result<HashMap<String, PoseInFrame>, PoseTrackerError> {
+        self.lock().unwrap().get_poses(body_names)
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_pose_tracker("fake", &FakePoseTracker::from_config)
+        .is_err()
+    {
+        log::error!("fake type is already registered");
+    }
+}
+
+/// Tracks the bodies listed in the `bodies` attribute, all of them at the origin of the
+/// world frame
+#[cfg(feature = "builtin-components")]
+#[derive(DoCommand)]
+pub struct FakePoseTracker {
+    bodies: HashMap<String, PoseInFrame>,
+}
+
+#[cfg(feature = "builtin-components")]
+impl FakePoseTracker {
+    pub fn new(bodies: Vec<String>) -> Self {
+        let bodies = bodies
+            .into_iter()
+            .map(|name| {
+                (
+                    name,
+                    PoseInFrame {
+                        reference_frame: "world".to_string(),
+                        pose: Some(Pose {
+                            o_z: 1.0,
+                            ..Default::default()
+                        }),
+                    },
+                )
+            })
+            .collect();
+        Self { bodies }
+    }
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<PoseTrackerType, PoseTrackerError> {
+        let bodies = cfg
+            .get_attribute::<Vec<String>>("bodies")
+            .unwrap_or_else(|_| vec!["body".to_string()]);
+        Ok(Arc::new(Mutex::new(FakePoseTracker::new(bodies))))
+    }
+}
+
+#[cfg(feature = "builtin-components")]
+impl PoseTracker for FakePoseTracker {
+    fn get_poses(
+        &mut self,
+        body_names: Vec<String>,
+    ) -> Result<HashMap<String, PoseInFrame>, PoseTrackerError> {
+        if body_names.is_empty() {
+            return Ok(self.bodies.clone());
+        }
+        body_names
+            .into_iter()
+            .map(|name| match self.bodies.get(&name) {
+                Some(pose) => Ok((name, pose.clone())),
+                None => Err(PoseTrackerError::PoseTrackerBodyNotFound(name)),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FakePoseTracker, PoseTracker, PoseTrackerError};
+
+    #[test_log::test]
+    fn test_fake_pose_tracker() {
+        let mut tracker = FakePoseTracker::new(vec!["hand".to_string(), "head".to_string()]);
+
+        let poses = tracker.get_poses(vec![]).unwrap();
+        assert_eq!(poses.len(), 2);
+        let pose = &poses["hand"];
+        assert_eq!(pose.reference_frame, "world");
+        assert_eq!(pose.pose.as_ref().unwrap().o_z, 1.0);
+
+        let poses = tracker.get_poses(vec!["head".to_string()]).unwrap();
+        assert_eq!(poses.len(), 1);
+        assert!(poses.contains_key("head"));
+
+        assert!(matches!(
+            tracker.get_poses(vec!["head".to_string(), "foot".to_string()]),
+            Err(PoseTrackerError::PoseTrackerBodyNotFound(name)) if name == "foot"
+        ));
+    }
+}
@@ -3,14 +3,20 @@ use std::collections::HashMap as Map;
 use thiserror::Error;
 
 use super::{
+    arm::{ArmError, ArmType},
+    audio_input::{AudioInputError, AudioInputType},
     base::{BaseError, BaseType},
     board::{BoardError, BoardType},
     button::{ButtonError, ButtonType},
     config::ConfigType,
     encoder::{EncoderError, EncoderType},
-    generic::{GenericComponentType, GenericError},
+    gantry::{GantryError, GantryType},
+    generic::{GenericComponentType, GenericError, GenericServiceType},
+    gripper::{GripperError, GripperType},
+    input_controller::{InputControllerError, InputControllerType},
     motor::{MotorError, MotorType},
     movement_sensor::MovementSensorType,
+    pose_tracker::{PoseTrackerError, PoseTrackerType},
     power_sensor::PowerSensorType,
     robot::Resource,
     sensor::{SensorError, SensorType},
@@ -34,6 +40,8 @@ pub enum RegistryError {
     ComponentTypeNotInDependencies(String),
     #[error("RegistryError: model '{0}' not found in dependencies under component type '{1}'")]
     ModelNotFoundInDependencies(String, String),
+    #[error("RegistryError: service type '{0}' isn't supported")]
+    ServiceTypeNotSupported(String),
 }
 
 pub fn get_board_from_dependencies(deps: Vec<Dependency>) -> Option<BoardType> {
@@ -75,6 +83,12 @@ impl TryFrom<ResourceName> for ResourceKey {
             "switch" => crate::common::switch::COMPONENT_NAME,
             "power_sensor" => crate::common::power_sensor::COMPONENT_NAME,
             "generic" => crate::common::generic::COMPONENT_NAME,
+            "arm" => crate::common::arm::COMPONENT_NAME,
+            "gripper" => crate::common::gripper::COMPONENT_NAME,
+            "gantry" => crate::common::gantry::COMPONENT_NAME,
+            "input_controller" => crate::common::input_controller::COMPONENT_NAME,
+            "pose_tracker" => crate::common::pose_tracker::COMPONENT_NAME,
+            "audio_input" => crate::common::audio_input::COMPONENT_NAME,
             _ => {
                 return Err(RegistryError::ModelNotFound(comp_type.into()));
             }
@@ -115,6 +129,27 @@ type ServoConstructor = dyn Fn(ConfigType, Vec<Dependency>) -> Result<ServoType,
 
 type SwitchConstructor = dyn Fn(ConfigType, Vec<Dependency>) -> Result<SwitchType, SwitchError>;
 
+/// Fn that returns an `ArmType`, `Arc<Mutex<dyn Arm>>`
+type ArmConstructor = dyn Fn(ConfigType, Vec<Dependency>) -> Result<ArmType, ArmError>;
+
+/// Fn that returns a `GripperType`, `Arc<Mutex<dyn Gripper>>`
+type GripperConstructor = dyn Fn(ConfigType, Vec<Dependency>) -> Result<GripperType, GripperError>;
+
+/// Fn that returns a `GantryType`, `Arc<Mutex<dyn Gantry>>`
+type GantryConstructor = dyn Fn(ConfigType, Vec<Dependency>) -> Result<GantryType, GantryError>;
+
+/// Fn that returns an `InputControllerType`, `Arc<Mutex<dyn InputController>>`
+type InputControllerConstructor =
+    dyn Fn(ConfigType, Vec<Dependency>) -> Result<InputControllerType, InputControllerError>;
+
+/// Fn that returns a `PoseTrackerType`, `Arc<Mutex<dyn PoseTracker>>`
+type PoseTrackerConstructor =
+    dyn Fn(ConfigType, Vec<Dependency>) -> Result<PoseTrackerType, PoseTrackerError>;
+
+/// Fn that returns an `AudioInputType`, `Arc<Mutex<dyn AudioInput>>`
+type AudioInputConstructor =
+    dyn Fn(ConfigType, Vec<Dependency>) -> Result<AudioInputType, AudioInputError>;
+
 /// Fn that returns a `PowerSensorType`, `Arc<Mutex<dyn PowerSensor>>`
 type PowerSensorConstructor =
     dyn Fn(ConfigType, Vec<Dependency>) -> Result<PowerSensorType, SensorError>;
@@ -123,6 +158,10 @@ type PowerSensorConstructor =
 type GenericComponentConstructor =
     dyn Fn(ConfigType, Vec<Dependency>) -> Result<GenericComponentType, GenericError>;
 
+/// Fn that returns a `GenericServiceType`, `Arc<Mutex<dyn GenericService>>`
+type ServiceConstructor =
+    dyn Fn(ConfigType, Vec<Dependency>) -> Result<GenericServiceType, GenericError>;
+
 type DependenciesFromConfig = dyn Fn(ConfigType) -> Vec<ResourceKey>;
 
 #[derive(Clone)]
@@ -138,8 +177,15 @@ pub struct ComponentRegistry {
     bases: Map<String, &'static BaseConstructor>,
     servos: Map<String, &'static ServoConstructor>,
     switches: Map<String, &'static SwitchConstructor>,
+    arms: Map<String, &'static ArmConstructor>,
+    grippers: Map<String, &'static GripperConstructor>,
+    gantries: Map<String, &'static GantryConstructor>,
+    input_controllers: Map<String, &'static InputControllerConstructor>,
+    pose_trackers: Map<String, &'static PoseTrackerConstructor>,
+    audio_inputs: Map<String, &'static AudioInputConstructor>,
     power_sensors: Map<String, &'static PowerSensorConstructor>,
     generic_components: Map<String, &'static GenericComponentConstructor>,
+    services: Map<String, Map<String, &'static ServiceConstructor>>,
     dependencies: Map<String, Map<String, &'static DependenciesFromConfig>>,
 }
 
@@ -159,10 +205,18 @@ impl Default for ComponentRegistry {
             crate::common::switch::register_models(&mut r);
             crate::common::movement_sensor::register_models(&mut r);
             crate::common::mpu6050::register_models(&mut r);
+            crate::common::merged_movement_sensor::register_models(&mut r);
             crate::common::adxl345::register_models(&mut r);
             crate::common::generic::register_models(&mut r);
             crate::common::ina::register_models(&mut r);
             crate::common::wheeled_base::register_models(&mut r);
+            crate::common::arm::register_models(&mut r);
+            crate::common::servo_arm::register_models(&mut r);
+            crate::common::gripper::register_models(&mut r);
+            crate::common::gantry::register_models(&mut r);
+            crate::common::gpio_input_controller::register_models(&mut r);
+            crate::common::pose_tracker::register_models(&mut r);
+            crate::common::audio_input::register_models(&mut r);
             #[cfg(feature = "camera")]
             crate::common::camera::register_models(&mut r);
         }
@@ -173,6 +227,7 @@ impl Default for ComponentRegistry {
             {
                 crate::esp32::encoder::register_models(&mut r);
                 crate::esp32::hcsr04::register_models(&mut r);
+                crate::esp32::i2s_audio_input::register_models(&mut r);
                 crate::esp32::single_encoder::register_models(&mut r);
                 crate::esp32::coredump::register_models(&mut r);
             }
@@ -200,6 +255,23 @@ impl ComponentRegistry {
             Map::new(),
         );
         dependency_func_map.insert(crate::common::generic::COMPONENT_NAME.into(), Map::new());
+        dependency_func_map.insert(crate::common::arm::COMPONENT_NAME.into(), Map::new());
+        dependency_func_map.insert(crate::common::gripper::COMPONENT_NAME.into(), Map::new());
+        dependency_func_map.insert(crate::common::gantry::COMPONENT_NAME.into(), Map::new());
+        dependency_func_map.insert(
+            crate::common::input_controller::COMPONENT_NAME.into(),
+            Map::new(),
+        );
+        dependency_func_map.insert(
+            crate::common::pose_tracker::COMPONENT_NAME.into(),
+            Map::new(),
+        );
+        dependency_func_map.insert(
+            crate::common::audio_input::COMPONENT_NAME.into(),
+            Map::new(),
+        );
+        let mut service_func_map = Map::new();
+        service_func_map.insert(crate::common::generic::COMPONENT_NAME.into(), Map::new());
         Self {
             motors: Map::new(),
             board: Map::new(),
@@ -212,8 +284,15 @@ impl ComponentRegistry {
             bases: Map::new(),
             servos: Map::new(),
             switches: Map::new(),
+            arms: Map::new(),
+            grippers: Map::new(),
+            gantries: Map::new(),
+            input_controllers: Map::new(),
+            pose_trackers: Map::new(),
+            audio_inputs: Map::new(),
             power_sensors: Map::new(),
             generic_components: Map::new(),
+            services: service_func_map,
             dependencies: dependency_func_map,
         }
     }
@@ -359,6 +438,84 @@ impl ComponentRegistry {
         Ok(())
     }
 
+    pub fn register_arm(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static ArmConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.arms.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.arms.insert(model, constructor);
+        Ok(())
+    }
+
+    pub fn register_gripper(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static GripperConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.grippers.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.grippers.insert(model, constructor);
+        Ok(())
+    }
+
+    pub fn register_gantry(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static GantryConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.gantries.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.gantries.insert(model, constructor);
+        Ok(())
+    }
+
+    pub fn register_input_controller(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static InputControllerConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.input_controllers.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.input_controllers.insert(model, constructor);
+        Ok(())
+    }
+
+    pub fn register_pose_tracker(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static PoseTrackerConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.pose_trackers.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.pose_trackers.insert(model, constructor);
+        Ok(())
+    }
+
+    pub fn register_audio_input(
+        &mut self,
+        model: impl Into<String>,
+        constructor: &'static AudioInputConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        if self.audio_inputs.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = self.audio_inputs.insert(model, constructor);
+        Ok(())
+    }
+
     pub fn register_generic_component(
         &mut self,
         model: impl Into<String>,
@@ -372,6 +529,26 @@ impl ComponentRegistry {
         Ok(())
     }
 
+    /// Registers a model of a service API under its subtype (only `generic` is supported), its
+    /// dependencies are the resources listed in the `depends_on` field of the service config
+    pub fn register_service(
+        &mut self,
+        service_type: &str,
+        model: impl Into<String>,
+        constructor: &'static ServiceConstructor,
+    ) -> Result<(), RegistryError> {
+        let model = model.into();
+        let models = self
+            .services
+            .get_mut(service_type)
+            .ok_or_else(|| RegistryError::ServiceTypeNotSupported(service_type.to_string()))?;
+        if models.contains_key(&model) {
+            return Err(RegistryError::ModelAlreadyRegistered(model));
+        }
+        let _ = models.insert(model, constructor);
+        Ok(())
+    }
+
     pub fn register_dependency_getter(
         &mut self,
         component_type: &str,
@@ -523,6 +700,66 @@ impl ComponentRegistry {
         Err(RegistryError::ModelNotFound(model.to_string()))
     }
 
+    pub(crate) fn get_arm_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static ArmConstructor, RegistryError> {
+        if let Some(ctor) = self.arms.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
+    pub(crate) fn get_gripper_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static GripperConstructor, RegistryError> {
+        if let Some(ctor) = self.grippers.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
+    pub(crate) fn get_gantry_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static GantryConstructor, RegistryError> {
+        if let Some(ctor) = self.gantries.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
+    pub(crate) fn get_input_controller_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static InputControllerConstructor, RegistryError> {
+        if let Some(ctor) = self.input_controllers.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
+    pub(crate) fn get_pose_tracker_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static PoseTrackerConstructor, RegistryError> {
+        if let Some(ctor) = self.pose_trackers.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
+    pub(crate) fn get_audio_input_constructor(
+        &self,
+        model: &str,
+    ) -> Result<&'static AudioInputConstructor, RegistryError> {
+        if let Some(ctor) = self.audio_inputs.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
+
     pub(crate) fn get_generic_component_constructor(
         &self,
         model: &str,
@@ -532,6 +769,21 @@ impl ComponentRegistry {
         }
         Err(RegistryError::ModelNotFound(model.to_string()))
     }
+
+    pub(crate) fn get_service_constructor(
+        &self,
+        service_type: &str,
+        model: &str,
+    ) -> Result<&'static ServiceConstructor, RegistryError> {
+        let models = self
+            .services
+            .get(service_type)
+            .ok_or_else(|| RegistryError::ServiceTypeNotSupported(service_type.to_string()))?;
+        if let Some(ctor) = models.get(model) {
+            return Ok(*ctor);
+        }
+        Err(RegistryError::ModelNotFound(model.to_string()))
+    }
 }
 #[cfg(test)]
 mod tests {
@@ -766,4 +1018,37 @@ mod tests {
         assert!(ret.is_err());
         assert_eq!(format!("{}", ret.err().unwrap()), "method:  not supported");
     }
+
+    #[test_log::test]
+    fn test_service_registry() {
+        let mut registry = ComponentRegistry::new();
+
+        let ctor = registry.get_service_constructor("generic", "fake");
+        assert_eq!(
+            ctor.err().unwrap(),
+            RegistryError::ModelNotFound("fake".into())
+        );
+        common::generic::register_models(&mut registry);
+        assert!(registry.get_service_constructor("generic", "fake").is_ok());
+        // models are looked up under the subtype of the service
+        assert_eq!(
+            registry.get_service_constructor("navigation", "fake").err(),
+            Some(RegistryError::ServiceTypeNotSupported("navigation".into()))
+        );
+
+        let ret = registry.register_service("generic", "fake", &|_, _| {
+            Err(common::generic::GenericError::MethodUnimplemented(""))
+        });
+        assert_eq!(
+            ret.err().unwrap(),
+            RegistryError::ModelAlreadyRegistered("fake".into())
+        );
+        let ret = registry.register_service("navigation", "fake", &|_, _| {
+            Err(common::generic::GenericError::MethodUnimplemented(""))
+        });
+        assert_eq!(
+            ret.err().unwrap(),
+            RegistryError::ServiceTypeNotSupported("navigation".into())
+        );
+    }
 }
@@ -4,7 +4,7 @@ use async_executor::Task;
 
 use chrono::{DateTime, FixedOffset};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
     time::Instant,
 };
@@ -18,7 +18,7 @@ use crate::{
         movement_sensor::MovementSensor, sensor::Sensor, switch::Switch,
     },
     proto::{
-        app::v1::RobotConfig,
+        app::v1::{ComponentConfig, RobotConfig, ServiceConfig},
         common::{self},
         robot,
     },
@@ -27,35 +27,47 @@ use log::*;
 
 #[cfg(feature = "data")]
 use super::{
-    data_collector::{DataCollectionError, DataCollector, DataCollectorConfig},
-    data_manager::{DataCollectAndSyncTask, DataManager},
-    data_store::DefaultDataStore,
+    data_collector::{CaptureCondition, DataCollectionError, DataCollector, DataCollectorConfig},
+    data_manager::{DataCollectAndSyncTask, DataManager, DataManagerError},
+    data_store::{DataStore, DefaultDataStore},
     system::FirmwareMode,
 };
+#[cfg(all(feature = "data", any(feature = "esp32", feature = "native")))]
+use super::{data_store::DataStoreError, flash_data_store::PersistentDataStore};
 
 use super::{
     actuator::ActuatorError,
     app_client::PeriodicAppClientTask,
+    arm::{Arm, ArmType},
+    audio_input::{AudioInput, AudioInputType},
     base::BaseType,
     board::BoardType,
     button::{Button, ButtonType},
     config::{AttributeError, ConfigType, DynamicComponentConfig, ResourceName},
     encoder::EncoderType,
     exec::Executor,
-    generic::{GenericComponent, GenericComponentType},
+    gantry::{Gantry, GantryType},
+    generic::{GenericComponent, GenericComponentType, GenericService, GenericServiceType},
+    gripper::{Gripper, GripperType},
+    input_controller::{InputController, InputControllerType},
     motor::MotorType,
     movement_sensor::MovementSensorType,
+    pose_tracker::{PoseTracker, PoseTrackerType},
     power_sensor::{PowerSensor, PowerSensorType},
     registry::{
         get_board_from_dependencies, ComponentRegistry, Dependency, RegistryError, ResourceKey,
     },
-    sensor::SensorType,
+    sensor::{Readings, SensorType},
     servo::{Servo, ServoType},
     switch::SwitchType,
 };
 
 use thiserror::Error;
 
+/// Name of the sensors service every robot serves, reading all of its sensors in a
+/// single request
+pub static SENSORS_SERVICE_NAME: &str = "builtin";
+
 #[derive(Clone)]
 pub enum ResourceType {
     Motor(MotorType),
@@ -68,9 +80,16 @@ pub enum ResourceType {
     PowerSensor(PowerSensorType),
     Servo(ServoType),
     Switch(SwitchType),
+    Arm(ArmType),
+    Gripper(GripperType),
+    Gantry(GantryType),
+    InputController(InputControllerType),
+    PoseTracker(PoseTrackerType),
+    AudioInput(AudioInputType),
     Generic(GenericComponentType),
     #[cfg(feature = "camera")]
     Camera(CameraType),
+    GenericService(GenericServiceType),
 }
 pub type Resource = ResourceType;
 pub type ResourceMap = HashMap<ResourceName, Resource>;
@@ -78,19 +97,26 @@ pub type ResourceMap = HashMap<ResourceName, Resource>;
 impl ResourceType {
     pub fn component_type(&self) -> String {
         match self {
+            Self::Arm(_) => "rdk:component:arm",
+            Self::AudioInput(_) => "rdk:component:audio_input",
             Self::Base(_) => "rdk:component:base",
             Self::Board(_) => "rdk:component:board",
             Self::Button(_) => "rdk:component:button",
             Self::Encoder(_) => "rdk:component:encoder",
+            Self::Gantry(_) => "rdk:component:gantry",
             Self::Generic(_) => "rdk:component:generic",
+            Self::Gripper(_) => "rdk:component:gripper",
+            Self::InputController(_) => "rdk:component:input_controller",
             Self::Motor(_) => "rdk:component:motor",
             Self::MovementSensor(_) => "rdk:component:movement_sensor",
+            Self::PoseTracker(_) => "rdk:component:pose_tracker",
             Self::PowerSensor(_) => "rdk:component:power_sensor",
             Self::Sensor(_) => "rdk:component:sensor",
             Self::Servo(_) => "rdk:component:servo",
             Self::Switch(_) => "rdk:component:switch",
             #[cfg(feature = "camera")]
             Self::Camera(_) => "rdk:component:camera",
+            Self::GenericService(_) => "rdk:service:generic",
         }
         .to_string()
     }
@@ -117,6 +143,9 @@ pub struct LocalRobot {
     // at some point using settimeofday (or something equivalent) and referenced thereof.
     pub(crate) start_time: Instant,
     cloud_metadata: Option<CloudMetadata>,
+    // The configuration the resources were built from, kept around so a new revision can be
+    // diffed against it and applied without restarting
+    config: RobotConfig,
 }
 
 #[derive(Error, Debug)]
@@ -146,6 +175,8 @@ pub enum RobotError {
     #[cfg(feature = "data")]
     #[error(transparent)]
     DataCollectorInitError(#[from] DataCollectionError),
+    #[error("config change requires a restart: {0}")]
+    RobotReconfigureNeedsRestart(String),
 }
 
 impl Default for LocalRobot {
@@ -167,6 +198,7 @@ impl LocalRobot {
             data_manager_sync_task: Default::default(),
             #[cfg(feature = "data")]
             data_collector_configs: Default::default(),
+            config: Default::default(),
         }
     }
     // Inserts components in order of dependency. If a component's dependencies are not satisfied it is
@@ -194,6 +226,17 @@ impl LocalRobot {
             let board = constructor(ConfigType::Dynamic(config))
                 .map_err(|e| RobotError::RobotResourceBuildError(e.into()))?;
             (Some(board), board_key)
+        } else if let Some((name, ResourceType::Board(board))) = self
+            .resources
+            .iter()
+            .find(|(_, r)| matches!(r, ResourceType::Board(_)))
+        {
+            // when reconfiguring, the board that is already running is given to the components
+            let board_key = Some(ResourceKey::new(
+                crate::common::board::COMPONENT_NAME,
+                name.get_name(),
+            ));
+            (Some(board.clone()), board_key)
         } else {
             (None, None)
         };
@@ -233,6 +276,86 @@ impl LocalRobot {
         Ok(())
     }
 
+    // Builds the services of the generic API once all the components they may depend on are built.
+    // Other services (data manager, OTA...) are configured separately and are skipped here. A
+    // service failing to build is logged and doesn't prevent the robot from starting.
+    pub(crate) fn process_services(
+        &mut self,
+        services: &[ServiceConfig],
+        registry: &mut Box<ComponentRegistry>,
+    ) -> Result<(), RobotError> {
+        for svc in services {
+            #[cfg(feature = "ota")]
+            if svc.model == *super::ota::OTA_MODEL_TRIPLET {
+                continue;
+            }
+            let cfg = match DynamicComponentConfig::try_from(svc) {
+                Ok(cfg) => cfg,
+                Err(e) => {
+                    log::error!("Failed to parse config of service `{}`: {:?}", svc.name, e);
+                    continue;
+                }
+            };
+            let r_name = cfg.get_resource_name();
+            if r_name.get_type() != "service"
+                || r_name.get_subtype() != crate::common::generic::COMPONENT_NAME
+            {
+                continue;
+            }
+            if let Err(e) = self.build_service(&cfg, &svc.depends_on, registry) {
+                log::error!(
+                    "Failed to build service `{}` of model `{}`: {:?}",
+                    r_name.get_name(),
+                    svc.model,
+                    e
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn build_service(
+        &mut self,
+        cfg: &DynamicComponentConfig,
+        depends_on: &[String],
+        registry: &mut ComponentRegistry,
+    ) -> Result<(), RobotError> {
+        // the dependencies of a service are the resources named in its `depends_on` field
+        let deps = depends_on
+            .iter()
+            .map(|dep| {
+                self.resources
+                    .iter()
+                    .find(|(name, _)| name.get_name() == dep)
+                    .map(|(name, res)| {
+                        Dependency(
+                            ResourceKey::new(name.get_subtype(), name.get_name()),
+                            res.clone(),
+                        )
+                    })
+                    .ok_or_else(|| {
+                        RobotError::RobotDependencyMissing(
+                            dep.clone(),
+                            cfg.get_resource_name().get_name().to_owned(),
+                        )
+                    })
+            })
+            .collect::<Result<Vec<Dependency>, RobotError>>()?;
+        let ctor = registry
+            .get_service_constructor(
+                cfg.get_resource_name().get_subtype(),
+                cfg.get_model().get_model(),
+            )
+            .map_err(RobotError::RobotRegistryError)?;
+        let service = ctor(ConfigType::Dynamic(cfg), deps)
+            .map_err(|e| RobotError::RobotResourceBuildError(e.into()))?;
+        self.resources.insert(
+            cfg.get_resource_name().clone(),
+            ResourceType::GenericService(service),
+        );
+        Ok(())
+    }
+
     // Creates a robot from the response of a gRPC call to acquire the robot configuration. The individual
     // component configs within the response are consumed and the corresponding components are generated
     // and added to the created robot.
@@ -262,6 +385,7 @@ impl LocalRobot {
             data_manager_sync_task: None,
             data_manager_collection_task: None,
             start_time: Instant::now(),
+            config: config.clone(),
         };
 
         let components: Result<Vec<Option<DynamicComponentConfig>>, AttributeError> = config
@@ -273,30 +397,29 @@ impl LocalRobot {
             components.map_err(RobotError::RobotParseConfigError)?,
             registry,
         )?;
+        robot.process_services(&config.services, registry)?;
 
         // TODO: When cfg's on expressions are valid, remove the outer scope.
         #[cfg(feature = "data")]
         {
             match agent_config.firmware_mode {
-                // TODO(RSDK-8125): Support selection of a DataStore trait other than
-                // DefaultDataStore in a way that is configurable
+                // captured data is persisted when the platform provides a data partition
+                // (see flash_data_store) and kept in RAM otherwise
                 FirmwareMode::Normal => {
-                    match DataManager::<DefaultDataStore>::from_robot_and_config(&robot, config) {
-                        Ok(None) => {}
-                        Ok(Some(mut data_manager)) => {
-                            if let Some(task) = data_manager.get_sync_task(robot.start_time) {
-                                let _ = robot.data_manager_sync_task.insert(Box::new(task));
-                            }
-                            let _ =
-                                robot
-                                    .data_manager_collection_task
-                                    .replace(robot.executor.spawn(async move {
-                                        data_manager.data_collection_task(robot.start_time).await;
-                                    }));
-                        }
-                        Err(err) => {
-                            log::error!("Error configuring data management: {:?}", err);
+                    #[cfg(any(feature = "esp32", feature = "native"))]
+                    let res = match robot.start_data_manager::<PersistentDataStore>(config) {
+                        Err(DataManagerError::StoreError(
+                            DataStoreError::PartitionUnavailable(reason),
+                        )) => {
+                            log::info!("captured data will be kept in RAM: {}", reason);
+                            robot.start_data_manager::<DefaultDataStore>(config)
                         }
+                        res => res,
+                    };
+                    #[cfg(not(any(feature = "esp32", feature = "native")))]
+                    let res = robot.start_data_manager::<DefaultDataStore>(config);
+                    if let Err(err) = res {
+                        log::error!("Error configuring data management: {:?}", err);
                     }
                 }
                 FirmwareMode::DeepSleepBetweenDataSyncs => {
@@ -322,6 +445,183 @@ impl LocalRobot {
         Ok(robot)
     }
 
+    // Applies a new configuration to a running robot without tearing it down. Components are
+    // diffed against the running configuration and only the ones that were added, removed or
+    // changed, along with the components depending on them, are rebuilt. Anything else in the
+    // config (services, cloud, network...) can't be changed in place and returns
+    // `RobotReconfigureNeedsRestart`, as does a change to the board, to a component capturing
+    // data or to a component a service depends on since their handles are shared beyond the
+    // resource map. The new resources are built before the running ones are replaced, when one
+    // can't be built the robot is left untouched and a restart is requested.
+    pub fn reconfigure(
+        &mut self,
+        config: &RobotConfig,
+        registry: &mut Box<ComponentRegistry>,
+        build_time: Option<DateTime<FixedOffset>>,
+    ) -> Result<(), RobotError> {
+        let strip = |cfg: &RobotConfig| RobotConfig {
+            components: vec![],
+            revision: String::new(),
+            ..cfg.clone()
+        };
+        if strip(&self.config) != strip(config) {
+            return Err(RobotError::RobotReconfigureNeedsRestart(
+                "non component configuration changed".to_owned(),
+            ));
+        }
+
+        let running = self
+            .config
+            .components
+            .iter()
+            .map(|c| DynamicComponentConfig::try_from(c).map(|d| (d.name, c)))
+            .collect::<Result<HashMap<ResourceName, &ComponentConfig>, _>>()?;
+        let components = config
+            .components
+            .iter()
+            .map(|c| DynamicComponentConfig::try_from(c).map(|d| (d, c)))
+            .collect::<Result<Vec<(DynamicComponentConfig, &ComponentConfig)>, _>>()?;
+
+        let mut dirty: HashSet<ResourceName> = running
+            .keys()
+            .filter(|name| {
+                !components
+                    .iter()
+                    .any(|(d, _)| d.get_resource_name() == *name)
+            })
+            .cloned()
+            .collect();
+        dirty.extend(
+            components
+                .iter()
+                .filter(|(d, c)| running.get(d.get_resource_name()) != Some(c))
+                .map(|(d, _)| d.get_resource_name().clone()),
+        );
+
+        // a component holds its dependencies, so it has to be rebuilt when one of them is
+        loop {
+            let dependents: Vec<ResourceName> = components
+                .iter()
+                .map(|(d, _)| d)
+                .filter(|d| !dirty.contains(d.get_resource_name()))
+                .filter(|d| {
+                    registry
+                        .get_dependency_function(
+                            d.get_resource_name().get_subtype(),
+                            d.get_model().get_model(),
+                        )
+                        .map_or(Vec::new(), |dep_fn| dep_fn(ConfigType::Dynamic(d)))
+                        .into_iter()
+                        .any(|key| dirty.contains(&ResourceName::new_builtin(key.1, key.0)))
+                })
+                .map(|d| d.get_resource_name().clone())
+                .collect();
+            if dependents.is_empty() {
+                break;
+            }
+            dirty.extend(dependents);
+        }
+
+        if let Some(board) = dirty.iter().find(|name| name.get_subtype() == "board") {
+            return Err(RobotError::RobotReconfigureNeedsRestart(format!(
+                "board {} changed",
+                board.get_name()
+            )));
+        }
+        if let Some(svc) = self.config.services.iter().find(|svc| {
+            svc.depends_on
+                .iter()
+                .any(|dep| dirty.iter().any(|name| name.get_name() == dep))
+        }) {
+            return Err(RobotError::RobotReconfigureNeedsRestart(format!(
+                "service {} depends on a changed component",
+                svc.name
+            )));
+        }
+        #[cfg(feature = "data")]
+        if let Some((name, _)) = self
+            .data_collector_configs
+            .iter()
+            .find(|(name, _)| dirty.contains(name))
+        {
+            return Err(RobotError::RobotReconfigureNeedsRestart(format!(
+                "data is captured from {}",
+                name.get_name()
+            )));
+        }
+        #[cfg(feature = "data")]
+        if let Some((d, _)) = components.iter().find(|(d, _)| {
+            dirty.contains(d.get_resource_name())
+                && d.data_collector_configs.iter().any(|cfg| !cfg.disabled)
+        }) {
+            return Err(RobotError::RobotReconfigureNeedsRestart(format!(
+                "data is captured from {}",
+                d.get_resource_name().get_name()
+            )));
+        }
+
+        // a component may not be buildable while its previous instance runs (e.g. it claims a
+        // peripheral exclusively), restarting applies it then
+        let mut staged = LocalRobot::new();
+        staged.resources = self
+            .resources
+            .iter()
+            .filter(|(name, _)| !dirty.contains(*name))
+            .map(|(name, resource)| (name.clone(), resource.clone()))
+            .collect();
+        let (to_build, to_build_names): (Vec<_>, Vec<_>) = components
+            .into_iter()
+            .filter(|(d, _)| dirty.contains(d.get_resource_name()))
+            .map(|(d, _)| {
+                let name = d.get_resource_name().clone();
+                (Some(d), name)
+            })
+            .unzip();
+        staged.process_components(to_build, registry)?;
+        if let Some(name) = to_build_names
+            .iter()
+            .find(|name| !staged.resources.contains_key(*name))
+        {
+            return Err(RobotError::RobotReconfigureNeedsRestart(format!(
+                "{} couldn't be built",
+                name.get_name()
+            )));
+        }
+
+        for name in dirty.iter() {
+            if let Some(resource) = self.resources.remove(name) {
+                log::info!(
+                    "removing resource `{}` of type `{}`",
+                    name.get_name(),
+                    name.get_subtype()
+                );
+                let stopped = match resource {
+                    ResourceType::Base(mut b) => b.stop(),
+                    ResourceType::Motor(mut m) => m.stop(),
+                    ResourceType::Arm(mut a) => a.stop(),
+                    ResourceType::Gripper(mut g) => g.stop(),
+                    ResourceType::Gantry(mut g) => g.stop(),
+                    _ => Ok(()),
+                };
+                if let Err(e) = stopped {
+                    log::error!("failed to stop `{}`: {:?}", name.get_name(), e);
+                }
+            }
+        }
+
+        for name in to_build_names {
+            if let Some(resource) = staged.resources.remove(&name) {
+                let _ = self.resources.insert(name, resource);
+            }
+        }
+
+        self.config = config.clone();
+        if build_time.is_some() {
+            self.build_time = build_time;
+        }
+        Ok(())
+    }
+
     fn build_resource(
         &mut self,
         config: &DynamicComponentConfig,
@@ -483,6 +783,54 @@ impl LocalRobot {
                     ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
                 )
             }
+            "arm" => {
+                let ctor = registry
+                    .get_arm_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::Arm(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
+            "gripper" => {
+                let ctor = registry
+                    .get_gripper_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::Gripper(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
+            "gantry" => {
+                let ctor = registry
+                    .get_gantry_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::Gantry(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
+            "input_controller" => {
+                let ctor = registry
+                    .get_input_controller_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::InputController(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
+            "pose_tracker" => {
+                let ctor = registry
+                    .get_pose_tracker_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::PoseTracker(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
+            "audio_input" => {
+                let ctor = registry
+                    .get_audio_input_constructor(&model)
+                    .map_err(RobotError::RobotRegistryError)?;
+                ResourceType::AudioInput(
+                    ctor(cfg, deps).map_err(|e| RobotError::RobotResourceBuildError(e.into()))?,
+                )
+            }
             "generic" => {
                 let ctor = registry
                     .get_generic_component_constructor(&model)
@@ -511,15 +859,40 @@ impl LocalRobot {
                     r_name.get_type().to_owned(),
                 )
             })?;
-            res.push(DataCollector::from_config(
-                r_name.get_name().to_owned(),
-                resource.clone(),
-                conf,
-            )?);
+            let mut collector =
+                DataCollector::from_config(r_name.get_name().to_owned(), resource.clone(), conf)?;
+            if let Some(CaptureCondition::Trigger { board, .. }) = &conf.capture_condition {
+                let board = self.get_board_by_name(board.clone()).ok_or_else(|| {
+                    RobotError::ResourceNotFound(board.clone(), "board".to_owned())
+                })?;
+                collector = collector.with_trigger_board(board);
+            }
+            res.push(collector);
         }
         Ok(res)
     }
 
+    #[cfg(feature = "data")]
+    fn start_data_manager<StoreType: DataStore + 'static>(
+        &mut self,
+        config: &RobotConfig,
+    ) -> Result<(), DataManagerError> {
+        if let Some(mut data_manager) =
+            DataManager::<StoreType>::from_robot_and_config(self, config)?
+        {
+            if let Some(task) = data_manager.get_sync_task(self.start_time) {
+                let _ = self.data_manager_sync_task.insert(Box::new(task));
+            }
+            let start_time = self.start_time;
+            let _ = self
+                .data_manager_collection_task
+                .replace(self.executor.spawn(async move {
+                    data_manager.data_collection_task(start_time).await;
+                }));
+        }
+        Ok(())
+    }
+
     pub fn get_periodic_app_client_tasks(&mut self) -> Vec<Box<dyn PeriodicAppClientTask>> {
         #[allow(unused_mut)]
         let mut tasks = Vec::<Box<dyn PeriodicAppClientTask>>::new();
@@ -533,13 +906,56 @@ impl LocalRobot {
     }
 
     pub fn get_resource_names(&self) -> Result<Vec<common::v1::ResourceName>, RobotError> {
+        let sensors_service = ResourceName::new_builtin_service(
+            SENSORS_SERVICE_NAME.to_owned(),
+            "sensors".to_owned(),
+        );
+        // a sensors service configured with the same name is already listed
+        let configured = self.resources.keys().any(|name| {
+            name.get_type() == "service"
+                && name.get_subtype() == "sensors"
+                && name.get_name() == SENSORS_SERVICE_NAME
+        });
         let names = self
             .resources
             .keys()
             .map(ResourceName::to_proto_resource_name)
+            .chain((!configured).then(|| sensors_service.to_proto_resource_name()))
             .collect();
         Ok(names)
     }
+
+    /// Returns the names of the resources served by the sensors service, that is every
+    /// sensor, movement sensor and power sensor
+    pub fn get_sensor_names(&self) -> Vec<common::v1::ResourceName> {
+        self.resources
+            .iter()
+            .filter(|(_, r)| {
+                matches!(
+                    r,
+                    ResourceType::Sensor(_)
+                        | ResourceType::MovementSensor(_)
+                        | ResourceType::PowerSensor(_)
+                )
+            })
+            .map(|(name, _)| name.to_proto_resource_name())
+            .collect()
+    }
+
+    /// Returns the sensor, movement sensor or power sensor designated by `name`
+    pub fn get_readings_by_name(
+        &self,
+        name: &common::v1::ResourceName,
+    ) -> Option<Box<dyn Readings>> {
+        let name = ResourceName::new_builtin(name.name.clone(), name.subtype.clone());
+        match self.resources.get(&name) {
+            Some(ResourceType::Sensor(r)) => Some(Box::new(r.clone())),
+            Some(ResourceType::MovementSensor(r)) => Some(Box::new(r.clone())),
+            Some(ResourceType::PowerSensor(r)) => Some(Box::new(r.clone())),
+            Some(_) => None,
+            None => None,
+        }
+    }
     pub fn get_motor_by_name(&self, name: String) -> Option<Arc<Mutex<dyn Motor>>> {
         let name = ResourceName::new_builtin(name, "motor".to_owned());
         match self.resources.get(&name) {
@@ -639,6 +1055,63 @@ impl LocalRobot {
         }
     }
 
+    pub fn get_arm_by_name(&self, name: String) -> Option<Arc<Mutex<dyn Arm>>> {
+        let name = ResourceName::new_builtin(name, "arm".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::Arm(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
+    pub fn get_gripper_by_name(&self, name: String) -> Option<Arc<Mutex<dyn Gripper>>> {
+        let name = ResourceName::new_builtin(name, "gripper".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::Gripper(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
+    pub fn get_gantry_by_name(&self, name: String) -> Option<Arc<Mutex<dyn Gantry>>> {
+        let name = ResourceName::new_builtin(name, "gantry".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::Gantry(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
+    pub fn get_input_controller_by_name(
+        &self,
+        name: String,
+    ) -> Option<Arc<Mutex<dyn InputController>>> {
+        let name = ResourceName::new_builtin(name, "input_controller".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::InputController(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
+    pub fn get_pose_tracker_by_name(&self, name: String) -> Option<Arc<Mutex<dyn PoseTracker>>> {
+        let name = ResourceName::new_builtin(name, "pose_tracker".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::PoseTracker(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
+    pub fn get_audio_input_by_name(&self, name: String) -> Option<Arc<Mutex<dyn AudioInput>>> {
+        let name = ResourceName::new_builtin(name, "audio_input".to_owned());
+        match self.resources.get(&name) {
+            Some(ResourceType::AudioInput(r)) => Some(r.clone()),
+            Some(_) => None,
+            None => None,
+        }
+    }
+
     pub fn get_generic_component_by_name(
         &self,
         name: String,
@@ -651,6 +1124,22 @@ impl LocalRobot {
         }
     }
 
+    pub fn get_generic_service_by_name(
+        &self,
+        name: String,
+    ) -> Option<Arc<Mutex<dyn GenericService>>> {
+        // the namespace of a service comes from its config, only its name and api are matched
+        self.resources.iter().find_map(|(r_name, res)| match res {
+            ResourceType::GenericService(r)
+                if r_name.get_name() == name
+                    && r_name.get_subtype() == crate::common::generic::COMPONENT_NAME =>
+            {
+                Some(r.clone())
+            }
+            _ => None,
+        })
+    }
+
     pub fn stop_all(&mut self) -> Result<(), RobotError> {
         let mut stop_errors: Vec<ActuatorError> = vec![];
         for resource in self.resources.values_mut() {
@@ -671,6 +1160,21 @@ impl LocalRobot {
                         }
                     };
                 }
+                ResourceType::Arm(a) => {
+                    if let Err(err) = a.stop() {
+                        stop_errors.push(err);
+                    }
+                }
+                ResourceType::Gripper(g) => {
+                    if let Err(err) = g.stop() {
+                        stop_errors.push(err);
+                    }
+                }
+                ResourceType::Gantry(g) => {
+                    if let Err(err) = g.stop() {
+                        stop_errors.push(err);
+                    }
+                }
                 _ => continue,
             }
         }
@@ -707,7 +1211,10 @@ impl Drop for LocalRobot {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
 
     use crate::{
         common::{
@@ -716,15 +1223,16 @@ mod tests {
             config::{AgentConfig, DynamicComponentConfig, Kind, Model, ResourceName},
             encoder::{Encoder, EncoderPositionType},
             exec::Executor,
+            generic::FakeGenericComponent,
             i2c::I2CHandle,
             motor::Motor,
             movement_sensor::MovementSensor,
-            robot::LocalRobot,
+            robot::{LocalRobot, ResourceType, RobotError},
             sensor::Readings,
             system::FirmwareMode,
         },
         google::{self, protobuf::Struct},
-        proto::app::v1::{ComponentConfig, RobotConfig},
+        proto::app::v1::{ComponentConfig, RobotConfig, ServiceConfig},
     };
 
     #[cfg(feature = "data")]
@@ -1056,6 +1564,22 @@ mod tests {
         assert_eq!(lin_acc.y, -100.3);
         assert_eq!(lin_acc.z, 100.4);
 
+        let sensor_names = robot.get_sensor_names();
+        assert_eq!(
+            sensor_names.len(),
+            if cfg!(feature = "data") { 3 } else { 2 }
+        );
+        let m_sensor_name = sensor_names.iter().find(|n| n.name == "m_sensor").unwrap();
+        assert_eq!(m_sensor_name.subtype, "movement_sensor");
+        let readings = robot
+            .get_readings_by_name(m_sensor_name)
+            .unwrap()
+            .get_generic_readings();
+        assert!(readings.is_ok());
+        let motor_name = ResourceName::new_builtin("motor".to_owned(), "motor".to_owned())
+            .to_proto_resource_name();
+        assert!(robot.get_readings_by_name(&motor_name).is_none());
+
         let mut enc1 = robot.get_encoder_by_name("enc1".to_string());
         assert!(enc1.is_some());
 
@@ -1398,4 +1922,265 @@ mod tests {
 
         assert!(enc.is_some());
     }
+
+    #[test_log::test]
+    fn test_reconfigure() {
+        let encoder = |name: &str, deg: f64| ComponentConfig {
+            name: name.to_string(),
+            model: "rdk:builtin:fake".to_string(),
+            api: "rdk:component:encoder".to_string(),
+            attributes: Some(Struct {
+                fields: HashMap::from([(
+                    "fake_deg".to_string(),
+                    google::protobuf::Value {
+                        kind: Some(google::protobuf::value::Kind::NumberValue(deg)),
+                    },
+                )]),
+            }),
+            ..Default::default()
+        };
+        let motor = |name: &str, encoder: &str| ComponentConfig {
+            name: name.to_string(),
+            model: "rdk:builtin:fake_with_dep".to_string(),
+            api: "rdk:component:motor".to_string(),
+            attributes: Some(Struct {
+                fields: HashMap::from([(
+                    "encoder".to_string(),
+                    google::protobuf::Value {
+                        kind: Some(google::protobuf::value::Kind::StringValue(
+                            encoder.to_string(),
+                        )),
+                    },
+                )]),
+            }),
+            ..Default::default()
+        };
+
+        let robot_cfg = RobotConfig {
+            components: vec![
+                encoder("enc1", 90.0),
+                motor("m1", "enc1"),
+                encoder("enc2", 180.0),
+                motor("m2", "enc2"),
+                encoder("enc3", 270.0),
+            ],
+            revision: "1".to_string(),
+            ..Default::default()
+        };
+
+        let mut registry = Box::default();
+        let robot = LocalRobot::from_cloud_config(
+            Executor::new(),
+            "".to_string(),
+            &robot_cfg,
+            &mut registry,
+            None,
+            &AgentConfig::default(),
+        );
+        assert!(robot.is_ok());
+        let mut robot = robot.unwrap();
+
+        let m2 = robot.get_motor_by_name("m2".to_string()).unwrap();
+        assert_eq!(
+            robot
+                .get_motor_by_name("m1".to_string())
+                .unwrap()
+                .get_position()
+                .unwrap(),
+            90
+        );
+
+        // enc1 changes so m1 depending on it is rebuilt, enc3 is removed and enc4 added
+        let new_cfg = RobotConfig {
+            components: vec![
+                encoder("enc1", 45.0),
+                motor("m1", "enc1"),
+                encoder("enc2", 180.0),
+                motor("m2", "enc2"),
+                encoder("enc4", 10.0),
+            ],
+            revision: "2".to_string(),
+            ..Default::default()
+        };
+        assert!(robot.reconfigure(&new_cfg, &mut registry, None).is_ok());
+
+        assert_eq!(
+            robot
+                .get_motor_by_name("m1".to_string())
+                .unwrap()
+                .get_position()
+                .unwrap(),
+            45
+        );
+        // untouched resources are kept as is
+        assert!(Arc::ptr_eq(
+            &m2,
+            &robot.get_motor_by_name("m2".to_string()).unwrap()
+        ));
+        assert!(robot.get_encoder_by_name("enc3".to_string()).is_none());
+        assert!(robot.get_encoder_by_name("enc4".to_string()).is_some());
+        assert_eq!(robot.get_resource_names().unwrap().len(), 6);
+
+        // a component that can't be built leaves the running one in place
+        let m1 = robot.get_motor_by_name("m1".to_string()).unwrap();
+        let broken_cfg = RobotConfig {
+            components: vec![
+                encoder("enc1", 45.0),
+                motor("m1", "missing"),
+                encoder("enc2", 180.0),
+                motor("m2", "enc2"),
+                encoder("enc4", 10.0),
+            ],
+            revision: "3".to_string(),
+            ..Default::default()
+        };
+        assert!(matches!(
+            robot.reconfigure(&broken_cfg, &mut registry, None),
+            Err(RobotError::RobotReconfigureNeedsRestart(_))
+        ));
+        assert!(Arc::ptr_eq(
+            &m1,
+            &robot.get_motor_by_name("m1".to_string()).unwrap()
+        ));
+        assert_eq!(m1.lock().unwrap().get_position().unwrap(), 45);
+
+        // services can't be changed in place
+        let new_cfg = RobotConfig {
+            services: vec![ServiceConfig {
+                name: "svc".to_string(),
+                ..Default::default()
+            }],
+            revision: "4".to_string(),
+            ..new_cfg
+        };
+        assert!(matches!(
+            robot.reconfigure(&new_cfg, &mut registry, None),
+            Err(RobotError::RobotReconfigureNeedsRestart(_))
+        ));
+        assert!(robot.get_encoder_by_name("enc4".to_string()).is_some());
+    }
+
+    #[test_log::test]
+    fn test_services_from_cloud_config() {
+        use crate::common::generic::DoCommand;
+
+        let robot_cfg = RobotConfig {
+            components: vec![ComponentConfig {
+                name: "enc1".to_string(),
+                model: "rdk:builtin:fake".to_string(),
+                api: "rdk:component:encoder".to_string(),
+                ..Default::default()
+            }],
+            services: vec![
+                ServiceConfig {
+                    name: "svc1".to_string(),
+                    model: "rdk:builtin:fake".to_string(),
+                    api: "rdk:service:generic".to_string(),
+                    depends_on: vec!["enc1".to_string()],
+                    ..Default::default()
+                },
+                // a missing dependency only prevents this service from being built
+                ServiceConfig {
+                    name: "svc2".to_string(),
+                    model: "rdk:builtin:fake".to_string(),
+                    api: "rdk:service:generic".to_string(),
+                    depends_on: vec!["enc2".to_string()],
+                    ..Default::default()
+                },
+                ServiceConfig {
+                    name: "svc3".to_string(),
+                    model: "rdk:builtin:unknown".to_string(),
+                    api: "rdk:service:generic".to_string(),
+                    ..Default::default()
+                },
+                // the namespace of the api is kept as configured
+                ServiceConfig {
+                    name: "svc4".to_string(),
+                    model: "rdk:builtin:fake".to_string(),
+                    api: "acme:service:generic".to_string(),
+                    ..Default::default()
+                },
+                ServiceConfig {
+                    name: "dm".to_string(),
+                    model: "rdk:builtin:builtin".to_string(),
+                    r#type: "data_manager".to_string(),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        let robot = LocalRobot::from_cloud_config(
+            Executor::new(),
+            "".to_string(),
+            &robot_cfg,
+            &mut Box::default(),
+            None,
+            &AgentConfig::default(),
+        );
+        assert!(robot.is_ok());
+        let robot = robot.unwrap();
+
+        let svc = robot.get_generic_service_by_name("svc1".to_string());
+        assert!(svc.is_some());
+        let res = svc.unwrap().do_command(Some(Struct {
+            fields: HashMap::from([("ping".to_string(), google::protobuf::Value { kind: None })]),
+        }));
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap().unwrap().fields.get("ping").unwrap().kind,
+            Some(google::protobuf::value::Kind::StringValue(
+                "pinged".to_string()
+            ))
+        );
+        assert!(robot
+            .get_generic_service_by_name("svc2".to_string())
+            .is_none());
+        assert!(robot
+            .get_generic_service_by_name("svc3".to_string())
+            .is_none());
+        assert!(robot
+            .get_generic_service_by_name("svc4".to_string())
+            .is_some());
+        // a service isn't mistaken for a generic component of the same name
+        assert!(robot
+            .get_generic_component_by_name("svc1".to_string())
+            .is_none());
+
+        let names = robot.get_resource_names().unwrap();
+        // the sensors service is always served
+        assert_eq!(names.len(), 4);
+        assert!(names.iter().any(|n| n.name == "svc1"
+            && n.namespace == "rdk"
+            && n.r#type == "service"
+            && n.subtype == "generic"));
+        assert!(names.iter().any(|n| n.name == "svc4"
+            && n.namespace == "acme"
+            && n.r#type == "service"
+            && n.subtype == "generic"));
+    }
+
+    #[test_log::test]
+    fn test_sensors_service_listed_once() {
+        let sensors_service = |names: &[crate::proto::common::v1::ResourceName]| {
+            names
+                .iter()
+                .filter(|n| n.r#type == "service" && n.subtype == "sensors")
+                .count()
+        };
+        let mut robot = LocalRobot::default();
+        assert_eq!(sensors_service(&robot.get_resource_names().unwrap()), 1);
+
+        // a sensors service of the same name provided by the configuration isn't duplicated
+        let _ = robot.resources.insert(
+            ResourceName::new_builtin_service(
+                super::SENSORS_SERVICE_NAME.to_owned(),
+                "sensors".to_owned(),
+            ),
+            ResourceType::GenericService(Arc::new(Mutex::new(FakeGenericComponent {}))),
+        );
+        let names = robot.get_resource_names().unwrap();
+        assert_eq!(names.len(), 1);
+        assert_eq!(sensors_service(&names), 1);
+    }
 }
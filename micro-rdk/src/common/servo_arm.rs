@@ -0,0 +1,248 @@
+//! An arm made of a chain of servos (such as `gpio` servos), one servo per joint.
+//!
+//! The servos are listed in the `servos` attribute, ordered from the base of the arm to
+//! the end effector. Joint positions are the angles of the servos in degrees, the arm has
+//! no kinematic model so the end position cannot be queried or commanded.
+//!
+//! Every joint accepts angles between `min_angle_deg` and `max_angle_deg` (0 and 180 by
+//! default). Servos don't report when they reach their position, the time a movement takes
+//! is estimated from `speed_degs_per_sec` (300 by default).
+
+use super::{
+    actuator::{Actuator, ActuatorError},
+    arm::{Arm, ArmError, ArmType, COMPONENT_NAME as ArmCompName},
+    config::ConfigType,
+    registry::{ComponentRegistry, Dependency, ResourceKey},
+    robot::Resource,
+    servo::{Servo, ServoType, COMPONENT_NAME as ServoCompName},
+};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+static DEFAULT_ANGLE_LIMITS_DEG: (u32, u32) = (0, 180);
+static DEFAULT_SPEED_DEGS_PER_SEC: f64 = 300.0;
+
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_arm("servo_chain", &ServoArm::<ServoType>::from_config)
+        .is_err()
+    {
+        log::error!("servo_chain model is already registered")
+    }
+    if registry
+        .register_dependency_getter(
+            ArmCompName,
+            "servo_chain",
+            &ServoArm::<ServoType>::dependencies_from_config,
+        )
+        .is_err()
+    {
+        log::error!("failed to register dependency getter for servo_chain model")
+    }
+}
+
+#[derive(DoCommand)]
+pub struct ServoArm<S> {
+    joints: Vec<S>,
+    angle_limits_deg: (u32, u32),
+    speed_degs_per_sec: f64,
+}
+
+impl<S> ServoArm<S>
+where
+    S: Servo,
+{
+    pub fn new(joints: Vec<S>) -> Self {
+        Self {
+            joints,
+            angle_limits_deg: DEFAULT_ANGLE_LIMITS_DEG,
+            speed_degs_per_sec: DEFAULT_SPEED_DEGS_PER_SEC,
+        }
+    }
+
+    pub(crate) fn from_config(cfg: ConfigType, deps: Vec<Dependency>) -> Result<ArmType, ArmError> {
+        let names = cfg.get_attribute::<Vec<String>>("servos")?;
+        if names.is_empty() {
+            return Err(ArmError::ArmConfigError("servos cannot be empty"));
+        }
+        let mut servos: Vec<(String, ServoType)> = deps
+            .into_iter()
+            .filter_map(|Dependency(key, res)| match res {
+                Resource::Servo(servo) => Some((key.1, servo)),
+                _ => None,
+            })
+            .collect();
+        let joints = names
+            .iter()
+            .map(|name| {
+                servos
+                    .iter()
+                    .position(|(key, _)| key == name)
+                    .map(|idx| servos.swap_remove(idx).1)
+                    .ok_or(ArmError::ArmConfigError("servo couldn't be found"))
+            })
+            .collect::<Result<Vec<ServoType>, ArmError>>()?;
+        let mut arm = ServoArm::new(joints);
+        if let Ok(min_angle_deg) = cfg.get_attribute::<u32>("min_angle_deg") {
+            arm.angle_limits_deg.0 = min_angle_deg;
+        }
+        if let Ok(max_angle_deg) = cfg.get_attribute::<u32>("max_angle_deg") {
+            arm.angle_limits_deg.1 = max_angle_deg;
+        }
+        if arm.angle_limits_deg.0 > arm.angle_limits_deg.1 {
+            return Err(ArmError::ArmConfigError(
+                "min_angle_deg cannot be greater than max_angle_deg",
+            ));
+        }
+        if let Ok(speed) = cfg.get_attribute::<f64>("speed_degs_per_sec") {
+            if !(speed > 0.0 && speed.is_finite()) {
+                return Err(ArmError::ArmConfigError(
+                    "speed_degs_per_sec must be positive",
+                ));
+            }
+            arm.speed_degs_per_sec = speed;
+        }
+        Ok(Arc::new(Mutex::new(arm)))
+    }
+
+    pub(crate) fn dependencies_from_config(cfg: ConfigType) -> Vec<ResourceKey> {
+        cfg.get_attribute::<Vec<String>>("servos")
+            .unwrap_or_default()
+            .into_iter()
+            .map(|name| ResourceKey::new(ServoCompName, name))
+            .collect()
+    }
+}
+
+impl<S> Arm for ServoArm<S>
+where
+    S: Servo,
+{
+    fn get_joint_positions(&mut self) -> Result<Vec<f64>, ArmError> {
+        self.joints
+            .iter_mut()
+            .map(|joint| Ok(joint.get_position()? as f64))
+            .collect()
+    }
+
+    fn check_joint_positions(&self, positions: &[f64]) -> Result<(), ArmError> {
+        if positions.len() != self.joints.len() {
+            return Err(ArmError::ArmJointCountMismatch(
+                self.joints.len(),
+                positions.len(),
+            ));
+        }
+        let (min, max) = self.angle_limits_deg;
+        if positions
+            .iter()
+            .any(|p| !(min as f64..=max as f64).contains(&p.round()))
+        {
+            return Err(ArmError::ArmInvalidArgument(
+                "servo joint positions must be within the joints' angle limits",
+            ));
+        }
+        Ok(())
+    }
+
+    fn move_to_joint_positions(
+        &mut self,
+        positions: Vec<f64>,
+    ) -> Result<Option<Duration>, ArmError> {
+        self.check_joint_positions(&positions)?;
+        let mut travel_deg: u32 = 0;
+        for (joint, position) in self.joints.iter_mut().zip(positions) {
+            let position = position.round() as u32;
+            travel_deg = travel_deg.max(joint.get_position()?.abs_diff(position));
+            joint.move_to(position)?;
+        }
+        Ok(Some(Duration::from_secs_f64(
+            travel_deg as f64 / self.speed_degs_per_sec,
+        )))
+    }
+}
+
+impl<S> Actuator for ServoArm<S>
+where
+    S: Servo,
+{
+    fn is_moving(&mut self) -> Result<bool, ActuatorError> {
+        for joint in self.joints.iter_mut() {
+            if joint.is_moving()? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+    fn stop(&mut self) -> Result<(), ActuatorError> {
+        for joint in self.joints.iter_mut() {
+            joint.stop()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use super::ServoArm;
+    use crate::common::{
+        arm::{move_through_joint_positions, Arm, ArmError, ArmType},
+        servo::{FakeServo, ServoType},
+    };
+
+    #[test_log::test]
+    fn test_servo_arm() {
+        let base: ServoType = Arc::new(Mutex::new(FakeServo::new()));
+        let elbow: ServoType = Arc::new(Mutex::new(FakeServo::new()));
+        let mut arm = ServoArm::new(vec![base.clone(), elbow.clone()]);
+
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![0.0, 0.0]);
+        // the travel time is the one of the joint moving the most
+        assert_eq!(
+            arm.move_to_joint_positions(vec![45.2, 90.0]).unwrap(),
+            Some(Duration::from_secs_f64(90.0 / 300.0))
+        );
+        assert_eq!(base.lock().unwrap().get_position().unwrap(), 45);
+        assert_eq!(elbow.lock().unwrap().get_position().unwrap(), 90);
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![45.0, 90.0]);
+
+        // no joint moves when any of the positions is invalid
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![10.0]),
+            Err(ArmError::ArmJointCountMismatch(2, 1))
+        ));
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![10.0, -10.0]),
+            Err(ArmError::ArmInvalidArgument(_))
+        ));
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![10.0, 200.0]),
+            Err(ArmError::ArmInvalidArgument(_))
+        ));
+        assert!(matches!(
+            arm.move_to_joint_positions(vec![10.0, f64::NAN]),
+            Err(ArmError::ArmInvalidArgument(_))
+        ));
+        assert_eq!(arm.get_joint_positions().unwrap(), vec![45.0, 90.0]);
+        assert!(matches!(
+            arm.get_end_position(),
+            Err(ArmError::ArmMethodUnimplemented(_))
+        ));
+
+        // each set of positions is reached before the arm moves to the next one
+        let arm: ArmType = Arc::new(Mutex::new(arm));
+        let start = Instant::now();
+        assert!(async_io::block_on(move_through_joint_positions(
+            arm.clone(),
+            vec![vec![15.0, 30.0], vec![45.0, 30.0]]
+        ))
+        .is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(300));
+        assert_eq!(
+            arm.lock().unwrap().get_joint_positions().unwrap(),
+            vec![45.0, 30.0]
+        );
+    }
+}
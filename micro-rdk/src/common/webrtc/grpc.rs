@@ -2,15 +2,21 @@
 #![allow(clippy::read_zero_byte_vec)]
 use std::{
     collections::HashMap,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
 };
 
 use bytes::{Bytes, BytesMut};
-use futures_lite::AsyncReadExt;
+use futures_lite::{AsyncReadExt, Future, StreamExt};
+use futures_util::{
+    future::{AbortHandle, Abortable},
+    stream::FuturesUnordered,
+    FutureExt,
+};
 use prost::Message;
+use std::pin::Pin;
 
 use crate::{
-    common::grpc::{GrpcResponse, ServerError},
+    common::grpc::{GrpcError, GrpcResponse, ServerError, StreamPoller, UnaryRpcFuture},
     google::rpc::Status,
     proto::rpc::webrtc::{
         self,
@@ -63,29 +69,55 @@ impl GrpcResponse for WebRtcGrpcBody {
     }
 }
 
-#[derive(Debug)]
 struct RpcCall(
     webrtc::v1::RequestHeaders,
     Option<Instant>,
     Option<RequestMessage>,
+    /// when a server streaming call was last polled
+    Option<SystemTime>,
+    /// cancels the unary call still waiting for its response
+    Option<AbortHandle>,
+    /// produces the messages of a server streaming call, built on its first poll
+    Option<StreamPoller>,
 );
 
+/// A unary call whose handler is still waiting, resolves to the stream id it answers.
+type PendingRpc = Pin<Box<dyn Future<Output = (u32, Result<Bytes, ServerError>)>>>;
+
+enum RpcOutcome {
+    /// the call is answered with the status, server streaming calls are polled again at the
+    /// instant
+    Done(Status, Option<Instant>),
+    /// the response is sent once the pending unary call completes, the handle cancels it
+    Pending(AbortHandle),
+}
+
+enum NextRpc {
+    Call(u32),
+    Completed(u32, Result<Bytes, ServerError>),
+}
+
 pub struct WebRtcGrpcServer<S> {
     service: S,
     channel: Channel,
     stream: Option<webrtc::v1::Stream>,
     headers: Option<RequestHeaders>,
     streams: HashMap<u32, RpcCall>,
+    pending: FuturesUnordered<PendingRpc>,
     buffer: BytesMut,
 }
 
 pub trait WebRtcGrpcService {
-    fn unary_rpc(&mut self, method: &str, data: &Bytes) -> Result<Bytes, ServerError>;
+    fn unary_rpc(&mut self, method: &str, data: &Bytes) -> UnaryRpcFuture;
+    /// Whether `method` is a server streaming RPC, served by `server_stream_rpc`
+    fn is_server_stream_rpc(&self, method: &str) -> bool;
+    /// Returns the poller producing the messages of a server streaming call, it's kept with
+    /// the call and polled until the stream ends
     fn server_stream_rpc(
         &mut self,
         method: &str,
         data: &Bytes,
-    ) -> Result<(Bytes, Instant), ServerError>;
+    ) -> Result<StreamPoller, ServerError>;
 }
 
 impl<S> WebRtcGrpcServer<S>
@@ -99,6 +131,7 @@ where
             stream: None,
             headers: None,
             streams: HashMap::new(),
+            pending: FuturesUnordered::new(),
             buffer: BytesMut::zeroed(WEBRTC_GRPC_BUFFER_SIZE),
         }
     }
@@ -113,32 +146,49 @@ where
         self.buffer.unsplit(b);
         Ok(())
     }
+    /// Returns `RpcOutcome::Pending` when the response will only be available once a pending
+    /// unary call completes, the call is then polled by `next_request` alongside incoming
+    /// requests. Server streaming calls are polled at `now` with `poller`, built on the first
+    /// poll, `since` being their previous poll.
     async fn process_rpc_request(
         &mut self,
         stream: Stream,
         msg: &RequestMessage,
         hdr: &RequestHeaders,
-    ) -> Result<(Status, Option<Instant>), WebRtcError> {
+        since: Option<SystemTime>,
+        now: SystemTime,
+        poller: &mut Option<StreamPoller>,
+    ) -> Result<RpcOutcome, WebRtcError> {
         let method = &hdr.method;
         log::debug!("processing req {:?}", method);
         let ret = if let Some(pkt) = msg.packet_message.as_ref() {
-            if method.contains("Stream") {
-                match self.service.server_stream_rpc(method, &pkt.data) {
-                    Ok(data) => {
-                        self.send_rpc_response(data.0, stream).await?;
+            if self.service.is_server_stream_rpc(method) {
+                let polled = match poller {
+                    Some(poller) => poller(since, now),
+                    None => self
+                        .service
+                        .server_stream_rpc(method, &pkt.data)
+                        .and_then(|p| poller.insert(p)(since, now)),
+                };
+                match polled {
+                    Ok((messages, next)) => {
+                        for data in messages {
+                            self.send_rpc_response(data, stream.clone()).await?;
+                        }
                         (
                             Status {
                                 code: 0,
                                 ..Default::default()
                             },
-                            Some(data.1),
+                            Some(next),
                         )
                     }
                     Err(e) => (e.to_status(), None),
                 }
             } else {
-                match self.service.unary_rpc(method, &pkt.data) {
-                    Ok(data) => {
+                let mut fut = self.service.unary_rpc(method, &pkt.data);
+                match (&mut fut).now_or_never() {
+                    Some(Ok(data)) => {
                         self.send_rpc_response(data, stream).await?;
                         (
                             Status {
@@ -148,7 +198,22 @@ where
                             None,
                         )
                     }
-                    Err(e) => (e.to_status(), None),
+                    Some(Err(e)) => (e.to_status(), None),
+                    None => {
+                        let id = stream.id as u32;
+                        let (abort, registration) = AbortHandle::new_pair();
+                        let fut = Abortable::new(fut, registration);
+                        // an aborted call's stream is gone, the error is never sent
+                        self.pending.push(Box::pin(async move {
+                            (
+                                id,
+                                fut.await.unwrap_or_else(|_| {
+                                    Err(ServerError::from(GrpcError::RpcCanceled))
+                                }),
+                            )
+                        }));
+                        return Ok(RpcOutcome::Pending(abort));
+                    }
                 }
             }
         } else {
@@ -160,7 +225,7 @@ where
                 None,
             )
         };
-        Ok(ret)
+        Ok(RpcOutcome::Done(ret.0, ret.1))
     }
     async fn send_rpc_response(&mut self, data: Bytes, stream: Stream) -> Result<(), WebRtcError> {
         let message_response = webrtc::v1::Response {
@@ -206,7 +271,7 @@ where
                         };
                         let _ = self.streams.insert(
                             req.stream.as_ref().unwrap().id as u32,
-                            RpcCall(hdr, None, None),
+                            RpcCall(hdr, None, None, None, None, None),
                         );
 
                         self.send_response(header_response).await?;
@@ -227,7 +292,10 @@ where
                         if rst {
                             let stream = req.stream.unwrap();
                             let key = stream.id as u32;
-                            let _ = self.streams.remove(&key);
+                            // dropping a pending unary call stops the movement it waits on
+                            if let Some(RpcCall(.., Some(abort), _)) = self.streams.remove(&key) {
+                                abort.abort();
+                            }
                             self.send_trailers(
                                 stream,
                                 Status {
@@ -265,20 +333,74 @@ where
             })
             .unwrap_or((0, async_io::Timer::never()));
 
-        let id = futures_lite::future::or(async { self.next_rpc_call().await }, async {
-            next_stream.1.await;
-            Ok(next_stream.0)
-        })
-        .await?;
-        if let Some(mut call) = self.streams.remove(&id) {
-            let r = self
-                .process_rpc_request(Stream { id: id as u64 }, call.2.as_ref().unwrap(), &call.0)
-                .await?;
-            if let Some(next) = r.1 {
-                let _ = call.1.insert(next);
-                let _ = self.streams.insert(id, call);
-            } else {
-                self.send_trailers(Stream { id: id as u64 }, r.0).await?;
+        // pending calls are taken out while racing so next_rpc_call can borrow self
+        let mut pending = std::mem::take(&mut self.pending);
+        let next = futures_lite::future::or(
+            async { self.next_rpc_call().await.map(NextRpc::Call) },
+            futures_lite::future::or(
+                async {
+                    next_stream.1.await;
+                    Ok(NextRpc::Call(next_stream.0))
+                },
+                async {
+                    match pending.next().await {
+                        Some((id, res)) => Ok(NextRpc::Completed(id, res)),
+                        None => futures_lite::future::pending().await,
+                    }
+                },
+            ),
+        )
+        .await;
+        self.pending = pending;
+
+        match next? {
+            NextRpc::Call(id) => {
+                if let Some(mut call) = self.streams.remove(&id) {
+                    // taken before sending the messages, which yields, so the next poll
+                    // doesn't miss what happens meanwhile
+                    let now = SystemTime::now();
+                    match self
+                        .process_rpc_request(
+                            Stream { id: id as u64 },
+                            call.2.as_ref().unwrap(),
+                            &call.0,
+                            call.3,
+                            now,
+                            &mut call.5,
+                        )
+                        .await?
+                    {
+                        RpcOutcome::Done(_, Some(next)) => {
+                            let _ = call.1.insert(next);
+                            let _ = call.3.insert(now);
+                            let _ = self.streams.insert(id, call);
+                        }
+                        RpcOutcome::Done(status, None) => {
+                            self.send_trailers(Stream { id: id as u64 }, status).await?;
+                        }
+                        // keep the call around so a reset of the stream cancels it
+                        RpcOutcome::Pending(abort) => {
+                            let _ = call.4.insert(abort);
+                            let _ = self.streams.insert(id, call);
+                        }
+                    }
+                }
+            }
+            NextRpc::Completed(id, res) => {
+                if self.streams.remove(&id).is_some() {
+                    let stream = Stream { id: id as u64 };
+                    let status = match res {
+                        Ok(data) => {
+                            self.send_rpc_response(data, stream.clone()).await?;
+                            Status {
+                                code: 0,
+                                ..Default::default()
+                            }
+                        }
+                        Err(e) => e.to_status(),
+                    };
+                    self.send_trailers(stream, status).await?;
+                }
             }
         }
         Ok(())
@@ -1,11 +1,12 @@
 use super::actuator::{Actuator, ActuatorError};
 use super::base::{Base, BaseError, BaseType, COMPONENT_NAME as BaseCompName};
-use super::config::ConfigType;
+use super::config::{AttributeError, ConfigType};
 use super::motor::{Motor, MotorType, COMPONENT_NAME as MotorCompName};
 use super::registry::{ComponentRegistry, Dependency, ResourceKey};
 use super::robot::Resource;
 use crate::proto::common::v1::Vector3;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 pub(crate) fn register_models(registry: &mut ComponentRegistry) {
     if registry
@@ -33,6 +34,9 @@ pub(crate) fn register_models(registry: &mut ComponentRegistry) {
 pub struct WheeledBase<ML, MR> {
     motor_right: MR,
     motor_left: ML,
+    // required to move by distance, angle or velocity, set_power works without them
+    wheel_circumference_mm: Option<f64>,
+    width_mm: Option<f64>,
 }
 
 impl<ML, MR> WheeledBase<ML, MR>
@@ -44,8 +48,51 @@ where
         WheeledBase {
             motor_right,
             motor_left,
+            wheel_circumference_mm: None,
+            width_mm: None,
         }
     }
+
+    pub fn with_dimensions(mut self, wheel_circumference_mm: f64, width_mm: f64) -> Self {
+        let _ = self.wheel_circumference_mm.insert(wheel_circumference_mm);
+        let _ = self.width_mm.insert(width_mm);
+        self
+    }
+
+    fn wheel_circumference_mm(&self) -> Result<f64, BaseError> {
+        self.wheel_circumference_mm
+            .filter(|c| *c > 0.0)
+            .ok_or(BaseError::BaseConfigError(
+                "wheel_circumference_mm must be set and positive",
+            ))
+    }
+
+    fn width_mm(&self) -> Result<f64, BaseError> {
+        self.width_mm
+            .filter(|w| *w > 0.0)
+            .ok_or(BaseError::BaseConfigError(
+                "width_mm must be set and positive",
+            ))
+    }
+
+    // Runs both motors for the same number of revolutions, each motor derives its power from
+    // its own max_rpm. Returns the longest of the durations reported by the motors.
+    fn go_for(
+        &mut self,
+        rpm_left: f64,
+        rpm_right: f64,
+        revolutions: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        let dur_left = self.motor_left.go_for(rpm_left, revolutions)?;
+        let dur_right = match self.motor_right.go_for(rpm_right, revolutions) {
+            Ok(dur) => dur,
+            Err(err) => {
+                let _ = self.motor_left.stop();
+                return Err(err.into());
+            }
+        };
+        Ok(dur_left.max(dur_right))
+    }
     #[allow(clippy::only_used_in_recursion)]
     fn differential_drive(&self, forward: f64, left: f64) -> (f64, f64) {
         if forward < 0.0 {
@@ -81,9 +128,23 @@ where
                 };
             }
         }
+        // the dimensions are optional, but a malformed one is still a config error
+        let wheel_circumference_mm = match cfg.get_attribute::<f64>("wheel_circumference_mm") {
+            Ok(circumference) => Some(circumference),
+            Err(AttributeError::KeyNotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+        let width_mm = match cfg.get_attribute::<f64>("width_mm") {
+            Ok(width) => Some(width),
+            Err(AttributeError::KeyNotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
         if let Some(l_motor) = l_motor {
             if let Some(r_motor) = r_motor {
-                Ok(Arc::new(Mutex::new(WheeledBase::new(r_motor, l_motor))))
+                let mut base = WheeledBase::new(r_motor, l_motor);
+                base.wheel_circumference_mm = wheel_circumference_mm;
+                base.width_mm = width_mm;
+                Ok(Arc::new(Mutex::new(base)))
             } else {
                 Err(BaseError::BaseConfigError("right motor couldn't be found"))
             }
@@ -132,4 +193,190 @@ where
         self.motor_right.set_power(r)?;
         Ok(())
     }
+
+    fn move_straight(
+        &mut self,
+        distance_mm: i64,
+        mm_per_sec: f64,
+    ) -> Result<Option<Duration>, BaseError> {
+        if mm_per_sec.is_nan() || mm_per_sec == 0.0 {
+            return Err(BaseError::BaseInvalidArgument("mm_per_sec cannot be 0"));
+        }
+        if distance_mm == 0 {
+            self.stop()?;
+            return Ok(None);
+        }
+        let circumference = self.wheel_circumference_mm()?;
+        let rpm = mm_per_sec / circumference * 60.0;
+        let revolutions = distance_mm as f64 / circumference;
+        self.go_for(rpm, rpm, revolutions)
+    }
+
+    fn spin(&mut self, angle_deg: f64, degs_per_sec: f64) -> Result<Option<Duration>, BaseError> {
+        if degs_per_sec.is_nan() || degs_per_sec == 0.0 {
+            return Err(BaseError::BaseInvalidArgument("degs_per_sec cannot be 0"));
+        }
+        if angle_deg == 0.0 {
+            self.stop()?;
+            return Ok(None);
+        }
+        let circumference = self.wheel_circumference_mm()?;
+        // each wheel travels along the circle whose diameter is the width of the base
+        let turn_circumference = std::f64::consts::PI * self.width_mm()?;
+        let rpm = degs_per_sec / 360.0 * turn_circumference / circumference * 60.0;
+        let revolutions = angle_deg / 360.0 * turn_circumference / circumference;
+        // turning counterclockwise means the left wheel goes backwards
+        self.go_for(-rpm, rpm, revolutions)
+    }
+
+    fn set_velocity(&mut self, lin: &Vector3, ang: &Vector3) -> Result<(), BaseError> {
+        let circumference = self.wheel_circumference_mm()?;
+        let width = self.width_mm()?;
+        // linear speed of each wheel in mm/s
+        let ang_rad = ang.z.to_radians();
+        let v_left = lin.y - ang_rad * width / 2.0;
+        let v_right = lin.y + ang_rad * width / 2.0;
+        self.motor_left.set_rpm(v_left / circumference * 60.0)?;
+        if let Err(err) = self.motor_right.set_rpm(v_right / circumference * 60.0) {
+            let _ = self.motor_left.stop();
+            return Err(err.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::WheeledBase;
+    use crate::common::base::BaseError;
+    use crate::common::config::{ConfigType, DynamicComponentConfig, Kind, Model, ResourceName};
+    use crate::common::motor::MotorType;
+    use crate::common::registry::{Dependency, ResourceKey};
+    use crate::common::robot::Resource;
+    use crate::common::{actuator::Actuator, base::Base, motor::FakeMotor};
+    use crate::proto::common::v1::Vector3;
+
+    fn base_config(attributes: HashMap<String, Kind>) -> DynamicComponentConfig {
+        DynamicComponentConfig {
+            name: ResourceName::new_builtin("base".to_owned(), "base".to_owned()),
+            model: Model::new_builtin("two_wheeled_base".to_owned()),
+            attributes: Some(attributes),
+            data_collector_configs: vec![],
+        }
+    }
+
+    #[test_log::test]
+    fn test_from_config_dimensions() {
+        let deps = || {
+            let left: MotorType = Arc::new(Mutex::new(FakeMotor::new()));
+            let right: MotorType = Arc::new(Mutex::new(FakeMotor::new()));
+            vec![
+                Dependency(ResourceKey::new("motor", "l"), Resource::Motor(left)),
+                Dependency(ResourceKey::new("motor", "r"), Resource::Motor(right)),
+            ]
+        };
+        let attributes = |dimensions: Vec<(&str, Kind)>| {
+            let mut attributes = HashMap::from([
+                ("left".to_owned(), Kind::StringValue("l".to_owned())),
+                ("right".to_owned(), Kind::StringValue("r".to_owned())),
+            ]);
+            attributes.extend(dimensions.into_iter().map(|(k, v)| (k.to_owned(), v)));
+            base_config(attributes)
+        };
+
+        let cfg = attributes(vec![
+            ("wheel_circumference_mm", Kind::NumberValue(200.0)),
+            ("width_mm", Kind::NumberValue(300.0)),
+        ]);
+        let base =
+            WheeledBase::<MotorType, MotorType>::from_config(ConfigType::Dynamic(&cfg), deps())
+                .unwrap();
+        assert!(base.lock().unwrap().move_straight(100, 100.0).is_ok());
+
+        // missing dimensions only matter when moving by distance
+        let cfg = attributes(vec![]);
+        let base =
+            WheeledBase::<MotorType, MotorType>::from_config(ConfigType::Dynamic(&cfg), deps())
+                .unwrap();
+        assert!(base.lock().unwrap().move_straight(100, 100.0).is_err());
+
+        let cfg = attributes(vec![(
+            "wheel_circumference_mm",
+            Kind::StringValue("wide".to_owned()),
+        )]);
+        assert!(matches!(
+            WheeledBase::<MotorType, MotorType>::from_config(ConfigType::Dynamic(&cfg), deps()),
+            Err(BaseError::BaseConfigAttributeError(_))
+        ));
+        let cfg = attributes(vec![("width_mm", Kind::BoolValue(true))]);
+        assert!(matches!(
+            WheeledBase::<MotorType, MotorType>::from_config(ConfigType::Dynamic(&cfg), deps()),
+            Err(BaseError::BaseConfigAttributeError(_))
+        ));
+    }
+
+    #[test_log::test]
+    fn test_move_straight() {
+        let mut base =
+            WheeledBase::new(FakeMotor::new(), FakeMotor::new()).with_dimensions(200.0, 300.0);
+        // 1000 mm at 100 mm/s
+        let dur = base.move_straight(1000, 100.0).unwrap();
+        assert!(dur.is_some());
+        assert!((dur.unwrap().as_secs_f64() - 10.0).abs() < 1e-6);
+        assert!(base.is_moving().unwrap());
+
+        assert!(base.move_straight(1000, 0.0).is_err());
+        assert_eq!(base.move_straight(0, 100.0).unwrap(), None);
+        assert!(!base.is_moving().unwrap());
+
+        let mut base = WheeledBase::new(FakeMotor::new(), FakeMotor::new());
+        assert!(base.move_straight(1000, 100.0).is_err());
+    }
+
+    #[test_log::test]
+    fn test_spin() {
+        let mut base =
+            WheeledBase::new(FakeMotor::new(), FakeMotor::new()).with_dimensions(200.0, 300.0);
+        let dur = base.spin(90.0, 45.0).unwrap();
+        assert!(dur.is_some());
+        assert!((dur.unwrap().as_secs_f64() - 2.0).abs() < 1e-6);
+        // FakeMotor only reports moving forward
+        assert!(!base.motor_left.is_moving().unwrap());
+        assert!(base.motor_right.is_moving().unwrap());
+
+        let _ = base.spin(-90.0, 45.0).unwrap();
+        assert!(base.motor_left.is_moving().unwrap());
+        assert!(!base.motor_right.is_moving().unwrap());
+
+        let mut base = WheeledBase::new(FakeMotor::new(), FakeMotor::new());
+        assert!(base.spin(90.0, 45.0).is_err());
+    }
+
+    #[test_log::test]
+    fn test_set_velocity() {
+        let mut base =
+            WheeledBase::new(FakeMotor::new(), FakeMotor::new()).with_dimensions(200.0, 300.0);
+        let lin = Vector3 {
+            x: 0.0,
+            y: 100.0,
+            z: 0.0,
+        };
+        let ang = Vector3::default();
+        assert!(base.set_velocity(&lin, &ang).is_ok());
+        assert!(base.motor_left.is_moving().unwrap());
+        assert!(base.motor_right.is_moving().unwrap());
+
+        let lin = Vector3::default();
+        let ang = Vector3 {
+            x: 0.0,
+            y: 0.0,
+            z: 90.0,
+        };
+        assert!(base.set_velocity(&lin, &ang).is_ok());
+        assert!(!base.motor_left.is_moving().unwrap());
+        assert!(base.motor_right.is_moving().unwrap());
+    }
 }
@@ -0,0 +1,78 @@
+use crate::{
+    common::{data_store::DataStoreError, flash_data_store::DataPartition},
+    esp32::esp_idf_svc::sys::{
+        esp, esp_partition_erase_range, esp_partition_find_first, esp_partition_read,
+        esp_partition_subtype_t_ESP_PARTITION_SUBTYPE_ANY, esp_partition_t,
+        esp_partition_type_t_ESP_PARTITION_TYPE_DATA, esp_partition_write, EspError,
+    },
+};
+
+/// Label of the data partition used to persist captured data, data is kept in RAM when the
+/// partition table doesn't have one
+pub const DATA_STORE_PARTITION_LABEL: &str = "data_store";
+
+fn esp_error(err: EspError) -> DataStoreError {
+    DataStoreError::PartitionError(err.to_string())
+}
+
+/// The flash partition backing the [crate::common::flash_data_store::FlashDataStore]
+pub struct EspDataPartition {
+    // the pointer remains valid for the lifetime of the program
+    partition: &'static esp_partition_t,
+}
+
+impl DataPartition for EspDataPartition {
+    fn open() -> Result<Self, DataStoreError> {
+        let label = format!("{}\0", DATA_STORE_PARTITION_LABEL);
+        let partition = unsafe {
+            esp_partition_find_first(
+                esp_partition_type_t_ESP_PARTITION_TYPE_DATA,
+                esp_partition_subtype_t_ESP_PARTITION_SUBTYPE_ANY,
+                label.as_ptr() as *const _,
+            )
+            .as_ref()
+        }
+        .ok_or_else(|| {
+            DataStoreError::PartitionUnavailable(format!(
+                "no `{}` partition found",
+                DATA_STORE_PARTITION_LABEL
+            ))
+        })?;
+        Ok(Self { partition })
+    }
+    fn sector_size(&self) -> usize {
+        self.partition.erase_size as usize
+    }
+    fn size(&self) -> usize {
+        self.partition.size as usize
+    }
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), DataStoreError> {
+        esp!(unsafe {
+            esp_partition_read(
+                self.partition,
+                offset,
+                buf.as_mut_ptr() as *mut _,
+                buf.len(),
+            )
+        })
+        .map_err(esp_error)
+    }
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), DataStoreError> {
+        esp!(unsafe {
+            esp_partition_write(
+                self.partition,
+                offset,
+                data.as_ptr() as *const _,
+                data.len(),
+            )
+        })
+        .map_err(esp_error)
+    }
+    fn erase_sector(&mut self, sector: usize) -> Result<(), DataStoreError> {
+        let sector_size = self.sector_size();
+        esp!(unsafe {
+            esp_partition_erase_range(self.partition, sector * sector_size, sector_size)
+        })
+        .map_err(esp_error)
+    }
+}
@@ -0,0 +1,216 @@
+//! An audio input reading a digital microphone (such as the INMP441) through the I2S0
+//! peripheral, in standard Philips mode with 16 bits samples. The samples are captured into
+//! DMA buffers by the peripheral, `read_samples` copies the ones available without blocking.
+//!
+//! ```json
+//! {
+//!   "model": "i2s",
+//!   "name": "microphone",
+//!   "type": "audio_input",
+//!   "attributes": {
+//!     "bclk_pin": 26,
+//!     "ws_pin": 25,
+//!     "din_pin": 33,
+//!     "sample_rate": 16000,
+//!     "channels": 1
+//!   }
+//! }
+//! ```
+//!
+//! `sample_rate` defaults to 16000 and `channels` to 1, a mono microphone is read from the
+//! left slot (its L/R pin tied to ground).
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use crate::{
+    common::{
+        audio_input::{AudioInput, AudioInputError, AudioInputType, AudioProperties},
+        config::{AttributeError, ConfigType},
+        registry::{ComponentRegistry, Dependency},
+    },
+    DoCommand,
+};
+
+use crate::esp32::esp_idf_svc::{
+    hal::{
+        gpio::AnyIOPin,
+        i2s::{
+            config::{
+                Config, DataBitWidth, SlotMode, StdClkConfig, StdConfig, StdGpioConfig,
+                StdSlotConfig,
+            },
+            I2sDriver, I2sRx, I2S0,
+        },
+    },
+    sys::{EspError, ESP_ERR_TIMEOUT},
+};
+
+const DEFAULT_SAMPLE_RATE: u32 = 16000;
+const DMA_BUFFER_COUNT: u32 = 6;
+const FRAMES_PER_DMA_BUFFER: u32 = 240;
+
+pub(crate) fn register_models(registry: &mut ComponentRegistry) {
+    if registry
+        .register_audio_input("i2s", &Esp32I2sAudioInput::from_config)
+        .is_err()
+    {
+        log::error!("i2s model is already registered");
+    }
+}
+
+impl From<EspError> for AudioInputError {
+    fn from(value: EspError) -> Self {
+        AudioInputError::AudioInputReadError(Box::new(value))
+    }
+}
+
+// only one driver can own the I2S0 peripheral at a time
+static I2S0_TAKEN: AtomicBool = AtomicBool::new(false);
+
+struct I2s0Claim;
+
+impl I2s0Claim {
+    fn take() -> Result<Self, AudioInputError> {
+        if I2S0_TAKEN.swap(true, Ordering::SeqCst) {
+            return Err(AudioInputError::AudioInputConfigError(
+                "the I2S0 peripheral is already in use",
+            ));
+        }
+        Ok(Self)
+    }
+}
+
+impl Drop for I2s0Claim {
+    fn drop(&mut self) {
+        I2S0_TAKEN.store(false, Ordering::SeqCst);
+    }
+}
+
+#[derive(DoCommand)]
+pub struct Esp32I2sAudioInput {
+    driver: I2sDriver<'static, I2sRx>,
+    // declared after the driver so the peripheral is released once it is uninstalled
+    _claim: I2s0Claim,
+    sample_rate: u32,
+    channels: u32,
+    buffer: Vec<u8>,
+}
+
+impl Esp32I2sAudioInput {
+    /// Fails if another audio input still owns the I2S0 peripheral
+    pub fn new(
+        bclk_pin: i32,
+        ws_pin: i32,
+        din_pin: i32,
+        sample_rate: u32,
+        channels: u32,
+    ) -> Result<Self, AudioInputError> {
+        let slot_mode = match channels {
+            1 => SlotMode::Mono,
+            2 => SlotMode::Stereo,
+            _ => {
+                return Err(AudioInputError::AudioInputConfigError(
+                    "channels must be 1 or 2",
+                ))
+            }
+        };
+        if sample_rate == 0 {
+            return Err(AudioInputError::AudioInputConfigError(
+                "sample_rate must be positive",
+            ));
+        }
+        let claim = I2s0Claim::take()?;
+        let config = StdConfig::new(
+            Config::default()
+                .dma_buffer_count(DMA_BUFFER_COUNT)
+                .frames_per_buffer(FRAMES_PER_DMA_BUFFER),
+            StdClkConfig::from_sample_rate_hz(sample_rate),
+            StdSlotConfig::philips_slot_default(DataBitWidth::Bits16, slot_mode),
+            StdGpioConfig::default(),
+        );
+        // SAFETY: the claim guarantees no other driver owns the peripheral
+        let mut driver = I2sDriver::new_std_rx(
+            unsafe { I2S0::new() },
+            &config,
+            unsafe { AnyIOPin::new(bclk_pin) },
+            unsafe { AnyIOPin::new(din_pin) },
+            Option::<AnyIOPin>::None,
+            unsafe { AnyIOPin::new(ws_pin) },
+        )?;
+        driver.rx_enable()?;
+        Ok(Self {
+            driver,
+            _claim: claim,
+            sample_rate,
+            channels,
+            buffer: vec![],
+        })
+    }
+
+    pub(crate) fn from_config(
+        cfg: ConfigType,
+        _: Vec<Dependency>,
+    ) -> Result<AudioInputType, AudioInputError> {
+        let bclk_pin = cfg.get_attribute::<i32>("bclk_pin")?;
+        let ws_pin = cfg.get_attribute::<i32>("ws_pin")?;
+        let din_pin = cfg.get_attribute::<i32>("din_pin")?;
+        let sample_rate = match cfg.get_attribute::<u32>("sample_rate") {
+            Ok(rate) => rate,
+            Err(AttributeError::KeyNotFound(_)) => DEFAULT_SAMPLE_RATE,
+            Err(err) => return Err(err.into()),
+        };
+        let channels = match cfg.get_attribute::<u32>("channels") {
+            Ok(channels) => channels,
+            Err(AttributeError::KeyNotFound(_)) => 1,
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Arc::new(Mutex::new(Esp32I2sAudioInput::new(
+            bclk_pin,
+            ws_pin,
+            din_pin,
+            sample_rate,
+            channels,
+        )?)))
+    }
+}
+
+impl AudioInput for Esp32I2sAudioInput {
+    fn get_properties(&mut self) -> Result<AudioProperties, AudioInputError> {
+        Ok(AudioProperties {
+            channel_count: self.channels,
+            sample_rate: self.sample_rate,
+            // a sample is available once the DMA buffer it was captured in is full
+            latency: Duration::from_secs_f64(
+                FRAMES_PER_DMA_BUFFER as f64 / self.sample_rate as f64,
+            ),
+        })
+    }
+
+    fn read_samples(&mut self, samples: &mut [i16]) -> Result<usize, AudioInputError> {
+        let frame_len = self.channels as usize;
+        let len = samples.len() / frame_len * frame_len;
+        self.buffer.resize(len * 2, 0);
+        let read = match self.driver.read(&mut self.buffer, 0) {
+            Ok(read) => read,
+            // nothing was captured since the previous read
+            Err(err) if err.code() == ESP_ERR_TIMEOUT => 0,
+            Err(err) => return Err(err.into()),
+        };
+        // DMA buffers hold whole frames
+        let read = read / 2 / frame_len * frame_len;
+        for (sample, bytes) in samples
+            .iter_mut()
+            .zip(self.buffer.chunks_exact(2))
+            .take(read)
+        {
+            *sample = i16::from_le_bytes([bytes[0], bytes[1]]);
+        }
+        Ok(read)
+    }
+}
@@ -5,6 +5,8 @@ pub mod board;
 #[cfg(all(feature = "camera", feature = "builtin-components"))]
 pub mod camera;
 pub mod certificate;
+#[cfg(feature = "data")]
+pub mod data_partition;
 pub mod dtls;
 #[cfg(feature = "builtin-components")]
 pub mod encoder;
@@ -12,6 +14,8 @@ pub mod esp_idf_svc;
 #[cfg(feature = "builtin-components")]
 pub mod hcsr04;
 pub mod i2c;
+#[cfg(feature = "builtin-components")]
+pub mod i2s_audio_input;
 pub mod log;
 pub mod pin;
 #[cfg(feature = "builtin-components")]
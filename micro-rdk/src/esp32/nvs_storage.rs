@@ -193,7 +193,16 @@ const NVS_NETWORK_SETTINGS_KEY: &str = "NETWORKS";
 #[cfg(feature = "ota")]
 const NVS_OTA_VERSION_KEY: &str = "OTA_VERSION";
 #[cfg(feature = "ota")]
-use crate::common::{credentials_storage::OtaMetadataStorage, ota::OtaMetadata};
+const NVS_OTA_PUBLIC_KEY_KEY: &str = "OTA_PUB_KEY";
+#[cfg(feature = "ota")]
+const NVS_OTA_PROGRESS_KEY: &str = "OTA_PROGRESS";
+#[cfg(feature = "ota")]
+const NVS_OTA_FAILED_VERSION_KEY: &str = "OTA_FAILED_VER";
+#[cfg(feature = "ota")]
+use crate::common::{
+    credentials_storage::OtaMetadataStorage,
+    ota::{OtaDownloadProgress, OtaMetadata},
+};
 
 #[cfg(feature = "ota")]
 impl OtaMetadataStorage for NVSStorage {
@@ -211,6 +220,37 @@ impl OtaMetadataStorage for NVSStorage {
     fn reset_ota_metadata(&self) -> Result<(), Self::Error> {
         self.erase_key(NVS_OTA_VERSION_KEY)
     }
+    fn has_ota_public_key(&self) -> bool {
+        self.has_blob(NVS_OTA_PUBLIC_KEY_KEY).unwrap_or(false)
+    }
+    fn get_ota_public_key(&self) -> Result<Vec<u8>, Self::Error> {
+        self.get_blob(NVS_OTA_PUBLIC_KEY_KEY)
+    }
+    fn store_ota_public_key(&self, public_key: &[u8]) -> Result<(), Self::Error> {
+        self.set_blob(NVS_OTA_PUBLIC_KEY_KEY, Bytes::copy_from_slice(public_key))
+    }
+    fn get_ota_download_progress(&self) -> Result<OtaDownloadProgress, Self::Error> {
+        let blob: Vec<u8> = self.get_blob(NVS_OTA_PROGRESS_KEY)?;
+        let progress: OtaDownloadProgress =
+            postcard::from_bytes(&blob).map_err(NVSDecodeError::Postcard)?;
+        Ok(progress)
+    }
+    fn store_ota_download_progress(
+        &self,
+        progress: &OtaDownloadProgress,
+    ) -> Result<(), Self::Error> {
+        let bytes: Vec<u8> = postcard::to_allocvec(progress)?;
+        self.set_blob(NVS_OTA_PROGRESS_KEY, bytes.into())
+    }
+    fn reset_ota_download_progress(&self) -> Result<(), Self::Error> {
+        self.erase_key(NVS_OTA_PROGRESS_KEY)
+    }
+    fn get_ota_failed_version(&self) -> Result<String, Self::Error> {
+        self.get_string(NVS_OTA_FAILED_VERSION_KEY)
+    }
+    fn store_ota_failed_version(&self, version: &str) -> Result<(), Self::Error> {
+        self.set_string(NVS_OTA_FAILED_VERSION_KEY, version)
+    }
 }
 
 impl RobotConfigurationStorage for NVSStorage {
@@ -64,6 +64,9 @@ impl Motor for SingleEncodedMotor {
     fn go_for(&mut self, rpm: f64, revolutions: f64) -> Result<Option<Duration>, MotorError> {
         self.motor.go_for(rpm, revolutions)
     }
+    fn is_powered(&mut self) -> Result<(bool, f64), MotorError> {
+        self.motor.is_powered()
+    }
     fn get_properties(&mut self) -> MotorSupportedProperties {
         MotorSupportedProperties {
             position_reporting: true,
@@ -184,6 +184,50 @@ pub mod proto {
                 include!("gen/viam.component.powersensor.v1.rs");
             }
         }
+
+        pub mod arm {
+            pub mod v1 {
+                include!("gen/viam.component.arm.v1.rs");
+            }
+        }
+
+        pub mod gripper {
+            pub mod v1 {
+                include!("gen/viam.component.gripper.v1.rs");
+            }
+        }
+
+        pub mod gantry {
+            pub mod v1 {
+                include!("gen/viam.component.gantry.v1.rs");
+            }
+        }
+
+        pub mod inputcontroller {
+            pub mod v1 {
+                include!("gen/viam.component.inputcontroller.v1.rs");
+            }
+        }
+
+        pub mod posetracker {
+            pub mod v1 {
+                include!("gen/viam.component.posetracker.v1.rs");
+            }
+        }
+
+        pub mod audioinput {
+            pub mod v1 {
+                include!("gen/viam.component.audioinput.v1.rs");
+            }
+        }
+    }
+
+    pub mod service {
+        pub mod sensors {
+            pub mod v1 {
+                include!("gen/viam.service.sensors.v1.rs");
+            }
+        }
     }
 }
 
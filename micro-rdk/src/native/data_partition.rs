@@ -0,0 +1,84 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::common::{data_store::DataStoreError, flash_data_store::DataPartition};
+
+/// Path of the file holding captured data, data is kept in RAM when unset
+pub const DATA_STORE_PATH_ENV: &str = "MICRO_RDK_DATA_STORE_PATH";
+const DATA_STORE_SIZE: usize = 1024 * 1024;
+const SECTOR_SIZE: usize = 4096;
+
+fn io_error(err: std::io::Error) -> DataStoreError {
+    DataStoreError::PartitionError(err.to_string())
+}
+
+/// A file emulating a flash partition for the [crate::common::flash_data_store::FlashDataStore]
+pub struct FileDataPartition {
+    file: File,
+    size: usize,
+}
+
+impl FileDataPartition {
+    pub fn new(path: impl AsRef<Path>, size: usize) -> Result<Self, DataStoreError> {
+        let size = size - size % SECTOR_SIZE;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)
+            .map_err(io_error)?;
+        if file.metadata().map_err(io_error)?.len() != size as u64 {
+            // new or resized, start from an erased partition
+            file.set_len(0).map_err(io_error)?;
+            file.write_all(&vec![0xFF; size]).map_err(io_error)?;
+            file.sync_all().map_err(io_error)?;
+        }
+        Ok(Self { file, size })
+    }
+
+    fn write_at(&mut self, offset: usize, data: &[u8]) -> Result<(), DataStoreError> {
+        self.file
+            .seek(SeekFrom::Start(offset as u64))
+            .and_then(|_| self.file.write_all(data))
+            .and_then(|_| self.file.sync_data())
+            .map_err(io_error)
+    }
+}
+
+impl DataPartition for FileDataPartition {
+    fn open() -> Result<Self, DataStoreError> {
+        let path = std::env::var(DATA_STORE_PATH_ENV).map_err(|_| {
+            DataStoreError::PartitionUnavailable(format!("{} is not set", DATA_STORE_PATH_ENV))
+        })?;
+        Self::new(path, DATA_STORE_SIZE)
+    }
+    fn sector_size(&self) -> usize {
+        SECTOR_SIZE
+    }
+    fn size(&self) -> usize {
+        self.size
+    }
+    fn read(&self, offset: usize, buf: &mut [u8]) -> Result<(), DataStoreError> {
+        let mut file = &self.file;
+        file.seek(SeekFrom::Start(offset as u64))
+            .and_then(|_| file.read_exact(buf))
+            .map_err(io_error)
+    }
+    fn write(&mut self, offset: usize, data: &[u8]) -> Result<(), DataStoreError> {
+        // like flash, writing can only clear bits
+        let mut current = vec![0; data.len()];
+        self.read(offset, &mut current)?;
+        current
+            .iter_mut()
+            .zip(data)
+            .for_each(|(dst, src)| *dst &= *src);
+        self.write_at(offset, &current)
+    }
+    fn erase_sector(&mut self, sector: usize) -> Result<(), DataStoreError> {
+        self.write_at(sector * SECTOR_SIZE, &[0xFF; SECTOR_SIZE])
+    }
+}
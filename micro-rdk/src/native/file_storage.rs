@@ -0,0 +1,475 @@
+use hyper::{http::uri::InvalidUri, Uri};
+use prost::Message;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{ErrorKind, Write},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+use thiserror::Error;
+
+use crate::{
+    common::{
+        config::NetworkSetting,
+        credentials_storage::{
+            EmptyStorageCollectionError, RobotConfigurationStorage, RobotCredentials,
+            StorageDiagnostic, TlsCertificate, WifiCredentialStorage,
+        },
+        grpc::{GrpcError, ServerError},
+    },
+    proto::{app::v1::RobotConfig, provisioning::v1::CloudConfig},
+};
+
+#[derive(Error, Debug)]
+pub enum FileDecodeError {
+    #[error(transparent)]
+    Postcard(#[from] postcard::Error),
+    #[error(transparent)]
+    Prost(#[from] prost::DecodeError),
+    #[error(transparent)]
+    Utf8(#[from] std::string::FromUtf8Error),
+}
+
+#[derive(Error, Debug)]
+pub enum FileStorageError {
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error("storage key {0} is absent")]
+    KeyAbsent(String),
+    #[error(transparent)]
+    ValueDecodeError(#[from] FileDecodeError),
+    #[error(transparent)]
+    ValueEncodeError(#[from] postcard::Error),
+    #[error(transparent)]
+    UriParseError(#[from] InvalidUri),
+    #[error("storage collection empty")]
+    CollectionEmpty(#[from] EmptyStorageCollectionError),
+}
+
+impl From<FileStorageError> for ServerError {
+    fn from(value: FileStorageError) -> Self {
+        Self::new(GrpcError::RpcUnavailable, Some(value.into()))
+    }
+}
+
+/// Persistent storage for native builds, every key is stored in its own file under a
+/// directory. Writes go to a temporary file which is then renamed over the previous value,
+/// so an interrupted write never leaves a partially written key behind.
+#[derive(Clone)]
+pub struct FileStorage {
+    dir: Rc<PathBuf>,
+}
+
+const TMP_EXTENSION: &str = "tmp";
+
+impl FileStorage {
+    /// Opens the storage in `dir`, creating the directory if needed
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, FileStorageError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        // leftovers of writes interrupted before being renamed
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == TMP_EXTENSION) {
+                let _ = fs::remove_file(path);
+            }
+        }
+        Ok(Self { dir: Rc::new(dir) })
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+
+    fn has_key(&self, key: &str) -> bool {
+        self.path(key).is_file()
+    }
+
+    fn get_blob(&self, key: &str) -> Result<Vec<u8>, FileStorageError> {
+        fs::read(self.path(key)).map_err(|err| match err.kind() {
+            ErrorKind::NotFound => FileStorageError::KeyAbsent(key.to_string()),
+            _ => err.into(),
+        })
+    }
+
+    fn set_blob(&self, key: &str, bytes: &[u8]) -> Result<(), FileStorageError> {
+        if self.get_blob(key).is_ok_and(|stored| stored == bytes) {
+            log::debug!("no change in write to storage key {:?}, skipping", key);
+            return Ok(());
+        }
+        let path = self.path(key);
+        let tmp_path = path.with_extension(TMP_EXTENSION);
+        let mut options = OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        // values include the machine secret and TLS private key, keep them private to the owner
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, &path).inspect_err(|_| {
+            let _ = fs::remove_file(&tmp_path);
+        })?;
+        // persist the rename itself
+        File::open(self.dir.as_path())?.sync_all()?;
+        Ok(())
+    }
+
+    fn get_string(&self, key: &str) -> Result<String, FileStorageError> {
+        let bytes = self.get_blob(key)?;
+        Ok(String::from_utf8(bytes).map_err(FileDecodeError::Utf8)?)
+    }
+
+    fn set_string(&self, key: &str, string: &str) -> Result<(), FileStorageError> {
+        self.set_blob(key, string.as_bytes())
+    }
+
+    fn erase_key(&self, key: &str) -> Result<(), FileStorageError> {
+        match fs::remove_file(self.path(key)) {
+            Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl StorageDiagnostic for FileStorage {
+    fn log_space_diagnostic(&self) {
+        let used_space: u64 = match fs::read_dir(self.dir.as_path()) {
+            Ok(entries) => entries
+                .filter_map(|entry| entry.and_then(|entry| entry.metadata()).ok())
+                .map(|metadata| metadata.len())
+                .sum(),
+            Err(err) => {
+                log::error!("could not read storage directory: {:?}", err);
+                return;
+            }
+        };
+        log::info!(
+            "file storage stats: {:?} bytes used in {:?}",
+            used_space,
+            self.dir
+        );
+    }
+}
+
+// keys are shared with the NVS storage so both layouts stay recognizable
+const ROBOT_SECRET_KEY: &str = "ROBOT_SECRET";
+const ROBOT_ID_KEY: &str = "ROBOT_ID";
+const ROBOT_APP_ADDRESS: &str = "ROBOT_APP_ADDR";
+const ROBOT_CONFIG_KEY: &str = "ROBOT_CONFIG";
+const TLS_CERTIFICATE_KEY: &str = "TLS_CERT";
+const TLS_PRIVATE_KEY_KEY: &str = "TLS_PRIV_KEY";
+const DEFAULT_SSID_KEY: &str = "WIFI_SSID";
+const DEFAULT_PASSWORD_KEY: &str = "WIFI_PASSWORD";
+const NETWORK_SETTINGS_KEY: &str = "NETWORKS";
+
+#[cfg(feature = "ota")]
+const OTA_VERSION_KEY: &str = "OTA_VERSION";
+#[cfg(feature = "ota")]
+const OTA_PUBLIC_KEY_KEY: &str = "OTA_PUB_KEY";
+#[cfg(feature = "ota")]
+const OTA_PROGRESS_KEY: &str = "OTA_PROGRESS";
+#[cfg(feature = "ota")]
+const OTA_FAILED_VERSION_KEY: &str = "OTA_FAILED_VER";
+
+impl RobotConfigurationStorage for FileStorage {
+    type Error = FileStorageError;
+    fn has_robot_credentials(&self) -> bool {
+        self.has_key(ROBOT_SECRET_KEY) && self.has_key(ROBOT_ID_KEY)
+    }
+
+    fn get_robot_credentials(&self) -> Result<RobotCredentials, Self::Error> {
+        let robot_secret = self.get_string(ROBOT_SECRET_KEY)?;
+        let robot_id = self.get_string(ROBOT_ID_KEY)?;
+        let app_address = self.get_string(ROBOT_APP_ADDRESS)?;
+        Ok(RobotCredentials {
+            robot_secret,
+            robot_id,
+            app_address: app_address.parse::<Uri>()?,
+        })
+    }
+
+    fn store_robot_credentials(&self, cfg: &CloudConfig) -> Result<(), Self::Error> {
+        self.set_string(ROBOT_SECRET_KEY, &cfg.secret)?;
+        self.set_string(ROBOT_ID_KEY, &cfg.id).inspect_err(|_| {
+            let _ = self.erase_key(ROBOT_SECRET_KEY);
+        })?;
+        self.set_string(ROBOT_APP_ADDRESS, &cfg.app_address)
+            .inspect_err(|_| {
+                let _ = self.erase_key(ROBOT_SECRET_KEY);
+                let _ = self.erase_key(ROBOT_ID_KEY);
+            })?;
+        Ok(())
+    }
+
+    fn reset_robot_credentials(&self) -> Result<(), Self::Error> {
+        self.erase_key(ROBOT_SECRET_KEY)?;
+        self.erase_key(ROBOT_ID_KEY)?;
+        self.erase_key(ROBOT_APP_ADDRESS)?;
+        Ok(())
+    }
+
+    fn has_robot_configuration(&self) -> bool {
+        self.has_key(ROBOT_CONFIG_KEY)
+    }
+
+    fn store_robot_configuration(&self, cfg: &RobotConfig) -> Result<(), Self::Error> {
+        self.set_blob(ROBOT_CONFIG_KEY, &cfg.encode_to_vec())
+    }
+
+    fn get_robot_configuration(&self) -> Result<RobotConfig, Self::Error> {
+        let robot_config = self.get_blob(ROBOT_CONFIG_KEY)?;
+        let config = RobotConfig::decode(&robot_config[..]).map_err(FileDecodeError::Prost)?;
+        Ok(config)
+    }
+
+    fn reset_robot_configuration(&self) -> Result<(), Self::Error> {
+        self.erase_key(ROBOT_CONFIG_KEY)
+    }
+
+    fn has_tls_certificate(&self) -> bool {
+        self.has_key(TLS_CERTIFICATE_KEY) && self.has_key(TLS_PRIVATE_KEY_KEY)
+    }
+
+    fn get_tls_certificate(&self) -> Result<TlsCertificate, Self::Error> {
+        let certificate = self.get_blob(TLS_CERTIFICATE_KEY)?;
+        let private_key = self.get_blob(TLS_PRIVATE_KEY_KEY)?;
+        Ok(TlsCertificate {
+            certificate,
+            private_key,
+        })
+    }
+
+    fn store_tls_certificate(&self, creds: &TlsCertificate) -> Result<(), Self::Error> {
+        self.set_blob(TLS_CERTIFICATE_KEY, &creds.certificate)?;
+        self.set_blob(TLS_PRIVATE_KEY_KEY, &creds.private_key)
+            .inspect_err(|_| {
+                let _ = self.erase_key(TLS_CERTIFICATE_KEY);
+            })?;
+        Ok(())
+    }
+
+    fn reset_tls_certificate(&self) -> Result<(), Self::Error> {
+        self.erase_key(TLS_CERTIFICATE_KEY)?;
+        self.erase_key(TLS_PRIVATE_KEY_KEY)?;
+        Ok(())
+    }
+}
+
+impl WifiCredentialStorage for FileStorage {
+    type Error = FileStorageError;
+    fn has_network_settings(&self) -> bool {
+        self.has_key(NETWORK_SETTINGS_KEY)
+    }
+
+    fn get_network_settings(&self) -> Result<Vec<NetworkSetting>, Self::Error> {
+        let blob = self.get_blob(NETWORK_SETTINGS_KEY)?;
+        let networks: Vec<NetworkSetting> =
+            postcard::from_bytes(&blob).map_err(FileDecodeError::Postcard)?;
+        Ok(networks)
+    }
+
+    fn store_network_settings(
+        &self,
+        network_settings: &[NetworkSetting],
+    ) -> Result<(), Self::Error> {
+        let bytes: Vec<u8> = postcard::to_allocvec(&network_settings)?;
+        self.set_blob(NETWORK_SETTINGS_KEY, &bytes)
+    }
+
+    fn reset_network_settings(&self) -> Result<(), Self::Error> {
+        self.erase_key(NETWORK_SETTINGS_KEY)
+    }
+
+    fn has_default_network(&self) -> bool {
+        self.has_key(DEFAULT_SSID_KEY) && self.has_key(DEFAULT_PASSWORD_KEY)
+    }
+
+    fn get_default_network(&self) -> Result<NetworkSetting, Self::Error> {
+        let ssid = self.get_string(DEFAULT_SSID_KEY)?;
+        let password = self.get_string(DEFAULT_PASSWORD_KEY)?;
+        Ok(NetworkSetting {
+            ssid,
+            password,
+            priority: 0,
+        })
+    }
+
+    fn get_all_networks(&self) -> Result<Vec<NetworkSetting>, Self::Error> {
+        // return error if failed to get default network (not provisioned/configured)
+        let default = self.get_default_network()?;
+        let mut networks = if self.has_network_settings() {
+            self.get_network_settings()
+                .inspect_err(|e| log::error!("failed to retrieve stored networks: {}", e))
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        networks.push(default);
+        Ok(networks)
+    }
+
+    fn store_default_network(&self, ssid: &str, password: &str) -> Result<(), Self::Error> {
+        self.set_string(DEFAULT_SSID_KEY, ssid)?;
+        self.set_string(DEFAULT_PASSWORD_KEY, password)
+            .inspect_err(|_| {
+                let _ = self.erase_key(DEFAULT_SSID_KEY);
+            })?;
+        Ok(())
+    }
+
+    fn reset_default_network(&self) -> Result<(), Self::Error> {
+        self.erase_key(DEFAULT_SSID_KEY)?;
+        self.erase_key(DEFAULT_PASSWORD_KEY)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ota")]
+use crate::common::{
+    credentials_storage::OtaMetadataStorage,
+    ota::{OtaDownloadProgress, OtaMetadata},
+};
+
+#[cfg(feature = "ota")]
+impl OtaMetadataStorage for FileStorage {
+    type Error = FileStorageError;
+    fn has_ota_metadata(&self) -> bool {
+        self.has_key(OTA_VERSION_KEY)
+    }
+    fn get_ota_metadata(&self) -> Result<OtaMetadata, Self::Error> {
+        let version = self.get_string(OTA_VERSION_KEY)?;
+        Ok(OtaMetadata { version })
+    }
+    fn store_ota_metadata(&self, ota_metadata: &OtaMetadata) -> Result<(), Self::Error> {
+        self.set_string(OTA_VERSION_KEY, &ota_metadata.version)
+    }
+    fn reset_ota_metadata(&self) -> Result<(), Self::Error> {
+        self.erase_key(OTA_VERSION_KEY)
+    }
+    fn has_ota_public_key(&self) -> bool {
+        self.has_key(OTA_PUBLIC_KEY_KEY)
+    }
+    fn get_ota_public_key(&self) -> Result<Vec<u8>, Self::Error> {
+        self.get_blob(OTA_PUBLIC_KEY_KEY)
+    }
+    fn store_ota_public_key(&self, public_key: &[u8]) -> Result<(), Self::Error> {
+        self.set_blob(OTA_PUBLIC_KEY_KEY, public_key)
+    }
+    fn get_ota_download_progress(&self) -> Result<OtaDownloadProgress, Self::Error> {
+        let blob = self.get_blob(OTA_PROGRESS_KEY)?;
+        let progress: OtaDownloadProgress =
+            postcard::from_bytes(&blob).map_err(FileDecodeError::Postcard)?;
+        Ok(progress)
+    }
+    fn store_ota_download_progress(
+        &self,
+        progress: &OtaDownloadProgress,
+    ) -> Result<(), Self::Error> {
+        let bytes: Vec<u8> = postcard::to_allocvec(progress)?;
+        self.set_blob(OTA_PROGRESS_KEY, &bytes)
+    }
+    fn reset_ota_download_progress(&self) -> Result<(), Self::Error> {
+        self.erase_key(OTA_PROGRESS_KEY)
+    }
+    fn get_ota_failed_version(&self) -> Result<String, Self::Error> {
+        self.get_string(OTA_FAILED_VERSION_KEY)
+    }
+    fn store_ota_failed_version(&self, version: &str) -> Result<(), Self::Error> {
+        self.set_string(OTA_FAILED_VERSION_KEY, version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::{
+        common::{
+            config::NetworkSetting,
+            credentials_storage::{
+                RobotConfigurationStorage, RobotCredentials, TlsCertificate, WifiCredentialStorage,
+            },
+        },
+        native::file_storage::{FileStorage, FileStorageError},
+        proto::app::v1::{ComponentConfig, RobotConfig},
+    };
+
+    fn storage_dir() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("micro-rdk-storage-{}", uuid::Uuid::new_v4()))
+    }
+
+    #[test_log::test]
+    fn test_values_persist_across_instances() {
+        let dir = storage_dir();
+        let storage = FileStorage::new(&dir).unwrap();
+        assert!(!storage.has_robot_credentials());
+        assert!(matches!(
+            storage.get_robot_configuration(),
+            Err(FileStorageError::KeyAbsent(_))
+        ));
+
+        storage
+            .store_robot_credentials(
+                &RobotCredentials::new(
+                    "robot-id".to_string(),
+                    "secret".to_string(),
+                    "http://localhost:8080".to_string(),
+                )
+                .unwrap()
+                .into(),
+            )
+            .unwrap();
+        let config = RobotConfig {
+            components: vec![ComponentConfig {
+                name: "board".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        storage.store_robot_configuration(&config).unwrap();
+        storage
+            .store_tls_certificate(&TlsCertificate {
+                certificate: b"cert".to_vec(),
+                private_key: b"key".to_vec(),
+            })
+            .unwrap();
+        storage.store_default_network("ssid", "password").unwrap();
+        storage
+            .store_network_settings(&[NetworkSetting {
+                ssid: "other".to_string(),
+                password: "pwd".to_string(),
+                priority: 1,
+            }])
+            .unwrap();
+
+        // a write interrupted before its rename is discarded when reopening
+        fs::write(dir.join("ROBOT_CONFIG.tmp"), b"garbage").unwrap();
+
+        let storage = FileStorage::new(&dir).unwrap();
+        assert!(!dir.join("ROBOT_CONFIG.tmp").exists());
+        let creds = storage.get_robot_credentials().unwrap();
+        assert_eq!(creds.robot_id(), "robot-id");
+        assert_eq!(creds.robot_secret(), "secret");
+        assert_eq!(storage.get_robot_configuration().unwrap(), config);
+        assert_eq!(storage.get_tls_certificate().unwrap().private_key, b"key");
+        assert_eq!(storage.get_all_networks().unwrap().len(), 2);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(dir.join("ROBOT_SECRET"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        storage.reset_robot_credentials().unwrap();
+        storage.reset_robot_credentials().unwrap();
+        assert!(!storage.has_robot_credentials());
+        assert!(storage.has_robot_configuration());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
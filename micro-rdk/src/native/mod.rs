@@ -1,5 +1,8 @@
 pub mod certificate;
+#[cfg(feature = "data")]
+pub mod data_partition;
 pub mod dtls;
+pub mod file_storage;
 pub mod log;
 pub mod tcp;
 pub mod conn {